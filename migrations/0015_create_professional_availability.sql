@@ -0,0 +1,27 @@
+-- Weekly recurring availability windows of a professional, each window is split in bookable slots of slot_duration minutes
+CREATE TABLE professional_availability (
+    id UUID PRIMARY KEY,
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    weekday INTEGER NOT NULL CHECK (weekday BETWEEN 1 AND 7), -- ISO 8601 weekday, 1 = Monday ... 7 = Sunday
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    slot_duration INTEGER NOT NULL DEFAULT 60 CHECK (slot_duration > 0), -- minutes
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (start_time < end_time)
+);
+
+CREATE INDEX idx_professional_availability_professional_id ON professional_availability(professional_id);
+
+-- One-off blocks on the availability (holidays, days off...), a NULL start_time/end_time blocks the whole day
+CREATE TABLE professional_availability_exceptions (
+    id UUID PRIMARY KEY,
+    professional_id UUID NOT NULL REFERENCES professionals(id) ON DELETE CASCADE,
+    exception_date DATE NOT NULL,
+    start_time TIME,
+    end_time TIME,
+    reason TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((start_time IS NULL AND end_time IS NULL) OR (start_time IS NOT NULL AND end_time IS NOT NULL AND start_time < end_time))
+);
+
+CREATE INDEX idx_professional_availability_exceptions_professional_date ON professional_availability_exceptions(professional_id, exception_date);
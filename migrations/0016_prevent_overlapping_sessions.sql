@@ -0,0 +1,48 @@
+-- btree_gist is needed to mix the equality on professional_id with the range overlap in the same exclusion constraint
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- Double bookings made before the constraint existed would make it fail. They may be paid, so they aren't cancelled
+-- here: the migration stops and lists them, an operator cancels (and refunds) one of each pair before running it again
+DO $$
+DECLARE
+    conflicts TEXT;
+BEGIN
+    SELECT string_agg(
+        format('session %s overlaps session %s (professional %s at %s)',
+            later.id, earlier.id, later.professional_id, later.session_date),
+        E'\n' ORDER BY later.professional_id, later.session_date
+    )
+    INTO conflicts
+    FROM sessions earlier
+    JOIN sessions later
+        ON later.professional_id = earlier.professional_id
+        AND (COALESCE(earlier.created_at, '-infinity'), earlier.id) < (COALESCE(later.created_at, '-infinity'), later.id)
+    WHERE earlier.session_status_id <> 4
+        AND later.session_status_id <> 4
+        AND tstzrange(
+            earlier.session_date AT TIME ZONE 'UTC',
+            (earlier.session_date + make_interval(mins => COALESCE(earlier.session_duration, 60))) AT TIME ZONE 'UTC',
+            '[)'
+        ) && tstzrange(
+            later.session_date AT TIME ZONE 'UTC',
+            (later.session_date + make_interval(mins => COALESCE(later.session_duration, 60))) AT TIME ZONE 'UTC',
+            '[)'
+        );
+
+    IF conflicts IS NOT NULL THEN
+        RAISE EXCEPTION 'Overlapping sessions must be resolved before the overlap constraint is added'
+            USING DETAIL = conflicts,
+                HINT = 'Cancel one session of each pair (session_status_id 4), refunding it if it was paid';
+    END IF;
+END $$;
+
+-- A professional can't have two non cancelled sessions (session_status_id 4) whose time windows overlap.
+-- Sessions stored without a duration are considered to last 60 minutes, session dates are stored in UTC.
+ALTER TABLE sessions ADD CONSTRAINT sessions_professional_no_overlap EXCLUDE USING gist (
+    professional_id WITH =,
+    tstzrange(
+        session_date AT TIME ZONE 'UTC',
+        (session_date + make_interval(mins => COALESCE(session_duration, 60))) AT TIME ZONE 'UTC',
+        '[)'
+    ) WITH &&
+) WHERE (session_status_id <> 4);
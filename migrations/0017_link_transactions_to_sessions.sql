@@ -0,0 +1,7 @@
+-- Links a checkout transaction with the session it pays, NULL for the payments not tied to a booking
+ALTER TABLE transactions ADD COLUMN booked_session_id UUID REFERENCES sessions(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_booked_session_id ON transactions(booked_session_id);
+
+-- Stripe checkout sessions are looked up by id on every payment confirmation
+CREATE UNIQUE INDEX idx_transactions_session_id ON transactions(session_id);
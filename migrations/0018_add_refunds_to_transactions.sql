@@ -0,0 +1,2 @@
+-- Amount given back to the customer (cents), a transaction becomes refunded or partially_refunded when this is > 0
+ALTER TABLE transactions ADD COLUMN refunded_amount BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,52 @@
+-- Billing details of a user, copied into the invoices when they are issued
+CREATE TABLE billing_details (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    legal_name VARCHAR(150) NOT NULL,
+    tax_id VARCHAR(20) NOT NULL, -- NIF/NIE
+    address VARCHAR(255) NOT NULL,
+    postal_code VARCHAR(20) NOT NULL,
+    city VARCHAR(100) NOT NULL,
+    country CHAR(2) NOT NULL DEFAULT 'ES',
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Last number issued on each fiscal year, the row is locked while an invoice is being issued so numbers are gap-free
+CREATE TABLE invoice_series (
+    fiscal_year INTEGER PRIMARY KEY,
+    last_number INTEGER NOT NULL
+);
+
+-- Invoices are never updated nor deleted, the parties are a snapshot of when they were issued
+CREATE TABLE invoices (
+    id UUID PRIMARY KEY,
+    transaction_id UUID NOT NULL UNIQUE REFERENCES transactions(id),
+    session_id UUID REFERENCES sessions(id) ON DELETE SET NULL,
+    patient_id UUID NOT NULL,
+    professional_id UUID NOT NULL,
+    fiscal_year INTEGER NOT NULL,
+    number INTEGER NOT NULL,
+    issued_at TIMESTAMP NOT NULL,
+    issuer_name VARCHAR(150) NOT NULL,
+    issuer_tax_id VARCHAR(20),
+    issuer_address VARCHAR(400),
+    recipient_name VARCHAR(150) NOT NULL,
+    recipient_tax_id VARCHAR(20),
+    recipient_address VARCHAR(400),
+    currency VARCHAR(3) NOT NULL,
+    UNIQUE (fiscal_year, number)
+);
+
+CREATE INDEX idx_invoices_patient_id ON invoices(patient_id);
+CREATE INDEX idx_invoices_professional_id ON invoices(professional_id);
+
+CREATE TABLE invoice_lines (
+    id UUID PRIMARY KEY,
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    description VARCHAR(255) NOT NULL,
+    quantity INTEGER NOT NULL,
+    unit_amount BIGINT NOT NULL, -- cents, VAT excluded
+    vat_rate INTEGER NOT NULL, -- percentage
+    vat_exemption VARCHAR(255), -- legal reason when the line is exempt
+    UNIQUE (invoice_id, position)
+);
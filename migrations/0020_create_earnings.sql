@@ -0,0 +1,21 @@
+-- Ledger of what each professional earns from the payments, rows are never updated except to mark them as paid out
+CREATE TABLE earnings (
+    id UUID PRIMARY KEY,
+    transaction_id UUID NOT NULL REFERENCES transactions(id),
+    session_id UUID REFERENCES sessions(id) ON DELETE SET NULL,
+    professional_id UUID NOT NULL REFERENCES professionals(id),
+    kind VARCHAR(20) NOT NULL, -- 'session' for a payment, 'refund' for the part given back to the patient
+    gross_amount BIGINT NOT NULL, -- cents, negative for refunds
+    commission_percent BIGINT NOT NULL, -- platform commission when the payment was recorded
+    commission_amount BIGINT NOT NULL,
+    net_amount BIGINT NOT NULL, -- owed to the professional
+    currency VARCHAR(3) NOT NULL,
+    earned_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    paid_out_at TIMESTAMP -- NULL until the professional has been paid
+);
+
+-- A payment is attributed only once, even if its confirmation is delivered more than once
+CREATE UNIQUE INDEX idx_earnings_session_transaction_id ON earnings(transaction_id) WHERE kind = 'session';
+
+CREATE INDEX idx_earnings_professional_id_earned_at ON earnings(professional_id, earned_at);
+CREATE INDEX idx_earnings_pending ON earnings(earned_at) WHERE paid_out_at IS NULL;
@@ -0,0 +1,5 @@
+-- Provider that created the videocall link of the session, NULL for the links set by hand
+ALTER TABLE sessions ADD COLUMN videocall_provider VARCHAR(20);
+
+-- Before the providers were configurable every generated link was a Whereby one
+UPDATE sessions SET videocall_provider = 'whereby' WHERE videocall_url LIKE '%whereby.com%';
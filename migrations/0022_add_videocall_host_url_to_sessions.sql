@@ -0,0 +1,5 @@
+-- Link with moderation rights for the professional, videocall_url is the one the patient joins with
+ALTER TABLE sessions ADD COLUMN videocall_host_url TEXT;
+
+-- Id of the meeting at the provider, to delete it when the session is cancelled or deleted
+ALTER TABLE sessions ADD COLUMN videocall_meeting_id VARCHAR(255);
@@ -0,0 +1,4 @@
+-- The same table holds the email verification and the password reset tokens, a token only works for its purpose
+ALTER TABLE user_tokens ADD COLUMN purpose VARCHAR(20) NOT NULL DEFAULT 'verification';
+
+CREATE INDEX idx_user_tokens_token ON user_tokens (token);
@@ -0,0 +1,2 @@
+-- An email change token carries the address it verifies, the user email is only swapped once the link is opened
+ALTER TABLE user_tokens ADD COLUMN new_email VARCHAR(100);
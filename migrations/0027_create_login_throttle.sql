@@ -0,0 +1,20 @@
+-- Consecutive failed logins by account (the email typed, even if no user has it) and by ip
+CREATE TABLE login_failures (
+    subject_kind VARCHAR(10) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    failures INTEGER NOT NULL,
+    last_failed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (subject_kind, subject)
+);
+
+-- Every lockout is kept for the admins
+CREATE TABLE login_lockouts (
+    id UUID PRIMARY KEY,
+    subject_kind VARCHAR(10) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    failures INTEGER NOT NULL,
+    locked_until TIMESTAMP NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_login_lockouts_created_at ON login_lockouts (created_at);
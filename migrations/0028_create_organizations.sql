@@ -0,0 +1,45 @@
+-- Clinics sharing the deployment, their data is only visible to their members
+CREATE TABLE organizations (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A user is a member of a single organization, the one its tokens are scoped to
+CREATE TABLE organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (organization_id, user_id)
+);
+
+-- Everything created before belongs to the default organization
+INSERT INTO organizations (id, name) VALUES ('00000000-0000-0000-0000-000000000001', 'Default');
+
+INSERT INTO organization_members (organization_id, user_id)
+SELECT '00000000-0000-0000-0000-000000000001', id FROM users;
+
+ALTER TABLE professionals ADD COLUMN organization_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE professionals ALTER COLUMN organization_id DROP DEFAULT;
+
+ALTER TABLE patients ADD COLUMN organization_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE patients ALTER COLUMN organization_id DROP DEFAULT;
+
+ALTER TABLE sessions ADD COLUMN organization_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE sessions ALTER COLUMN organization_id DROP DEFAULT;
+
+ALTER TABLE session_types ADD COLUMN organization_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE session_types ALTER COLUMN organization_id DROP DEFAULT;
+
+ALTER TABLE blog_posts ADD COLUMN organization_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE blog_posts ALTER COLUMN organization_id DROP DEFAULT;
+
+-- Every clinic has its own blog
+ALTER TABLE blog_posts DROP CONSTRAINT blog_posts_slug_key;
+ALTER TABLE blog_posts ADD CONSTRAINT blog_posts_organization_id_slug_key UNIQUE (organization_id, slug);
+
+CREATE INDEX idx_professionals_organization_id ON professionals(organization_id);
+CREATE INDEX idx_patients_organization_id ON patients(organization_id);
+CREATE INDEX idx_sessions_organization_id ON sessions(organization_id);
+CREATE INDEX idx_session_types_organization_id ON session_types(organization_id);
+CREATE INDEX idx_blog_posts_organization_id ON blog_posts(organization_id);
@@ -0,0 +1,31 @@
+-- Who read or changed clinical records, rows are only ever inserted
+CREATE TABLE audit_events (
+    id UUID PRIMARY KEY,
+    organization_id UUID REFERENCES organizations(id),
+    actor_user_id UUID NOT NULL, -- no foreign key, the events outlive the users
+    actor_role_id INTEGER NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    resource_type VARCHAR(32) NOT NULL,
+    resource_id UUID NOT NULL,
+    ip VARCHAR(45),
+    request_id UUID,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_audit_events_resource ON audit_events (resource_type, resource_id, created_at);
+CREATE INDEX idx_audit_events_actor ON audit_events (actor_user_id, created_at);
+CREATE INDEX idx_audit_events_organization_id ON audit_events (organization_id, created_at);
+
+CREATE FUNCTION audit_events_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_append_only
+BEFORE UPDATE OR DELETE ON audit_events
+FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
+
+CREATE TRIGGER audit_events_no_truncate
+BEFORE TRUNCATE ON audit_events
+FOR EACH STATEMENT EXECUTE FUNCTION audit_events_append_only();
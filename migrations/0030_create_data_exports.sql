@@ -0,0 +1,14 @@
+-- Archives with a copy of the personal data of a user, generated in the background on request and
+-- downloadable by the user until they expire
+CREATE TABLE data_exports (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL,
+    archive TEXT, -- the zip, encrypted like the clinical fields, set once ready
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    expires_at TIMESTAMP
+);
+
+CREATE INDEX idx_data_exports_user_id ON data_exports (user_id, created_at);
+CREATE INDEX idx_data_exports_expires_at ON data_exports (expires_at);
@@ -0,0 +1,16 @@
+-- Users are pseudonymized instead of deleted, their patient row must always point to an existing user.
+-- Patients left pointing to users that no longer exist keep their clinical record without a user
+UPDATE patients SET user_id = NULL WHERE user_id IS NOT NULL AND user_id NOT IN (SELECT id FROM users);
+
+ALTER TABLE patients ADD CONSTRAINT patients_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);
+
+-- Accounts waiting to be erased once their grace period is over, the row stays as the record of the erasure
+CREATE TABLE account_deletions (
+    user_id UUID PRIMARY KEY REFERENCES users(id),
+    requested_by UUID NOT NULL, -- the user itself, or the admin that erased it right away
+    requested_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    scheduled_for TIMESTAMP NOT NULL,
+    completed_at TIMESTAMP
+);
+
+CREATE INDEX idx_account_deletions_scheduled_for ON account_deletions (scheduled_for) WHERE completed_at IS NULL;
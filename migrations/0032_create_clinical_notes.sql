@@ -0,0 +1,65 @@
+-- Notes written by the professional about a session, their content lives in the versions
+CREATE TABLE clinical_notes (
+    id UUID PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES sessions(id), -- no cascade, the clinical record outlives the session rows
+    organization_id UUID NOT NULL REFERENCES organizations(id),
+    author_user_id UUID NOT NULL, -- no foreign key, the notes outlive the users
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_clinical_notes_session_id ON clinical_notes (session_id, created_at);
+CREATE INDEX idx_clinical_notes_organization_id ON clinical_notes (organization_id);
+
+-- Every edit of a note adds a version, the latest one is the current content
+CREATE TABLE clinical_note_versions (
+    id UUID PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES clinical_notes(id),
+    version INTEGER NOT NULL,
+    visibility VARCHAR(16) NOT NULL,
+    subjective TEXT, -- the SOAP sections are encrypted
+    objective TEXT,
+    assessment TEXT,
+    plan TEXT,
+    edited_by UUID NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (note_id, version)
+);
+
+CREATE FUNCTION clinical_notes_immutable() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION '% is append only', TG_TABLE_NAME;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER clinical_notes_immutable
+BEFORE UPDATE OR DELETE ON clinical_notes
+FOR EACH ROW EXECUTE FUNCTION clinical_notes_immutable();
+
+CREATE TRIGGER clinical_notes_no_truncate
+BEFORE TRUNCATE ON clinical_notes
+FOR EACH STATEMENT EXECUTE FUNCTION clinical_notes_immutable();
+
+-- Rewrapping the data keys after a master key rotation rewrites the encrypted sections, nothing else of a version changes
+CREATE FUNCTION clinical_note_versions_immutable() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND NEW.id = OLD.id
+        AND NEW.note_id = OLD.note_id
+        AND NEW.version = OLD.version
+        AND NEW.visibility = OLD.visibility
+        AND NEW.edited_by = OLD.edited_by
+        AND NEW.created_at = OLD.created_at THEN
+        RETURN NEW;
+    END IF;
+
+    RAISE EXCEPTION 'clinical_note_versions is append only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER clinical_note_versions_immutable
+BEFORE UPDATE OR DELETE ON clinical_note_versions
+FOR EACH ROW EXECUTE FUNCTION clinical_note_versions_immutable();
+
+CREATE TRIGGER clinical_note_versions_no_truncate
+BEFORE TRUNCATE ON clinical_note_versions
+FOR EACH STATEMENT EXECUTE FUNCTION clinical_notes_immutable();
@@ -0,0 +1,27 @@
+-- Ciphertext of an encrypted section, `enc1:key_id:wrapped data key:ciphertext`. Values that aren't encrypted are
+-- returned whole, the versions are always written encrypted so there is nothing to rewrap in them
+CREATE FUNCTION clinical_note_section_ciphertext(stored TEXT) RETURNS TEXT AS $$
+    SELECT CASE WHEN stored LIKE 'enc1:%' THEN split_part(stored, ':', 4) ELSE stored END;
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Rewrapping the data keys after a master key rotation only changes the key id and the wrapped data key of the
+-- sections. Their ciphertext, and so what the note says, can't be changed once written
+CREATE OR REPLACE FUNCTION clinical_note_versions_immutable() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND NEW.id = OLD.id
+        AND NEW.note_id = OLD.note_id
+        AND NEW.version = OLD.version
+        AND NEW.visibility = OLD.visibility
+        AND NEW.edited_by IS NOT DISTINCT FROM OLD.edited_by
+        AND NEW.created_at = OLD.created_at
+        AND clinical_note_section_ciphertext(NEW.subjective) IS NOT DISTINCT FROM clinical_note_section_ciphertext(OLD.subjective)
+        AND clinical_note_section_ciphertext(NEW.objective) IS NOT DISTINCT FROM clinical_note_section_ciphertext(OLD.objective)
+        AND clinical_note_section_ciphertext(NEW.assessment) IS NOT DISTINCT FROM clinical_note_section_ciphertext(OLD.assessment)
+        AND clinical_note_section_ciphertext(NEW.plan) IS NOT DISTINCT FROM clinical_note_section_ciphertext(OLD.plan) THEN
+        RETURN NEW;
+    END IF;
+
+    RAISE EXCEPTION 'clinical_note_versions is append only';
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,20 @@
+-- The notes moved from the free text notes of the sessions are marked as such. The ones of a professional without a
+-- user have no author, they were credited to the nil uuid before
+ALTER TABLE clinical_notes ADD COLUMN legacy BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE clinical_notes ALTER COLUMN author_user_id DROP NOT NULL;
+ALTER TABLE clinical_note_versions ALTER COLUMN edited_by DROP NOT NULL;
+
+-- The notes are append only, the triggers are only lifted for this fix
+ALTER TABLE clinical_notes DISABLE TRIGGER clinical_notes_immutable;
+ALTER TABLE clinical_note_versions DISABLE TRIGGER clinical_note_versions_immutable;
+
+UPDATE clinical_notes
+SET author_user_id = NULL, legacy = TRUE
+WHERE author_user_id = '00000000-0000-0000-0000-000000000000';
+
+UPDATE clinical_note_versions
+SET edited_by = NULL
+WHERE edited_by = '00000000-0000-0000-0000-000000000000';
+
+ALTER TABLE clinical_notes ENABLE TRIGGER clinical_notes_immutable;
+ALTER TABLE clinical_note_versions ENABLE TRIGGER clinical_note_versions_immutable;
@@ -0,0 +1,2 @@
+-- Carried by the access tokens, bumping it makes every access token already issued to the user stop working
+ALTER TABLE users ADD COLUMN credentials_version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,20 @@
+-- Every professional issues its own invoices, so each of them numbers them on its own series. The series of each
+-- professional carries on from the last number it already issued
+CREATE TABLE invoice_series_by_issuer (
+    professional_id UUID NOT NULL,
+    fiscal_year INTEGER NOT NULL,
+    last_number INTEGER NOT NULL,
+    PRIMARY KEY (professional_id, fiscal_year)
+);
+
+INSERT INTO invoice_series_by_issuer (professional_id, fiscal_year, last_number)
+SELECT professional_id, fiscal_year, MAX(number)
+FROM invoices
+GROUP BY professional_id, fiscal_year;
+
+DROP TABLE invoice_series;
+ALTER TABLE invoice_series_by_issuer RENAME TO invoice_series;
+ALTER TABLE invoice_series RENAME CONSTRAINT invoice_series_by_issuer_pkey TO invoice_series_pkey;
+
+ALTER TABLE invoices DROP CONSTRAINT invoices_fiscal_year_number_key;
+ALTER TABLE invoices ADD CONSTRAINT invoices_professional_id_fiscal_year_number_key UNIQUE (professional_id, fiscal_year, number);
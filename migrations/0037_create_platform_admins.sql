@@ -0,0 +1,6 @@
+-- Operators of the platform, the only ones who can create clinics. Clinic admins are per organization and can't.
+-- Granted by hand, no endpoint adds or removes them
+CREATE TABLE platform_admins (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use aes_gcm::{
+    AeadCore, Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, OsRng, Payload},
+};
+
+use crate::app_error::{AppError, AppResult};
+
+/// Marks the values written by this version of the format, anything else is legacy plaintext
+const PREFIX: &str = "enc1";
+const NONCE_LEN: usize = 12;
+
+/// Envelope encryption of the sensitive columns. Every value gets its own data key, stored next to the value
+/// wrapped by a master key from the config. Rotating the master key only needs the data keys to be rewrapped
+pub struct FieldCipher {
+    current_key_id: String,
+    master_keys: HashMap<String, Aes256Gcm>,
+}
+
+impl FieldCipher {
+    /// Parses `id:hex_key` pairs separated by commas, the first one encrypts and every one decrypts
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let mut current_key_id = None;
+        let mut master_keys = HashMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (id, hex_key) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Encryption key {} is not id:hex_key", entry))?;
+
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(format!("Encryption key id {} must be alphanumeric", id));
+            }
+
+            let key = hex::decode(hex_key).map_err(|_| format!("Encryption key {} is not hex", id))?;
+            let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| format!("Encryption key {} is not 32 bytes", id))?;
+
+            if master_keys.insert(id.to_string(), cipher).is_some() {
+                return Err(format!("Encryption key {} is repeated", id));
+            }
+
+            current_key_id.get_or_insert_with(|| id.to_string());
+        }
+
+        Ok(Self {
+            current_key_id: current_key_id.ok_or("No encryption key")?,
+            master_keys,
+        })
+    }
+
+    /// The context (column and row) is authenticated, a value copied to another row doesn't decrypt
+    pub fn encrypt(&self, plaintext: Option<&str>, context: &str) -> AppResult<Option<String>> {
+        plaintext.map(|plaintext| self.encrypt_bytes(plaintext.as_bytes(), context)).transpose()
+    }
+
+    pub fn decrypt(&self, stored: Option<String>, context: &str) -> AppResult<Option<String>> {
+        let Some(stored) = stored else {
+            return Ok(None);
+        };
+
+        if EncryptedField::parse(&stored)?.is_none() {
+            return Ok(Some(stored));
+        }
+
+        String::from_utf8(self.decrypt_bytes(&stored, context)?)
+            .map(Some)
+            .map_err(|_| AppError::Internal("Decrypted field is not utf-8".into()))
+    }
+
+    /// Same format as the text fields, for binary values such as the data export archives
+    pub fn encrypt_bytes(&self, plaintext: &[u8], context: &str) -> AppResult<String> {
+        let data_key = Aes256Gcm::generate_key(&mut OsRng);
+        let data_cipher = Aes256Gcm::new(&data_key);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = data_cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: context.as_bytes() })
+            .map_err(|_| AppError::Internal("Field encryption failed".into()))?;
+
+        let wrapped_key = self.wrap(&self.current_key_id, &data_key, context)?;
+
+        Ok(format!(
+            "{}:{}:{}:{}",
+            PREFIX,
+            self.current_key_id,
+            wrapped_key,
+            hex::encode([nonce.as_slice(), &ciphertext].concat())
+        ))
+    }
+
+    /// Unlike decrypt, a value that isn't encrypted is an error
+    pub fn decrypt_bytes(&self, stored: &str, context: &str) -> AppResult<Vec<u8>> {
+        let parts = EncryptedField::parse(stored)?
+            .ok_or_else(|| AppError::Internal("Field is not encrypted".into()))?;
+
+        let data_key = self.unwrap(parts.key_id, parts.wrapped_key, context)?;
+        let (nonce, ciphertext) = split_nonce(parts.ciphertext)?;
+
+        Aes256Gcm::new_from_slice(&data_key)
+            .map_err(|_| AppError::Internal("Invalid data key".into()))?
+            .decrypt(&nonce, Payload { msg: &ciphertext, aad: context.as_bytes() })
+            .map_err(|_| AppError::Internal("Field decryption failed".into()))
+    }
+
+    /// Whether the value is plaintext or its data key is wrapped by an older master key
+    pub fn is_outdated(&self, stored: &str) -> bool {
+        !stored.starts_with(&self.current_prefix())
+    }
+
+    /// Start of every value wrapped by the current master key
+    pub fn current_prefix(&self) -> String {
+        format!("{}:{}:", PREFIX, self.current_key_id)
+    }
+
+    /// Wraps the data key of the value with the current master key, the data itself is left as it is.
+    /// Plaintext values are encrypted
+    pub fn rewrap(&self, stored: &str, context: &str) -> AppResult<String> {
+        let Some(parts) = EncryptedField::parse(stored)? else {
+            return self.encrypt_bytes(stored.as_bytes(), context);
+        };
+
+        let data_key = self.unwrap(parts.key_id, parts.wrapped_key, context)?;
+        let wrapped_key = self.wrap(&self.current_key_id, &data_key, context)?;
+
+        Ok(format!("{}:{}:{}:{}", PREFIX, self.current_key_id, wrapped_key, parts.ciphertext))
+    }
+
+    fn master_key(&self, key_id: &str) -> AppResult<&Aes256Gcm> {
+        self.master_keys
+            .get(key_id)
+            .ok_or_else(|| AppError::Internal(format!("Unknown encryption key {}", key_id)))
+    }
+
+    fn wrap(&self, key_id: &str, data_key: &[u8], context: &str) -> AppResult<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped = self
+            .master_key(key_id)?
+            .encrypt(&nonce, Payload { msg: data_key, aad: context.as_bytes() })
+            .map_err(|_| AppError::Internal("Data key wrapping failed".into()))?;
+
+        Ok(hex::encode([nonce.as_slice(), &wrapped].concat()))
+    }
+
+    fn unwrap(&self, key_id: &str, wrapped_key: &str, context: &str) -> AppResult<Vec<u8>> {
+        let (nonce, wrapped) = split_nonce(wrapped_key)?;
+
+        self.master_key(key_id)?
+            .decrypt(&nonce, Payload { msg: &wrapped, aad: context.as_bytes() })
+            .map_err(|_| AppError::Internal("Data key unwrapping failed".into()))
+    }
+}
+
+/// `enc1:key_id:hex(nonce + wrapped data key):hex(nonce + ciphertext)`
+struct EncryptedField<'a> {
+    key_id: &'a str,
+    wrapped_key: &'a str,
+    ciphertext: &'a str,
+}
+
+impl<'a> EncryptedField<'a> {
+    /// None for the values stored before the encryption
+    fn parse(stored: &'a str) -> AppResult<Option<Self>> {
+        let Some(rest) = stored.strip_prefix(PREFIX).and_then(|rest| rest.strip_prefix(':')) else {
+            return Ok(None);
+        };
+
+        let mut parts = rest.splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(key_id), Some(wrapped_key), Some(ciphertext)) => Ok(Some(Self { key_id, wrapped_key, ciphertext })),
+            _ => Err(AppError::Internal("Malformed encrypted field".into())),
+        }
+    }
+}
+
+fn split_nonce(hex_value: &str) -> AppResult<(Nonce<<Aes256Gcm as AeadCore>::NonceSize>, Vec<u8>)> {
+    let bytes = hex::decode(hex_value).map_err(|_| AppError::Internal("Malformed encrypted field".into()))?;
+
+    if bytes.len() < NONCE_LEN {
+        return Err(AppError::Internal("Malformed encrypted field".into()));
+    }
+
+    let (nonce, rest) = bytes.split_at(NONCE_LEN);
+
+    Ok((Nonce::clone_from_slice(nonce), rest.to_vec()))
+}
+
+/// Authenticated context of a column of a row
+pub fn field_context(column: &str, id: &uuid::Uuid) -> String {
+    format!("{}:{}", column, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_KEY: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+    const NEW_KEY: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+
+    fn cipher(spec: &str) -> FieldCipher {
+        FieldCipher::from_spec(spec).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = cipher(&format!("v1:{}", OLD_KEY));
+
+        let stored = cipher.encrypt(Some("Anxiety"), "patients.medical_history:1").unwrap().unwrap();
+
+        assert!(stored.starts_with("enc1:v1:"));
+        assert!(!stored.contains("Anxiety"));
+        assert_eq!(
+            cipher.decrypt(Some(stored), "patients.medical_history:1").unwrap().as_deref(),
+            Some("Anxiety")
+        );
+        assert_eq!(cipher.encrypt(None, "patients.medical_history:1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_value_of_another_row_fails() {
+        let cipher = cipher(&format!("v1:{}", OLD_KEY));
+
+        let stored = cipher.encrypt(Some("Anxiety"), "patients.medical_history:1").unwrap();
+
+        assert!(cipher.decrypt(stored, "patients.medical_history:2").is_err());
+    }
+
+    #[test]
+    fn test_plaintext_is_read_as_is() {
+        let cipher = cipher(&format!("v1:{}", OLD_KEY));
+
+        assert_eq!(
+            cipher.decrypt(Some(String::from("Penicillin")), "patients.allergies:1").unwrap().as_deref(),
+            Some("Penicillin")
+        );
+        assert!(cipher.is_outdated("Penicillin"));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let old_cipher = cipher(&format!("v1:{}", OLD_KEY));
+        let stored = old_cipher.encrypt(Some("Sertraline"), "ctx").unwrap().unwrap();
+
+        let new_cipher = cipher(&format!("v2:{},v1:{}", NEW_KEY, OLD_KEY));
+        assert!(new_cipher.is_outdated(&stored));
+        assert_eq!(new_cipher.decrypt(Some(stored.clone()), "ctx").unwrap().as_deref(), Some("Sertraline"));
+
+        let rewrapped = new_cipher.rewrap(&stored, "ctx").unwrap();
+        assert!(!new_cipher.is_outdated(&rewrapped));
+        assert_eq!(rewrapped.rsplit(':').next(), stored.rsplit(':').next());
+
+        let only_new_cipher = cipher(&format!("v2:{}", NEW_KEY));
+        assert_eq!(only_new_cipher.decrypt(Some(rewrapped), "ctx").unwrap().as_deref(), Some("Sertraline"));
+        assert!(only_new_cipher.decrypt(Some(stored), "ctx").is_err());
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let cipher = cipher(&format!("v1:{}", OLD_KEY));
+
+        let stored = cipher.encrypt_bytes(&[0, 159, 146, 150], "data_exports.archive:1").unwrap();
+
+        assert_eq!(cipher.decrypt_bytes(&stored, "data_exports.archive:1").unwrap(), vec![0, 159, 146, 150]);
+        assert!(cipher.decrypt_bytes("PK", "data_exports.archive:1").is_err());
+    }
+
+    #[test]
+    fn test_invalid_spec() {
+        assert!(FieldCipher::from_spec("").is_err());
+        assert!(FieldCipher::from_spec("v1:abcd").is_err());
+        assert!(FieldCipher::from_spec(&format!("v%:{}", OLD_KEY)).is_err());
+        assert!(FieldCipher::from_spec(&format!("v1:{},v1:{}", OLD_KEY, NEW_KEY)).is_err());
+    }
+}
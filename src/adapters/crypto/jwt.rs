@@ -6,9 +6,10 @@ use crate::{
     infra::config::AppConfig,
     use_cases::{user::UserJwtService, user_token::UserTokenJwtService},
 };
-use chrono::{Duration, Utc};
+use chrono::Utc;
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Claims {
@@ -17,9 +18,22 @@ pub struct Claims {
     pub role: i32,
     pub verified: bool,
     pub needs_onboarding: bool,
+    pub org: String, // organization every query of the user is scoped to
+    #[serde(default)]
+    pub ver: i32, // credentials version of the user when the token was issued
     exp: usize,
 }
 
+/// Only proves the password was checked, it can't be used as a `Claims` token (the fields don't match)
+#[derive(Debug, Serialize, Deserialize)]
+struct PreAuthClaims {
+    sub: String,
+    purpose: String,
+    exp: usize,
+}
+
+const PRE_AUTH_PURPOSE: &str = "two_factor";
+
 pub struct JwtService {
     config: Arc<AppConfig>,
 }
@@ -31,7 +45,7 @@ impl JwtService {
 }
 
 impl UserJwtService for JwtService {
-    fn generate_token(&self, user: &User) -> AppResult<String> {
+    fn generate_token(&self, user: &User, organization_id: &Uuid, credentials_version: i32) -> AppResult<String> {
         let token = encode(
             &Header::default(),
             &Claims {
@@ -40,7 +54,9 @@ impl UserJwtService for JwtService {
                 role: user.role.to_id(),
                 verified: user.verified.unwrap_or(false),
                 needs_onboarding: user.needs_onboarding.unwrap_or(true),
-                exp: (Utc::now() + Duration::minutes(120)).timestamp() as usize,
+                org: organization_id.to_string(),
+                ver: credentials_version,
+                exp: (Utc::now() + self.config.access_token_ttl).timestamp() as usize,
             },
             &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
         )
@@ -67,6 +83,40 @@ impl UserJwtService for JwtService {
 
         Ok(result.claims)
     }
+
+    fn generate_pre_auth_token(&self, user_id: &Uuid) -> AppResult<String> {
+        encode(
+            &Header::default(),
+            &PreAuthClaims {
+                sub: user_id.to_string(),
+                purpose: PRE_AUTH_PURPOSE.to_string(),
+                exp: (Utc::now() + self.config.pre_auth_token_ttl).timestamp() as usize,
+            },
+            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
+        )
+        .map_err(|_| AppError::Internal("JWT Creation Failed".into()))
+    }
+
+    fn validate_pre_auth_token(&self, token: &str) -> AppResult<Uuid> {
+        let claims = decode::<PreAuthClaims>(
+            token,
+            &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|e| match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
+                AppError::Unauthorized("Pre auth token expired".into())
+            }
+            _ => AppError::Unauthorized("Invalid pre auth token".into()),
+        })?
+        .claims;
+
+        if claims.purpose != PRE_AUTH_PURPOSE {
+            return Err(AppError::Unauthorized("Invalid pre auth token".into()));
+        }
+
+        Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized("Invalid pre auth token".into()))
+    }
 }
 
 impl UserTokenJwtService for JwtService {
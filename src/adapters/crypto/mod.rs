@@ -1,2 +1,4 @@
 pub mod argon2;
+pub mod field_cipher;
 pub mod jwt;
+pub mod totp;
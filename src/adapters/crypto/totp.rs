@@ -0,0 +1,91 @@
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    app_error::{AppError, AppResult},
+    use_cases::user::UserTotpService,
+};
+
+/// RFC 6238 defaults, the ones every authenticator app supports
+const DIGITS: usize = 6;
+const STEP_SECS: u64 = 30;
+/// Also accepts the codes of the previous and next step, clocks drift
+const SKEW: u8 = 1;
+
+pub struct TotpService {
+    issuer: String, // name the authenticator apps show next to the code
+}
+
+impl TotpService {
+    pub fn new(issuer: String) -> Self {
+        Self { issuer }
+    }
+
+    fn totp(&self, secret: &str, account_name: &str) -> AppResult<TOTP> {
+        let secret = Secret::Encoded(secret.to_string())
+            .to_bytes()
+            .map_err(|_| AppError::Internal("Invalid TOTP secret".into()))?;
+
+        TOTP::new(
+            Algorithm::SHA1,
+            DIGITS,
+            SKEW,
+            STEP_SECS,
+            secret,
+            Some(self.issuer.clone()),
+            account_name.to_string(),
+        )
+        .map_err(|e| AppError::Internal(format!("TOTP creation failed: {}", e)))
+    }
+}
+
+impl UserTotpService for TotpService {
+    fn generate_secret(&self) -> String {
+        Secret::generate_secret().to_encoded().to_string()
+    }
+
+    fn provisioning_uri(&self, secret: &str, account_name: &str) -> AppResult<String> {
+        Ok(self.totp(secret, account_name)?.get_url())
+    }
+
+    fn verify_code(&self, secret: &str, code: &str) -> AppResult<bool> {
+        self.totp(secret, "")?
+            .check_current(code.trim())
+            .map_err(|_| AppError::Internal("System time before unix epoch".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> TotpService {
+        TotpService::new(String::from("Mipsicored"))
+    }
+
+    #[test]
+    fn test_verify_current_code() {
+        let service = service();
+        let secret = service.generate_secret();
+        let code = service.totp(&secret, "").unwrap().generate_current().unwrap();
+
+        assert!(service.verify_code(&secret, &code).unwrap());
+        assert!(!service.verify_code(&secret, "not a code").unwrap());
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        let service = service();
+        let secret = service.generate_secret();
+
+        let uri = service.provisioning_uri(&secret, "doctor@example.com").unwrap();
+
+        assert!(uri.starts_with("otpauth://totp/Mipsicored:doctor%40example.com?"));
+        assert!(uri.contains(&format!("secret={}", secret)));
+        assert!(uri.contains("issuer=Mipsicored"));
+    }
+
+    #[test]
+    fn test_invalid_secret_fails() {
+        assert!(matches!(service().verify_code("not base32!", "123456"), Err(AppError::Internal(_))));
+    }
+}
@@ -6,7 +6,7 @@ use resend_rs::{Resend, types::CreateEmailBaseOptions};
 use crate::{
     app_error::{AppError, AppResult},
     infra::config::AppConfig,
-    use_cases::user_token::UserTokenEmailService,
+    use_cases::{cancellation::CancellationEmailService, user_token::UserTokenEmailService},
 };
 
 pub struct EmailService {
@@ -48,6 +48,83 @@ impl UserTokenEmailService for EmailService {
 
         Ok((self.config.resend_from_email.clone(), body))
     }
+
+    /// Returns the 'from' email and the email body
+    async fn send_password_reset_email(
+        &self,
+        to: &[String],
+        token: &str,
+    ) -> AppResult<(String, String)> {
+        let body = password_reset_email_html(&self.config.base_frontend_url, token);
+
+        let email = CreateEmailBaseOptions::new(
+            &self.config.resend_from_email,
+            to,
+            "Reset your Password",
+        )
+        .with_html(&body);
+
+        self.client
+            .emails
+            .send(email)
+            .await
+            .map_err(|e| AppError::Internal(format!("Error sending mail: {}", e)))?;
+
+        Ok((self.config.resend_from_email.clone(), body))
+    }
+
+    /// Returns the 'from' email and the email body
+    async fn send_email_change_email(
+        &self,
+        to: &[String],
+        token: &str,
+    ) -> AppResult<(String, String)> {
+        let body = email_change_email_html(&self.config.base_frontend_url, token);
+
+        let email = CreateEmailBaseOptions::new(
+            &self.config.resend_from_email,
+            to,
+            "Confirm your new Email",
+        )
+        .with_html(&body);
+
+        self.client
+            .emails
+            .send(email)
+            .await
+            .map_err(|e| AppError::Internal(format!("Error sending mail: {}", e)))?;
+
+        Ok((self.config.resend_from_email.clone(), body))
+    }
+}
+
+#[async_trait]
+impl CancellationEmailService for EmailService {
+    /// Returns the 'from' email and the email body
+    async fn send_cancellation_email(
+        &self,
+        to: &[String],
+        session_date: Option<chrono::NaiveDateTime>,
+        refunded_amount: i64,
+        currency: Option<&str>,
+    ) -> AppResult<(String, String)> {
+        let body = cancellation_email_html(session_date, refunded_amount, currency);
+
+        let email = CreateEmailBaseOptions::new(
+            &self.config.resend_from_email,
+            to,
+            "Session Cancelled",
+        )
+        .with_html(&body);
+
+        self.client
+            .emails
+            .send(email)
+            .await
+            .map_err(|e| AppError::Internal(format!("Error sending mail: {}", e)))?;
+
+        Ok((self.config.resend_from_email.clone(), body))
+    }
 }
 
 fn verification_email_html(base_frontend_url: &str, token: &str) -> String {
@@ -100,3 +177,165 @@ fn verification_email_html(base_frontend_url: &str, token: &str) -> String {
         verify_url = verify_url
     )
 }
+
+fn password_reset_email_html(base_frontend_url: &str, token: &str) -> String {
+    let reset_url = format!("{}/reset-password?token={}", base_frontend_url, token);
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="en">
+        <head>
+            <meta charset="UTF-8" />
+            <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+            <title>Reset Your Password</title>
+        </head>
+        <body style="font-family: Arial, sans-serif; background-color: #f9f9f9; margin:0; padding:0;">
+            <table width="100%" cellpadding="0" cellspacing="0" style="background-color:#f9f9f9; padding: 40px 0;">
+                <tr>
+                    <td align="center">
+                        <table width="600" cellpadding="0" cellspacing="0" style="background:#ffffff; border-radius:8px; padding:40px; box-shadow:0 2px 6px rgba(0,0,0,0.1);">
+                            <tr>
+                                <td align="center" style="font-size:24px; font-weight:bold; color:#333333; padding-bottom:20px;">
+                                    Reset Your Password
+                                </td>
+                            </tr>
+                            <tr>
+                                <td style="font-size:16px; color:#555555; text-align:center; padding-bottom:30px;">
+                                    We received a request to reset your password. Click the button below to choose a new one. If you didn't ask for it, you can ignore this email.
+                                </td>
+                            </tr>
+                            <tr>
+                                <td align="center" style="padding-bottom:30px;">
+                                    <a href="{reset_url}" style="background-color:#4CAF50; color:#ffffff; text-decoration:none; padding:14px 28px; border-radius:6px; font-size:16px; display:inline-block;">
+                                        Reset Password
+                                    </a>
+                                </td>
+                            </tr>
+                            <tr>
+                                <td style="font-size:14px; color:#999999; text-align:center;">
+                                    If the button doesn’t work, copy and paste this link into your browser:<br/>
+                                    <a href="{reset_url}" style="color:#4CAF50; word-break:break-all;">{reset_url}</a>
+                                </td>
+                            </tr>
+                        </table>
+                    </td>
+                </tr>
+            </table>
+        </body>
+        </html>
+        "#,
+        reset_url = reset_url
+    )
+}
+
+fn email_change_email_html(base_frontend_url: &str, token: &str) -> String {
+    let confirm_url = format!("{}/confirm-email?token={}", base_frontend_url, token);
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="en">
+        <head>
+            <meta charset="UTF-8" />
+            <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+            <title>Confirm Your New Email</title>
+        </head>
+        <body style="font-family: Arial, sans-serif; background-color: #f9f9f9; margin:0; padding:0;">
+            <table width="100%" cellpadding="0" cellspacing="0" style="background-color:#f9f9f9; padding: 40px 0;">
+                <tr>
+                    <td align="center">
+                        <table width="600" cellpadding="0" cellspacing="0" style="background:#ffffff; border-radius:8px; padding:40px; box-shadow:0 2px 6px rgba(0,0,0,0.1);">
+                            <tr>
+                                <td align="center" style="font-size:24px; font-weight:bold; color:#333333; padding-bottom:20px;">
+                                    Confirm Your New Email
+                                </td>
+                            </tr>
+                            <tr>
+                                <td style="font-size:16px; color:#555555; text-align:center; padding-bottom:30px;">
+                                    We received a request to use this address for your account. Please confirm it by clicking the button below. If you didn't ask for it, you can ignore this email.
+                                </td>
+                            </tr>
+                            <tr>
+                                <td align="center" style="padding-bottom:30px;">
+                                    <a href="{confirm_url}" style="background-color:#4CAF50; color:#ffffff; text-decoration:none; padding:14px 28px; border-radius:6px; font-size:16px; display:inline-block;">
+                                        Confirm Email
+                                    </a>
+                                </td>
+                            </tr>
+                            <tr>
+                                <td style="font-size:14px; color:#999999; text-align:center;">
+                                    If the button doesn’t work, copy and paste this link into your browser:<br/>
+                                    <a href="{confirm_url}" style="color:#4CAF50; word-break:break-all;">{confirm_url}</a>
+                                </td>
+                            </tr>
+                        </table>
+                    </td>
+                </tr>
+            </table>
+        </body>
+        </html>
+        "#,
+        confirm_url = confirm_url
+    )
+}
+
+fn cancellation_email_html(
+    session_date: Option<chrono::NaiveDateTime>,
+    refunded_amount: i64,
+    currency: Option<&str>,
+) -> String {
+    let session_date = session_date
+        .map(|date| format!(" of {} (UTC)", date.format("%Y-%m-%d %H:%M")))
+        .unwrap_or_default();
+
+    let refund = if refunded_amount > 0 {
+        format!(
+            "A refund of {:.2} {} has been issued to the original payment method.",
+            refunded_amount as f64 / 100.0,
+            currency.unwrap_or_default().to_uppercase()
+        )
+    } else {
+        String::from("No refund applies to this cancellation.")
+    };
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="en">
+        <head>
+            <meta charset="UTF-8" />
+            <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+            <title>Session Cancelled</title>
+        </head>
+        <body style="font-family: Arial, sans-serif; background-color: #f9f9f9; margin:0; padding:0;">
+            <table width="100%" cellpadding="0" cellspacing="0" style="background-color:#f9f9f9; padding: 40px 0;">
+                <tr>
+                    <td align="center">
+                        <table width="600" cellpadding="0" cellspacing="0" style="background:#ffffff; border-radius:8px; padding:40px; box-shadow:0 2px 6px rgba(0,0,0,0.1);">
+                            <tr>
+                                <td align="center" style="font-size:24px; font-weight:bold; color:#333333; padding-bottom:20px;">
+                                    Session Cancelled
+                                </td>
+                            </tr>
+                            <tr>
+                                <td style="font-size:16px; color:#555555; text-align:center; padding-bottom:30px;">
+                                    The session{session_date} has been cancelled.
+                                </td>
+                            </tr>
+                            <tr>
+                                <td style="font-size:14px; color:#999999; text-align:center;">
+                                    {refund}
+                                </td>
+                            </tr>
+                        </table>
+                    </td>
+                </tr>
+            </table>
+        </body>
+        </html>
+        "#,
+        session_date = session_date,
+        refund = refund
+    )
+}
@@ -1,6 +1,6 @@
 use crate::app_error::AppError;
 use axum::{
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 
@@ -24,7 +24,15 @@ impl IntoResponse for AppError {
             }
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg).into_response(),
             AppError::ExternalServiceError(msg) => (StatusCode::BAD_GATEWAY, msg).into_response(),
+            AppError::Unavailable(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg).into_response(),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg).into_response(),
             AppError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "Unauthorized").into_response(),
+            AppError::TooManyRequests(retry_after_secs) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_secs.max(1).to_string())],
+                "Too many requests, try again later",
+            )
+                .into_response(),
         }
     }
 }
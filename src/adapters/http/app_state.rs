@@ -5,10 +5,19 @@ use axum::extract::FromRef;
 use crate::{
     infra::config::AppConfig,
     use_cases::{
+        account_deletion::AccountDeletionUseCases,
+        audit::AuditUseCases,
         blog_post::BlogPostUseCases,
+        cancellation::CancellationUseCases,
+        clinical_note::ClinicalNoteUseCases,
+        data_export::DataExportUseCases,
+        earning::EarningUseCases,
+        invoice::InvoiceUseCases,
+        organization::OrganizationUseCases,
         patient::PatientUseCases,
         payment::PaymentUseCases,
         professional::ProfessionalUseCases,
+        professional_availability::ProfessionalAvailabilityUseCases,
         professional_language::ProfessionalLanguageUseCases,
         professional_specialization::ProfessionalSpecializationUseCases, session::SessionUseCases,
         session_type::SessionTypeUseCases, user::UserUseCases, user_token::UserTokenUseCases,
@@ -23,11 +32,20 @@ pub struct AppState {
     pub patient_use_cases: Arc<PatientUseCases>,
     pub session_type_use_cases: Arc<SessionTypeUseCases>,
     pub session_use_cases: Arc<SessionUseCases>,
+    pub clinical_note_use_cases: Arc<ClinicalNoteUseCases>,
     pub professional_use_cases: Arc<ProfessionalUseCases>,
+    pub professional_availability_use_cases: Arc<ProfessionalAvailabilityUseCases>,
     pub professional_languages_use_cases: Arc<ProfessionalLanguageUseCases>,
     pub professional_specializations_use_cases: Arc<ProfessionalSpecializationUseCases>,
     pub blog_post_use_cases: Arc<BlogPostUseCases>,
     pub payment_use_cases: Arc<PaymentUseCases>,
+    pub cancellation_use_cases: Arc<CancellationUseCases>,
+    pub invoice_use_cases: Arc<InvoiceUseCases>,
+    pub earning_use_cases: Arc<EarningUseCases>,
+    pub organization_use_cases: Arc<OrganizationUseCases>,
+    pub audit_use_cases: Arc<AuditUseCases>,
+    pub data_export_use_cases: Arc<DataExportUseCases>,
+    pub account_deletion_use_cases: Arc<AccountDeletionUseCases>,
 }
 
 impl FromRef<AppState> for Arc<UserUseCases> {
@@ -54,6 +72,36 @@ impl FromRef<AppState> for Arc<SessionTypeUseCases> {
     }
 }
 
+impl FromRef<AppState> for Arc<OrganizationUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.organization_use_cases.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<AuditUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.audit_use_cases.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<ClinicalNoteUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.clinical_note_use_cases.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<DataExportUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.data_export_use_cases.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<AccountDeletionUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.account_deletion_use_cases.clone()
+    }
+}
+
 impl FromRef<AppState> for Arc<SessionUseCases> {
     fn from_ref(app_state: &AppState) -> Self {
         app_state.session_use_cases.clone()
@@ -66,6 +114,12 @@ impl FromRef<AppState> for Arc<ProfessionalUseCases> {
     }
 }
 
+impl FromRef<AppState> for Arc<ProfessionalAvailabilityUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.professional_availability_use_cases.clone()
+    }
+}
+
 impl FromRef<AppState> for Arc<ProfessionalLanguageUseCases> {
     fn from_ref(app_state: &AppState) -> Self {
         app_state.professional_languages_use_cases.clone()
@@ -91,3 +145,20 @@ impl FromRef<AppState> for Arc<PaymentUseCases> {
     }
 }
 
+impl FromRef<AppState> for Arc<CancellationUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.cancellation_use_cases.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<InvoiceUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.invoice_use_cases.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<EarningUseCases> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.earning_use_cases.clone()
+    }
+}
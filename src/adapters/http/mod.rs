@@ -1,3 +1,4 @@
 pub mod app_error_impl;
 pub mod app_state;
+pub mod rate_limit;
 pub mod routes;
@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::infra::config::AppConfig;
+
+/// Above this many tracked clients the ones with a full bucket are forgotten, they are the same as new ones
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Token bucket of a route group, every request takes a token and they refill continuously
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub capacity: u32, // requests allowed in a burst
+    pub per_minute: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Buckets of every client of a route group, kept in memory so each instance of the app limits on its own
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token of the client, fails with the time until the next one when the bucket is empty
+    pub fn acquire(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.limit.capacity);
+        let refill_per_sec = f64::from(self.limit.per_minute) / 60.0;
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_KEYS {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.updated_at).as_secs_f64() * refill_per_sec < capacity
+            });
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        if refill_per_sec <= 0.0 {
+            return Err(Duration::from_secs(60));
+        }
+
+        Err(Duration::from_secs_f64((1.0 - bucket.tokens) / refill_per_sec))
+    }
+}
+
+/// Groups of routes limited together, each one with its own limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitGroup {
+    Public,    // by ip
+    Protected, // by user
+    Auth,      // register and login, by ip
+    Email,     // routes that send an email, by user or by ip when public
+}
+
+/// The limiters of every group, shared by all the routes
+#[derive(Debug)]
+pub struct RateLimiters {
+    pub trust_proxy_headers: bool,
+    public: RateLimiter,
+    protected: RateLimiter,
+    auth: RateLimiter,
+    email: RateLimiter,
+}
+
+impl RateLimiters {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            trust_proxy_headers: config.trust_proxy_headers,
+            public: RateLimiter::new(RateLimit {
+                capacity: config.rate_limit_public,
+                per_minute: config.rate_limit_public_per_minute,
+            }),
+            protected: RateLimiter::new(RateLimit {
+                capacity: config.rate_limit_protected,
+                per_minute: config.rate_limit_protected_per_minute,
+            }),
+            auth: RateLimiter::new(RateLimit {
+                capacity: config.rate_limit_auth,
+                per_minute: config.rate_limit_auth_per_minute,
+            }),
+            email: RateLimiter::new(RateLimit {
+                capacity: config.rate_limit_email,
+                per_minute: config.rate_limit_email_per_minute,
+            }),
+        }
+    }
+
+    pub fn limiter(&self, group: RateLimitGroup) -> &RateLimiter {
+        match group {
+            RateLimitGroup::Public => &self.public,
+            RateLimitGroup::Protected => &self.protected,
+            RateLimitGroup::Auth => &self.auth,
+            RateLimitGroup::Email => &self.email,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(RateLimit {
+            capacity: 2,
+            per_minute: 6,
+        })
+    }
+
+    #[test]
+    fn allows_a_burst_up_to_the_capacity() {
+        let limiter = limiter();
+        let now = Instant::now();
+
+        assert!(limiter.acquire("client", now).is_ok());
+        assert!(limiter.acquire("client", now).is_ok());
+
+        let retry_after = limiter.acquire("client", now).unwrap_err();
+        assert_eq!(retry_after.as_secs(), 10);
+    }
+
+    #[test]
+    fn refills_over_time() {
+        let limiter = limiter();
+        let now = Instant::now();
+        limiter.acquire("client", now).unwrap();
+        limiter.acquire("client", now).unwrap();
+
+        assert!(limiter.acquire("client", now + Duration::from_secs(5)).is_err());
+        assert!(limiter.acquire("client", now + Duration::from_secs(10)).is_ok());
+        assert!(limiter.acquire("client", now + Duration::from_secs(10)).is_err());
+    }
+
+    #[test]
+    fn clients_have_their_own_bucket() {
+        let limiter = limiter();
+        let now = Instant::now();
+        limiter.acquire("client", now).unwrap();
+        limiter.acquire("client", now).unwrap();
+
+        assert!(limiter.acquire("client", now).is_err());
+        assert!(limiter.acquire("other_client", now).is_ok());
+    }
+}
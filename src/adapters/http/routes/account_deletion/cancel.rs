@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::AuthUser,
+    app_error::{AppError, AppResult},
+    use_cases::account_deletion::AccountDeletionUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountDeletionCancelResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/account-deletion/cancel", 
+    responses( 
+        (status = 200, description = "Deletion cancelled, the account is kept", body = AccountDeletionCancelResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No pending deletion"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Account Deletion",
+    summary = "Cancels the pending deletion of the account of the requesting user",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn cancel_account_deletion(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<AccountDeletionUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Cancel account deletion called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    use_cases
+        .cancel(&user_uuid)
+        .await?;
+
+    Ok((StatusCode::OK, Json(AccountDeletionCancelResponse { success: true })))
+}
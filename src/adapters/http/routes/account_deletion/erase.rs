@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::AuthUser,
+    app_error::{AppError, AppResult},
+    use_cases::account_deletion::AccountDeletionUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountEraseResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/account-deletion/{user_id}/erase", 
+    params(
+        ("user_id" = String, Path, description = "User to erase")
+    ),
+    responses( 
+        (status = 200, description = "Account erased", body = AccountEraseResponse),
+        (status = 400, description = "Invalid user id"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found in the organization"),
+        (status = 409, description = "Account already erased"),
+        (status = 500, description = "Internal server error or database error"),
+        (status = 502, description = "A future session couldn't be refunded, nothing was erased")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Account Deletion",
+    summary = "Erases an account right away",
+    description = "Skips the grace period, whether the user requested the deletion or not.\n\n**Required:** Verified Email + Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn erase_account(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<AccountDeletionUseCases>>,
+    Path(user_id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    info!("Erase account called");
+
+    let user_uuid = Uuid::parse_str(&user_id).map_err(|_| AppError::InvalidPayload)?;
+    let admin_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    use_cases
+        .erase_now(&user_uuid, &admin_uuid)
+        .await?;
+
+    Ok((StatusCode::OK, Json(AccountEraseResponse { success: true })))
+}
@@ -0,0 +1,67 @@
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::{
+        app_state::AppState,
+        rate_limit::RateLimitGroup,
+        routes::{
+            account_deletion::{
+                cancel::cancel_account_deletion, erase::erase_account, read_mine::read_my_account_deletion,
+                request::request_account_deletion,
+            },
+            auth_middleware, rate_limit, rate_limit_middleware, require_admin, require_role_middleware,
+            verified_middleware,
+        },
+    },
+    entities::account_deletion::AccountDeletion,
+};
+
+pub mod cancel;
+pub mod erase;
+pub mod read_mine;
+pub mod request;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct AccountDeletionResponse {
+    pub user_id: Uuid,
+    pub requested_by: Uuid,
+    pub requested_at: Option<chrono::NaiveDateTime>,
+    /// The account can be kept until then
+    pub scheduled_for: chrono::NaiveDateTime,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<AccountDeletion> for AccountDeletionResponse {
+    fn from(deletion: AccountDeletion) -> Self {
+        AccountDeletionResponse {
+            user_id: deletion.user_id,
+            requested_by: deletion.requested_by,
+            requested_at: deletion.requested_at,
+            scheduled_for: deletion.scheduled_for,
+            completed_at: deletion.completed_at,
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/request", post(request_account_deletion)) // Required: Verified Email
+        .route("/cancel", post(cancel_account_deletion)) // Required: Verified Email
+        .route("/mine", get(read_my_account_deletion)) // Required: Verified Email
+        .route(
+            "/{user_id}/erase", // Required: Verified Email + Admin Role
+            post(erase_account)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
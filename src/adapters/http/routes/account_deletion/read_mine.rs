@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, account_deletion::AccountDeletionResponse},
+    app_error::{AppError, AppResult},
+    use_cases::account_deletion::AccountDeletionUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountDeletionReadMineResponse {
+    /// None when no deletion was requested
+    data: Option<AccountDeletionResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/account-deletion/mine", 
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = AccountDeletionReadMineResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Account Deletion",
+    summary = "Retrieves the pending deletion of the account of the requesting user",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_my_account_deletion(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<AccountDeletionUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read my account deletion called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let deletion = use_cases
+        .read(&user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AccountDeletionReadMineResponse { success: true, data: deletion.map(Into::into) }),
+    ))
+}
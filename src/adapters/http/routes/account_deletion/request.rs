@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, account_deletion::AccountDeletionResponse},
+    app_error::{AppError, AppResult},
+    use_cases::account_deletion::AccountDeletionUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountDeletionRequestResponse {
+    data: AccountDeletionResponse,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/account-deletion/request", 
+    responses( 
+        (status = 202, description = "Deletion scheduled", body = AccountDeletionRequestResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Deletion already requested"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Account Deletion",
+    summary = "Requests the deletion of the account of the requesting user",
+    description = "The account is erased once the grace period is over, until then it works as usual and the deletion can be cancelled. Erasing it cancels the future sessions and removes or pseudonymizes the personal data, the minimum clinical record and the invoices are kept as the law requires.\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn request_account_deletion(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<AccountDeletionUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Request account deletion called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let deletion = use_cases
+        .request(&user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(AccountDeletionRequestResponse { success: true, data: deletion.into() }),
+    ))
+}
@@ -0,0 +1,26 @@
+use axum::{Router, middleware, routing::get};
+
+use crate::adapters::http::{
+    app_state::AppState,
+    rate_limit::RateLimitGroup,
+    routes::{
+        audit::read::read_audit_events, auth_middleware, rate_limit, rate_limit_middleware, require_admin,
+        require_role_middleware, verified_middleware,
+    },
+};
+
+pub mod read;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/events", // Required: Verified Email + Admin Role
+            get(read_audit_events)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
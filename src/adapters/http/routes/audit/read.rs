@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::audit_event::{AuditEvent, AuditEventFilter},
+    use_cases::audit::AuditUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+pub struct AuditEventsQuery {
+    actor_user_id: Option<String>,
+    /// create, read, update or delete
+    #[param(example = "read")]
+    action: Option<String>,
+    /// patient, session or clinical_note
+    #[param(example = "patient")]
+    resource_type: Option<String>,
+    resource_id: Option<String>,
+    #[param(example = "2025-01-01T00:00:00")]
+    from: Option<chrono::NaiveDateTime>,
+    to: Option<chrono::NaiveDateTime>,
+    /// 100 by default and up to 500
+    #[param(example = 100)]
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl AuditEventsQuery {
+    fn into_filter(self) -> AppResult<AuditEventFilter> {
+        let uuid = |value: Option<String>| {
+            value
+                .map(|value| Uuid::parse_str(&value).map_err(|_| AppError::InvalidPayload))
+                .transpose()
+        };
+
+        Ok(AuditEventFilter {
+            actor_user_id: uuid(self.actor_user_id)?,
+            action: self.action.map(|action| action.parse()).transpose().map_err(|_| AppError::InvalidPayload)?,
+            resource_type: self
+                .resource_type
+                .map(|resource_type| resource_type.parse())
+                .transpose()
+                .map_err(|_| AppError::InvalidPayload)?,
+            resource_id: uuid(self.resource_id)?,
+            from: self.from,
+            to: self.to,
+            limit: self.limit.unwrap_or(100),
+            offset: self.offset.unwrap_or(0),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditEventData {
+    id: Uuid,
+    actor_user_id: Uuid,
+    actor_role: String,
+    action: String,
+    resource_type: String,
+    resource_id: Uuid,
+    ip: Option<String>,
+    request_id: Option<Uuid>,
+    created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<AuditEvent> for AuditEventData {
+    fn from(event: AuditEvent) -> Self {
+        AuditEventData {
+            id: event.id,
+            actor_user_id: event.actor_user_id,
+            actor_role: event.actor_role.to_string(),
+            action: event.action.to_string(),
+            resource_type: event.resource_type.to_string(),
+            resource_id: event.resource_id,
+            ip: event.ip,
+            request_id: event.request_id,
+            created_at: event.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditEventsResponse {
+    success: bool,
+    data: Vec<AuditEventData>,
+}
+
+#[utoipa::path(get, path = "/api/audit/events", 
+    params(AuditEventsQuery),
+    responses( 
+        (status = 200, description = "Success", body = AuditEventsResponse),
+        (status = 400, description = "Invalid query"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Audit",
+    summary = "Get the audit events of the clinical records",
+    description = "Reads and changes of patients, sessions and clinical notes in the organization, most recent first. \n\n
+        **Required:** Verified Email + Admin Role"
+)]
+#[instrument(skip(audit_use_cases))]
+pub async fn read_audit_events(
+    State(audit_use_cases): State<Arc<AuditUseCases>>,
+    Query(params): Query<AuditEventsQuery>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read audit events called");
+
+    let events = audit_use_cases.read(&params.into_filter()?).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AuditEventsResponse {
+            success: true,
+            data: events.into_iter().map(Into::into).collect(),
+        }),
+    ))
+}
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::{
     adapters::http::{
         app_state::AppState,
+        rate_limit::RateLimitGroup,
         routes::{
             auth_middleware,
             blog_post::{
@@ -16,7 +17,7 @@ use crate::{
                 read_single::read_single_blog_post, update::update_blog_post,
             },
             require_admin, require_professional_or_admin, require_role_middleware,
-            verified_middleware,
+            rate_limit, rate_limit_middleware, verified_middleware,
         },
     },
     entities::blog_post::BlogPost,
@@ -96,5 +97,7 @@ pub fn router() -> Router<AppState> {
                 .route_layer(require_professional_or_admin()),
         )
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, dtos::payment::booking::BookingRequestDTO, use_cases::{patient::PatientUseCases, payment::PaymentUseCases}
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BookingPayload {
+    professional_id: String,
+    session_type_id: String,
+    /// Start of a free slot of the professional, the session lasts as long as the slot
+    session_date: chrono::NaiveDateTime,
+    success_url: String,
+    cancel_url: String,
+}
+
+impl Validateable for BookingPayload {
+    fn valid(&self) -> bool {
+        !self.professional_id.is_empty()
+            && !self.session_type_id.is_empty()
+            && !self.success_url.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BookingData {
+    session_id: Uuid,
+    client_secret: String,
+    amount: i64,
+    currency: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BookingResponse {
+    data: BookingData,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/checkout/booking", 
+    responses( 
+        (status = 201, description = "Slot reserved, pay with the returned checkout", body = BookingResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 404, description = "The requesting user has no patient profile"),
+        (status = 409, description = "The professional already has a session at the requested time"),
+        (status = 422, description = "No free slot of the professional starts at the requested time"),
+        (status = 500, description = "Internal server error or database error"),
+        (status = 502, description = "Payment provider error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Checkout",
+    summary = "Books a session with a professional for the requesting patient",
+    description = "The session lasts as long as the availability slot starting at the requested time, the price is computed from it and the hourly rate of the professional. The session stays pending until the checkout is paid.\n\n**Required:** Verified Email + Patient profile"
+)]
+#[instrument(skip(patient_use_cases, payment_use_cases))]
+pub async fn book_session(
+    Extension(auth_user): Extension<AuthUser>,
+    State(patient_use_cases): State<Arc<PatientUseCases>>,
+    State(payment_use_cases): State<Arc<PaymentUseCases>>,
+    Json(payload): Json<BookingPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Book session called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let professional_uuid = Uuid::parse_str(&payload.professional_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let session_type_uuid = Uuid::parse_str(&payload.session_type_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    // Patients can only book for themselves
+    let patient = patient_use_cases
+        .read_by_user(&auth_user.actor()?, &user_uuid)
+        .await?;
+
+    let patient_uuid = patient.id.ok_or_else(|| AppError::NotFound("Patient not found".into()))?;
+
+    let booking = payment_use_cases
+        .book_session(BookingRequestDTO {
+            patient_id: patient_uuid,
+            professional_id: professional_uuid,
+            session_type_id: session_type_uuid,
+            session_date: payload.session_date,
+            success_url: payload.success_url,
+            cancel_url: payload.cancel_url,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(BookingResponse {
+            success: true,
+            data: BookingData {
+                session_id: booking.session_id,
+                client_secret: booking.client_secret,
+                amount: booking.amount,
+                currency: booking.currency,
+            },
+        }),
+    ))
+}
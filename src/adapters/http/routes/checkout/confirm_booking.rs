@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::Validateable, app_error::{AppError, AppResult}, use_cases::payment::PaymentUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ConfirmBookingPayload {
+    checkout_session_id: String,
+}
+
+impl Validateable for ConfirmBookingPayload {
+    fn valid(&self) -> bool {
+        !self.checkout_session_id.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmBookingResponse {
+    data: String, // transaction status: pending, completed, failed or expired
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/checkout/booking/confirm", 
+    responses( 
+        (status = 200, description = "Booking synced with the payment provider", body = ConfirmBookingResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 404, description = "Transaction not found"),
+        (status = 500, description = "Internal server error or database error"),
+        (status = 502, description = "Payment provider error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Checkout",
+    summary = "Checks the checkout of a booking and confirms the session if it has been paid",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn confirm_booking(
+    State(use_cases): State<Arc<PaymentUseCases>>,
+    Json(payload): Json<ConfirmBookingPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Confirm booking called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let status = use_cases
+        .sync_booking(&payload.checkout_session_id)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ConfirmBookingResponse { success: true, data: status.to_string() }),
+    ))
+}
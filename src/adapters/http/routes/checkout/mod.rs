@@ -0,0 +1,32 @@
+use axum::{Router, middleware, routing::post};
+
+use crate::adapters::http::{
+    app_state::AppState,
+    rate_limit::RateLimitGroup,
+    routes::{
+        auth_middleware,
+        checkout::{
+            booking::book_session, confirm_booking::confirm_booking, webhook::stripe_webhook,
+        },
+        rate_limit, rate_limit_middleware, verified_middleware,
+    },
+};
+
+pub mod booking;
+pub mod confirm_booking;
+pub mod webhook;
+
+pub fn router() -> Router<AppState> {
+    let public_routes = Router::new()
+        .route("/webhook", post(stripe_webhook)); // Required: Valid Stripe signature, not limited since Stripe retries the calls
+
+    let protected_routes = Router::new()
+        .route("/booking", post(book_session)) // Required: Verified Email + Patient profile
+        .route("/booking/confirm", post(confirm_booking)) // Required: Verified Email
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware)); // Main auth middleware always has to be the LAST
+
+    Router::new().merge(public_routes).merge(protected_routes)
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::{HeaderMap, StatusCode}, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::persistence::tenant, app_error::{AppError, AppResult}, use_cases::payment::PaymentUseCases
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StripeWebhookResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/checkout/webhook", 
+    request_body(content = String, description = "Raw Stripe event, as sent by Stripe", content_type = "application/json"),
+    params(
+        ("Stripe-Signature" = String, Header, description = "Signature of the payload computed by Stripe")
+    ),
+    responses( 
+        (status = 200, description = "Event processed or ignored", body = StripeWebhookResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Missing or invalid signature"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    tag = "Checkout",
+    summary = "Receives the Stripe events to reconcile the transactions",
+    description = "Handles checkout.session.completed, checkout.session.expired and payment_intent.payment_failed, any other event is acknowledged and ignored. With PAYMENT_GATEWAY=fake the body is {\"type\": \"<event type>\", \"checkout_session_id\": \"cs_fake_...\"} and any signature is accepted.\n\n**Required:** Valid Stripe signature"
+)]
+#[instrument(skip(use_cases, headers, body))]
+pub async fn stripe_webhook(
+    State(use_cases): State<Arc<PaymentUseCases>>,
+    headers: HeaderMap,
+    body: String, // the signature is computed over the raw body, so it can't be deserialized before verifying it
+) -> AppResult<impl IntoResponse> {
+    info!("Stripe webhook called");
+
+    let signature = headers
+        .get("Stripe-Signature")
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::Unauthorized("Missing webhook signature".into()))?;
+
+    // Stripe calls on behalf of no user, the transaction the event is about tells the organization
+    tenant::unscoped(use_cases.handle_webhook(&body, signature)).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(StripeWebhookResponse { success: true }),
+    ))
+}
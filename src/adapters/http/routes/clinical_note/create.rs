@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, clinical_note::ClinicalNoteResponse},
+    app_error::{AppError, AppResult},
+    entities::clinical_note::{NoteVisibility, SoapSections},
+    use_cases::clinical_note::ClinicalNoteUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ClinicalNoteCreatePayload {
+    session_id: String,
+    /// professional (default) or patient
+    visibility: Option<String>,
+    subjective: Option<String>,
+    objective: Option<String>,
+    assessment: Option<String>,
+    plan: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClinicalNoteCreateResponse {
+    data: ClinicalNoteResponse,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/clinical-note/create", 
+    responses( 
+        (status = 201, description = "Created", body = ClinicalNoteCreateResponse),
+        (status = 400, description = "Invalid payload or every section empty"),
+        (status = 401, description = "Not the professional of the session"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Clinical Note",
+    summary = "Adds a SOAP note to a session",
+    description = "The note is kept from the patient unless its visibility is patient.\n\n**Required:** Verified Email + session professional"
+)]
+#[instrument(skip(use_cases, payload))]
+pub async fn create_clinical_note(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<ClinicalNoteUseCases>>,
+    Json(payload): Json<ClinicalNoteCreatePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Create clinical note called");
+
+    let session_uuid = Uuid::parse_str(&payload.session_id).map_err(|_| AppError::InvalidPayload)?;
+    let visibility = payload
+        .visibility
+        .map(|visibility| visibility.parse::<NoteVisibility>().map_err(|_| AppError::InvalidPayload))
+        .transpose()?
+        .unwrap_or_default();
+    let sections = SoapSections {
+        subjective: payload.subjective,
+        objective: payload.objective,
+        assessment: payload.assessment,
+        plan: payload.plan,
+    };
+
+    let note = use_cases
+        .create(&auth_user.actor()?, &session_uuid, visibility, sections)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ClinicalNoteCreateResponse { success: true, data: note.into() }),
+    ))
+}
@@ -0,0 +1,94 @@
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::{
+        app_state::AppState,
+        rate_limit::RateLimitGroup,
+        routes::{
+            auth_middleware,
+            clinical_note::{
+                create::create_clinical_note, read_by_session::read_session_clinical_notes,
+                read_versions::read_clinical_note_versions, revise::revise_clinical_note,
+            },
+            rate_limit, rate_limit_middleware, verified_middleware,
+        },
+    },
+    entities::clinical_note::{ClinicalNote, ClinicalNoteVersion},
+};
+
+pub mod create;
+pub mod read_by_session;
+pub mod read_versions;
+pub mod revise;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ClinicalNoteResponse {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// None for the legacy notes of a professional without a user
+    pub author_user_id: Option<Uuid>,
+    /// Moved from the free text notes of the session
+    pub legacy: bool,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    /// The latest version
+    pub current: ClinicalNoteVersionResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ClinicalNoteVersionResponse {
+    pub version: i32,
+    /// professional or patient
+    pub visibility: String,
+    pub subjective: Option<String>,
+    pub objective: Option<String>,
+    pub assessment: Option<String>,
+    pub plan: Option<String>,
+    pub edited_by: Option<Uuid>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<ClinicalNote> for ClinicalNoteResponse {
+    fn from(note: ClinicalNote) -> Self {
+        ClinicalNoteResponse {
+            id: note.id,
+            session_id: note.session_id,
+            author_user_id: note.author_user_id,
+            legacy: note.legacy,
+            created_at: note.created_at,
+            current: note.current.into(),
+        }
+    }
+}
+
+impl From<ClinicalNoteVersion> for ClinicalNoteVersionResponse {
+    fn from(version: ClinicalNoteVersion) -> Self {
+        ClinicalNoteVersionResponse {
+            version: version.version,
+            visibility: version.visibility.to_string(),
+            subjective: version.sections.subjective,
+            objective: version.sections.objective,
+            assessment: version.sections.assessment,
+            plan: version.sections.plan,
+            edited_by: version.edited_by,
+            created_at: version.created_at,
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/create", post(create_clinical_note)) // Required: Verified Email + session professional
+        .route("/{id}/revise", post(revise_clinical_note)) // Required: Verified Email + session professional
+        .route("/session/{session_id}", get(read_session_clinical_notes)) // Required: Verified Email + session party or Admin Role
+        .route("/{id}/versions", get(read_clinical_note_versions)) // Required: Verified Email + session professional or Admin Role
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
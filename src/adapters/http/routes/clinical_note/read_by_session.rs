@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, clinical_note::ClinicalNoteResponse},
+    app_error::{AppError, AppResult},
+    use_cases::clinical_note::ClinicalNoteUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClinicalNotesReadResponse {
+    data: Vec<ClinicalNoteResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/clinical-note/session/{session_id}", 
+    params(
+        ("session_id" = String, Path, description = "Session of the notes")
+    ),
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = ClinicalNotesReadResponse),
+        (status = 400, description = "Invalid session id"),
+        (status = 401, description = "Not a party of the session"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Clinical Note",
+    summary = "Retrieves the notes of a session with their latest version",
+    description = "The patient of the session only gets the notes shared with them.\n\n**Required:** Verified Email + session patient or professional, or Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_session_clinical_notes(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<ClinicalNoteUseCases>>,
+    Path(session_id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read session clinical notes called");
+
+    let session_uuid = Uuid::parse_str(&session_id).map_err(|_| AppError::InvalidPayload)?;
+
+    let notes = use_cases
+        .read_by_session(&auth_user.actor()?, &session_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ClinicalNotesReadResponse { success: true, data: notes.into_iter().map(Into::into).collect() }),
+    ))
+}
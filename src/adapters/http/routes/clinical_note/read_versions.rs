@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, clinical_note::ClinicalNoteVersionResponse},
+    app_error::{AppError, AppResult},
+    use_cases::clinical_note::ClinicalNoteUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClinicalNoteVersionsReadResponse {
+    /// Oldest first
+    data: Vec<ClinicalNoteVersionResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/clinical-note/{id}/versions", 
+    params(
+        ("id" = String, Path, description = "Note of the versions")
+    ),
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = ClinicalNoteVersionsReadResponse),
+        (status = 400, description = "Invalid note id"),
+        (status = 401, description = "Not the professional of the session"),
+        (status = 404, description = "Note not found"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Clinical Note",
+    summary = "Retrieves every version of a note",
+    description = "\n\n**Required:** Verified Email + session professional or Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_clinical_note_versions(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<ClinicalNoteUseCases>>,
+    Path(id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read clinical note versions called");
+
+    let note_uuid = Uuid::parse_str(&id).map_err(|_| AppError::InvalidPayload)?;
+
+    let versions = use_cases
+        .read_versions(&auth_user.actor()?, &note_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ClinicalNoteVersionsReadResponse { success: true, data: versions.into_iter().map(Into::into).collect() }),
+    ))
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, clinical_note::ClinicalNoteVersionResponse},
+    app_error::{AppError, AppResult},
+    entities::clinical_note::{NoteVisibility, SoapSections},
+    use_cases::clinical_note::ClinicalNoteUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ClinicalNoteRevisePayload {
+    /// professional or patient
+    visibility: String,
+    subjective: Option<String>,
+    objective: Option<String>,
+    assessment: Option<String>,
+    plan: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClinicalNoteReviseResponse {
+    data: ClinicalNoteVersionResponse,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/clinical-note/{id}/revise", 
+    params(
+        ("id" = String, Path, description = "Note to revise")
+    ),
+    responses( 
+        (status = 201, description = "Version added", body = ClinicalNoteReviseResponse),
+        (status = 400, description = "Invalid payload or every section empty"),
+        (status = 401, description = "Not the professional of the session"),
+        (status = 404, description = "Note not found"),
+        (status = 409, description = "Nothing changed or the note was edited by someone else meanwhile"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Clinical Note",
+    summary = "Edits a note",
+    description = "The whole content is sent, it becomes a new version and the previous ones are kept unchanged. Sharing a note with the patient, or no longer sharing it, is an edit as well.\n\n**Required:** Verified Email + session professional"
+)]
+#[instrument(skip(use_cases, payload))]
+pub async fn revise_clinical_note(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<ClinicalNoteUseCases>>,
+    Path(id): Path<String>,
+    Json(payload): Json<ClinicalNoteRevisePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Revise clinical note called");
+
+    let note_uuid = Uuid::parse_str(&id).map_err(|_| AppError::InvalidPayload)?;
+    let visibility: NoteVisibility = payload.visibility.parse().map_err(|_| AppError::InvalidPayload)?;
+    let sections = SoapSections {
+        subjective: payload.subjective,
+        objective: payload.objective,
+        assessment: payload.assessment,
+        plan: payload.plan,
+    };
+
+    let version = use_cases
+        .revise(&auth_user.actor()?, &note_uuid, visibility, sections)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ClinicalNoteReviseResponse { success: true, data: version.into() }),
+    ))
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::AuthUser,
+    app_error::{AppError, AppResult},
+    use_cases::data_export::DataExportUseCases,
+};
+
+#[utoipa::path(get, path = "/api/data-export/{id}/download", 
+    params(
+        ("id" = String, Path, description = "Data export id")
+    ),
+    responses( 
+        (status = 200, description = "Zip with the personal data as JSON files", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Data export not found, expired or of another user"),
+        (status = 409, description = "Data export not ready"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Data Export",
+    summary = "Downloads a data export",
+    description = "\n\n**Required:** Verified Email + owner of the export"
+)]
+#[instrument(skip(use_cases))]
+pub async fn download_data_export(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<DataExportUseCases>>,
+    Path(id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    info!("Download data export called");
+
+    let export_uuid = Uuid::parse_str(&id).map_err(|_| AppError::InvalidPayload)?;
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let archive = use_cases
+        .download(&export_uuid, &user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, String::from("application/zip")),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"data-export-{}.zip\"", export_uuid),
+            ),
+            (header::CACHE_CONTROL, String::from("no-store")),
+        ],
+        archive,
+    ))
+}
@@ -0,0 +1,61 @@
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::{
+        app_state::AppState,
+        rate_limit::RateLimitGroup,
+        routes::{
+            auth_middleware,
+            data_export::{download::download_data_export, read_mine::read_my_data_exports, request::request_data_export},
+            rate_limit, rate_limit_middleware, verified_middleware,
+        },
+    },
+    entities::data_export::{DataExport, DataExportStatus},
+};
+
+pub mod download;
+pub mod read_mine;
+pub mod request;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct DataExportResponse {
+    pub id: Uuid,
+    /// pending, ready or failed
+    pub status: String,
+    /// Authenticated link to the zip, only once ready
+    pub download_url: Option<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<DataExport> for DataExportResponse {
+    fn from(export: DataExport) -> Self {
+        DataExportResponse {
+            id: export.id,
+            status: export.status.to_string(),
+            download_url: (export.status == DataExportStatus::Ready)
+                .then(|| format!("/api/data-export/{}/download", export.id)),
+            created_at: export.created_at,
+            completed_at: export.completed_at,
+            expires_at: export.expires_at,
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/request", post(request_data_export)) // Required: Verified Email
+        .route("/mine", get(read_my_data_exports)) // Required: Verified Email
+        .route("/{id}/download", get(download_data_export)) // Required: Verified Email + owner of the export
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
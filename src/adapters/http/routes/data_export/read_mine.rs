@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, data_export::DataExportResponse},
+    app_error::{AppError, AppResult},
+    use_cases::data_export::DataExportUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DataExportReadMineResponse {
+    data: Vec<DataExportResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/data-export/mine", 
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = DataExportReadMineResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Data Export",
+    summary = "Retrieves the data exports of the requesting user that haven't expired, newest first",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_my_data_exports(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<DataExportUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read my data exports called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let exports = use_cases
+        .read_by_user(&user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(DataExportReadMineResponse {
+            success: true,
+            data: exports.into_iter().map(Into::into).collect(),
+        }),
+    ))
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, data_export::DataExportResponse},
+    app_error::{AppError, AppResult},
+    use_cases::data_export::DataExportUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DataExportRequestResponse {
+    data: DataExportResponse,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/data-export/request", 
+    responses( 
+        (status = 202, description = "Export requested, it is generated in the background", body = DataExportRequestResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "An export of the user is already being generated"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Data Export",
+    summary = "Requests a copy of the personal data of the requesting user",
+    description = "Gathers the user, its onboarding and consents, its patient profile, sessions, payments and the emails sent to it into a zip of JSON files. Poll /api/data-export/mine until it is ready and download it from its link before it expires.\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn request_data_export(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<DataExportUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Request data export called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let export = use_cases
+        .request(&user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(DataExportRequestResponse { success: true, data: export.into() }),
+    ))
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::AuthUser, app_error::{AppError, AppResult}, entities::user::Role, use_cases::invoice::InvoiceUseCases
+};
+
+#[utoipa::path(get, path = "/api/invoice/{id}/pdf", 
+    params(
+        ("id" = String, Path, description = "Invoice id")
+    ),
+    responses( 
+        (status = 200, description = "Invoice PDF", content_type = "application/pdf", body = Vec<u8>),
+        (status = 404, description = "Invoice not found or not visible to the requesting user"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Invoice",
+    summary = "Downloads an invoice as PDF",
+    description = "\n\n**Required:** Verified Email + Admin/Receptionist Role or invoice patient/professional"
+)]
+#[instrument(skip(use_cases))]
+pub async fn download_invoice(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<InvoiceUseCases>>,
+    Path(id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    info!("Download invoice called");
+
+    let invoice_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let role = Role::from_id(auth_user.role_id).unwrap_or_default();
+
+    let (invoice, pdf) = use_cases
+        .read_pdf(&invoice_uuid, &user_uuid, &role)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, String::from("application/pdf")),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"invoice-{}.pdf\"", invoice.invoice_number()),
+            ),
+        ],
+        pdf,
+    ))
+}
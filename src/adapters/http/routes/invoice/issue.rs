@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{Validateable, invoice::InvoiceResponse}, app_error::{AppError, AppResult}, use_cases::invoice::InvoiceUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct InvoiceIssuePayload {
+    session_id: String,
+}
+
+impl Validateable for InvoiceIssuePayload {
+    fn valid(&self) -> bool {
+        !self.session_id.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvoiceIssueResponse {
+    data: InvoiceResponse,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/invoice/issue", 
+    responses( 
+        (status = 200, description = "Invoice issued, or the one already issued", body = InvoiceIssueResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 404, description = "The session has no transaction"),
+        (status = 422, description = "The transaction of the session is not paid"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Invoice",
+    summary = "Issues the invoice of a paid session",
+    description = "Invoices are issued automatically when a booking is paid, this is meant for the ones that failed to be issued then.\n\n**Required:** Verified Email + Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn issue_invoice(
+    State(use_cases): State<Arc<InvoiceUseCases>>,
+    Json(payload): Json<InvoiceIssuePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Issue invoice called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let session_uuid = Uuid::parse_str(&payload.session_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let invoice = use_cases
+        .issue_for_session(&session_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(InvoiceIssueResponse { success: true, data: invoice.into() }),
+    ))
+}
@@ -0,0 +1,102 @@
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::{
+        app_state::AppState,
+        rate_limit::RateLimitGroup,
+        routes::{
+            auth_middleware,
+            invoice::{
+                download::download_invoice, issue::issue_invoice,
+                read_billing_details::read_billing_details, read_mine::read_my_invoices,
+                update_billing_details::update_billing_details,
+            },
+            require_admin, require_role_middleware, rate_limit, rate_limit_middleware, verified_middleware,
+        },
+    },
+    entities::invoice::{BillingDetails, Invoice},
+};
+
+pub mod download;
+pub mod issue;
+pub mod read_billing_details;
+pub mod read_mine;
+pub mod update_billing_details;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct InvoiceResponse {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub session_id: Option<Uuid>,
+    pub issued_at: chrono::NaiveDateTime,
+    pub issuer_name: String,
+    pub recipient_name: String,
+    pub total: i64, // cents
+    pub currency: String,
+}
+
+impl From<Invoice> for InvoiceResponse {
+    fn from(invoice: Invoice) -> Self {
+        InvoiceResponse {
+            id: invoice.id,
+            invoice_number: invoice.invoice_number(),
+            session_id: invoice.session_id,
+            issued_at: invoice.issued_at,
+            total: invoice.total(),
+            issuer_name: invoice.issuer.name,
+            recipient_name: invoice.recipient.name,
+            currency: invoice.currency,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct BillingDetailsResponse {
+    pub legal_name: String,
+    pub tax_id: String,
+    pub address: String,
+    pub postal_code: String,
+    pub city: String,
+    pub country: String,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<BillingDetails> for BillingDetailsResponse {
+    fn from(details: BillingDetails) -> Self {
+        BillingDetailsResponse {
+            legal_name: details.legal_name,
+            tax_id: details.tax_id,
+            address: details.address,
+            postal_code: details.postal_code,
+            city: details.city,
+            country: details.country,
+            updated_at: details.updated_at,
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(read_my_invoices)) // Required: Verified Email
+        .route("/{id}/pdf", get(download_invoice)) // Required: Verified Email + Admin/Receptionist Role or invoice patient/professional
+        .route(
+            "/issue", // Required: Verified Email + Admin Role
+            post(issue_invoice)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .route(
+            "/billing-details", // Required: Verified Email
+            get(read_billing_details).put(update_billing_details),
+        )
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
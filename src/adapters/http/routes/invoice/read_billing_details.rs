@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, invoice::BillingDetailsResponse}, app_error::{AppError, AppResult}, use_cases::invoice::InvoiceUseCases
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BillingDetailsReadResponse {
+    data: Option<BillingDetailsResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/invoice/billing-details", 
+    responses( 
+        (status = 200, description = "Data retrieved correctly, data is null if the user has none", body = BillingDetailsReadResponse),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Invoice",
+    summary = "Retrieves the billing details of the requesting user",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_billing_details(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<InvoiceUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read billing details called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let details = use_cases
+        .read_billing_details(&user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(BillingDetailsReadResponse { success: true, data: details.map(Into::into) }),
+    ))
+}
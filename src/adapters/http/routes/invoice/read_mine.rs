@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, invoice::InvoiceResponse}, app_error::{AppError, AppResult}, use_cases::invoice::InvoiceUseCases
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvoiceReadMineResponse {
+    data: Vec<InvoiceResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/invoice", 
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = InvoiceReadMineResponse),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Invoice",
+    summary = "Retrieves the invoices of the requesting user",
+    description = "Invoices where the requesting user is the patient or the professional, newest first.\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_my_invoices(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<InvoiceUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read my invoices called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let invoices = use_cases
+        .read_by_user(&user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(InvoiceReadMineResponse { success: true, data: invoices.into_iter().map(Into::into).collect() }),
+    ))
+}
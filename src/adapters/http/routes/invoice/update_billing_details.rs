@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::invoice::BillingDetails, use_cases::invoice::InvoiceUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BillingDetailsUpdatePayload {
+    legal_name: String,
+    tax_id: String,
+    address: String,
+    postal_code: String,
+    city: String,
+    country: Option<String>, // ISO 3166-1 alpha-2, ES if not given
+}
+
+impl Validateable for BillingDetailsUpdatePayload {
+    fn valid(&self) -> bool {
+        !self.legal_name.trim().is_empty()
+            && !self.tax_id.trim().is_empty()
+            && !self.address.trim().is_empty()
+            && !self.postal_code.trim().is_empty()
+            && !self.city.trim().is_empty()
+            && self
+                .country
+                .as_ref()
+                .is_none_or(|country| country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()))
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BillingDetailsUpdateResponse {
+    success: bool,
+}
+
+#[utoipa::path(put, path = "/api/invoice/billing-details", 
+    responses( 
+        (status = 200, description = "Updated", body = BillingDetailsUpdateResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Invoice",
+    summary = "Sets the billing details of the requesting user",
+    description = "Used for the invoices issued from now on, the ones already issued keep the details they were issued with.\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn update_billing_details(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<InvoiceUseCases>>,
+    Json(payload): Json<BillingDetailsUpdatePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Update billing details called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let details = BillingDetails {
+        user_id: user_uuid,
+        legal_name: payload.legal_name.trim().to_string(),
+        tax_id: payload.tax_id.trim().to_uppercase(),
+        address: payload.address.trim().to_string(),
+        postal_code: payload.postal_code.trim().to_string(),
+        city: payload.city.trim().to_string(),
+        country: payload.country.unwrap_or_else(|| String::from("ES")).to_uppercase(),
+        updated_at: None,
+    };
+
+    use_cases
+        .update_billing_details(&details)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(BillingDetailsUpdateResponse { success: true }),
+    ))
+}
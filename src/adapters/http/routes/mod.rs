@@ -1,6 +1,13 @@
+pub mod account_deletion;
+pub mod audit;
 pub mod blog_post;
+pub mod clinical_note;
+pub mod data_export;
+pub mod invoice;
+pub mod organization;
 pub mod patient;
 pub mod professional;
+pub mod professional_availability;
 pub mod professional_language;
 pub mod professional_specialization;
 pub mod session;
@@ -9,16 +16,36 @@ pub mod user;
 pub mod user_token;
 pub mod checkout;
 
-use std::sync::Arc;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Instant,
+};
 
 use crate::{
-    adapters::{crypto::jwt::Claims, http::app_state::AppState},
-    app_error::AppError,
-    entities::user::Role,
-    use_cases::user::UserJwtService,
+    adapters::{
+        crypto::jwt::Claims,
+        http::{
+            app_state::AppState,
+            rate_limit::{RateLimitGroup, RateLimiters},
+        },
+        persistence::tenant,
+    },
+    app_error::{AppError, AppResult},
+    entities::{
+        permission::{Actor, RequestOrigin},
+        user::Role,
+    },
+    use_cases::user::UserUseCases,
+};
+use axum::{
+    Extension, Router,
+    extract::{ConnectInfo, FromRequestParts, Request},
+    http::{Extensions, HeaderMap, HeaderValue, request::Parts},
+    middleware::Next,
+    response::Response,
 };
-use axum::{Extension, Router, extract::Request, middleware::Next, response::Response, routing::post};
-use checkout::create_checkout_session;
+use uuid::Uuid;
 
 /// Trait that a Payload should implement in order to be validated (TODO: Can I enforce this)
 trait Validateable {
@@ -33,6 +60,22 @@ pub struct AuthUser {
     pub role_id: i32,
     pub verified: bool,
     pub needs_onboarding: bool,
+    pub organization_id: String,
+    pub origin: Option<RequestOrigin>, // set by the auth middleware
+}
+
+impl AuthUser {
+    /// The user the use cases act for
+    pub fn actor(&self) -> AppResult<Actor> {
+        let user_id = Uuid::parse_str(&self.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+        let actor = Actor::new(user_id, Role::from_id(self.role_id).unwrap_or_default());
+
+        Ok(match self.origin {
+            Some(origin) => actor.with_origin(origin),
+            None => actor,
+        })
+    }
 }
 
 impl From<Claims> for AuthUser {
@@ -43,13 +86,67 @@ impl From<Claims> for AuthUser {
             role_id: value.role,
             verified: value.verified,
             needs_onboarding: value.needs_onboarding,
+            organization_id: value.org,
+            origin: None,
         }
     }
 }
 
+/// Ip of the client, taken from the last X-Forwarded-For entry when the proxy headers are trusted
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(client_ip(
+            &parts.headers,
+            &parts.extensions,
+            state.config.trust_proxy_headers,
+        )))
+    }
+}
+
+fn client_ip(headers: &HeaderMap, extensions: &Extensions, trust_proxy_headers: bool) -> IpAddr {
+    if trust_proxy_headers
+        && let Some(ip) = headers
+            .get("X-Forwarded-For")
+            .and_then(|value| value.to_str().ok())
+            // Appended by the proxy, the entries before it are sent by the client and can be anything
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok())
+    {
+        return ip;
+    }
+
+    // Only missing when the app is not served with the connect info, like in the tests
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |ConnectInfo(addr)| addr.ip())
+}
+
+/// Id of a request, logged with it, returned in the X-Request-Id header and recorded in the audit events
+#[derive(Debug, Clone, Copy)]
+pub struct RequestId(pub Uuid);
+
+/// Middleware that gives every request an id
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = Uuid::new_v4();
+    request.extensions_mut().insert(RequestId(request_id));
+
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id.to_string()) {
+        response.headers_mut().insert("X-Request-Id", value);
+    }
+
+    response
+}
+
 /// Middleware that extracts the bearer Token from the request and verifies it.
 async fn auth_middleware(
-    Extension(user_jwt_service): Extension<Arc<dyn UserJwtService>>,
+    Extension(user_use_cases): Extension<Arc<UserUseCases>>,
+    Extension(rate_limiters): Extension<Arc<RateLimiters>>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
@@ -64,15 +161,28 @@ async fn auth_middleware(
         .ok_or_else(|| AppError::Unauthorized("Invalid authorization format".to_string()))?;
 
     // Verify token and get user
-    let claims = user_jwt_service
-        .validate_token(token)
+    let claims = user_use_cases
+        .authenticate(token)
+        .await
         .map_err(|e| AppError::Unauthorized(format!("Invalid token: {}", e)))?;
 
+    let organization_id = Uuid::parse_str(&claims.org)
+        .map_err(|_| AppError::Unauthorized("Invalid token: missing organization".to_string()))?;
+
     // Insert the authenticated user into request extensions
     // we should be able to extract AuthUser now from endpoints using: Extension(auth_user): Extension<AuthUser> as a param in our endpoint
-    request.extensions_mut().insert(AuthUser::from(claims));
+    let mut auth_user = AuthUser::from(claims);
+    auth_user.origin = Some(RequestOrigin {
+        ip: client_ip(request.headers(), request.extensions(), rate_limiters.trust_proxy_headers),
+        request_id: request
+            .extensions()
+            .get::<RequestId>()
+            .map_or_else(Uuid::new_v4, |RequestId(id)| *id),
+    });
+    request.extensions_mut().insert(auth_user);
 
-    Ok(next.run(request).await)
+    // Every query of the request only sees the data of the organization of the user
+    Ok(tenant::scope(organization_id, next.run(request)).await)
 }
 
 /// Middleware that only let's a request through if the user claims to be verified in the jwt
@@ -107,6 +217,34 @@ async fn require_role_middleware(
     Ok(next.run(request).await)
 }
 
+/// Middleware that limits the requests of a route group, by user behind the auth middleware and by ip otherwise
+async fn rate_limit_middleware(
+    Extension(rate_limiters): Extension<Arc<RateLimiters>>,
+    Extension(group): Extension<RateLimitGroup>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let key = match request.extensions().get::<AuthUser>() {
+        Some(auth_user) => format!("user:{}", auth_user.user_id),
+        None => format!(
+            "ip:{}",
+            client_ip(request.headers(), request.extensions(), rate_limiters.trust_proxy_headers)
+        ),
+    };
+
+    if let Err(retry_after) = rate_limiters.limiter(group).acquire(&key, Instant::now()) {
+        tracing::warn!("Rate limited {:?} request of {}", group, key);
+        return Err(AppError::TooManyRequests(retry_after.as_secs_f64().ceil() as i64));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Route group the rate limit middleware limits the requests by
+fn rate_limit(group: RateLimitGroup) -> Extension<RateLimitGroup> {
+    Extension(group)
+}
+
 /// Helper functions for common roles
 #[allow(dead_code)]
 fn require_roles(roles: Vec<Role>) -> Extension<RequiredRoles> {
@@ -123,6 +261,11 @@ fn require_professional_or_admin() -> Extension<RequiredRoles> {
     Extension(RequiredRoles(vec![Role::Admin, Role::Professional]))
 }
 
+#[allow(dead_code)]
+fn require_staff() -> Extension<RequiredRoles> {
+    Extension(RequiredRoles(vec![Role::Admin, Role::Professional, Role::Receptionist]))
+}
+
 #[allow(dead_code)]
 fn require_patient_or_admin() -> Extension<RequiredRoles> {
     Extension(RequiredRoles(vec![Role::Admin, Role::Patient]))
@@ -135,9 +278,19 @@ pub fn router() -> Router<AppState> {
         .nest("/user_token", user_token::router())
         .nest("/patient", patient::router())
         .nest("/session-type", session_type::router())
-        .nest("/checkout", Router::new().route("/session", post(create_checkout_session)))
+        .nest("/checkout", checkout::router())
+        .nest("/invoice", invoice::router())
+        .nest("/organization", organization::router())
+        .nest("/audit", audit::router())
+        .nest("/data-export", data_export::router())
+        .nest("/account-deletion", account_deletion::router())
         .nest("/session", session::router())
+        .nest("/clinical-note", clinical_note::router())
         .nest("/professional", professional::router())
+        .nest(
+            "/professional/{id}/availability",
+            professional_availability::router(),
+        )
         .nest("/professional_language", professional_language::router())
         .nest(
             "/professional_specialization",
@@ -145,3 +298,32 @@ pub fn router() -> Router<AppState> {
         )
         .nest("/blog_post", blog_post::router())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn client_ip_is_the_address_appended_by_the_proxy() {
+        let headers = forwarded_for("198.51.100.1, 203.0.113.7");
+
+        let ip = client_ip(&headers, &Extensions::new(), true);
+
+        assert_eq!(ip, "203.0.113.7".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn client_ip_ignores_the_proxy_headers_unless_trusted() {
+        let headers = forwarded_for("203.0.113.7");
+
+        let ip = client_ip(&headers, &Extensions::new(), false);
+
+        assert_eq!(ip, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    }
+}
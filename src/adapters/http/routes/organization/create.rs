@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable},
+    app_error::{AppError, AppResult},
+    use_cases::organization::OrganizationUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateOrganizationPayload {
+    name: String,
+    /// Member of the current organization that becomes the admin of the new one
+    admin_user_id: String,
+}
+
+impl Validateable for CreateOrganizationPayload {
+    fn valid(&self) -> bool {
+        !self.name.trim().is_empty() && !self.admin_user_id.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateOrganizationData {
+    id: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateOrganizationResponse {
+    data: CreateOrganizationData,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/organization/create", 
+    request_body = CreateOrganizationPayload,
+    responses( 
+        (status = 201, description = "Organization created", body = CreateOrganizationResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized or not a platform admin"),
+        (status = 404, description = "The admin is not a member of the current organization"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Organization",
+    summary = "Creates a new clinic",
+    description = "The given member leaves the current organization and becomes the admin of the new one, it has to login again. Its profile and sessions stay in the current organization.\n\n**Required:** Verified Email, Admin Role, Platform Admin"
+)]
+#[instrument(skip(use_cases))]
+pub async fn create_organization(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<OrganizationUseCases>>,
+    Json(payload): Json<CreateOrganizationPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Create organization called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let admin_uuid = Uuid::parse_str(&payload.admin_user_id).map_err(|_| AppError::InvalidPayload)?;
+    let organization_uuid = Uuid::parse_str(&auth_user.organization_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let id = use_cases
+        .create(&payload.name, &admin_uuid, &organization_uuid, &user_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateOrganizationResponse { success: true, data: CreateOrganizationData { id } }),
+    ))
+}
@@ -0,0 +1,85 @@
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::{
+        app_state::AppState,
+        rate_limit::RateLimitGroup,
+        routes::{
+            auth_middleware,
+            organization::{create::create_organization, read_current::read_current_organization, read_members::read_organization_members},
+            rate_limit, rate_limit_middleware, require_admin, require_role_middleware, verified_middleware,
+        },
+    },
+    entities::organization::{Organization, OrganizationMember},
+};
+
+pub mod create;
+pub mod read_current;
+pub mod read_members;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct OrganizationResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<Organization> for OrganizationResponse {
+    fn from(organization: Organization) -> Self {
+        OrganizationResponse {
+            id: organization.id,
+            name: organization.name,
+            created_at: organization.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct OrganizationMemberResponse {
+    pub user_id: Uuid,
+    pub username: String,
+    pub usersurname: String,
+    pub email: String,
+    pub role: String,
+    pub joined_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<OrganizationMember> for OrganizationMemberResponse {
+    fn from(member: OrganizationMember) -> Self {
+        OrganizationMemberResponse {
+            user_id: member.user_id,
+            username: member.username,
+            usersurname: member.usersurname,
+            email: member.email,
+            role: member.role.to_string(),
+            joined_at: member.joined_at,
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/create", // Required: Verified Email + Admin Role + Platform Admin
+            post(create_organization)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .route("/current", get(read_current_organization)) // Required: Verified Email
+        .route(
+            "/members", // Required: Verified Email + Admin Role
+            get(read_organization_members)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, organization::OrganizationResponse},
+    app_error::{AppError, AppResult},
+    use_cases::organization::OrganizationUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizationReadCurrentResponse {
+    data: OrganizationResponse,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/organization/current", 
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = OrganizationReadCurrentResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Organization not found"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Organization",
+    summary = "Retrieves the organization of the requesting user",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_current_organization(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<OrganizationUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read current organization called");
+
+    let organization_uuid = Uuid::parse_str(&auth_user.organization_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let organization = use_cases
+        .read(&organization_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(OrganizationReadCurrentResponse { success: true, data: organization.into() }),
+    ))
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, organization::OrganizationMemberResponse},
+    app_error::{AppError, AppResult},
+    use_cases::organization::OrganizationUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizationReadMembersResponse {
+    data: Vec<OrganizationMemberResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/organization/members", 
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = OrganizationReadMembersResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Organization",
+    summary = "Retrieves the members of the organization of the requesting admin",
+    description = "\n\n**Required:** Verified Email, Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_organization_members(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<OrganizationUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read organization members called");
+
+    let organization_uuid = Uuid::parse_str(&auth_user.organization_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let members = use_cases
+        .read_members(&organization_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(OrganizationReadMembersResponse {
+            success: true,
+            data: members.into_iter().map(Into::into).collect(),
+        }),
+    ))
+}
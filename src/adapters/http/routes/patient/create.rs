@@ -7,7 +7,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::{gender::Gender, patient::Patient, sexual_orientation::SexualOrientation, user::Role}, use_cases::patient::PatientUseCases
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::{gender::Gender, patient::Patient, sexual_orientation::SexualOrientation}, use_cases::patient::PatientUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema)]
@@ -47,7 +47,7 @@ pub struct PatientCreateResponse {
     ), 
     tag = "Patient",
     summary = "Creates a new patient",
-    description = "\n\n**Required:** Verified Email + Admin/Professional Role OR Creating for requesting user_id"
+    description = "\n\n**Required:** Verified Email + Admin/Professional/Receptionist Role OR Creating for requesting user_id"
 )]
 #[instrument(skip(use_cases))]
 pub async fn create_patient(
@@ -56,12 +56,6 @@ pub async fn create_patient(
     Json(payload): Json<PatientCreatePayload>,
 ) -> AppResult<impl IntoResponse> {
     info!("Create patient called");
-    let is_authorized = authorized(&auth_user, &payload);
-    if !is_authorized {
-        return Err(AppError::Unauthorized(
-            String::from("You don't have permission for this endpoint")
-        ));
-    }
 
     if !payload.valid() {
         return AppResult::Err(AppError::InvalidPayload);
@@ -75,7 +69,7 @@ pub async fn create_patient(
     let patient = Patient { id: None, user_id: user_uuid, gender:  Gender::from_id(payload.gender_id).unwrap_or_default(), sexual_orientation:  SexualOrientation::from_id(payload.sexual_orientation_id).unwrap_or_default(), birthdate: payload.birthdate, phone: payload.phone, emergency_contact_name: payload.emergency_contact_name, emergency_contact_phone: payload.emergency_contact_phone, insurance_policy_number: payload.insurance_policy_number, medical_history: payload.medical_history, current_medications: payload.current_medications, allergies: payload.allergies, created_at: None };
 
     use_cases
-        .create(&patient)
+        .create(&auth_user.actor()?, &patient)
         .await?;
 
     Ok((
@@ -83,18 +77,3 @@ pub async fn create_patient(
         Json(PatientCreateResponse { success:true }),
     ))
 }
-
-fn authorized(auth_user: &AuthUser, payload: &PatientCreatePayload) -> bool {
-    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
-    
-    // Check authorization
-    match requesting_role {
-        Role::Admin | Role::Professional => true,
-        Role::Patient => {
-            payload.user_id
-                .as_ref()
-                .map(|id| id == &auth_user.user_id)
-                .unwrap_or(false) // Don't allow if no user_id specified
-        }
-    }
-}
\ No newline at end of file
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::{
     adapters::http::{
         app_state::AppState,
+        rate_limit::RateLimitGroup,
         routes::{
             auth_middleware,
             patient::{
@@ -17,7 +18,7 @@ use crate::{
                 read_by_user::read_patient_by_user, read_single::read_single_patient,
                 update::update_patient,
             },
-            require_admin, require_role_middleware, verified_middleware,
+            require_admin, require_role_middleware, rate_limit, rate_limit_middleware, verified_middleware,
         },
     },
     entities::patient::Patient,
@@ -70,7 +71,7 @@ impl From<Patient> for PatientResponse {
 
 pub fn router() -> Router<AppState> {
     Router::new()
-        .route("/create", post(create_patient)) // Required: Verified Email + Admin/Professional Role OR Creating for requesting user_id
+        .route("/create", post(create_patient)) // Required: Verified Email + Admin/Professional/Receptionist Role OR Creating for requesting user_id
         .route(
             "/delete",
             delete(delete_patient) // Require verified + admin
@@ -83,10 +84,12 @@ pub fn router() -> Router<AppState> {
                 .route_layer(middleware::from_fn(require_role_middleware))
                 .route_layer(require_admin()),
         )
-        .route("/single", get(read_single_patient)) // Required: Verified Email + Admin/Professional Role or requesting user_id
-        .route("/user", get(read_patient_by_user)) // Required: Verified Email + Admin/Professional Role or requesting user_id
-        .route("/update", patch(update_patient)) // Only auth + mail verified required
-        .route("/professional", get(read_patients_by_professional)) // Required: Verified Email + Admin/Professional Role and requesting professional_id
+        .route("/single", get(read_single_patient)) // Required: Verified Email + Admin Role, requesting user_id or a Professional of the patient (Receptionist without clinical data)
+        .route("/user", get(read_patient_by_user)) // Required: Verified Email + Admin Role, requesting user_id or a Professional of the patient (Receptionist without clinical data)
+        .route("/update", patch(update_patient)) // Required: Verified Email + Admin Role, requesting user_id or a Professional of the patient
+        .route("/professional", get(read_patients_by_professional)) // Required: Verified Email + Admin Role or requesting professional_id (Receptionist without clinical data)
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::{Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 
 use crate::{
-    adapters::http::routes::{patient::PatientResponse}, app_error::{AppResult}, use_cases::patient::PatientUseCases
+    adapters::http::routes::{AuthUser, patient::PatientResponse}, app_error::AppResult, use_cases::patient::PatientUseCases
 };
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -29,12 +29,13 @@ pub struct PatientReadAllResponse {
 )]
 #[instrument(skip(use_cases))]
 pub async fn read_all_patients(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<PatientUseCases>>,
 ) -> AppResult<impl IntoResponse> {
     info!("Read all patients called");
 
     let patients = use_cases
-        .read_all()
+        .read_all(&auth_user.actor()?)
         .await?;
 
     Ok((
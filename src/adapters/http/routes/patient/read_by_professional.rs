@@ -7,7 +7,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{AuthUser, Validateable, patient::PatientResponse}, app_error::{AppError, AppResult}, entities::{professional::Professional, user::Role}, use_cases::{patient::PatientUseCases, professional::ProfessionalUseCases}
+    adapters::http::routes::{AuthUser, Validateable, patient::PatientResponse}, app_error::{AppError, AppResult}, use_cases::{patient::PatientUseCases, professional::ProfessionalUseCases}
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
@@ -41,7 +41,7 @@ pub struct PatientReadByProfessionalResponse {
     ),
     tag = "Patient",
     summary = "Retrieves data of all patients of the requested professional",
-    description = "\n\n**Required:** Verified Email + Admin/Professional Role and requesting professional_id"
+    description = "\n\n**Required:** Verified Email + Admin Role or requesting professional_id. Receptionists get them without the clinical data"
 )]
 #[instrument(skip(patient_use_cases, professional_use_cases))]
 pub async fn read_patients_by_professional(
@@ -58,15 +58,8 @@ pub async fn read_patients_by_professional(
     let professional_uuid = Uuid::parse_str(&params.professional_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
     let professional = professional_use_cases.read_single(&professional_uuid).await?;
 
-    let is_authorized = authorized(&auth_user, &professional);
-    if !is_authorized {
-        return Err(AppError::Unauthorized(
-            String::from("You don't have permission for this endpoint")
-        ));
-    }
-
     let patients = patient_use_cases
-        .read_by_professional(&professional_uuid)
+        .read_by_professional(&auth_user.actor()?, &professional)
         .await?;
     
     Ok((
@@ -74,19 +67,3 @@ pub async fn read_patients_by_professional(
         Json(PatientReadByProfessionalResponse { success:true , data: patients.into_iter().map(Into::into).collect() }),
     ))
 }
-
-fn authorized(auth_user: &AuthUser, professional: &Professional) -> bool {
-    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
-    
-    // Check authorization
-    match requesting_role {
-        Role::Admin => true,
-        Role::Professional =>{
-            auth_user.user_id
-                .parse::<Uuid>()
-                .ok()
-                .is_some_and(|id| Some(id) == professional.user_id)
-        },
-        _ => false,
-    }
-}
\ No newline at end of file
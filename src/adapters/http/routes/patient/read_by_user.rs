@@ -7,7 +7,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{patient::PatientResponse, AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::{user::Role}, use_cases::patient::PatientUseCases
+    adapters::http::routes::{patient::PatientResponse, AuthUser, Validateable}, app_error::{AppError, AppResult}, use_cases::patient::PatientUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
@@ -40,7 +40,7 @@ pub struct PatientReadByUserResponse {
     ),
     tag = "Patient",
     summary = "Retrieves data of a single patient",
-    description = "\n\n**Required:** Verified Email + Admin/Professional Role or requesting user_id"
+    description = "\n\n**Required:** Verified Email + Admin Role, requesting user_id or a Professional with sessions with the patient. Receptionists get it without the clinical data"
 )]
 #[instrument(skip(use_cases))]
 pub async fn read_patient_by_user(
@@ -55,15 +55,8 @@ pub async fn read_patient_by_user(
 
     let user_uuid = Uuid::parse_str(&params.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
 
-    let is_authorized = authorized(&auth_user, &user_uuid);
-    if !is_authorized {
-        return Err(AppError::Unauthorized(
-            String::from("You don't have permission for this endpoint")
-        ));
-    }
-
     let patient = use_cases
-        .read_by_user(&user_uuid)
+        .read_by_user(&auth_user.actor()?, &user_uuid)
         .await?;
     
     Ok((
@@ -71,15 +64,3 @@ pub async fn read_patient_by_user(
         Json(PatientReadByUserResponse { success:true , data: patient.into()}),
     ))
 }
-
-fn authorized(auth_user: &AuthUser, user_id: &Uuid) -> bool {
-    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
-    
-    // Check authorization
-    match requesting_role {
-        Role::Admin | Role::Professional => true,
-        Role::Patient => {
-            user_id.to_string() == auth_user.user_id
-        }
-    }
-}
\ No newline at end of file
@@ -7,7 +7,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{patient::PatientResponse, AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::{patient::Patient, user::Role}, use_cases::patient::PatientUseCases
+    adapters::http::routes::{patient::PatientResponse, AuthUser, Validateable}, app_error::{AppError, AppResult}, use_cases::patient::PatientUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
@@ -40,7 +40,7 @@ pub struct PatientReadSingleResponse {
     ),
     tag = "Patient",
     summary = "Retrieves data of a single patient",
-    description = "\n\n**Required:** Verified Email + Admin/Professional Role or requesting user_id"
+    description = "\n\n**Required:** Verified Email + Admin Role, requesting user_id or a Professional with sessions with the patient. Receptionists get it without the clinical data"
 )]
 #[instrument(skip(use_cases))]
 pub async fn read_single_patient(
@@ -56,33 +56,11 @@ pub async fn read_single_patient(
     let patient_uuid = Uuid::parse_str(&params.patient_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
 
     let patient = use_cases
-        .read_single(&patient_uuid)
+        .read_single(&auth_user.actor()?, &patient_uuid)
         .await?;
 
-    let is_authorized = authorized(&auth_user, &patient);
-    if !is_authorized {
-        return Err(AppError::Unauthorized(
-            String::from("You don't have permission for this endpoint")
-        ));
-    }
-
     Ok((
         StatusCode::OK,
         Json(PatientReadSingleResponse { success:true , data: patient.into()}),
     ))
 }
-
-fn authorized(auth_user: &AuthUser, patient: &Patient) -> bool {
-    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
-    
-    // Check authorization
-    match requesting_role {
-        Role::Admin | Role::Professional => true,
-        Role::Patient => {
-            patient.user_id
-                .as_ref()
-                .map(|id| id.to_string() == auth_user.user_id)
-                .unwrap_or(false) // Don't allow if no user_id specified
-        }
-    }
-}
\ No newline at end of file
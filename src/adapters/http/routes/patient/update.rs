@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::Validateable, app_error::{AppError, AppResult}, entities::{gender::Gender, patient::Patient, sexual_orientation::SexualOrientation}, use_cases::patient::PatientUseCases
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::{gender::Gender, patient::Patient, sexual_orientation::SexualOrientation}, use_cases::patient::PatientUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema)]
@@ -47,10 +47,11 @@ pub struct PatientUpdateResponse {
     ),
     tag = "Patient",
     summary = "Updates a patient",
-    description = "\n\n**Required:** Verified Email"
+    description = "\n\n**Required:** Verified Email + Admin Role, requesting user_id or a Professional with sessions with the patient"
 )]
 #[instrument(skip(use_cases))]
 pub async fn update_patient(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<PatientUseCases>>,
     Json(payload): Json<PatientUpdatePayload>,
 ) -> AppResult<impl IntoResponse> {
@@ -65,7 +66,7 @@ pub async fn update_patient(
     let patient = Patient { id: Some(id), user_id: None, gender:  Gender::from_id(payload.gender_id).unwrap_or_default(), sexual_orientation:  SexualOrientation::from_id(payload.sexual_orientation_id).unwrap_or_default(), birthdate: payload.birthdate, phone: payload.phone, emergency_contact_name: payload.emergency_contact_name, emergency_contact_phone: payload.emergency_contact_phone, insurance_policy_number: payload.insurance_policy_number, medical_history: payload.medical_history, current_medications: payload.current_medications, allergies: payload.allergies, created_at: None };
 
     use_cases
-        .update(&patient)
+        .update(&auth_user.actor()?, &patient)
         .await?;
 
     Ok((
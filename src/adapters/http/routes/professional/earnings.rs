@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::{Query, State}, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable, professional::EarningsSummaryResponse}, app_error::{AppError, AppResult}, entities::user::Role, use_cases::earning::EarningUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+pub struct EarningsQuery {
+    #[param(example = "2030-01-01")]
+    from: chrono::NaiveDate,
+    #[param(example = "2030-01-31")]
+    to: chrono::NaiveDate,
+    /// Only used by admins, professionals always get their own earnings
+    #[param(example = "insert-professional-uuid")]
+    professional_id: Option<String>,
+}
+
+impl Validateable for EarningsQuery {
+    fn valid(&self) -> bool {
+        self.from <= self.to
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EarningsReadResponse {
+    data: EarningsSummaryResponse,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/professional/earnings", 
+    params(EarningsQuery),
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = EarningsReadResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 404, description = "The requesting user has no professional profile"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional",
+    summary = "Retrieves the earnings of a professional between two dates (both included)",
+    description = "Amounts are in cents, the net amount is what is owed to the professional once the platform commission is kept. Refunds are taken out of the month they happened in.\n\n**Required:** Verified Email + Professional Role, or Admin Role + professional_id"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_earnings(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<EarningUseCases>>,
+    Query(params): Query<EarningsQuery>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read earnings called");
+    if !params.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let role = Role::from_id(auth_user.role_id).unwrap_or_default();
+
+    let summary = match (role, &params.professional_id) {
+        (Role::Admin, Some(professional_id)) => {
+            let professional_uuid = Uuid::parse_str(professional_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+            use_cases
+                .read_summary(&professional_uuid, params.from, params.to)
+                .await?
+        }
+        (Role::Admin, None) => return AppResult::Err(AppError::InvalidPayload),
+        _ => {
+            let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+            use_cases
+                .read_summary_by_user(&user_uuid, params.from, params.to)
+                .await?
+        }
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(EarningsReadResponse { success: true, data: summary.into() }),
+    ))
+}
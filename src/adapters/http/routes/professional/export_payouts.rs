@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::{extract::{Query, State}, http::{StatusCode, header}, response::IntoResponse};
+use serde::Deserialize;
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    adapters::http::routes::professional::payouts_csv, app_error::AppResult, use_cases::earning::EarningUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+pub struct PayoutsExportQuery {
+    #[param(example = "2030-01-31")]
+    until: chrono::NaiveDate,
+}
+
+#[utoipa::path(get, path = "/api/professional/earnings/payouts", 
+    params(PayoutsExportQuery),
+    responses( 
+        (status = 200, description = "Payouts as CSV", content_type = "text/csv", body = String),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional",
+    summary = "Exports what is owed to each professional up to a date (included)",
+    description = "One row per professional and currency with the earnings not paid out yet, refunds of earnings already paid out are substracted. Nothing is marked as paid out, see /api/professional/earnings/payouts/settle.\n\n**Required:** Verified Email + Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn export_payouts(
+    State(use_cases): State<Arc<EarningUseCases>>,
+    Query(params): Query<PayoutsExportQuery>,
+) -> AppResult<impl IntoResponse> {
+    info!("Export payouts called");
+
+    let payouts = use_cases
+        .read_pending_payouts(params.until)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, String::from("text/csv; charset=utf-8")),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"payouts-{}.csv\"", params.until),
+            ),
+        ],
+        payouts_csv(&payouts),
+    ))
+}
@@ -9,27 +9,36 @@ use uuid::Uuid;
 use crate::{
     adapters::http::{
         app_state::AppState,
+        rate_limit::RateLimitGroup,
         routes::{
             auth_middleware,
             professional::{
                 create::create_professional, delete::delete_professional,
+                earnings::read_earnings, export_payouts::export_payouts,
                 read_all::read_all_professionals, read_by_user::read_professional_by_user,
                 read_single::read_single_professional, selector::professionals_selector,
-                update::update_professional,
+                settle_payouts::settle_payouts, update::update_professional,
             },
             require_admin, require_professional_or_admin, require_role_middleware,
-            verified_middleware,
+            rate_limit, rate_limit_middleware, verified_middleware,
         },
     },
+    dtos::earning::{
+        payout::PayoutDTO,
+        summary::{EarningsMonthDTO, EarningsSummaryDTO, EarningsTotalsDTO},
+    },
     entities::professional::Professional,
 };
 
 pub mod create;
 pub mod delete;
+pub mod earnings;
+pub mod export_payouts;
 pub mod read_all;
 pub mod read_by_user;
 pub mod read_single;
 pub mod selector;
+pub mod settle_payouts;
 pub mod update;
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -65,6 +74,125 @@ impl From<Professional> for ProfessionalResponse {
     }
 }
 
+/// Amounts are in cents
+#[derive(Debug, Serialize, ToSchema)]
+struct EarningsTotalsResponse {
+    pub sessions: i64,
+    pub gross_amount: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub paid_out_amount: i64,
+}
+
+impl From<EarningsTotalsDTO> for EarningsTotalsResponse {
+    fn from(totals: EarningsTotalsDTO) -> Self {
+        EarningsTotalsResponse {
+            sessions: totals.sessions,
+            gross_amount: totals.gross_amount,
+            commission_amount: totals.commission_amount,
+            net_amount: totals.net_amount,
+            paid_out_amount: totals.paid_out_amount,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct EarningsMonthResponse {
+    pub month: String,
+    pub totals: EarningsTotalsResponse,
+}
+
+impl From<EarningsMonthDTO> for EarningsMonthResponse {
+    fn from(month: EarningsMonthDTO) -> Self {
+        EarningsMonthResponse {
+            month: month.month,
+            totals: month.totals.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct EarningsSummaryResponse {
+    pub professional_id: Uuid,
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    pub currency: String,
+    pub totals: EarningsTotalsResponse,
+    pub months: Vec<EarningsMonthResponse>,
+}
+
+impl From<EarningsSummaryDTO> for EarningsSummaryResponse {
+    fn from(summary: EarningsSummaryDTO) -> Self {
+        EarningsSummaryResponse {
+            professional_id: summary.professional_id,
+            from: summary.from,
+            to: summary.to,
+            currency: summary.currency,
+            totals: summary.totals.into(),
+            months: summary.months.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Amounts are in cents
+#[derive(Debug, Serialize, ToSchema)]
+struct PayoutResponse {
+    pub professional_id: Uuid,
+    pub professional_name: String,
+    pub professional_email: String,
+    pub entries: i64,
+    pub gross_amount: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub currency: String,
+}
+
+impl From<PayoutDTO> for PayoutResponse {
+    fn from(payout: PayoutDTO) -> Self {
+        PayoutResponse {
+            professional_id: payout.professional_id,
+            professional_name: payout.professional_name,
+            professional_email: payout.professional_email,
+            entries: payout.entries,
+            gross_amount: payout.gross_amount,
+            commission_amount: payout.commission_amount,
+            net_amount: payout.net_amount,
+            currency: payout.currency,
+        }
+    }
+}
+
+/// Payouts as CSV, amounts in units with two decimals so the file can be used as is by the accounting
+fn payouts_csv(payouts: &[PayoutDTO]) -> String {
+    let mut csv = String::from("professional_id,name,email,entries,gross_amount,commission_amount,net_amount,currency\n");
+
+    for payout in payouts {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            payout.professional_id,
+            csv_field(&payout.professional_name),
+            csv_field(&payout.professional_email),
+            payout.entries,
+            csv_amount(payout.gross_amount),
+            csv_amount(payout.commission_amount),
+            csv_amount(payout.net_amount),
+            payout.currency.to_uppercase(),
+        ));
+    }
+
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn csv_amount(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+
+    format!("{}{}.{:02}", sign, (cents / 100).abs(), (cents % 100).abs())
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route(
@@ -92,6 +220,26 @@ pub fn router() -> Router<AppState> {
                 .route_layer(require_professional_or_admin()),
         )
         .route("/selector", get(professionals_selector))
+        .route(
+            "/earnings", // Required: Verified Email + Professional Role, or Admin Role + professional_id
+            get(read_earnings)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_professional_or_admin()),
+        )
+        .route(
+            "/earnings/payouts", // Required: Verified Email + Admin Role
+            get(export_payouts)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .route(
+            "/earnings/payouts/settle", // Required: Verified Email + Admin Role
+            post(settle_payouts)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -81,6 +81,7 @@ fn authorized(auth_user: &AuthUser, _professional: &Professional) -> bool {
     match requesting_role {
         Role::Admin => true,
         Role::Patient => true,
+        Role::Receptionist => true,
         Role::Professional => {
             // TODO: [lazaropaul] A professional can only read their own profile, or ideally they can read other profs too?
             // For now allow any verified user to read professional profiles, as they are public on the platform
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::professional::PayoutResponse, app_error::AppResult, use_cases::earning::EarningUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PayoutsSettlePayload {
+    #[schema(example = "2030-01-31")]
+    until: chrono::NaiveDate,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PayoutsSettleResponse {
+    data: Vec<PayoutResponse>,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/professional/earnings/payouts/settle", 
+    responses( 
+        (status = 200, description = "Earnings marked as paid out, grouped by professional", body = PayoutsSettleResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 422, description = "The day has not ended yet"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional",
+    summary = "Marks what is owed to each professional up to a date (included) as paid out",
+    description = "Meant to be called once the payouts exported for the same date have been paid, only days that already ended can be settled.\n\n**Required:** Verified Email + Admin Role"
+)]
+#[instrument(skip(use_cases))]
+pub async fn settle_payouts(
+    State(use_cases): State<Arc<EarningUseCases>>,
+    Json(payload): Json<PayoutsSettlePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Settle payouts called");
+
+    let payouts = use_cases
+        .settle_payouts(payload.until)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(PayoutsSettleResponse { success: true, data: payouts.into_iter().map(Into::into).collect() }),
+    ))
+}
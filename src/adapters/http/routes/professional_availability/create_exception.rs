@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::{Path, State}, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable, professional_availability::can_manage}, app_error::{AppError, AppResult}, entities::professional_availability::AvailabilityException, use_cases::{professional::ProfessionalUseCases, professional_availability::ProfessionalAvailabilityUseCases}
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AvailabilityExceptionCreatePayload {
+    exception_date: chrono::NaiveDate,
+    #[schema(value_type = Option<String>, example = "09:00:00")]
+    start_time: Option<chrono::NaiveTime>, // Leave both start and end empty to block the whole day
+    #[schema(value_type = Option<String>, example = "13:00:00")]
+    end_time: Option<chrono::NaiveTime>,
+    reason: Option<String>,
+}
+
+impl Validateable for AvailabilityExceptionCreatePayload {
+    fn valid(&self) -> bool {
+        match (self.start_time, self.end_time) {
+            (None, None) => true,
+            (Some(start), Some(end)) => start < end,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilityExceptionCreateResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/professional/{id}/availability/exceptions", 
+    params(
+        ("id" = String, Path, description = "Professional id")
+    ),
+    responses( 
+        (status = 201, description = "Created", body = AvailabilityExceptionCreateResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional Availability",
+    summary = "Blocks the availability of a professional on a given date",
+    description = "\n\n**Required:** Verified Email + Admin/Receptionist Role or owning Professional"
+)]
+#[instrument(skip(professional_use_cases, availability_use_cases))]
+pub async fn create_availability_exception(
+    Extension(auth_user): Extension<AuthUser>,
+    State(professional_use_cases): State<Arc<ProfessionalUseCases>>,
+    State(availability_use_cases): State<Arc<ProfessionalAvailabilityUseCases>>,
+    Path(id): Path<String>,
+    Json(payload): Json<AvailabilityExceptionCreatePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Create availability exception called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let professional_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let professional = professional_use_cases
+        .read_single(&professional_uuid)
+        .await?;
+
+    if !can_manage(&auth_user, &professional) {
+        return Err(AppError::Unauthorized(
+            String::from("You don't have permission for this endpoint")
+        ));
+    }
+
+    let exception = AvailabilityException {
+        id: None,
+        professional_id: professional_uuid,
+        exception_date: payload.exception_date,
+        start_time: payload.start_time,
+        end_time: payload.end_time,
+        reason: payload.reason,
+        created_at: None,
+    };
+
+    availability_use_cases
+        .create_exception(&exception)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AvailabilityExceptionCreateResponse { success:true }),
+    ))
+}
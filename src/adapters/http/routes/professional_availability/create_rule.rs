@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::{Path, State}, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable, professional_availability::can_manage}, app_error::{AppError, AppResult}, entities::professional_availability::{ProfessionalAvailability, weekday_from_id}, use_cases::{professional::ProfessionalUseCases, professional_availability::ProfessionalAvailabilityUseCases}
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AvailabilityRuleCreatePayload {
+    weekday: i32, // ISO 8601 weekday, 1 = Monday ... 7 = Sunday
+    #[schema(value_type = String, example = "09:00:00")]
+    start_time: chrono::NaiveTime,
+    #[schema(value_type = String, example = "13:00:00")]
+    end_time: chrono::NaiveTime,
+    slot_duration: i32,
+}
+
+impl Validateable for AvailabilityRuleCreatePayload {
+    fn valid(&self) -> bool {
+        weekday_from_id(self.weekday).is_some() && self.start_time < self.end_time && self.slot_duration > 0
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilityRuleCreateResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/professional/{id}/availability/rules", 
+    params(
+        ("id" = String, Path, description = "Professional id")
+    ),
+    responses( 
+        (status = 201, description = "Created", body = AvailabilityRuleCreateResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional Availability",
+    summary = "Creates a weekly availability rule for a professional",
+    description = "\n\n**Required:** Verified Email + Admin/Receptionist Role or owning Professional"
+)]
+#[instrument(skip(professional_use_cases, availability_use_cases))]
+pub async fn create_availability_rule(
+    Extension(auth_user): Extension<AuthUser>,
+    State(professional_use_cases): State<Arc<ProfessionalUseCases>>,
+    State(availability_use_cases): State<Arc<ProfessionalAvailabilityUseCases>>,
+    Path(id): Path<String>,
+    Json(payload): Json<AvailabilityRuleCreatePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Create availability rule called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let professional_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let professional = professional_use_cases
+        .read_single(&professional_uuid)
+        .await?;
+
+    if !can_manage(&auth_user, &professional) {
+        return Err(AppError::Unauthorized(
+            String::from("You don't have permission for this endpoint")
+        ));
+    }
+
+    let rule = ProfessionalAvailability {
+        id: None,
+        professional_id: professional_uuid,
+        weekday: weekday_from_id(payload.weekday).unwrap_or(chrono::Weekday::Mon), // already checked on valid()
+        start_time: payload.start_time,
+        end_time: payload.end_time,
+        slot_duration: payload.slot_duration,
+        created_at: None,
+    };
+
+    availability_use_cases
+        .create_rule(&rule)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AvailabilityRuleCreateResponse { success:true }),
+    ))
+}
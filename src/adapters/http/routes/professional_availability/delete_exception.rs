@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::{Path, State}, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, professional_availability::can_manage}, app_error::{AppError, AppResult}, use_cases::{professional::ProfessionalUseCases, professional_availability::ProfessionalAvailabilityUseCases}
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilityExceptionDeleteResponse {
+    success: bool,
+}
+
+#[utoipa::path(delete, path = "/api/professional/{id}/availability/exceptions/{exception_id}", 
+    params(
+        ("id" = String, Path, description = "Professional id"),
+        ("exception_id" = String, Path, description = "Availability exception id")
+    ),
+    responses( 
+        (status = 200, description = "Deleted", body = AvailabilityExceptionDeleteResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional Availability",
+    summary = "Deletes an availability exception of a professional",
+    description = "\n\n**Required:** Verified Email + Admin/Receptionist Role or owning Professional"
+)]
+#[instrument(skip(professional_use_cases, availability_use_cases))]
+pub async fn delete_availability_exception(
+    Extension(auth_user): Extension<AuthUser>,
+    State(professional_use_cases): State<Arc<ProfessionalUseCases>>,
+    State(availability_use_cases): State<Arc<ProfessionalAvailabilityUseCases>>,
+    Path((id, exception_id)): Path<(String, String)>,
+) -> AppResult<impl IntoResponse> {
+    info!("Delete availability exception called");
+
+    let professional_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let exception_uuid = Uuid::parse_str(&exception_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let professional = professional_use_cases
+        .read_single(&professional_uuid)
+        .await?;
+
+    if !can_manage(&auth_user, &professional) {
+        return Err(AppError::Unauthorized(
+            String::from("You don't have permission for this endpoint")
+        ));
+    }
+
+    availability_use_cases
+        .delete_exception(&professional_uuid, &exception_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AvailabilityExceptionDeleteResponse { success:true }),
+    ))
+}
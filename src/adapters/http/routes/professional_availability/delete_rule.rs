@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::{Path, State}, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, professional_availability::can_manage}, app_error::{AppError, AppResult}, use_cases::{professional::ProfessionalUseCases, professional_availability::ProfessionalAvailabilityUseCases}
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilityRuleDeleteResponse {
+    success: bool,
+}
+
+#[utoipa::path(delete, path = "/api/professional/{id}/availability/rules/{rule_id}", 
+    params(
+        ("id" = String, Path, description = "Professional id"),
+        ("rule_id" = String, Path, description = "Availability rule id")
+    ),
+    responses( 
+        (status = 200, description = "Deleted", body = AvailabilityRuleDeleteResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional Availability",
+    summary = "Deletes a weekly availability rule of a professional",
+    description = "\n\n**Required:** Verified Email + Admin/Receptionist Role or owning Professional"
+)]
+#[instrument(skip(professional_use_cases, availability_use_cases))]
+pub async fn delete_availability_rule(
+    Extension(auth_user): Extension<AuthUser>,
+    State(professional_use_cases): State<Arc<ProfessionalUseCases>>,
+    State(availability_use_cases): State<Arc<ProfessionalAvailabilityUseCases>>,
+    Path((id, rule_id)): Path<(String, String)>,
+) -> AppResult<impl IntoResponse> {
+    info!("Delete availability rule called");
+
+    let professional_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let rule_uuid = Uuid::parse_str(&rule_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let professional = professional_use_cases
+        .read_single(&professional_uuid)
+        .await?;
+
+    if !can_manage(&auth_user, &professional) {
+        return Err(AppError::Unauthorized(
+            String::from("You don't have permission for this endpoint")
+        ));
+    }
+
+    availability_use_cases
+        .delete_rule(&professional_uuid, &rule_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AvailabilityRuleDeleteResponse { success:true }),
+    ))
+}
@@ -0,0 +1,145 @@
+use axum::{
+    Router, middleware,
+    routing::{delete, get},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::{
+        app_state::AppState,
+        rate_limit::RateLimitGroup,
+        routes::{
+            AuthUser, auth_middleware,
+            professional_availability::{
+                create_exception::create_availability_exception,
+                create_rule::create_availability_rule,
+                delete_exception::delete_availability_exception,
+                delete_rule::delete_availability_rule,
+                read_exceptions::read_availability_exceptions,
+                read_rules::read_availability_rules, read_slots::read_availability_slots,
+            },
+            rate_limit, rate_limit_middleware, verified_middleware,
+        },
+    },
+    entities::{
+        permission::Permission,
+        professional::Professional,
+        professional_availability::{
+            AvailabilityException, ProfessionalAvailability, TimeSlot, weekday_to_id,
+        },
+        user::Role,
+    },
+};
+
+pub mod create_exception;
+pub mod create_rule;
+pub mod delete_exception;
+pub mod delete_rule;
+pub mod read_exceptions;
+pub mod read_rules;
+pub mod read_slots;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct AvailabilityRuleResponse {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub weekday: i32, // ISO 8601 weekday, 1 = Monday ... 7 = Sunday
+    #[schema(value_type = String)]
+    pub start_time: chrono::NaiveTime,
+    #[schema(value_type = String)]
+    pub end_time: chrono::NaiveTime,
+    pub slot_duration: i32,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<ProfessionalAvailability> for AvailabilityRuleResponse {
+    fn from(rule: ProfessionalAvailability) -> Self {
+        AvailabilityRuleResponse {
+            id: rule.id.unwrap(), // This should never panic as this should never be null when responding
+            professional_id: rule.professional_id,
+            weekday: weekday_to_id(rule.weekday),
+            start_time: rule.start_time,
+            end_time: rule.end_time,
+            slot_duration: rule.slot_duration,
+            created_at: rule.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct AvailabilityExceptionResponse {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub exception_date: chrono::NaiveDate,
+    #[schema(value_type = Option<String>)]
+    pub start_time: Option<chrono::NaiveTime>,
+    #[schema(value_type = Option<String>)]
+    pub end_time: Option<chrono::NaiveTime>,
+    pub reason: Option<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<AvailabilityException> for AvailabilityExceptionResponse {
+    fn from(exception: AvailabilityException) -> Self {
+        AvailabilityExceptionResponse {
+            id: exception.id.unwrap(), // This should never panic as this should never be null when responding
+            professional_id: exception.professional_id,
+            exception_date: exception.exception_date,
+            start_time: exception.start_time,
+            end_time: exception.end_time,
+            reason: exception.reason,
+            created_at: exception.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct TimeSlotResponse {
+    pub start: chrono::NaiveDateTime,
+    pub end: chrono::NaiveDateTime,
+}
+
+impl From<TimeSlot> for TimeSlotResponse {
+    fn from(slot: TimeSlot) -> Self {
+        TimeSlotResponse {
+            start: slot.start,
+            end: slot.end,
+        }
+    }
+}
+
+/// Only the professional itself or the staff managing every agenda can manage the availability of a professional
+fn can_manage(auth_user: &AuthUser, professional: &Professional) -> bool {
+    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
+
+    requesting_role.can(Permission::ManageAvailability)
+        || professional
+            .user_id
+            .as_ref()
+            .map(|id| id.to_string() == auth_user.user_id)
+            .unwrap_or(false) // Don't allow if no user_id specified
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(read_availability_slots)) // Required: Verified Email
+        .route(
+            "/rules", // Required: Verified Email (Admin/Receptionist Role or Professional + owner to create)
+            get(read_availability_rules).post(create_availability_rule),
+        )
+        .route("/rules/{rule_id}", delete(delete_availability_rule)) // Required: Verified Email + Admin/Receptionist Role or Professional + owner
+        .route(
+            "/exceptions", // Required: Verified Email (Admin/Receptionist Role or Professional + owner to create)
+            get(read_availability_exceptions).post(create_availability_exception),
+        )
+        .route(
+            "/exceptions/{exception_id}", // Required: Verified Email + Admin/Receptionist Role or Professional + owner
+            delete(delete_availability_exception),
+        )
+        .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
+        .layer(middleware::from_fn(auth_middleware))
+}
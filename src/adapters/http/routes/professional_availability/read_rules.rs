@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::{Path, State}, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::professional_availability::AvailabilityRuleResponse, app_error::{AppError, AppResult}, use_cases::professional_availability::ProfessionalAvailabilityUseCases
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilityRulesReadResponse {
+    data: Vec<AvailabilityRuleResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/professional/{id}/availability/rules", 
+    params(
+        ("id" = String, Path, description = "Professional id")
+    ),
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = AvailabilityRulesReadResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional Availability",
+    summary = "Retrieves the weekly availability rules of a professional",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_availability_rules(
+    State(use_cases): State<Arc<ProfessionalAvailabilityUseCases>>,
+    Path(id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read availability rules called");
+
+    let professional_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let rules = use_cases
+        .read_rules(&professional_uuid)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AvailabilityRulesReadResponse { success:true , data: rules.into_iter().map(Into::into).collect() }),
+    ))
+}
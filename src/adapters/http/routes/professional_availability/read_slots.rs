@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::{Path, Query, State}, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{Validateable, professional_availability::TimeSlotResponse}, app_error::{AppError, AppResult}, use_cases::professional_availability::ProfessionalAvailabilityUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+pub struct AvailabilitySlotsQuery {
+    #[param(example = "2030-01-07")]
+    from: chrono::NaiveDate,
+    #[param(example = "2030-01-13")]
+    to: chrono::NaiveDate,
+}
+
+impl Validateable for AvailabilitySlotsQuery {
+    fn valid(&self) -> bool {
+        self.from <= self.to
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailabilitySlotsResponse {
+    data: Vec<TimeSlotResponse>,
+    success: bool,
+}
+
+#[utoipa::path(get, path = "/api/professional/{id}/availability", 
+    params(
+        ("id" = String, Path, description = "Professional id"),
+        AvailabilitySlotsQuery
+    ),
+    responses( 
+        (status = 200, description = "Data retrieved correctly", body = AvailabilitySlotsResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Professional Availability",
+    summary = "Retrieves the free slots of a professional between two dates (both included)",
+    description = "\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(use_cases))]
+pub async fn read_availability_slots(
+    State(use_cases): State<Arc<ProfessionalAvailabilityUseCases>>,
+    Path(id): Path<String>,
+    Query(params): Query<AvailabilitySlotsQuery>,
+) -> AppResult<impl IntoResponse> {
+    info!("Read availability slots called");
+    if !params.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let professional_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let slots = use_cases
+        .free_slots(&professional_uuid, params.from, params.to)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AvailabilitySlotsResponse { success:true , data: slots.into_iter().map(Into::into).collect() }),
+    ))
+}
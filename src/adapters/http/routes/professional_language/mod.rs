@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::{
     adapters::http::{
         app_state::AppState,
+        rate_limit::RateLimitGroup,
         routes::{
             auth_middleware,
             professional_language::{
@@ -18,7 +19,7 @@ use crate::{
                 update::update_professional_language,
             },
             require_admin, require_professional_or_admin, require_role_middleware,
-            verified_middleware,
+            rate_limit, rate_limit_middleware, verified_middleware,
         },
     },
     entities::professional_language::ProfessionalLanguage,
@@ -82,5 +83,7 @@ pub fn router() -> Router<AppState> {
                 .route_layer(require_professional_or_admin()),
         )
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware))
 }
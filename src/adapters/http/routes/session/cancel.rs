@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::user::Role, use_cases::cancellation::CancellationUseCases
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SessionCancelPayload {
+    session_id: String,
+}
+
+impl Validateable for SessionCancelPayload {
+    fn valid(&self) -> bool {
+        !self.session_id.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionCancelData {
+    refunded_amount: i64, // cents
+    currency: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionCancelResponse {
+    data: SessionCancelData,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/session/cancel", 
+    responses( 
+        (status = 200, description = "Cancelled", body = SessionCancelResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "The requesting user is not part of the session"),
+        (status = 404, description = "Session not found"),
+        (status = 409, description = "The session is already cancelled or completed"),
+        (status = 500, description = "Internal server error or database error"),
+        (status = 502, description = "Payment provider error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "Session",
+    summary = "Cancels a session and refunds it according to the cancellation policy",
+    description = "Patients get the refund allowed by the cancellation policy, sessions cancelled by the professional or staff are refunded in full. Both parties are notified by email.\n\n**Required:** Verified Email + Admin/Receptionist Role or session patient/professional"
+)]
+#[instrument(skip(use_cases))]
+pub async fn cancel_session(
+    Extension(auth_user): Extension<AuthUser>,
+    State(use_cases): State<Arc<CancellationUseCases>>,
+    Json(payload): Json<SessionCancelPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Cancel session called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let session_uuid = Uuid::parse_str(&payload.session_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let role = Role::from_id(auth_user.role_id).unwrap_or_default();
+
+    let cancellation = use_cases
+        .cancel_session(&session_uuid, &user_uuid, &role)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(SessionCancelResponse {
+            success: true,
+            data: SessionCancelData {
+                refunded_amount: cancellation.refunded_amount,
+                currency: cancellation.currency,
+            },
+        }),
+    ))
+}
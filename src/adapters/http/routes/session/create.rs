@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{Extension, extract::State, http::StatusCode, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::Validateable, app_error::{AppError, AppResult}, entities:: session::{Session, SessionStatus}, use_cases::session::SessionUseCases
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities:: session::{Session, SessionStatus}, use_cases::session::SessionUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema)]
@@ -15,10 +15,8 @@ pub struct SessionCreatePayload {
     patient_id: String,
     professional_id: String,
     session_type_id: Option<String>,
-    session_status_id: Option<i32>,
     session_date: Option<chrono::NaiveDateTime>,
     videocall_url: Option<String>,
-    notes: Option<String>,
     session_duration: Option<i32>,
 }
 
@@ -37,6 +35,9 @@ pub struct SessionCreateResponse {
     responses( 
         (status = 201, description = "Created", body = SessionCreateResponse),
         (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Neither staff nor the professional of the session"),
+        (status = 409, description = "The professional already has a session at the requested time"),
+        (status = 422, description = "The professional is not available at the requested time"),
         (status = 500, description = "Internal server error or database error")
     ),
     security(
@@ -44,10 +45,11 @@ pub struct SessionCreateResponse {
     ), 
     tag = "Session",
     summary = "Creates a new session",
-    description = "\n\n**Required:** Verified Email + Admin/Professional Role"
+    description = "session_date and session_duration are required and must fit inside an open slot of the professional availability. The session starts scheduled, patients book and pay theirs through the checkout.\n\n**Required:** Verified Email + Admin/Receptionist Role or session professional"
 )]
 #[instrument(skip(use_cases))]
 pub async fn create_session(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<SessionUseCases>>,
     Json(payload): Json<SessionCreatePayload>,
 ) -> AppResult<impl IntoResponse> {
@@ -64,10 +66,10 @@ pub async fn create_session(
         .map(|uid| Uuid::parse_str(&uid).map_err(|_| AppError::Internal("Invalid UUID string".into())))
         .transpose()?;
 
-    let session = Session { id: None, patient_id: patient_uuid, professional_id: professional_uuid, session_type_id: session_type_uuid, session_status: SessionStatus::from_id(payload.session_status_id.unwrap_or(1)).unwrap_or_default(), session_date: payload.session_date, videocall_url: payload.videocall_url, notes: payload.notes, completed: false, session_duration: payload.session_duration, created_at: None };
+    let session = Session { id: None, patient_id: patient_uuid, professional_id: professional_uuid, session_type_id: session_type_uuid, session_status: SessionStatus::Scheduled, session_date: payload.session_date, videocall_url: payload.videocall_url, videocall_provider: None, videocall_host_url: None, videocall_meeting_id: None, completed: false, session_duration: payload.session_duration, created_at: None };
 
     use_cases
-        .create(session)
+        .create(&auth_user.actor()?, session)
         .await?;
 
     Ok((
@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::Validateable, app_error::{AppError, AppResult}, use_cases::session::SessionUseCases
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, use_cases::session::SessionUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema)]
@@ -41,6 +41,7 @@ pub struct SessionDeleteResponse {
 )]
 #[instrument(skip(use_cases))]
 pub async fn delete_session(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<SessionUseCases>>,
     Json(payload): Json<SessionDeletePayload>,
 ) -> AppResult<impl IntoResponse> {
@@ -53,7 +54,7 @@ pub async fn delete_session(
     let session_uuid = Uuid::parse_str(&payload.session_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
 
     use_cases
-        .delete(&session_uuid)
+        .delete(&auth_user.actor()?, &session_uuid)
         .await?;
 
     Ok((
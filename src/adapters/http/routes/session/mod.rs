@@ -9,20 +9,22 @@ use uuid::Uuid;
 use crate::{
     adapters::http::{
         app_state::AppState,
+        rate_limit::RateLimitGroup,
         routes::{
-            auth_middleware, require_admin, require_professional_or_admin, require_role_middleware,
+            auth_middleware, require_admin, require_role_middleware, require_staff,
             session::{
-                create::create_session, delete::delete_session, patient::read_patient_sessions,
+                cancel::cancel_session, create::create_session, delete::delete_session, patient::read_patient_sessions,
                 professional::read_professional_sessions, read_all::read_all_sessions,
                 read_single::read_single_session, update::update_session,
                 videocall::get_videocall_url,
             },
-            verified_middleware,
+            rate_limit, rate_limit_middleware, verified_middleware,
         },
     },
     entities::session::Session,
 };
 
+pub mod cancel;
 pub mod create;
 pub mod delete;
 pub mod patient;
@@ -41,7 +43,7 @@ struct SessionResponse {
     pub session_status_id: i32,
     pub session_date: Option<chrono::NaiveDateTime>,
     pub videocall_url: Option<String>,
-    pub notes: Option<String>,
+    pub videocall_provider: Option<String>,
     pub completed: bool,
     pub session_duration: Option<i32>,
     pub created_at: Option<chrono::NaiveDateTime>,
@@ -57,7 +59,7 @@ impl From<Session> for SessionResponse {
             session_status_id: session.session_status.to_id(),
             session_date: session.session_date,
             videocall_url: session.videocall_url,
-            notes: session.notes,
+            videocall_provider: session.videocall_provider.map(|provider| provider.to_string()),
             completed: session.completed,
             session_duration: session.session_duration,
             created_at: session.created_at,
@@ -68,9 +70,10 @@ impl From<Session> for SessionResponse {
 pub fn router() -> Router<AppState> {
     Router::new()
         .route(
-            "/create", // Required: Verified Email
+            "/create", // Required: Verified Email + Admin/Receptionist Role or session professional
             post(create_session)
         )
+        .route("/cancel", post(cancel_session)) // Required: Verified Email + Admin/Receptionist Role or session patient/professional
         .route(
             "/delete", // Required: Verified Email + Admin Role
             delete(delete_session)
@@ -83,21 +86,18 @@ pub fn router() -> Router<AppState> {
                 .route_layer(middleware::from_fn(require_role_middleware))
                 .route_layer(require_admin()),
         )
+        .route("/single", get(read_single_session)) // Required: Verified Email + Admin/Receptionist Role or session patient/professional
+        .route("/patient", get(read_patient_sessions)) // Required: Verified Email + Admin/Receptionist Role, requesting patient_id or a Professional of the patient
+        .route("/professional", get(read_professional_sessions)) // Required: Verified Email + Receptionist/Admin Role or requesting professional_id
         .route(
-            "/single", // Required: Verified Email + Admin/Professional Role
-            get(read_single_session)
-                .route_layer(middleware::from_fn(require_role_middleware))
-                .route_layer(require_professional_or_admin()),
-        )
-        .route("/patient", get(read_patient_sessions)) // Required: Verified Email + Admin/Professional Role or requesting patient_id
-        .route("/professional", get(read_professional_sessions)) // Required: Verified Email + Admin Role or Professional + requesting professional_id
-        .route(
-            "/update", // Required: Verified Email + Admin/Professional Role
+            "/update", // Required: Verified Email + Admin/Receptionist Role or session professional
             patch(update_session)
                 .route_layer(middleware::from_fn(require_role_middleware))
-                .route_layer(require_professional_or_admin()),
+                .route_layer(require_staff()),
         )
         .route("/{id}/videocall", get(get_videocall_url))
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -7,7 +7,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{AuthUser, Validateable, session::SessionResponse}, app_error::{AppError, AppResult}, entities::{patient::Patient, user::Role}, use_cases::{patient::PatientUseCases, session::SessionUseCases}
+    adapters::http::routes::{AuthUser, Validateable, session::SessionResponse}, app_error::{AppError, AppResult}, use_cases::{patient::PatientUseCases, session::SessionUseCases}
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
@@ -40,7 +40,7 @@ pub struct SessionReadPatientResponse {
     ),
     tag = "Session",
     summary = "Retrieves data of all sessions of a given patient",
-    description = "\n\n**Required:**  Verified Email + Admin/Professional Role or requesting patient_id"
+    description = "\n\n**Required:** Verified Email + Admin/Receptionist Role, requesting patient_id or a Professional with sessions with the patient"
 )]
 #[instrument(skip(patient_use_cases, session_use_cases))]
 pub async fn read_patient_sessions(
@@ -56,19 +56,13 @@ pub async fn read_patient_sessions(
 
     let patient_uuid = Uuid::parse_str(&params.patient_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
 
-     let patient = patient_use_cases
-        .read_single(&patient_uuid)
+    // Fails unless the user can read the patient
+    patient_use_cases
+        .read_single(&auth_user.actor()?, &patient_uuid)
         .await?;
 
-    let is_authorized = authorized(&auth_user, &patient);
-    if !is_authorized {
-        return Err(AppError::Unauthorized(
-            String::from("You don't have permission for this endpoint")
-        ));
-    }
-
     let sessions = session_use_cases
-        .read_patient(&patient_uuid)
+        .read_patient(&auth_user.actor()?, &patient_uuid)
         .await?;
 
     Ok((
@@ -76,18 +70,3 @@ pub async fn read_patient_sessions(
         Json(SessionReadPatientResponse { success:true , data: sessions.into_iter().map(Into::into).collect() }),
     ))
 }
-
-fn authorized(auth_user: &AuthUser, patient: &Patient) -> bool {
-    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
-    
-    // Check authorization
-    match requesting_role {
-        Role::Admin | Role::Professional => true,
-        Role::Patient => {
-            patient.user_id
-                .as_ref()
-                .map(|id| id.to_string() == auth_user.user_id)
-                .unwrap_or(false) // Don't allow if no user_id specified
-        }
-    }
-}
\ No newline at end of file
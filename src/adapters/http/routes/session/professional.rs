@@ -7,7 +7,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{AuthUser, Validateable, session::SessionResponse}, app_error::{AppError, AppResult}, entities::{professional::Professional, user::Role}, use_cases::{professional::ProfessionalUseCases, session::SessionUseCases}
+    adapters::http::routes::{AuthUser, Validateable, session::SessionResponse}, app_error::{AppError, AppResult}, use_cases::session::SessionUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
@@ -33,6 +33,7 @@ pub struct SessionReadProfessionalResponse {
     responses( 
         (status = 200, description = "Data retrieved correctly", body = SessionReadProfessionalResponse),
         (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Neither staff nor the requested professional"),
         (status = 500, description = "Internal server error or database error")
     ),
     security(
@@ -40,12 +41,11 @@ pub struct SessionReadProfessionalResponse {
     ),
     tag = "Session",
     summary = "Retrieves data of all sessions of a given professional",
-    description = "\n\n**Required:**  Verified Email + Admin Role or Professional + requesting professional_id"
+    description = "Patients check when a professional can be booked with GET /api/professional/{id}/availability.\n\n**Required:**  Verified Email + Receptionist/Admin Role or requesting professional_id"
 )]
-#[instrument(skip(professional_use_cases, session_use_cases))]
+#[instrument(skip(session_use_cases))]
 pub async fn read_professional_sessions(
     Extension(auth_user): Extension<AuthUser>,
-    State(professional_use_cases): State<Arc<ProfessionalUseCases>>,
     State(session_use_cases): State<Arc<SessionUseCases>>,
     Query(params): Query<SessionReadProfessionalQuery>,
 ) -> AppResult<impl IntoResponse> {
@@ -56,19 +56,8 @@ pub async fn read_professional_sessions(
 
     let professional_uuid = Uuid::parse_str(&params.professional_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
 
-    let professional = professional_use_cases
-        .read_single(&professional_uuid)
-        .await?;
-
-    let is_authorized = authorized(&auth_user, &professional);
-    if !is_authorized {
-        return Err(AppError::Unauthorized(
-            String::from("You don't have permission for this endpoint")
-        ));
-    }
-
     let sessions = session_use_cases
-        .read_professional(&professional_uuid)
+        .read_professional(&auth_user.actor()?, &professional_uuid)
         .await?;
 
     Ok((
@@ -76,19 +65,3 @@ pub async fn read_professional_sessions(
         Json(SessionReadProfessionalResponse { success:true , data: sessions.into_iter().map(Into::into).collect() }),
     ))
 }
-
-fn authorized(auth_user: &AuthUser, professional: &Professional) -> bool {
-    let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
-    
-    // Check authorization
-    match requesting_role {
-        Role::Admin => true,
-        Role::Professional => {
-            professional.user_id
-                .as_ref()
-                .map(|id| id.to_string() == auth_user.user_id)
-                .unwrap_or(false) // Don't allow if no user_id specified
-        },
-        Role::Patient => true, // Patients need to fetch professional sessions to check availability
-    }
-}
\ No newline at end of file
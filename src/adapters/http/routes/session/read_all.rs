@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::{Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 
 use crate::{
-    adapters::http::routes::{session::SessionResponse}, app_error::AppResult, use_cases::session::SessionUseCases
+    adapters::http::routes::{AuthUser, session::SessionResponse}, app_error::AppResult, use_cases::session::SessionUseCases
 };
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -29,12 +29,13 @@ pub struct SessionReadAllResponse {
 )]
 #[instrument(skip(use_cases))]
 pub async fn read_all_sessions(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<SessionUseCases>>,
 ) -> AppResult<impl IntoResponse> {
     info!("Read all sessions called");
 
     let sessions = use_cases
-        .read_all()
+        .read_all(&auth_user.actor()?)
         .await?;
 
     Ok((
@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
-use axum::{extract::{Query, State}, http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::{Query, State}, http::StatusCode, response::IntoResponse, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::{session::SessionResponse, Validateable}, app_error::{AppError, AppResult}, use_cases::session::SessionUseCases
+    adapters::http::routes::{session::SessionResponse, AuthUser, Validateable}, app_error::{AppError, AppResult}, use_cases::session::SessionUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
@@ -33,6 +33,7 @@ pub struct SessionReadSingleResponse {
     responses( 
         (status = 200, description = "Data retrieved correctly", body = SessionReadSingleResponse),
         (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Not a party of the session"),
         (status = 500, description = "Internal server error or database error")
     ),
     security(
@@ -40,10 +41,11 @@ pub struct SessionReadSingleResponse {
     ),
     tag = "Session",
     summary = "Retrieves data of a single session",
-    description = "\n\n**Required:**  Verified Email + Admin/Professional Role"
+    description = "\n\n**Required:**  Verified Email + Admin/Receptionist Role or session patient/professional"
 )]
 #[instrument(skip(use_cases))]
 pub async fn read_single_session(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<SessionUseCases>>,
     Query(params): Query<SessionReadSingleQuery>,
 ) -> AppResult<impl IntoResponse> {
@@ -55,7 +57,7 @@ pub async fn read_single_session(
     let session_uuid = Uuid::parse_str(&params.session_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
 
     let session = use_cases
-        .read_single(&session_uuid)
+        .read_single(&auth_user.actor()?, &session_uuid)
         .await?;
 
     Ok((
@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::Validateable, app_error::{AppError, AppResult}, entities::session::{Session, SessionStatus}, use_cases::session::SessionUseCases
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::session::{Session, SessionStatus}, use_cases::session::SessionUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema)]
@@ -16,10 +16,8 @@ pub struct SessionUpdatePayload {
     patient_id: String,
     professional_id: String,
     session_type_id: Option<String>,
-    session_status_id: i32,
     session_date: Option<chrono::NaiveDateTime>,
     videocall_url: Option<String>,
-    notes: Option<String>,
     session_duration: Option<i32>,
 }
 
@@ -38,6 +36,8 @@ pub struct SessionUpdateResponse {
     responses( 
         (status = 200, description = "Updated", body = SessionUpdateResponse),
         (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Not the professional of the session"),
+        (status = 409, description = "The professional already has a session at the requested time, or the patient or the professional was changed"),
         (status = 500, description = "Internal server error or database error")
     ),
     security(
@@ -45,10 +45,11 @@ pub struct SessionUpdateResponse {
     ),
     tag = "Session",
     summary = "Updates a session",
-    description = "\n\n**Required:** Verified Email + Admin/Professional Role"
+    description = "The status of the session is kept, sessions are cancelled through POST /api/session/cancel so the payment is refunded.\n\n**Required:** Verified Email + Admin/Receptionist Role or session professional"
 )]
 #[instrument(skip(use_cases))]
 pub async fn update_session(
+    Extension(auth_user): Extension<AuthUser>,
     State(use_cases): State<Arc<SessionUseCases>>,
     Json(payload): Json<SessionUpdatePayload>,
 ) -> AppResult<impl IntoResponse> {
@@ -65,11 +66,11 @@ pub async fn update_session(
         .map(|uid| Uuid::parse_str(&uid).map_err(|_| AppError::Internal("Invalid UUID string".into())))
         .transpose()?;
 
-    let session = Session { id: Some(id), patient_id: patient_uuid, professional_id: professional_uuid, session_type_id: session_type_uuid, session_status: SessionStatus::from_id(payload.session_status_id).unwrap_or_default(), session_date: payload.session_date, videocall_url: payload.videocall_url, notes: payload.notes, completed: false, session_duration: payload.session_duration, created_at: None };
+    let session = Session { id: Some(id), patient_id: patient_uuid, professional_id: professional_uuid, session_type_id: session_type_uuid, session_status: SessionStatus::default(), session_date: payload.session_date, videocall_url: payload.videocall_url, videocall_provider: None, videocall_host_url: None, videocall_meeting_id: None, completed: false, session_duration: payload.session_duration, created_at: None };
 
 
     use_cases
-        .update(&session)
+        .update(&auth_user.actor()?, &session)
         .await?;
 
     Ok((
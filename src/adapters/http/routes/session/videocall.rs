@@ -8,6 +8,7 @@ use uuid::Uuid;
 use crate::{
     adapters::http::routes::AuthUser,
     app_error::{AppError, AppResult},
+    entities::user::Role,
     use_cases::session::SessionUseCases,
 };
 
@@ -19,8 +20,10 @@ pub struct VideoCallResponse {
 #[utoipa::path(get, path = "/api/session/{id}/videocall", 
     responses( 
         (status = 200, description = "Success", body = VideoCallResponse),
-        (status = 400, description = "Too early to join or missing data"),
+        (status = 401, description = "The requesting user is not part of the session"),
         (status = 404, description = "Session not found"),
+        (status = 409, description = "The session is cancelled"),
+        (status = 422, description = "Too early to join, the session is over or its date is not set"),
         (status = 500, description = "Internal server error")
     ),
     params(
@@ -31,23 +34,24 @@ pub struct VideoCallResponse {
     ), 
     tag = "Session",
     summary = "Gets or generates the videocall URL for a session",
-    description = "Available starting 5 minutes before the session starts."
+    description = "Available from a few minutes before the session starts until a few minutes after it ends, see VIDEOCALL_JOIN_MINUTES_BEFORE and VIDEOCALL_JOIN_MINUTES_AFTER. The patient gets the participant link, the professional and the admins the host link.\n\n**Required:** Verified Email + Admin Role or session patient/professional"
 )]
-#[instrument(skip(use_cases, _auth_user))]
+#[instrument(skip(use_cases, auth_user))]
 pub async fn get_videocall_url(
     State(use_cases): State<Arc<SessionUseCases>>,
-    Extension(_auth_user): Extension<AuthUser>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<String>,
 ) -> AppResult<impl IntoResponse> {
     info!("Get videocall URL called for session: {}", id);
 
     let session_uuid = Uuid::parse_str(&id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
     
-    let user_id = Uuid::parse_str(&_auth_user.user_id)
+    let user_id = Uuid::parse_str(&auth_user.user_id)
         .map_err(|_| AppError::Internal("Invalid User UUID in token".into()))?;
+    let role = Role::from_id(auth_user.role_id).unwrap_or_default();
 
     let url = use_cases
-        .get_videocall_url(&session_uuid, &user_id)
+        .get_videocall_url(&session_uuid, &user_id, &role)
         .await?;
 
     Ok(Json(VideoCallResponse { url }))
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::{
     adapters::http::{
         app_state::AppState,
+        rate_limit::RateLimitGroup,
         routes::{
             auth_middleware, require_admin, require_professional_or_admin, require_role_middleware,
             session_type::{
@@ -16,7 +17,7 @@ use crate::{
                 read_all::read_all_session_types, read_single::read_single_session_type,
                 update::update_session_type,
             },
-            verified_middleware,
+            rate_limit, rate_limit_middleware, verified_middleware,
         },
     },
     entities::session_type::SessionType,
@@ -68,5 +69,7 @@ pub fn router() -> Router<AppState> {
                 .route_layer(require_professional_or_admin()),
         )
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware))
 }
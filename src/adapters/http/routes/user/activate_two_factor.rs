@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ActivateTwoFactorPayload {
+    /// Current code of the authenticator app
+    #[schema(example = "123456")]
+    code: String,
+}
+
+impl Validateable for ActivateTwoFactorPayload {
+    fn valid(&self) -> bool {
+        !self.code.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActivateTwoFactorData {
+    /// Single use codes to login without the authenticator app, they are only shown now
+    recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActivateTwoFactorResponse {
+    data: ActivateTwoFactorData,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/two-factor/activate", 
+    request_body = ActivateTwoFactorPayload,
+    responses( 
+        (status = 200, description = "Ok, returns the recovery codes", body = ActivateTwoFactorResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid code"),
+        (status = 404, description = "Two factor enrollment not found"),
+        (status = 409, description = "Two factor authentication is already enabled"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Enables the two factor authentication of the requesting user",
+    description = "From now on the login asks for a code.\n\n**Required:** Verified Email, a pending enrollment from /api/user/two-factor/enroll"
+)]
+#[instrument(skip(user_use_cases, payload))]
+pub async fn activate_two_factor(
+    Extension(auth_user): Extension<AuthUser>,
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    Json(payload): Json<ActivateTwoFactorPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Activate two factor called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let recovery_codes = user_use_cases.activate_two_factor(&user_uuid, &payload.code).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ActivateTwoFactorResponse { success: true, data: ActivateTwoFactorData { recovery_codes } }),
+    ))
+}
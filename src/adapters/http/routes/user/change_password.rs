@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable, user::refresh_token_cookie},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ChangePasswordPayload {
+    #[schema(value_type = String, format = "password")]
+    current_password: SecretString,
+    #[schema(value_type = String, format = "password")]
+    new_password: SecretString,
+}
+
+impl Validateable for ChangePasswordPayload {
+    fn valid(&self) -> bool {
+        !self.current_password.expose_secret().is_empty() && !self.new_password.expose_secret().is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangePasswordResponse {
+    jwt: String,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/change-password", 
+    request_body = ChangePasswordPayload,
+    responses( 
+        (status = 200, description = "Ok, the new refresh token is set as an HttpOnly cookie", body = ChangePasswordResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid current password"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Changes the password of the requesting user",
+    description = "Every other login of the user is revoked, this one gets a new jwt and refresh token.\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(user_use_cases, jar, payload))]
+pub async fn change_password(
+    Extension(auth_user): Extension<AuthUser>,
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
+    Json(payload): Json<ChangePasswordPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Change password called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let tokens = user_use_cases
+        .change_password(&user_uuid, &payload.current_password, &payload.new_password)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        jar.add(refresh_token_cookie(&tokens)),
+        Json(ChangePasswordResponse { success: true, jwt: tokens.access_token }),
+    ))
+}
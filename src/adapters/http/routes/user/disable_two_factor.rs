@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable, user::removed_refresh_token_cookie},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct DisableTwoFactorPayload {
+    #[schema(value_type = String, format = "password")]
+    password: SecretString,
+}
+
+impl Validateable for DisableTwoFactorPayload {
+    fn valid(&self) -> bool {
+        !self.password.expose_secret().is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DisableTwoFactorResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/two-factor/disable", 
+    request_body = DisableTwoFactorPayload,
+    responses( 
+        (status = 200, description = "Ok, the refresh token cookie is removed", body = DisableTwoFactorResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid password"),
+        (status = 409, description = "Two factor authentication is required for the role of the user"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Disables the two factor authentication of the requesting user",
+    description = "Removes the secret and the recovery codes, every session of the user is revoked.\n\n**Required:** Verified Email, a role that does not require two factor authentication"
+)]
+#[instrument(skip(user_use_cases, jar, payload))]
+pub async fn disable_two_factor(
+    Extension(auth_user): Extension<AuthUser>,
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
+    Json(payload): Json<DisableTwoFactorPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Disable two factor called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    user_use_cases.disable_two_factor(&user_uuid, &payload.password).await?;
+
+    Ok((
+        StatusCode::OK,
+        jar.remove(removed_refresh_token_cookie()),
+        Json(DisableTwoFactorResponse { success: true }),
+    ))
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::AuthUser,
+    app_error::{AppError, AppResult},
+    dtos::user::two_factor::TwoFactorEnrollmentDTO,
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorEnrollmentData {
+    /// Base32 secret, for the users that can't scan the QR code
+    secret: String,
+    /// Show it as a QR code for the authenticator app
+    #[schema(example = "otpauth://totp/Mipsicored:user%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Mipsicored")]
+    otpauth_uri: String,
+}
+
+impl From<TwoFactorEnrollmentDTO> for TwoFactorEnrollmentData {
+    fn from(enrollment: TwoFactorEnrollmentDTO) -> Self {
+        TwoFactorEnrollmentData {
+            secret: enrollment.secret,
+            otpauth_uri: enrollment.otpauth_uri,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EnrollTwoFactorResponse {
+    data: TwoFactorEnrollmentData,
+    success: bool,
+}
+
+impl From<TwoFactorEnrollmentDTO> for EnrollTwoFactorResponse {
+    fn from(enrollment: TwoFactorEnrollmentDTO) -> Self {
+        EnrollTwoFactorResponse { success: true, data: enrollment.into() }
+    }
+}
+
+#[utoipa::path(post, path = "/api/user/two-factor/enroll", 
+    responses( 
+        (status = 200, description = "Ok, returns the secret and its provisioning uri", body = EnrollTwoFactorResponse),
+        (status = 409, description = "Two factor authentication is already enabled"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Starts the two factor enrollment of the requesting user",
+    description = "Nothing changes until it is activated with a code from the authenticator app at /api/user/two-factor/activate. Enrolling again replaces the pending secret.\n\n**Required:** Verified Email"
+)]
+#[instrument(skip(user_use_cases))]
+pub async fn enroll_two_factor(
+    Extension(auth_user): Extension<AuthUser>,
+    State(user_use_cases): State<Arc<UserUseCases>>,
+) -> AppResult<impl IntoResponse> {
+    info!("Enroll two factor called");
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    let enrollment = user_use_cases.enroll_two_factor(&user_uuid).await?;
+
+    Ok((StatusCode::OK, Json(EnrollTwoFactorResponse::from(enrollment))))
+}
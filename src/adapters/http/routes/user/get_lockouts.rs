@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::Validateable,
+    app_error::{AppError, AppResult},
+    entities::login_throttle::LoginLockout,
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+pub struct GetLockoutsQuery {
+    /// Lockouts of the last days, 7 by default and up to 365
+    #[param(example = 7)]
+    days: Option<i64>,
+}
+
+impl Validateable for GetLockoutsQuery {
+    fn valid(&self) -> bool {
+        self.days.is_none_or(|days| (1..=365).contains(&days))
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LockoutData {
+    id: Uuid,
+    /// account (the email typed) or ip
+    kind: String,
+    subject: String,
+    failures: i32,
+    locked_until: chrono::NaiveDateTime,
+    created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<LoginLockout> for LockoutData {
+    fn from(lockout: LoginLockout) -> Self {
+        LockoutData {
+            id: lockout.id,
+            kind: lockout.kind.to_string(),
+            subject: lockout.subject,
+            failures: lockout.failures,
+            locked_until: lockout.locked_until,
+            created_at: lockout.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GetLockoutsResponse {
+    success: bool,
+    data: Vec<LockoutData>,
+}
+
+#[utoipa::path(get, path = "/api/user/lockouts", 
+    params(GetLockoutsQuery),
+    responses( 
+        (status = 200, description = "Success", body = GetLockoutsResponse),
+        (status = 400, description = "Invalid query"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Get the login lockouts",
+    description = "Accounts and ips locked by too many failed logins, most recent first. \n\n
+        **Required:** Verified Email + Admin Role"
+)]
+#[instrument(skip(user_use_cases))]
+pub async fn get_lockouts(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    Query(params): Query<GetLockoutsQuery>,
+) -> AppResult<impl IntoResponse> {
+    info!("Get login lockouts called");
+
+    if !params.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let since = chrono::Utc::now().naive_utc() - chrono::Duration::days(params.days.unwrap_or(7));
+    let lockouts = user_use_cases.get_login_lockouts(since).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetLockoutsResponse {
+            success: true,
+            data: lockouts.into_iter().map(Into::into).collect(),
+        }),
+    ))
+}
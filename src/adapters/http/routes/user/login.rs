@@ -1,14 +1,16 @@
 use std::sync::Arc;
 
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 
 use crate::{
-    adapters::http::routes::Validateable,
+    adapters::http::routes::{ClientIp, Validateable, user::refresh_token_cookie},
     app_error::{AppError, AppResult},
+    dtos::user::login::LoginOutcomeDTO,
     use_cases::user::UserUseCases,
 };
 
@@ -28,22 +30,33 @@ impl Validateable for LoginPayload {
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
-    jwt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jwt: Option<String>,
+    /// Sent instead of the jwt when a second step is needed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pre_auth_token: Option<String>,
+    two_factor_required: bool,
+    two_factor_enrollment_required: bool,
     success: bool,
 }
 
 #[utoipa::path(post, path = "/api/user/login", 
     responses( 
-        (status = 200, description = "Ok", body = LoginResponse),
+        (status = 200, description = "Ok, the refresh token is set as an HttpOnly cookie unless a two factor step is required", body = LoginResponse),
         (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid credentials, the same whether the email exists or not"),
+        (status = 429, description = "Too many failed logins of the account or the ip, see the Retry-After header"),
         (status = 500, description = "Internal server error or database error")
     ), 
     tag = "User",
-    summary = "Login as a specific user"
+    summary = "Login as a specific user",
+    description = "The jwt is short lived, use /api/user/refresh to get a new one.\n\nUsers with two factor authentication get a pre auth token instead, exchange it at /api/user/login/two-factor. When the role of the user requires two factor authentication and it is not enabled yet, enroll with /api/user/login/two-factor/enroll and /api/user/login/two-factor/activate.\n\nAfter a few failed logins every new one doubles the wait before the next attempt, too many of them lock the account (or the ip) for a while."
 )]
-#[instrument(skip(user_use_cases))]
+#[instrument(skip(user_use_cases, jar))]
 pub async fn login(
     State(user_use_cases): State<Arc<UserUseCases>>,
+    ClientIp(ip): ClientIp,
+    jar: CookieJar,
     Json(payload): Json<LoginPayload>,
 ) -> AppResult<impl IntoResponse> {
     info!("Login user called");
@@ -52,12 +65,42 @@ pub async fn login(
         return AppResult::Err(AppError::InvalidPayload);
     }
 
-    let jwt = user_use_cases
-        .login(&payload.email, &payload.password)
+    let outcome = user_use_cases
+        .login(&payload.email, &payload.password, &ip)
         .await?;
 
-    Ok((
-        StatusCode::OK,
-        Json(LoginResponse { success: true, jwt }),
-    ))
+    let (jar, response) = match outcome {
+        LoginOutcomeDTO::Authenticated(tokens) => (
+            jar.add(refresh_token_cookie(&tokens)),
+            LoginResponse {
+                jwt: Some(tokens.access_token),
+                pre_auth_token: None,
+                two_factor_required: false,
+                two_factor_enrollment_required: false,
+                success: true,
+            },
+        ),
+        LoginOutcomeDTO::TwoFactorRequired { pre_auth_token } => (
+            jar,
+            LoginResponse {
+                jwt: None,
+                pre_auth_token: Some(pre_auth_token),
+                two_factor_required: true,
+                two_factor_enrollment_required: false,
+                success: true,
+            },
+        ),
+        LoginOutcomeDTO::TwoFactorEnrollmentRequired { pre_auth_token } => (
+            jar,
+            LoginResponse {
+                jwt: None,
+                pre_auth_token: Some(pre_auth_token),
+                two_factor_required: true,
+                two_factor_enrollment_required: true,
+                success: true,
+            },
+        ),
+    };
+
+    Ok((StatusCode::OK, jar, Json(response)))
 }
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::{Validateable, user::refresh_token_cookie},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct LoginActivateTwoFactorPayload {
+    pre_auth_token: String,
+    /// Current code of the authenticator app
+    #[schema(example = "123456")]
+    code: String,
+}
+
+impl Validateable for LoginActivateTwoFactorPayload {
+    fn valid(&self) -> bool {
+        !self.pre_auth_token.is_empty() && !self.code.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginActivateTwoFactorResponse {
+    jwt: String,
+    /// Single use codes to login without the authenticator app, they are only shown now
+    recovery_codes: Vec<String>,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/login/two-factor/activate", 
+    request_body = LoginActivateTwoFactorPayload,
+    responses( 
+        (status = 200, description = "Ok, returns the recovery codes and the refresh token is set as an HttpOnly cookie", body = LoginActivateTwoFactorResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid or expired pre auth token, or invalid code"),
+        (status = 404, description = "Two factor enrollment not found"),
+        (status = 409, description = "Two factor authentication is already enabled"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User",
+    summary = "Enables the two factor authentication during the login and logs the user in",
+    description = "For the users whose role requires two factor authentication, after /api/user/login/two-factor/enroll."
+)]
+#[instrument(skip(user_use_cases, jar, payload))]
+pub async fn login_activate_two_factor(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
+    Json(payload): Json<LoginActivateTwoFactorPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Login activate two factor called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let activation = user_use_cases
+        .activate_two_factor_on_login(&payload.pre_auth_token, &payload.code)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        jar.add(refresh_token_cookie(&activation.tokens)),
+        Json(LoginActivateTwoFactorResponse {
+            success: true,
+            jwt: activation.tokens.access_token,
+            recovery_codes: activation.recovery_codes,
+        }),
+    ))
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Deserialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::{Validateable, user::enroll_two_factor::EnrollTwoFactorResponse},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct LoginEnrollTwoFactorPayload {
+    pre_auth_token: String,
+}
+
+impl Validateable for LoginEnrollTwoFactorPayload {
+    fn valid(&self) -> bool {
+        !self.pre_auth_token.is_empty()
+    }
+}
+
+#[utoipa::path(post, path = "/api/user/login/two-factor/enroll", 
+    request_body = LoginEnrollTwoFactorPayload,
+    responses( 
+        (status = 200, description = "Ok, returns the secret and its provisioning uri", body = EnrollTwoFactorResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid or expired pre auth token"),
+        (status = 409, description = "Two factor authentication is already enabled"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User",
+    summary = "Starts the two factor enrollment during the login",
+    description = "For the users whose role requires two factor authentication, with the pre auth token of /api/user/login. Activate it at /api/user/login/two-factor/activate."
+)]
+#[instrument(skip(user_use_cases, payload))]
+pub async fn login_enroll_two_factor(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    Json(payload): Json<LoginEnrollTwoFactorPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Login enroll two factor called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let enrollment = user_use_cases
+        .enroll_two_factor_on_login(&payload.pre_auth_token)
+        .await?;
+
+    Ok((StatusCode::OK, Json(EnrollTwoFactorResponse::from(enrollment))))
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::{ClientIp, Validateable, user::refresh_token_cookie},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct LoginTwoFactorPayload {
+    pre_auth_token: String,
+    /// TOTP code of the authenticator app or one of the recovery codes
+    #[schema(example = "123456")]
+    code: String,
+}
+
+impl Validateable for LoginTwoFactorPayload {
+    fn valid(&self) -> bool {
+        !self.pre_auth_token.is_empty() && !self.code.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginTwoFactorResponse {
+    jwt: String,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/login/two-factor", 
+    request_body = LoginTwoFactorPayload,
+    responses( 
+        (status = 200, description = "Ok, the refresh token is set as an HttpOnly cookie", body = LoginTwoFactorResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid or expired pre auth token, or invalid code"),
+        (status = 429, description = "Too many failed logins of the account or the ip, see the Retry-After header"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User",
+    summary = "Second login step of the users with two factor authentication",
+    description = "Each recovery code can only be used once. Invalid codes count as failed logins."
+)]
+#[instrument(skip(user_use_cases, jar, payload))]
+pub async fn login_two_factor(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    ClientIp(ip): ClientIp,
+    jar: CookieJar,
+    Json(payload): Json<LoginTwoFactorPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Login two factor called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let tokens = user_use_cases
+        .login_two_factor(&payload.pre_auth_token, &payload.code, &ip)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        jar.add(refresh_token_cookie(&tokens)),
+        Json(LoginTwoFactorResponse { success: true, jwt: tokens.access_token }),
+    ))
+}
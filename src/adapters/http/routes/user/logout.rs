@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::user::{REFRESH_TOKEN_COOKIE, removed_refresh_token_cookie},
+    app_error::AppResult,
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LogoutResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/logout", 
+    responses( 
+        (status = 200, description = "Ok, the refresh token cookie is removed", body = LogoutResponse),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User",
+    summary = "Revokes the refresh token cookie",
+    description = "The jwt already issued keeps working until it expires, the client should drop it."
+)]
+#[instrument(skip(user_use_cases, jar))]
+pub async fn logout(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
+) -> AppResult<impl IntoResponse> {
+    info!("Logout called");
+
+    if let Some(cookie) = jar.get(REFRESH_TOKEN_COOKIE) {
+        user_use_cases.logout(cookie.value()).await?;
+    }
+
+    Ok((
+        StatusCode::OK,
+        jar.remove(removed_refresh_token_cookie()),
+        Json(LogoutResponse { success: true }),
+    ))
+}
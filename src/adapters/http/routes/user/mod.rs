@@ -1,7 +1,8 @@
 use axum::{
     Router, middleware,
-    routing::{get, post},
+    routing::{get, post, put},
 };
+use axum_extra::extract::cookie::{Cookie, SameSite};
 use serde::Serialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -11,16 +12,40 @@ use crate::adapters::http::routes::{
     user::{onboard::onboard_user, register::register},
 };
 use crate::adapters::http::routes::{user::login::login, verified_middleware};
-use crate::adapters::http::{app_state::AppState, routes::auth_middleware};
-use crate::{adapters::http::routes::user::get_all::get_all_users, entities::user::User};
+use crate::adapters::http::{
+    app_state::AppState,
+    rate_limit::RateLimitGroup,
+    routes::{auth_middleware, rate_limit, rate_limit_middleware},
+};
+use crate::{
+    adapters::http::routes::user::get_all::get_all_users, dtos::user::auth_tokens::AuthTokensDTO,
+    entities::user::User,
+};
 
+pub mod change_password;
+pub mod activate_two_factor;
+pub mod disable_two_factor;
+pub mod enroll_two_factor;
 pub mod get_all;
+pub mod get_lockouts;
 pub mod get_me;
 pub mod login;
+pub mod login_activate_two_factor;
+pub mod login_enroll_two_factor;
+pub mod login_two_factor;
+pub mod logout;
 pub mod onboard;
+pub mod refresh;
 pub mod register;
+pub mod revoke_sessions;
+pub mod set_role;
+pub mod two_factor_policy;
 pub mod upload_profile_picture;
 
+/// The refresh token is kept away from js and only sent back to the user endpoints
+const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+const REFRESH_TOKEN_COOKIE_PATH: &str = "/api/user";
+
 #[derive(Debug, Serialize, ToSchema)]
 struct UserResponse {
     pub id: Uuid,
@@ -55,10 +80,38 @@ impl From<User> for UserResponse {
     }
 }
 
+fn refresh_token_cookie(tokens: &AuthTokensDTO) -> Cookie<'static> {
+    let max_age = tokens.refresh_token_expires_at - chrono::Utc::now().naive_utc();
+
+    Cookie::build((REFRESH_TOKEN_COOKIE, tokens.refresh_token.clone()))
+        .path(REFRESH_TOKEN_COOKIE_PATH)
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .max_age(time::Duration::seconds(max_age.num_seconds()))
+        .build()
+}
+
+/// Removing needs the same path the cookie was set with
+fn removed_refresh_token_cookie() -> Cookie<'static> {
+    Cookie::build(REFRESH_TOKEN_COOKIE).path(REFRESH_TOKEN_COOKIE_PATH).build()
+}
+
 pub fn router() -> Router<AppState> {
-    let public_routes = Router::new()
+    let auth_routes = Router::new()
         .route("/register", post(register))
-        .route("/login", post(login));
+        .route("/login", post(login))
+        .route("/login/two-factor", post(login_two_factor::login_two_factor))
+        .route("/login/two-factor/enroll", post(login_enroll_two_factor::login_enroll_two_factor))
+        .route("/login/two-factor/activate", post(login_activate_two_factor::login_activate_two_factor))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Auth));
+
+    let public_routes = Router::new()
+        .route("/refresh", post(refresh::refresh))
+        .route("/logout", post(logout::logout))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Public));
 
     let protected_routes = Router::new()
         .route(
@@ -67,11 +120,36 @@ pub fn router() -> Router<AppState> {
                 .route_layer(middleware::from_fn(require_role_middleware))
                 .route_layer(require_admin()), // Extension needs to go AFTER the middleware
         )
+        .route(
+            "/lockouts",
+            get(get_lockouts::get_lockouts)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
+        .route(
+            "/role",
+            put(set_role::set_role)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
         .route("/onboarded", post(onboard_user))
         .route("/profile-picture", post(upload_profile_picture::upload_profile_picture))
         .route("/me", get(get_me::get_me))
+        .route("/sessions/revoke", post(revoke_sessions::revoke_sessions))
+        .route("/change-password", post(change_password::change_password))
+        .route("/two-factor/enroll", post(enroll_two_factor::enroll_two_factor))
+        .route("/two-factor/activate", post(activate_two_factor::activate_two_factor))
+        .route("/two-factor/disable", post(disable_two_factor::disable_two_factor))
+        .route(
+            "/two-factor/policy",
+            put(two_factor_policy::set_two_factor_policy)
+                .route_layer(middleware::from_fn(require_role_middleware))
+                .route_layer(require_admin()),
+        )
         .layer(middleware::from_fn(verified_middleware))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware)); // Main auth middleware always has to be the LAST
 
-    Router::new().merge(public_routes).merge(protected_routes)
+    Router::new().merge(auth_routes).merge(public_routes).merge(protected_routes)
 }
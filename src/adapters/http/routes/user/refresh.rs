@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::user::{REFRESH_TOKEN_COOKIE, refresh_token_cookie},
+    app_error::{AppError, AppResult},
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RefreshResponse {
+    jwt: String,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/refresh", 
+    responses( 
+        (status = 200, description = "Ok, the new refresh token replaces the cookie", body = RefreshResponse),
+        (status = 401, description = "Missing, expired or revoked refresh token"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User",
+    summary = "Gets a new jwt with the refresh token cookie",
+    description = "Every refresh token can only be used once. Using one again revokes every token of its login, the user has to log in again."
+)]
+#[instrument(skip(user_use_cases, jar))]
+pub async fn refresh(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
+) -> AppResult<impl IntoResponse> {
+    info!("Refresh token called");
+
+    let refresh_token = jar
+        .get(REFRESH_TOKEN_COOKIE)
+        .map(|cookie| cookie.value().to_string())
+        .ok_or_else(|| AppError::Unauthorized(String::from("Missing refresh token")))?;
+
+    let tokens = user_use_cases.refresh(&refresh_token).await?;
+
+    Ok((
+        StatusCode::OK,
+        jar.add(refresh_token_cookie(&tokens)),
+        Json(RefreshResponse { success: true, jwt: tokens.access_token }),
+    ))
+}
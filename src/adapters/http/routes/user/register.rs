@@ -1,14 +1,17 @@
 use std::sync::Arc;
 
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 use crate::{
-    adapters::http::routes::Validateable,
+    adapters::http::routes::{Validateable, user::refresh_token_cookie},
     app_error::{AppError, AppResult},
+    entities::organization::DEFAULT_ORGANIZATION_ID,
     use_cases::user::UserUseCases,
 };
 
@@ -19,6 +22,8 @@ pub struct RegisterPayload {
     email: String,
     #[schema(value_type = String, format = "password")]
     password: SecretString,
+    /// Clinic the user registers into, the default one when missing
+    organization_id: Option<String>,
 }
 
 impl Validateable for RegisterPayload {
@@ -39,16 +44,18 @@ pub struct RegisterResponse {
 
 #[utoipa::path(post, path = "/api/user/register", 
     responses( 
-        (status = 201, description = "Created", body = RegisterResponse),
+        (status = 201, description = "Created, the refresh token is set as an HttpOnly cookie", body = RegisterResponse),
         (status = 400, description = "Invalid payload"),
+        (status = 404, description = "Organization not found"),
         (status = 500, description = "Internal server error or database error")
     ), 
     tag = "User",
     summary = "Creates a new user based on the submitted credentials"
 )]
-#[instrument(skip(user_use_cases))]
+#[instrument(skip(user_use_cases, jar))]
 pub async fn register(
     State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
     Json(payload): Json<RegisterPayload>,
 ) -> AppResult<impl IntoResponse> {
     info!("Register user called");
@@ -57,17 +64,24 @@ pub async fn register(
         return AppResult::Err(AppError::InvalidPayload);
     }
 
-    let jwt = user_use_cases
+    let organization_uuid = match &payload.organization_id {
+        Some(organization_id) => Uuid::parse_str(organization_id).map_err(|_| AppError::InvalidPayload)?,
+        None => DEFAULT_ORGANIZATION_ID,
+    };
+
+    let tokens = user_use_cases
         .add(
             &payload.username,
             &payload.usersurname,
             &payload.email,
             &payload.password,
+            &organization_uuid,
         )
         .await?;
 
     Ok((
         StatusCode::CREATED,
-        Json(RegisterResponse {success:true, jwt }),
+        jar.add(refresh_token_cookie(&tokens)),
+        Json(RegisterResponse { success: true, jwt: tokens.access_token }),
     ))
 }
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, user::removed_refresh_token_cookie},
+    app_error::{AppError, AppResult},
+    entities::user::Role,
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RevokeSessionsPayload {
+    /// Only used by admins, the rest of the users always revoke their own sessions
+    #[schema(example = "insert-user-uuid")]
+    user_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokeSessionsData {
+    revoked: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokeSessionsResponse {
+    data: RevokeSessionsData,
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user/sessions/revoke", 
+    request_body = RevokeSessionsPayload,
+    responses( 
+        (status = 200, description = "Ok, returns the number of refresh tokens revoked", body = RevokeSessionsResponse),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Logs a user out of every device",
+    description = "Revokes every refresh token of the user, the jwts already issued keep working until they expire.\n\n**Required:** Verified Email, Admin Role to revoke the sessions of another user"
+)]
+#[instrument(skip(user_use_cases, jar))]
+pub async fn revoke_sessions(
+    Extension(auth_user): Extension<AuthUser>,
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    jar: CookieJar,
+    Json(payload): Json<RevokeSessionsPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Revoke sessions called");
+
+    let own_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+    let role = Role::from_id(auth_user.role_id).unwrap_or_default();
+
+    let user_uuid = match (role, &payload.user_id) {
+        (Role::Admin, Some(user_id)) => Uuid::parse_str(user_id).map_err(|_| AppError::InvalidPayload)?,
+        _ => own_uuid,
+    };
+
+    let revoked = user_use_cases.revoke_sessions(&user_uuid).await?;
+
+    // Revoking the own sessions logs out this device as well
+    let jar = if user_uuid == own_uuid { jar.remove(removed_refresh_token_cookie()) } else { jar };
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(RevokeSessionsResponse { success: true, data: RevokeSessionsData { revoked } }),
+    ))
+}
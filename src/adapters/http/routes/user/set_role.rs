@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::user::Role,
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetRolePayload {
+    user_id: String,
+    /// 1 Patient, 2 Professional, 3 Admin, 4 Receptionist
+    #[schema(example = 4)]
+    role_id: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetRoleResponse {
+    success: bool,
+}
+
+#[utoipa::path(put, path = "/api/user/role", 
+    request_body = SetRolePayload,
+    responses( 
+        (status = 200, description = "Ok", body = SetRoleResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Sets the role of a user",
+    description = "The user gets the rights of the new role once the access token is refreshed.\n\n**Required:** Verified Email, Admin Role"
+)]
+#[instrument(skip(user_use_cases))]
+pub async fn set_role(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    Json(payload): Json<SetRolePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Set role called");
+
+    let user_uuid = Uuid::parse_str(&payload.user_id).map_err(|_| AppError::InvalidPayload)?;
+    let role = Role::from_id(payload.role_id).ok_or(AppError::InvalidPayload)?;
+
+    user_use_cases
+        .set_role(&user_uuid, &role)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SetRoleResponse { success: true })))
+}
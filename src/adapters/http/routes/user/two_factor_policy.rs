@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::user::Role,
+    use_cases::user::UserUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TwoFactorPolicyPayload {
+    /// 1 Patient, 2 Professional, 3 Admin, 4 Receptionist
+    #[schema(example = 2)]
+    role_id: i32,
+    required: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorPolicyResponse {
+    success: bool,
+}
+
+#[utoipa::path(put, path = "/api/user/two-factor/policy", 
+    request_body = TwoFactorPolicyPayload,
+    responses( 
+        (status = 200, description = "Ok", body = TwoFactorPolicyResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User",
+    summary = "Sets whether the users of a role need two factor authentication",
+    description = "Users of a role that requires it have to enroll on their next login and can't disable it. The sessions already open are kept.\n\n**Required:** Verified Email, Admin Role"
+)]
+#[instrument(skip(user_use_cases))]
+pub async fn set_two_factor_policy(
+    State(user_use_cases): State<Arc<UserUseCases>>,
+    Json(payload): Json<TwoFactorPolicyPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Set two factor policy called");
+
+    let role = Role::from_id(payload.role_id).ok_or(AppError::InvalidPayload)?;
+
+    user_use_cases
+        .set_two_factor_requirement(&role, payload.required)
+        .await?;
+
+    Ok((StatusCode::OK, Json(TwoFactorPolicyResponse { success: true })))
+}
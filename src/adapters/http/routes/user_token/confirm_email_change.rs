@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::Validateable,
+    app_error::{AppError, AppResult},
+    use_cases::user_token::UserTokenUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ConfirmEmailChangePayload {
+    token: String,
+}
+
+impl Validateable for ConfirmEmailChangePayload {
+    fn valid(&self) -> bool {
+        !self.token.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmEmailChangeResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user_token/confirm-email-change", 
+    request_body = ConfirmEmailChangePayload,
+    responses( 
+        (status = 200, description = "Ok, the email is changed", body = ConfirmEmailChangeResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid or expired token"),
+        (status = 409, description = "Email already in use"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User Token",
+    summary = "Swaps in the new email using an email change token",
+    description = "The user is verified with the new email. Every session of the user is revoked, they have to login again."
+)]
+#[instrument(skip(user_token_use_cases, payload))]
+pub async fn confirm_email_change(
+    State(user_token_use_cases): State<Arc<UserTokenUseCases>>,
+    Json(payload): Json<ConfirmEmailChangePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Confirm email change called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    user_token_use_cases.confirm_email_change(&payload.token).await?;
+
+    Ok((StatusCode::OK, Json(ConfirmEmailChangeResponse { success: true })))
+}
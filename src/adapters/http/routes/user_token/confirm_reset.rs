@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::Validateable,
+    app_error::{AppError, AppResult},
+    use_cases::user_token::UserTokenUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ConfirmResetPayload {
+    token: String,
+    #[schema(value_type = String, format = "password")]
+    password: SecretString,
+}
+
+impl Validateable for ConfirmResetPayload {
+    fn valid(&self) -> bool {
+        !self.token.is_empty() && !self.password.expose_secret().is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmResetResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user_token/confirm-reset", 
+    request_body = ConfirmResetPayload,
+    responses( 
+        (status = 200, description = "Ok, the password is changed", body = ConfirmResetResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid or expired token"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User Token",
+    summary = "Sets a new password using a password reset token",
+    description = "Every session of the user is revoked, they have to login again."
+)]
+#[instrument(skip(user_token_use_cases, payload))]
+pub async fn confirm_reset(
+    State(user_token_use_cases): State<Arc<UserTokenUseCases>>,
+    Json(payload): Json<ConfirmResetPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Confirm password reset called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    user_token_use_cases
+        .confirm_password_reset(&payload.token, &payload.password)
+        .await?;
+
+    Ok((StatusCode::OK, Json(ConfirmResetResponse { success: true })))
+}
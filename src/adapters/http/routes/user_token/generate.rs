@@ -6,7 +6,7 @@ use tracing::{info, instrument};
 use utoipa::ToSchema;
 
 use crate::{
-    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::user::Role, use_cases::user_token::UserTokenUseCases
+    adapters::http::routes::{AuthUser, Validateable}, app_error::{AppError, AppResult}, entities::{permission::Permission, user::Role}, use_cases::user_token::UserTokenUseCases
 };
 
 #[derive(Debug, Clone, Deserialize, ToSchema)]
@@ -37,7 +37,7 @@ pub struct GenerateResponse {
     ),
     tag = "User Token",
     summary = "Generates a new verification token for the given user_id and sends them a confirmation email",
-    description = "\n\n**Required:** Admin Role OR Generating for requesting user_id"
+    description = "\n\n**Required:** Admin/Receptionist Role OR Patient generating for requesting user_id"
 )]
 #[instrument(skip(user_token_use_cases))]
 pub async fn generate_token(
@@ -73,11 +73,6 @@ fn authorized(auth_user: &AuthUser, payload: &GeneratePayload) -> bool {
     let requesting_role = Role::from_id(auth_user.role_id).unwrap_or_default();
     
     // Check authorization
-    match requesting_role {
-        Role::Admin => true,
-        Role::Patient => {
-            payload.user_id == auth_user.user_id
-        }
-        _ => false
-    }
+    requesting_role.can(Permission::SendVerificationEmails)
+        || (requesting_role == Role::Patient && payload.user_id == auth_user.user_id)
 }
\ No newline at end of file
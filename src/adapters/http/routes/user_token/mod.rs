@@ -4,13 +4,25 @@ use serde::Serialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::adapters::http::routes::user_token::confirm_email_change::confirm_email_change;
+use crate::adapters::http::routes::user_token::confirm_reset::confirm_reset;
 use crate::adapters::http::routes::user_token::generate::generate_token;
+use crate::adapters::http::routes::user_token::request_email_change::request_email_change;
+use crate::adapters::http::routes::user_token::request_reset::request_reset;
 use crate::adapters::http::routes::user_token::validate::validate_token;
 use crate::adapters::http::routes::user_token::verify::verify;
-use crate::adapters::http::{app_state::AppState, routes::auth_middleware};
+use crate::adapters::http::{
+    app_state::AppState,
+    rate_limit::RateLimitGroup,
+    routes::{auth_middleware, rate_limit, rate_limit_middleware},
+};
 use crate::entities::user_token::UserToken;
 
+pub mod confirm_email_change;
+pub mod confirm_reset;
 pub mod generate;
+pub mod request_email_change;
+pub mod request_reset;
 pub mod validate;
 pub mod verify;
 
@@ -37,11 +49,36 @@ impl From<UserToken> for UserTokenResponse {
 }
 
 pub fn router() -> Router<AppState> {
-    let public_routes = Router::new().route("/verify", get(verify));
+    // The routes sending an email are also limited as a group, every email counts against our sending quota
+    let public_routes = Router::new()
+        .route("/verify", get(verify))
+        .route(
+            "/request-reset",
+            post(request_reset)
+                .route_layer(middleware::from_fn(rate_limit_middleware))
+                .route_layer(rate_limit(RateLimitGroup::Email)),
+        )
+        .route("/confirm-reset", post(confirm_reset))
+        .route("/confirm-email-change", post(confirm_email_change))
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Public));
 
     let protected_routes = Router::new()
-        .route("/generate", post(generate_token)) // **Required:** Admin Role OR Generating for requesting user_id
+        .route(
+            "/generate", // **Required:** Admin Role OR Generating for requesting user_id
+            post(generate_token)
+                .route_layer(middleware::from_fn(rate_limit_middleware))
+                .route_layer(rate_limit(RateLimitGroup::Email)),
+        )
         .route("/validate", post(validate_token))
+        .route(
+            "/request-email-change",
+            post(request_email_change)
+                .route_layer(middleware::from_fn(rate_limit_middleware))
+                .route_layer(rate_limit(RateLimitGroup::Email)),
+        )
+        .layer(middleware::from_fn(rate_limit_middleware))
+        .layer(rate_limit(RateLimitGroup::Protected))
         .layer(middleware::from_fn(auth_middleware));
 
     Router::new().merge(public_routes).merge(protected_routes)
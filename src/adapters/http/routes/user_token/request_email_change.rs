@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    adapters::http::routes::{AuthUser, Validateable},
+    app_error::{AppError, AppResult},
+    use_cases::user_token::UserTokenUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RequestEmailChangePayload {
+    email: String,
+    #[schema(value_type = String, format = "password")]
+    password: SecretString,
+}
+
+impl Validateable for RequestEmailChangePayload {
+    fn valid(&self) -> bool {
+        !self.email.is_empty() && !self.password.expose_secret().is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RequestEmailChangeResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user_token/request-email-change", 
+    request_body = RequestEmailChangePayload,
+    responses( 
+        (status = 200, description = "Ok, a confirmation link is sent to the new email", body = RequestEmailChangeResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 401, description = "Invalid password"),
+        (status = 409, description = "Email already in use"),
+        (status = 500, description = "Internal server error or database error")
+    ),
+    security(
+        ("bearer_auth" = [])  
+    ),
+    tag = "User Token",
+    summary = "Requests changing the email of the requesting user",
+    description = "The email is only changed once the link sent to the new address is opened.\n\n**Required:** Current password"
+)]
+#[instrument(skip(user_token_use_cases, payload))]
+pub async fn request_email_change(
+    Extension(auth_user): Extension<AuthUser>,
+    State(user_token_use_cases): State<Arc<UserTokenUseCases>>,
+    Json(payload): Json<RequestEmailChangePayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Request email change called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    let user_uuid = Uuid::parse_str(&auth_user.user_id).map_err(|_| AppError::Internal("Invalid UUID string".into()))?;
+
+    user_token_use_cases
+        .request_email_change(&user_uuid, &payload.email, &payload.password)
+        .await?;
+
+    Ok((StatusCode::OK, Json(RequestEmailChangeResponse { success: true })))
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    adapters::http::routes::Validateable,
+    app_error::{AppError, AppResult},
+    use_cases::user_token::UserTokenUseCases,
+};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RequestResetPayload {
+    email: String,
+}
+
+impl Validateable for RequestResetPayload {
+    fn valid(&self) -> bool {
+        !self.email.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RequestResetResponse {
+    success: bool,
+}
+
+#[utoipa::path(post, path = "/api/user_token/request-reset", 
+    request_body = RequestResetPayload,
+    responses( 
+        (status = 200, description = "Ok, an email is sent if the account exists", body = RequestResetResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 500, description = "Internal server error or database error")
+    ), 
+    tag = "User Token",
+    summary = "Sends a password reset link to the given email",
+    description = "Answers the same whether the email is registered or not."
+)]
+#[instrument(skip(user_token_use_cases, payload))]
+pub async fn request_reset(
+    State(user_token_use_cases): State<Arc<UserTokenUseCases>>,
+    Json(payload): Json<RequestResetPayload>,
+) -> AppResult<impl IntoResponse> {
+    info!("Request password reset called");
+
+    if !payload.valid() {
+        return AppResult::Err(AppError::InvalidPayload);
+    }
+
+    user_token_use_cases.request_password_reset(&payload.email).await?;
+
+    Ok((StatusCode::OK, Json(RequestResetResponse { success: true })))
+}
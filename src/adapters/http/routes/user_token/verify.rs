@@ -43,7 +43,7 @@ pub struct VerifyResponse {
     tag = "User Token",
     summary = "Verifies the given token"
 )]
-#[instrument(skip(user_token_use_cases))]
+#[instrument(skip(user_token_use_cases, params))]
 pub async fn verify(
     State(user_token_use_cases): State<Arc<UserTokenUseCases>>,
     Query(params): Query<VerifyQuery>,
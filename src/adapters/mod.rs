@@ -1,6 +1,8 @@
 pub mod crypto;
 pub mod email;
 pub mod http;
+pub mod pdf;
 pub mod persistence;
 pub mod utils;
 pub mod videocall;
+pub mod zip;
@@ -0,0 +1,169 @@
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::invoice::{BillingParty, Invoice},
+    use_cases::invoice::InvoiceRenderer,
+};
+
+const PAGE_WIDTH: f32 = 210.0; // A4, mm
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const LINE_HEIGHT: f32 = 5.5;
+
+/// Renders the invoices as a single A4 page with the standard PDF fonts, labels are in Spanish as the invoices are issued in Spain
+#[derive(Default)]
+pub struct PdfInvoiceRenderer;
+
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+}
+
+impl InvoiceRenderer for PdfInvoiceRenderer {
+    fn render_pdf(&self, invoice: &Invoice) -> AppResult<Vec<u8>> {
+        let title = format!("Factura {}", invoice.invoice_number());
+        let (doc, page, layer) = PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Invoice");
+
+        let fonts = Fonts {
+            regular: doc.add_builtin_font(BuiltinFont::Helvetica).map_err(pdf_error)?,
+            bold: doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(pdf_error)?,
+        };
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let mut y = PAGE_HEIGHT - MARGIN;
+
+        layer.use_text("FACTURA", 20.0, Mm(MARGIN), Mm(y), &fonts.bold);
+        y -= LINE_HEIGHT * 2.0;
+        layer.use_text(format!("Nº {}", invoice.invoice_number()), 11.0, Mm(MARGIN), Mm(y), &fonts.regular);
+        y -= LINE_HEIGHT;
+        layer.use_text(
+            format!("Fecha de emisión: {}", invoice.issued_at.format("%d/%m/%Y")),
+            11.0,
+            Mm(MARGIN),
+            Mm(y),
+            &fonts.regular,
+        );
+        y -= LINE_HEIGHT * 2.5;
+
+        party(&layer, &fonts, "Emisor", &invoice.issuer, MARGIN, y);
+        party(&layer, &fonts, "Cliente", &invoice.recipient, PAGE_WIDTH / 2.0, y);
+        y -= LINE_HEIGHT * 6.0;
+
+        let columns = [MARGIN, 120.0, 135.0, 160.0, 175.0];
+        for (label, x) in ["Concepto", "Cant.", "Precio", "IVA", "Importe"].iter().zip(columns) {
+            layer.use_text(*label, 10.0, Mm(x), Mm(y), &fonts.bold);
+        }
+        y -= LINE_HEIGHT * 1.5;
+
+        for line in &invoice.lines {
+            let cells = [
+                line.description.clone(),
+                line.quantity.to_string(),
+                money(line.unit_amount, &invoice.currency),
+                format!("{}%", line.vat_rate),
+                money(line.total(), &invoice.currency),
+            ];
+            for (cell, x) in cells.iter().zip(columns) {
+                layer.use_text(cell.as_str(), 9.0, Mm(x), Mm(y), &fonts.regular);
+            }
+            y -= LINE_HEIGHT;
+
+            if let Some(exemption) = &line.vat_exemption {
+                layer.use_text(exemption.as_str(), 8.0, Mm(MARGIN), Mm(y), &fonts.regular);
+                y -= LINE_HEIGHT;
+            }
+        }
+        y -= LINE_HEIGHT;
+
+        let base: i64 = invoice.lines.iter().map(|line| line.base()).sum();
+        let vat: i64 = invoice.lines.iter().map(|line| line.vat()).sum();
+        let totals = [
+            ("Base imponible", money(base, &invoice.currency)),
+            ("IVA", money(vat, &invoice.currency)),
+            ("Total", money(invoice.total(), &invoice.currency)),
+        ];
+        for (label, amount) in totals {
+            layer.use_text(label, 10.0, Mm(135.0), Mm(y), &fonts.bold);
+            layer.use_text(amount, 10.0, Mm(175.0), Mm(y), &fonts.regular);
+            y -= LINE_HEIGHT;
+        }
+
+        doc.save_to_bytes().map_err(pdf_error)
+    }
+}
+
+fn party(layer: &PdfLayerReference, fonts: &Fonts, label: &str, party: &BillingParty, x: f32, y: f32) {
+    layer.use_text(label, 10.0, Mm(x), Mm(y), &fonts.bold);
+
+    let lines = [
+        Some(party.name.clone()),
+        party.tax_id.as_ref().map(|tax_id| format!("NIF: {}", tax_id)),
+        party.address.clone(),
+    ];
+    for (i, text) in lines.into_iter().flatten().enumerate() {
+        layer.use_text(text, 9.0, Mm(x), Mm(y - LINE_HEIGHT * (i as f32 + 1.0)), &fonts.regular);
+    }
+}
+
+/// e.g. 1.234,50 EUR
+fn money(cents: i64, currency: &str) -> String {
+    let units = (cents / 100).abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in units.chars().enumerate() {
+        if i > 0 && (units.len() - i).is_multiple_of(3) {
+            grouped.push('.');
+        }
+        grouped.push(digit);
+    }
+
+    let sign = if cents < 0 { "-" } else { "" };
+
+    format!("{}{},{:02} {}", sign, grouped, (cents % 100).abs(), currency.to_uppercase())
+}
+
+fn pdf_error(e: printpdf::Error) -> AppError {
+    AppError::Internal(format!("Error rendering pdf: {}", e))
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::entities::invoice::InvoiceLine;
+
+    use super::*;
+
+    #[test]
+    fn money_works() {
+        assert_eq!(money(6000, "eur"), "60,00 EUR");
+        assert_eq!(money(123450, "eur"), "1.234,50 EUR");
+        assert_eq!(money(-5, "eur"), "-0,05 EUR");
+    }
+
+    #[test]
+    fn render_pdf_works() {
+        let invoice = Invoice {
+            id: Uuid::new_v4(),
+            transaction_id: Uuid::new_v4(),
+            session_id: None,
+            patient_id: Uuid::new_v4(),
+            professional_id: Uuid::new_v4(),
+            fiscal_year: 2026,
+            number: 1,
+            issued_at: chrono::Utc::now().naive_utc(),
+            issuer: BillingParty {
+                name: String::from("Ana García"),
+                tax_id: Some(String::from("12345678Z")),
+                address: Some(String::from("Calle Mayor 1, 28013 Madrid, ES")),
+            },
+            recipient: BillingParty { name: String::from("Juan Pérez"), tax_id: None, address: None },
+            currency: String::from("eur"),
+            lines: vec![InvoiceLine::healthcare_service(String::from("Individual therapy session"), 6000)],
+        };
+
+        let pdf = PdfInvoiceRenderer.render_pdf(&invoice).unwrap();
+
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
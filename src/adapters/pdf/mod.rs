@@ -0,0 +1 @@
+pub mod invoice_pdf;
@@ -0,0 +1,310 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, tenant},
+    app_error::{AppError, AppResult},
+    entities::{account_deletion::AccountDeletion, session::SessionStatus},
+    use_cases::{account_deletion::AccountDeletionPersistence, user::login_account},
+};
+
+/// Where the uploaded profile pictures are served from and stored
+const PROFILE_PICTURES_URL: &str = "/api/uploads/profiles/";
+const PROFILE_PICTURES_DIR: &str = "uploads/profiles/";
+
+// Account deletion as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct AccountDeletionDb {
+    pub user_id: Uuid,
+    pub requested_by: Uuid,
+    pub requested_at: NaiveDateTime,
+    pub scheduled_for: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl From<AccountDeletionDb> for AccountDeletion {
+    fn from(deletion_db: AccountDeletionDb) -> Self {
+        AccountDeletion {
+            user_id: deletion_db.user_id,
+            requested_by: deletion_db.requested_by,
+            requested_at: Some(deletion_db.requested_at),
+            scheduled_for: deletion_db.scheduled_for,
+            completed_at: deletion_db.completed_at,
+        }
+    }
+}
+
+#[async_trait]
+impl AccountDeletionPersistence for PostgresPersistence {
+    async fn create(&self, deletion: &AccountDeletion) -> AppResult<()> {
+        let is_member = sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                SELECT 1 FROM users
+                WHERE id = $1 AND ($2::uuid IS NULL OR id IN (SELECT user_id FROM organization_members WHERE organization_id = $2))
+            ) AS "exists!""#,
+            deletion.user_id,
+            tenant::current()?
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        if !is_member {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO account_deletions (user_id, requested_by, scheduled_for)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id) DO NOTHING
+            "#,
+            deletion.user_id,
+            deletion.requested_by,
+            deletion.scheduled_for
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Account deletion already requested".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn read(&self, user_id: &Uuid) -> AppResult<Option<AccountDeletion>> {
+        sqlx::query_as!(
+            AccountDeletionDb,
+            r#"
+                SELECT user_id, requested_by, requested_at, scheduled_for, completed_at
+                FROM account_deletions
+                WHERE user_id = $1
+                    AND ($2::uuid IS NULL OR user_id IN (SELECT user_id FROM organization_members WHERE organization_id = $2))
+            "#,
+            user_id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|deletion| deletion.map(AccountDeletion::from))
+    }
+
+    async fn delete_pending(&self, user_id: &Uuid) -> AppResult<()> {
+        let result = sqlx::query!(
+            "DELETE FROM account_deletions WHERE user_id = $1 AND completed_at IS NULL",
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("No pending account deletion".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn read_due(&self) -> AppResult<Vec<AccountDeletion>> {
+        sqlx::query_as!(
+            AccountDeletionDb,
+            r#"
+                SELECT user_id, requested_by, requested_at, scheduled_for, completed_at
+                FROM account_deletions
+                WHERE completed_at IS NULL AND scheduled_for <= $1
+                ORDER BY scheduled_for
+            "#,
+            chrono::Utc::now().naive_utc()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|deletions| deletions.into_iter().map(AccountDeletion::from).collect())
+    }
+
+    async fn read_future_sessions(&self, user_id: &Uuid) -> AppResult<Vec<Uuid>> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT id
+                FROM sessions
+                WHERE session_status_id = ANY($2)
+                    AND (session_date IS NULL OR session_date > $3)
+                    AND (patient_id IN (SELECT id FROM patients WHERE user_id = $1)
+                        OR professional_id IN (SELECT id FROM professionals WHERE user_id = $1))
+                ORDER BY session_date
+            "#,
+            user_id,
+            &[SessionStatus::Scheduled.to_id(), SessionStatus::PendingPayment.to_id()],
+            chrono::Utc::now().naive_utc()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    async fn erase(&self, user_id: &Uuid) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let user = sqlx::query!(
+            "SELECT email, profile_picture_url FROM users WHERE id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        // The row stays for the records tied to it, nothing left identifies the person and nobody can login
+        sqlx::query!(
+            r#"
+                UPDATE users
+                SET username = 'Deleted', usersurname = 'User', email = 'deleted-' || id || '@deleted.invalid',
+                    password_hash = '', profile_picture_url = NULL, verified = false, needs_onboarding = false,
+                    credentials_version = credentials_version + 1
+                WHERE id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM refresh_tokens WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM user_tokens WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM user_two_factor WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM two_factor_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM user_onboardings WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM user_consents WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        // The issued invoices keep their own copy, as tax law requires
+        sqlx::query!("DELETE FROM billing_details WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM data_exports WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM emails WHERE to_mail = $1", user.email)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        let account = login_account(&user.email);
+
+        sqlx::query!("DELETE FROM login_failures WHERE subject = $1", account)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM login_lockouts WHERE subject = $1", account)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        // The clinical fields stay as the minimum clinical record, the birthdate is kept as the year of birth
+        sqlx::query!(
+            r#"
+                UPDATE patients
+                SET phone = '', birthdate = date_trunc('year', birthdate)::date, emergency_contact_name = NULL,
+                    emergency_contact_phone = NULL, insurance_policy_number = NULL
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        // The consent of the guardian of a minor patient is kept as proof the treatment was authorized, for as long as
+        // the clinical record is. Only its certificate and date are needed for that, who signed it is erased
+        sqlx::query!(
+            r#"
+                UPDATE parent_consents
+                SET guardian_name = 'Deleted', guardian_id_document = '', signature_data = ''
+                WHERE patient_id IN (SELECT id FROM patients WHERE user_id = $1)
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!(
+            r#"
+                UPDATE professionals
+                SET birthdate = date_trunc('year', birthdate)::date, bio = NULL, education = NULL
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET videocall_url = NULL, videocall_host_url = NULL, videocall_meeting_id = NULL
+                WHERE patient_id IN (SELECT id FROM patients WHERE user_id = $1)
+                    OR professional_id IN (SELECT id FROM professionals WHERE user_id = $1)
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!(
+            "UPDATE account_deletions SET completed_at = CURRENT_TIMESTAMP WHERE user_id = $1",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        if let Some(file) = user
+            .profile_picture_url
+            .as_deref()
+            .and_then(|url| url.strip_prefix(PROFILE_PICTURES_URL))
+            .filter(|file| !file.contains('/') && !file.contains(".."))
+            && let Err(e) = tokio::fs::remove_file(format!("{}{}", PROFILE_PICTURES_DIR, file)).await
+        {
+            warn!("Failed to remove the profile picture of erased user {}: {:?}", user_id, e);
+        }
+
+        Ok(())
+    }
+}
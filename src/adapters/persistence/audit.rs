@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, tenant, user::RoleDb},
+    app_error::{AppError, AppResult},
+    entities::audit_event::{AuditEvent, AuditEventFilter},
+    use_cases::audit::AuditPersistence,
+};
+
+// Audit event as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct AuditEventDb {
+    pub id: Uuid,
+    pub actor_user_id: Uuid,
+    pub actor_role: RoleDb,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Uuid,
+    pub ip: Option<String>,
+    pub request_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+impl TryFrom<AuditEventDb> for AuditEvent {
+    type Error = AppError;
+
+    fn try_from(event_db: AuditEventDb) -> Result<Self, Self::Error> {
+        Ok(AuditEvent {
+            id: event_db.id,
+            actor_user_id: event_db.actor_user_id,
+            actor_role: event_db.actor_role.into(),
+            action: event_db.action.parse().map_err(AppError::Internal)?,
+            resource_type: event_db.resource_type.parse().map_err(AppError::Internal)?,
+            resource_id: event_db.resource_id,
+            ip: event_db.ip,
+            request_id: event_db.request_id,
+            created_at: Some(event_db.created_at),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditPersistence for PostgresPersistence {
+    async fn record(&self, events: &[AuditEvent]) -> AppResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<Uuid> = events.iter().map(|event| event.id).collect();
+        let actor_user_ids: Vec<Uuid> = events.iter().map(|event| event.actor_user_id).collect();
+        let actor_role_ids: Vec<i32> = events.iter().map(|event| event.actor_role.to_id()).collect();
+        let actions: Vec<String> = events.iter().map(|event| event.action.to_string()).collect();
+        let resource_types: Vec<String> = events.iter().map(|event| event.resource_type.to_string()).collect();
+        let resource_ids: Vec<Uuid> = events.iter().map(|event| event.resource_id).collect();
+        let ips: Vec<Option<String>> = events.iter().map(|event| event.ip.clone()).collect();
+        let request_ids: Vec<Option<Uuid>> = events.iter().map(|event| event.request_id).collect();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO audit_events (id, organization_id, actor_user_id, actor_role_id, action, resource_type, resource_id, ip, request_id)
+                SELECT id, $9, actor_user_id, actor_role_id, action, resource_type, resource_id, ip, request_id
+                FROM UNNEST($1::uuid[], $2::uuid[], $3::int4[], $4::text[], $5::text[], $6::uuid[], $7::text[], $8::uuid[])
+                    AS e(id, actor_user_id, actor_role_id, action, resource_type, resource_id, ip, request_id)
+            "#,
+            &ids,
+            &actor_user_ids,
+            &actor_role_ids,
+            &actions,
+            &resource_types,
+            &resource_ids,
+            &ips as &[Option<String>],
+            &request_ids as &[Option<Uuid>],
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read(&self, filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>> {
+        sqlx::query_as!(
+            AuditEventDb,
+            r#"
+                SELECT id, actor_user_id, actor_role_id AS actor_role, action, resource_type, resource_id, ip, request_id, created_at
+                FROM audit_events
+                WHERE ($1::uuid IS NULL OR actor_user_id = $1)
+                    AND ($2::text IS NULL OR action = $2)
+                    AND ($3::text IS NULL OR resource_type = $3)
+                    AND ($4::uuid IS NULL OR resource_id = $4)
+                    AND ($5::timestamp IS NULL OR created_at >= $5)
+                    AND ($6::timestamp IS NULL OR created_at <= $6)
+                    AND ($7::uuid IS NULL OR organization_id = $7)
+                ORDER BY created_at DESC, id
+                LIMIT $8 OFFSET $9
+            "#,
+            filter.actor_user_id,
+            filter.action.map(|action| action.to_string()),
+            filter.resource_type.map(|resource_type| resource_type.to_string()),
+            filter.resource_id,
+            filter.from,
+            filter.to,
+            tenant::current()?,
+            filter.limit,
+            filter.offset
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(AuditEvent::try_from)
+        .collect()
+    }
+}
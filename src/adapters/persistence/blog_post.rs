@@ -3,7 +3,7 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, tenant},
     app_error::{AppError, AppResult},
     entities::blog_post::{BlogPost, BlogPostStatus},
     use_cases::blog_post::BlogPostPersistence,
@@ -52,8 +52,8 @@ impl BlogPostPersistence for PostgresPersistence {
         let uuid = Uuid::new_v4();
 
         sqlx::query!(
-            "INSERT INTO blog_posts (id, author_id, blog_post_status_id, title, slug, summary, content, tags, featured_image, reading_time_minutes, view_count) 
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            "INSERT INTO blog_posts (id, author_id, blog_post_status_id, title, slug, summary, content, tags, featured_image, reading_time_minutes, view_count, organization_id) 
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
             uuid,
             blog_post.author_id,
             blog_post.blog_post_status.to_id(),
@@ -64,7 +64,8 @@ impl BlogPostPersistence for PostgresPersistence {
             blog_post.tags.as_deref(),
             blog_post.featured_image,
             blog_post.reading_time_minutes,
-            blog_post.view_count
+            blog_post.view_count,
+            tenant::required()?
         )
         .execute(&self.pool)
         .await
@@ -79,7 +80,9 @@ impl BlogPostPersistence for PostgresPersistence {
             r#"
                 SELECT id, author_id, blog_post_status_id, title, slug, summary, content, tags, featured_image, reading_time_minutes, view_count, created_at
                 FROM blog_posts
-            "#
+                WHERE ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -93,9 +96,10 @@ impl BlogPostPersistence for PostgresPersistence {
             r#"
                 SELECT id, author_id, blog_post_status_id, title, slug, summary, content, tags, featured_image, reading_time_minutes, view_count, created_at
                 FROM blog_posts 
-                WHERE id = $1
+                WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -107,7 +111,7 @@ impl BlogPostPersistence for PostgresPersistence {
         sqlx::query!(
             "UPDATE blog_posts 
                 SET blog_post_status_id = $2, title = $3, slug = $4, summary = $5, content = $6, tags = $7, featured_image = $8, reading_time_minutes = $9, view_count = $10
-                WHERE id = $1",
+                WHERE id = $1 AND ($11::uuid IS NULL OR organization_id = $11)",
             blog_post.id,
             blog_post.blog_post_status.to_id(),
             blog_post.title,
@@ -117,7 +121,8 @@ impl BlogPostPersistence for PostgresPersistence {
             blog_post.tags.as_deref(),
             blog_post.featured_image,
             blog_post.reading_time_minutes,
-            blog_post.view_count
+            blog_post.view_count,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -127,10 +132,14 @@ impl BlogPostPersistence for PostgresPersistence {
     }
 
     async fn delete(&self, id: Uuid) -> AppResult<()> {
-        sqlx::query!("DELETE FROM blog_posts WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(AppError::Database)?;
+        sqlx::query!(
+            "DELETE FROM blog_posts WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)",
+            id,
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
 
         Ok(())
     }
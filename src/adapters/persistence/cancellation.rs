@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, tenant},
+    app_error::{AppError, AppResult},
+    dtos::session::parties::SessionPartiesDTO,
+    use_cases::cancellation::CancellationPersistence,
+};
+
+#[async_trait]
+impl CancellationPersistence for PostgresPersistence {
+    async fn read_session_parties(&self, session_id: &Uuid) -> AppResult<SessionPartiesDTO> {
+        let parties = sqlx::query!(
+            r#"
+                SELECT
+                    patient_users.id AS "patient_user_id?",
+                    patient_users.email AS "patient_email?",
+                    professional_users.id AS "professional_user_id?",
+                    professional_users.email AS "professional_email?"
+                FROM sessions
+                JOIN patients ON patients.id = sessions.patient_id
+                LEFT JOIN users patient_users ON patient_users.id = patients.user_id
+                JOIN professionals ON professionals.id = sessions.professional_id
+                LEFT JOIN users professional_users ON professional_users.id = professionals.user_id
+                WHERE sessions.id = $1 AND ($2::uuid IS NULL OR sessions.organization_id = $2)
+            "#,
+            session_id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+
+        Ok(SessionPartiesDTO {
+            patient_user_id: parties.patient_user_id,
+            patient_email: parties.patient_email,
+            professional_user_id: parties.professional_user_id,
+            professional_email: parties.professional_email,
+        })
+    }
+}
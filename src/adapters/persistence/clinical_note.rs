@@ -0,0 +1,352 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::{
+    adapters::{
+        crypto::field_cipher::{FieldCipher, field_context},
+        persistence::{PostgresPersistence, tenant},
+    },
+    app_error::{AppError, AppResult},
+    entities::clinical_note::{ClinicalNote, ClinicalNoteVersion, NoteVisibility, SoapSections},
+    use_cases::clinical_note::ClinicalNotePersistence,
+};
+
+/// SQLSTATE raised by postgres when a unique constraint is violated
+const UNIQUE_VIOLATION: &str = "23505";
+/// SQLSTATE raised by postgres when a foreign key is violated
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+pub const SUBJECTIVE: &str = "clinical_note_versions.subjective";
+pub const OBJECTIVE: &str = "clinical_note_versions.objective";
+pub const ASSESSMENT: &str = "clinical_note_versions.assessment";
+pub const PLAN: &str = "clinical_note_versions.plan";
+/// Free text notes of the sessions from before the clinical notes, moved to them on startup
+pub const LEGACY_SESSION_NOTES: &str = "sessions.notes";
+
+/// Rows moved per query
+const BATCH_SIZE: i64 = 200;
+
+// Clinical note version as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct ClinicalNoteVersionDb {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub version: i32,
+    pub visibility: String,
+    pub subjective: Option<String>,
+    pub objective: Option<String>,
+    pub assessment: Option<String>,
+    pub plan: Option<String>,
+    pub edited_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+// Clinical note as stored in the db, joined with its latest version.
+#[derive(sqlx::FromRow, Debug)]
+pub struct ClinicalNoteDb {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub author_user_id: Option<Uuid>,
+    pub legacy: bool,
+    pub created_at: NaiveDateTime,
+    pub version_id: Uuid,
+    pub version: i32,
+    pub visibility: String,
+    pub subjective: Option<String>,
+    pub objective: Option<String>,
+    pub assessment: Option<String>,
+    pub plan: Option<String>,
+    pub edited_by: Option<Uuid>,
+    pub version_created_at: NaiveDateTime,
+}
+
+impl ClinicalNoteVersionDb {
+    /// The sections are stored encrypted
+    pub fn decrypt(self, cipher: &FieldCipher) -> AppResult<ClinicalNoteVersion> {
+        Ok(ClinicalNoteVersion {
+            id: self.id,
+            note_id: self.note_id,
+            version: self.version,
+            // Unknown values can only come from a newer release, they're kept from the patient
+            visibility: self.visibility.parse().unwrap_or(NoteVisibility::Professional),
+            sections: SoapSections {
+                subjective: cipher.decrypt(self.subjective, &field_context(SUBJECTIVE, &self.id))?,
+                objective: cipher.decrypt(self.objective, &field_context(OBJECTIVE, &self.id))?,
+                assessment: cipher.decrypt(self.assessment, &field_context(ASSESSMENT, &self.id))?,
+                plan: cipher.decrypt(self.plan, &field_context(PLAN, &self.id))?,
+            },
+            edited_by: self.edited_by,
+            created_at: Some(self.created_at),
+        })
+    }
+}
+
+impl ClinicalNoteDb {
+    pub fn decrypt(self, cipher: &FieldCipher) -> AppResult<ClinicalNote> {
+        let current = ClinicalNoteVersionDb {
+            id: self.version_id,
+            note_id: self.id,
+            version: self.version,
+            visibility: self.visibility,
+            subjective: self.subjective,
+            objective: self.objective,
+            assessment: self.assessment,
+            plan: self.plan,
+            edited_by: self.edited_by,
+            created_at: self.version_created_at,
+        };
+
+        Ok(ClinicalNote {
+            id: self.id,
+            session_id: self.session_id,
+            author_user_id: self.author_user_id,
+            legacy: self.legacy,
+            created_at: Some(self.created_at),
+            current: current.decrypt(cipher)?,
+        })
+    }
+}
+
+#[async_trait]
+impl ClinicalNotePersistence for PostgresPersistence {
+    async fn create(&self, note: &ClinicalNote) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let created = sqlx::query!(
+            r#"
+                INSERT INTO clinical_notes (id, session_id, organization_id, author_user_id)
+                SELECT $1, id, organization_id, $3
+                FROM sessions
+                WHERE id = $2 AND ($4::uuid IS NULL OR organization_id = $4)
+            "#,
+            note.id,
+            note.session_id,
+            note.author_user_id,
+            tenant::current()?
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if created.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Session {} not found", note.session_id)));
+        }
+
+        self.insert_version(&mut tx, &note.current).await?;
+
+        tx.commit().await.map_err(AppError::Database)
+    }
+
+    async fn read_single(&self, id: &Uuid) -> AppResult<ClinicalNote> {
+        sqlx::query_as!(
+            ClinicalNoteDb,
+            r#"
+                SELECT n.id, n.session_id, n.author_user_id, n.legacy, n.created_at,
+                    v.id AS "version_id!", v.version AS "version!", v.visibility AS "visibility!",
+                    v.subjective, v.objective, v.assessment, v.plan,
+                    v.edited_by AS "edited_by?", v.created_at AS "version_created_at!"
+                FROM clinical_notes n
+                JOIN LATERAL (
+                    SELECT * FROM clinical_note_versions WHERE note_id = n.id ORDER BY version DESC LIMIT 1
+                ) v ON TRUE
+                WHERE n.id = $1 AND ($2::uuid IS NULL OR n.organization_id = $2)
+            "#,
+            id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound(format!("Clinical note {} not found", id)))?
+        .decrypt(&self.cipher)
+    }
+
+    async fn read_by_session(&self, session_id: &Uuid) -> AppResult<Vec<ClinicalNote>> {
+        sqlx::query_as!(
+            ClinicalNoteDb,
+            r#"
+                SELECT n.id, n.session_id, n.author_user_id, n.legacy, n.created_at,
+                    v.id AS "version_id!", v.version AS "version!", v.visibility AS "visibility!",
+                    v.subjective, v.objective, v.assessment, v.plan,
+                    v.edited_by AS "edited_by?", v.created_at AS "version_created_at!"
+                FROM clinical_notes n
+                JOIN LATERAL (
+                    SELECT * FROM clinical_note_versions WHERE note_id = n.id ORDER BY version DESC LIMIT 1
+                ) v ON TRUE
+                WHERE n.session_id = $1 AND ($2::uuid IS NULL OR n.organization_id = $2)
+                ORDER BY n.created_at
+            "#,
+            session_id,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(|note| note.decrypt(&self.cipher))
+        .collect()
+    }
+
+    async fn read_versions(&self, note_id: &Uuid) -> AppResult<Vec<ClinicalNoteVersion>> {
+        sqlx::query_as!(
+            ClinicalNoteVersionDb,
+            r#"
+                SELECT v.id, v.note_id, v.version, v.visibility, v.subjective, v.objective, v.assessment, v.plan, v.edited_by, v.created_at
+                FROM clinical_note_versions v
+                JOIN clinical_notes n ON n.id = v.note_id
+                WHERE v.note_id = $1 AND ($2::uuid IS NULL OR n.organization_id = $2)
+                ORDER BY v.version
+            "#,
+            note_id,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(|version| version.decrypt(&self.cipher))
+        .collect()
+    }
+
+    async fn create_version(&self, version: &ClinicalNoteVersion) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        self.insert_version(&mut tx, version).await?;
+
+        tx.commit().await.map_err(AppError::Database)
+    }
+}
+
+impl PostgresPersistence {
+    async fn insert_version(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        version: &ClinicalNoteVersion,
+    ) -> AppResult<()> {
+        let sections = &version.sections;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO clinical_note_versions (id, note_id, version, visibility, subjective, objective, assessment, plan, edited_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            version.id,
+            version.note_id,
+            version.version,
+            version.visibility.to_string(),
+            self.cipher.encrypt(sections.subjective.as_deref(), &field_context(SUBJECTIVE, &version.id))?,
+            self.cipher.encrypt(sections.objective.as_deref(), &field_context(OBJECTIVE, &version.id))?,
+            self.cipher.encrypt(sections.assessment.as_deref(), &field_context(ASSESSMENT, &version.id))?,
+            self.cipher.encrypt(sections.plan.as_deref(), &field_context(PLAN, &version.id))?,
+            version.edited_by
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(map_version_taken_error)?;
+
+        Ok(())
+    }
+
+    /// Moves the free text notes of the sessions to a professional only clinical note written by the professional
+    /// of the session, and clears them from the session. Returns the moved notes
+    pub async fn move_legacy_session_notes(&self) -> AppResult<u64> {
+        let mut moved = 0;
+        let mut skipped: Vec<Uuid> = vec![]; // changed by someone else while moved, picked up on the next run
+
+        loop {
+            let rows = sqlx::query!(
+                r#"
+                    SELECT s.id, s.notes AS "notes!", p.user_id AS "professional_user_id?"
+                    FROM sessions s
+                    JOIN professionals p ON p.id = s.professional_id
+                    WHERE s.notes IS NOT NULL AND s.id <> ALL($1)
+                    LIMIT $2
+                "#,
+                &skipped,
+                BATCH_SIZE
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+            if rows.is_empty() {
+                return Ok(moved);
+            }
+
+            for row in rows {
+                let text = self.cipher.decrypt(Some(row.notes.clone()), &field_context(LEGACY_SESSION_NOTES, &row.id))?;
+                let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+                let cleared = sqlx::query!(
+                    "UPDATE sessions SET notes = NULL WHERE id = $1 AND notes = $2",
+                    row.id,
+                    row.notes
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::Database)?;
+
+                if cleared.rows_affected() == 0 {
+                    skipped.push(row.id);
+                    continue;
+                }
+
+                // Blank notes are just cleared, the professionals without a user keep theirs without an author
+                if let Some(text) = text.filter(|text| !text.trim().is_empty()) {
+                    let sections = SoapSections { subjective: Some(text), ..SoapSections::default() };
+                    let note = ClinicalNote::legacy(row.id, row.professional_user_id, sections);
+
+                    sqlx::query!(
+                        r#"
+                            INSERT INTO clinical_notes (id, session_id, organization_id, author_user_id, legacy, created_at)
+                            SELECT $1, id, organization_id, $3, TRUE, COALESCE(created_at, CURRENT_TIMESTAMP)
+                            FROM sessions
+                            WHERE id = $2
+                        "#,
+                        note.id,
+                        note.session_id,
+                        note.author_user_id
+                    )
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(AppError::Database)?;
+
+                    self.insert_version(&mut tx, &note.current).await?;
+                    moved += 1;
+                }
+
+                tx.commit().await.map_err(AppError::Database)?;
+            }
+        }
+    }
+}
+
+/// Two professionals editing the same version of a note, the second one has to look at the first edit
+fn map_version_taken_error(error: sqlx::Error) -> AppError {
+    let is_taken = error
+        .as_database_error()
+        .and_then(|db_error| db_error.code())
+        .is_some_and(|code| code == UNIQUE_VIOLATION);
+
+    if is_taken {
+        return AppError::Conflict(String::from("The note was edited by someone else, reload it"));
+    }
+
+    AppError::Database(error)
+}
+
+/// The clinical notes keep their session, and so its patient and professional, from being deleted
+pub(crate) fn map_clinical_notes_kept_error(error: sqlx::Error) -> AppError {
+    let is_kept = error
+        .as_database_error()
+        .filter(|db_error| db_error.code().is_some_and(|code| code == FOREIGN_KEY_VIOLATION))
+        .and_then(|db_error| db_error.constraint())
+        .is_some_and(|constraint| constraint == "clinical_notes_session_id_fkey");
+
+    if is_kept {
+        return AppError::Conflict(String::from("The session has clinical notes, they must be kept"));
+    }
+
+    AppError::Database(error)
+}
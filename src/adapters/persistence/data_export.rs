@@ -0,0 +1,335 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::{
+    adapters::{
+        crypto::field_cipher::field_context,
+        persistence::{
+            PostgresPersistence, clinical_note::ClinicalNoteDb, email::EmailDb, parent_consent::ParentConsentDb,
+            patient::PatientDb, session::SessionDb, user::UserDb,
+        },
+    },
+    app_error::{AppError, AppResult},
+    dtos::user::personal_data::PersonalDataDTO,
+    entities::{
+        clinical_note::{ClinicalNote, NoteVisibility},
+        data_export::{DataExport, DataExportStatus},
+        email::Email,
+        onboarding::{UserConsent, UserOnboarding},
+        parent_consent::ParentConsent,
+        session::Session,
+        transaction::{Transaction, TransactionStatus},
+        user::User,
+    },
+    use_cases::data_export::DataExportPersistence,
+};
+
+pub const ARCHIVE: &str = "data_exports.archive";
+
+// Data export as stored in the db, without its archive.
+#[derive(sqlx::FromRow, Debug)]
+pub struct DataExportDb {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl From<DataExportDb> for DataExport {
+    fn from(export_db: DataExportDb) -> Self {
+        DataExport {
+            id: export_db.id,
+            user_id: export_db.user_id,
+            status: export_db.status.parse().unwrap_or(DataExportStatus::Failed),
+            created_at: Some(export_db.created_at),
+            completed_at: export_db.completed_at,
+            expires_at: export_db.expires_at,
+        }
+    }
+}
+
+#[async_trait]
+impl DataExportPersistence for PostgresPersistence {
+    async fn create(&self, export: &DataExport) -> AppResult<()> {
+        sqlx::query!(
+            "INSERT INTO data_exports (id, user_id, status) VALUES ($1, $2, $3)",
+            export.id,
+            export.user_id,
+            export.status.to_string()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read_single(&self, id: &Uuid) -> AppResult<DataExport> {
+        sqlx::query_as!(
+            DataExportDb,
+            "SELECT id, user_id, status, created_at, completed_at, expires_at FROM data_exports WHERE id = $1",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound(format!("Data export {} not found", id)))
+        .map(DataExport::from)
+    }
+
+    async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<DataExport>> {
+        sqlx::query_as!(
+            DataExportDb,
+            r#"
+                SELECT id, user_id, status, created_at, completed_at, expires_at
+                FROM data_exports
+                WHERE user_id = $1
+                ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|exports| exports.into_iter().map(DataExport::from).collect())
+    }
+
+    async fn complete(&self, id: &Uuid, archive: &[u8], expires_at: NaiveDateTime) -> AppResult<()> {
+        let archive = self.cipher.encrypt_bytes(archive, &field_context(ARCHIVE, id))?;
+
+        sqlx::query!(
+            r#"
+                UPDATE data_exports
+                SET status = $2, archive = $3, completed_at = CURRENT_TIMESTAMP, expires_at = $4
+                WHERE id = $1
+            "#,
+            id,
+            DataExportStatus::Ready.to_string(),
+            archive,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn fail(&self, id: &Uuid) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE data_exports SET status = $2, completed_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            DataExportStatus::Failed.to_string()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read_archive(&self, id: &Uuid) -> AppResult<Vec<u8>> {
+        let archive = sqlx::query_scalar!("SELECT archive FROM data_exports WHERE id = $1", id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::Database)?
+            .flatten()
+            .ok_or_else(|| AppError::NotFound(format!("Data export {} not found", id)))?;
+
+        self.cipher.decrypt_bytes(&archive, &field_context(ARCHIVE, id))
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
+        sqlx::query!("DELETE FROM data_exports WHERE expires_at <= CURRENT_TIMESTAMP")
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)
+            .map(|result| result.rows_affected())
+    }
+
+    /// Not scoped to the organization, everything the user has left in any of them is theirs
+    async fn read_personal_data(&self, user_id: &Uuid) -> AppResult<PersonalDataDTO> {
+        let user = sqlx::query_as!(
+            UserDb,
+            r#"SELECT id, role_id as role, username, usersurname, email, verified, needs_onboarding, ''::text as "password_hash!", profile_picture_url, created_at
+                FROM users
+                WHERE id = $1"#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+        .map(User::from)?;
+
+        let onboarding = sqlx::query_as!(
+            UserOnboarding,
+            r#"
+                SELECT id, user_id as "user_id!", user_type, full_name, phone, birthdate, reason, experience, created_at
+                FROM user_onboardings
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        let consent = sqlx::query_as!(
+            UserConsent,
+            r#"
+                SELECT id, user_id as "user_id!", is_monoparental, guardian_name, guardian_id_document, signature, guardian2_name, guardian2_id_document, signature2, created_at
+                FROM user_consents
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        let patient = sqlx::query_as!(
+            PatientDb,
+            r#"
+                SELECT id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies, created_at
+                FROM patients
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .map(|patient| patient.decrypt(&self.cipher))
+        .transpose()?;
+
+        let (parent_consent, sessions, clinical_notes, transactions) = match patient.as_ref().and_then(|patient| patient.id) {
+            Some(patient_id) => (
+                self.read_parent_consent(&patient_id).await?,
+                self.read_patient_sessions(&patient_id).await?,
+                self.read_patient_clinical_notes(&patient_id).await?,
+                self.read_patient_transactions(&patient_id).await?,
+            ),
+            None => (None, vec![], vec![], vec![]),
+        };
+
+        let emails = sqlx::query_as!(
+            EmailDb,
+            r#"
+                SELECT id, from_mail, to_mail, mail_subject, mail_body, email_kind as "email_kind: _", created_at
+                FROM emails
+                WHERE to_mail = $1
+                ORDER BY created_at
+            "#,
+            user.email
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(Email::from)
+        .collect();
+
+        Ok(PersonalDataDTO {
+            user,
+            onboarding,
+            consent,
+            patient,
+            parent_consent,
+            sessions,
+            clinical_notes,
+            transactions,
+            emails,
+        })
+    }
+}
+
+impl PostgresPersistence {
+    async fn read_parent_consent(&self, patient_id: &Uuid) -> AppResult<Option<ParentConsent>> {
+        sqlx::query_as!(ParentConsentDb, "SELECT * FROM parent_consents WHERE patient_id = $1", patient_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::Database)
+            .map(|consent| consent.map(ParentConsent::from))
+    }
+
+    async fn read_patient_sessions(&self, patient_id: &Uuid) -> AppResult<Vec<Session>> {
+        sqlx::query_as!(
+            SessionDb,
+            r#"
+                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, videocall_provider, videocall_host_url, videocall_meeting_id, completed, session_duration, created_at
+                FROM sessions
+                WHERE patient_id = $1
+                ORDER BY session_date
+            "#,
+            patient_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|sessions| sessions.into_iter().map(Session::from).collect())
+    }
+
+    /// The notes kept from the patient stay out, as they do from the api
+    async fn read_patient_clinical_notes(&self, patient_id: &Uuid) -> AppResult<Vec<ClinicalNote>> {
+        sqlx::query_as!(
+            ClinicalNoteDb,
+            r#"
+                SELECT n.id, n.session_id, n.author_user_id, n.legacy, n.created_at,
+                    v.id AS "version_id!", v.version AS "version!", v.visibility AS "visibility!",
+                    v.subjective, v.objective, v.assessment, v.plan,
+                    v.edited_by AS "edited_by?", v.created_at AS "version_created_at!"
+                FROM clinical_notes n
+                JOIN sessions s ON s.id = n.session_id
+                JOIN LATERAL (
+                    SELECT * FROM clinical_note_versions WHERE note_id = n.id ORDER BY version DESC LIMIT 1
+                ) v ON TRUE
+                WHERE s.patient_id = $1 AND v.visibility = $2
+                ORDER BY n.created_at
+            "#,
+            patient_id,
+            NoteVisibility::Patient.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(|note| note.decrypt(&self.cipher))
+        .collect()
+    }
+
+    async fn read_patient_transactions(&self, patient_id: &Uuid) -> AppResult<Vec<Transaction>> {
+        let rows = sqlx::query!(
+            r#"
+                SELECT t.id, t.payment_intent_id, t.session_id, t.booked_session_id, t.amount, t.currency, t.status, t.refunded_amount, t.created_at, t.updated_at
+                FROM transactions t
+                JOIN sessions s ON s.id = t.booked_session_id
+                WHERE s.patient_id = $1
+                ORDER BY t.created_at
+            "#,
+            patient_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Transaction {
+                id: row.id,
+                payment_intent_id: row.payment_intent_id,
+                session_id: row.session_id,
+                booked_session_id: row.booked_session_id,
+                amount: row.amount,
+                currency: row.currency,
+                status: TransactionStatus::from(row.status),
+                refunded_amount: row.refunded_amount,
+                created_at: row.created_at.unwrap_or_default().and_utc(),
+                updated_at: row.updated_at.unwrap_or_default().and_utc(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, tenant},
+    app_error::{AppError, AppResult},
+    dtos::earning::payout::PayoutDTO,
+    entities::earning::Earning,
+    use_cases::earning::EarningPersistence,
+};
+
+const UNIQUE_VIOLATION: &str = "23505";
+
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct EarningDb {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub professional_id: Uuid,
+    pub kind: String,
+    pub gross_amount: i64,
+    pub commission_percent: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub currency: String,
+    pub earned_at: NaiveDateTime,
+    pub paid_out_at: Option<NaiveDateTime>,
+}
+
+impl From<EarningDb> for Earning {
+    fn from(earning_db: EarningDb) -> Self {
+        Earning {
+            id: earning_db.id,
+            transaction_id: earning_db.transaction_id,
+            session_id: earning_db.session_id,
+            professional_id: earning_db.professional_id,
+            kind: earning_db.kind.into(),
+            gross_amount: earning_db.gross_amount,
+            commission_percent: earning_db.commission_percent,
+            commission_amount: earning_db.commission_amount,
+            net_amount: earning_db.net_amount,
+            currency: earning_db.currency,
+            earned_at: earning_db.earned_at,
+            paid_out_at: earning_db.paid_out_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct PayoutDb {
+    pub professional_id: Uuid,
+    pub professional_name: String,
+    pub professional_email: String,
+    pub entries: i64,
+    pub gross_amount: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub currency: String,
+}
+
+impl From<PayoutDb> for PayoutDTO {
+    fn from(payout_db: PayoutDb) -> Self {
+        PayoutDTO {
+            professional_id: payout_db.professional_id,
+            professional_name: payout_db.professional_name,
+            professional_email: payout_db.professional_email,
+            entries: payout_db.entries,
+            gross_amount: payout_db.gross_amount,
+            commission_amount: payout_db.commission_amount,
+            net_amount: payout_db.net_amount,
+            currency: payout_db.currency,
+        }
+    }
+}
+
+#[async_trait]
+impl EarningPersistence for PostgresPersistence {
+    async fn create(&self, earning: &Earning) -> AppResult<()> {
+        sqlx::query!(
+            "INSERT INTO earnings (id, transaction_id, session_id, professional_id, kind, gross_amount,
+                commission_percent, commission_amount, net_amount, currency, earned_at, paid_out_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            earning.id,
+            earning.transaction_id,
+            earning.session_id,
+            earning.professional_id,
+            earning.kind.to_string(),
+            earning.gross_amount,
+            earning.commission_percent,
+            earning.commission_amount,
+            earning.net_amount,
+            earning.currency,
+            earning.earned_at,
+            earning.paid_out_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(map_already_recorded_error)?;
+
+        Ok(())
+    }
+
+    async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Vec<Earning>> {
+        let earnings = sqlx::query_as!(
+            EarningDb,
+            r#"
+                SELECT id, transaction_id, session_id, professional_id, kind, gross_amount,
+                    commission_percent, commission_amount, net_amount, currency, earned_at, paid_out_at
+                FROM earnings
+                WHERE transaction_id = $1
+                ORDER BY earned_at
+            "#,
+            transaction_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(earnings.into_iter().map(Earning::from).collect())
+    }
+
+    async fn read_by_professional(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> AppResult<Vec<Earning>> {
+        let earnings = sqlx::query_as!(
+            EarningDb,
+            r#"
+                SELECT id, transaction_id, session_id, professional_id, kind, gross_amount,
+                    commission_percent, commission_amount, net_amount, currency, earned_at, paid_out_at
+                FROM earnings
+                WHERE professional_id = $1 AND earned_at >= $2 AND earned_at < $3
+                    AND ($4::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $4))
+                ORDER BY earned_at
+            "#,
+            professional_id,
+            from,
+            to,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(earnings.into_iter().map(Earning::from).collect())
+    }
+
+    async fn read_pending_payouts(&self, until: NaiveDateTime) -> AppResult<Vec<PayoutDTO>> {
+        let payouts = sqlx::query_as!(
+            PayoutDb,
+            r#"
+                SELECT
+                    earnings.professional_id,
+                    users.username || ' ' || users.usersurname AS "professional_name!",
+                    users.email AS professional_email,
+                    COUNT(*) AS "entries!",
+                    SUM(earnings.gross_amount)::BIGINT AS "gross_amount!",
+                    SUM(earnings.commission_amount)::BIGINT AS "commission_amount!",
+                    SUM(earnings.net_amount)::BIGINT AS "net_amount!",
+                    earnings.currency
+                FROM earnings
+                JOIN professionals ON professionals.id = earnings.professional_id
+                JOIN users ON users.id = professionals.user_id
+                WHERE earnings.paid_out_at IS NULL AND earnings.earned_at < $1
+                    AND ($2::uuid IS NULL OR professionals.organization_id = $2)
+                GROUP BY earnings.professional_id, users.username, users.usersurname, users.email, earnings.currency
+                ORDER BY "professional_name!"
+            "#,
+            until,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(payouts.into_iter().map(PayoutDTO::from).collect())
+    }
+
+    async fn settle_payouts(
+        &self,
+        until: NaiveDateTime,
+        paid_out_at: NaiveDateTime,
+    ) -> AppResult<Vec<PayoutDTO>> {
+        // A single statement, so entries recorded meanwhile are either settled and returned or left pending
+        let payouts = sqlx::query_as!(
+            PayoutDb,
+            r#"
+                WITH settled AS (
+                    UPDATE earnings SET paid_out_at = $2
+                    WHERE paid_out_at IS NULL AND earned_at < $1
+                        AND ($3::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $3))
+                    RETURNING professional_id, gross_amount, commission_amount, net_amount, currency
+                )
+                SELECT
+                    settled.professional_id,
+                    users.username || ' ' || users.usersurname AS "professional_name!",
+                    users.email AS professional_email,
+                    COUNT(*) AS "entries!",
+                    SUM(settled.gross_amount)::BIGINT AS "gross_amount!",
+                    SUM(settled.commission_amount)::BIGINT AS "commission_amount!",
+                    SUM(settled.net_amount)::BIGINT AS "net_amount!",
+                    settled.currency
+                FROM settled
+                JOIN professionals ON professionals.id = settled.professional_id
+                JOIN users ON users.id = professionals.user_id
+                GROUP BY settled.professional_id, users.username, users.usersurname, users.email, settled.currency
+                ORDER BY "professional_name!"
+            "#,
+            until,
+            paid_out_at,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(payouts.into_iter().map(PayoutDTO::from).collect())
+    }
+}
+
+/// The payment of a transaction can only be recorded once, surface a concurrent record as a conflict
+fn map_already_recorded_error(error: sqlx::Error) -> AppError {
+    let is_duplicate = error
+        .as_database_error()
+        .and_then(|db_error| db_error.code())
+        .is_some_and(|code| code == UNIQUE_VIOLATION);
+
+    if is_duplicate {
+        return AppError::Conflict("The payment was already recorded".into());
+    }
+
+    AppError::Database(error)
+}
@@ -47,12 +47,18 @@ impl From<EmailDb> for Email {
 pub enum EmailKindDb {
     #[default]
     Verification,
+    SessionCancelled,
+    PasswordReset,
+    EmailChange,
 }
 
 impl From<EmailKindDb> for EmailKind {
     fn from(value: EmailKindDb) -> Self {
         match value {
             EmailKindDb::Verification => EmailKind::Verification,
+            EmailKindDb::SessionCancelled => EmailKind::SessionCancelled,
+            EmailKindDb::PasswordReset => EmailKind::PasswordReset,
+            EmailKindDb::EmailChange => EmailKind::EmailChange,
         }
     }
 }
@@ -71,6 +77,9 @@ impl EmailKindDb {
     pub fn from_id(id: i32) -> Option<Self> {
         EmailKind::from_id(id).map(|kind| match kind {
             EmailKind::Verification => EmailKindDb::Verification,
+            EmailKind::SessionCancelled => EmailKindDb::SessionCancelled,
+            EmailKind::PasswordReset => EmailKindDb::PasswordReset,
+            EmailKind::EmailChange => EmailKindDb::EmailChange,
         })
     }
 }
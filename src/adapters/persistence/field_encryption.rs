@@ -0,0 +1,157 @@
+use uuid::Uuid;
+
+use crate::{
+    adapters::{
+        crypto::field_cipher::{FieldCipher, field_context},
+        persistence::{
+            PostgresPersistence,
+            clinical_note::{ASSESSMENT, OBJECTIVE, PLAN, SUBJECTIVE},
+            patient::{ALLERGIES, CURRENT_MEDICATIONS, INSURANCE_POLICY_NUMBER, MEDICAL_HISTORY},
+        },
+    },
+    app_error::{AppError, AppResult},
+};
+
+/// Rows rewrapped per query
+const BATCH_SIZE: i64 = 200;
+
+impl PostgresPersistence {
+    /// Encrypts the values stored in plaintext and wraps the data keys of the older master keys with the current one.
+    /// Once it's done the older master keys can be removed from the config. Returns the updated rows
+    pub async fn rewrap_encrypted_fields(&self) -> AppResult<u64> {
+        Ok(self.rewrap_patients().await? + self.rewrap_clinical_notes().await?)
+    }
+
+    async fn rewrap_patients(&self) -> AppResult<u64> {
+        let current = format!("{}%", self.cipher.current_prefix());
+        let mut updated = 0;
+        let mut skipped: Vec<Uuid> = vec![]; // changed by someone else while rewrapped, picked up on the next run
+
+        loop {
+            let rows = sqlx::query!(
+                r#"
+                    SELECT id, insurance_policy_number, medical_history, current_medications, allergies
+                    FROM patients
+                    WHERE ((insurance_policy_number IS NOT NULL AND insurance_policy_number NOT LIKE $1)
+                        OR (medical_history IS NOT NULL AND medical_history NOT LIKE $1)
+                        OR (current_medications IS NOT NULL AND current_medications NOT LIKE $1)
+                        OR (allergies IS NOT NULL AND allergies NOT LIKE $1))
+                        AND id <> ALL($2)
+                    LIMIT $3
+                "#,
+                current,
+                &skipped,
+                BATCH_SIZE
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+            if rows.is_empty() {
+                return Ok(updated);
+            }
+
+            for row in rows {
+                let result = sqlx::query!(
+                    r#"
+                        UPDATE patients
+                        SET insurance_policy_number = $6, medical_history = $7, current_medications = $8, allergies = $9
+                        WHERE id = $1
+                            AND insurance_policy_number IS NOT DISTINCT FROM $2
+                            AND medical_history IS NOT DISTINCT FROM $3
+                            AND current_medications IS NOT DISTINCT FROM $4
+                            AND allergies IS NOT DISTINCT FROM $5
+                    "#,
+                    row.id,
+                    row.insurance_policy_number,
+                    row.medical_history,
+                    row.current_medications,
+                    row.allergies,
+                    rewrap(&self.cipher, row.insurance_policy_number.as_deref(), INSURANCE_POLICY_NUMBER, &row.id)?,
+                    rewrap(&self.cipher, row.medical_history.as_deref(), MEDICAL_HISTORY, &row.id)?,
+                    rewrap(&self.cipher, row.current_medications.as_deref(), CURRENT_MEDICATIONS, &row.id)?,
+                    rewrap(&self.cipher, row.allergies.as_deref(), ALLERGIES, &row.id)?
+                )
+                .execute(&self.pool)
+                .await
+                .map_err(AppError::Database)?;
+
+                match result.rows_affected() {
+                    0 => skipped.push(row.id),
+                    rows => updated += rows,
+                }
+            }
+        }
+    }
+
+    /// The versions of the notes can't change, only their sections are rewrapped
+    async fn rewrap_clinical_notes(&self) -> AppResult<u64> {
+        let current = format!("{}%", self.cipher.current_prefix());
+        let mut updated = 0;
+        let mut skipped: Vec<Uuid> = vec![];
+
+        loop {
+            let rows = sqlx::query!(
+                r#"
+                    SELECT id, subjective, objective, assessment, plan
+                    FROM clinical_note_versions
+                    WHERE ((subjective IS NOT NULL AND subjective NOT LIKE $1)
+                        OR (objective IS NOT NULL AND objective NOT LIKE $1)
+                        OR (assessment IS NOT NULL AND assessment NOT LIKE $1)
+                        OR (plan IS NOT NULL AND plan NOT LIKE $1))
+                        AND id <> ALL($2)
+                    LIMIT $3
+                "#,
+                current,
+                &skipped,
+                BATCH_SIZE
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+            if rows.is_empty() {
+                return Ok(updated);
+            }
+
+            for row in rows {
+                let result = sqlx::query!(
+                    r#"
+                        UPDATE clinical_note_versions
+                        SET subjective = $6, objective = $7, assessment = $8, plan = $9
+                        WHERE id = $1
+                            AND subjective IS NOT DISTINCT FROM $2
+                            AND objective IS NOT DISTINCT FROM $3
+                            AND assessment IS NOT DISTINCT FROM $4
+                            AND plan IS NOT DISTINCT FROM $5
+                    "#,
+                    row.id,
+                    row.subjective,
+                    row.objective,
+                    row.assessment,
+                    row.plan,
+                    rewrap(&self.cipher, row.subjective.as_deref(), SUBJECTIVE, &row.id)?,
+                    rewrap(&self.cipher, row.objective.as_deref(), OBJECTIVE, &row.id)?,
+                    rewrap(&self.cipher, row.assessment.as_deref(), ASSESSMENT, &row.id)?,
+                    rewrap(&self.cipher, row.plan.as_deref(), PLAN, &row.id)?
+                )
+                .execute(&self.pool)
+                .await
+                .map_err(AppError::Database)?;
+
+                match result.rows_affected() {
+                    0 => skipped.push(row.id),
+                    rows => updated += rows,
+                }
+            }
+        }
+    }
+}
+
+/// Values already wrapped by the current master key are kept
+fn rewrap(cipher: &FieldCipher, stored: Option<&str>, column: &str, id: &Uuid) -> AppResult<Option<String>> {
+    match stored {
+        Some(stored) if cipher.is_outdated(stored) => cipher.rewrap(stored, &field_context(column, id)).map(Some),
+        stored => Ok(stored.map(String::from)),
+    }
+}
@@ -0,0 +1,408 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, tenant},
+    app_error::{AppError, AppResult},
+    dtos::invoice::source::InvoiceSourceDTO,
+    entities::invoice::{BillingDetails, BillingParty, Invoice, InvoiceLine},
+    use_cases::invoice::InvoicePersistence,
+};
+
+const UNIQUE_VIOLATION: &str = "23505";
+
+// Invoice as stored in the db, without its lines.
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct InvoiceDb {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub patient_id: Uuid,
+    pub professional_id: Uuid,
+    pub fiscal_year: i32,
+    pub number: i32,
+    pub issued_at: NaiveDateTime,
+    pub issuer_name: String,
+    pub issuer_tax_id: Option<String>,
+    pub issuer_address: Option<String>,
+    pub recipient_name: String,
+    pub recipient_tax_id: Option<String>,
+    pub recipient_address: Option<String>,
+    pub currency: String,
+}
+
+impl InvoiceDb {
+    fn into_invoice(self, lines: Vec<InvoiceLine>) -> Invoice {
+        Invoice {
+            id: self.id,
+            transaction_id: self.transaction_id,
+            session_id: self.session_id,
+            patient_id: self.patient_id,
+            professional_id: self.professional_id,
+            fiscal_year: self.fiscal_year,
+            number: self.number,
+            issued_at: self.issued_at,
+            issuer: BillingParty {
+                name: self.issuer_name,
+                tax_id: self.issuer_tax_id,
+                address: self.issuer_address,
+            },
+            recipient: BillingParty {
+                name: self.recipient_name,
+                tax_id: self.recipient_tax_id,
+                address: self.recipient_address,
+            },
+            currency: self.currency,
+            lines,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct InvoiceLineDb {
+    pub invoice_id: Uuid,
+    pub description: String,
+    pub quantity: i32,
+    pub unit_amount: i64,
+    pub vat_rate: i32,
+    pub vat_exemption: Option<String>,
+}
+
+impl From<InvoiceLineDb> for InvoiceLine {
+    fn from(line_db: InvoiceLineDb) -> Self {
+        InvoiceLine {
+            description: line_db.description,
+            quantity: line_db.quantity,
+            unit_amount: line_db.unit_amount,
+            vat_rate: line_db.vat_rate,
+            vat_exemption: line_db.vat_exemption,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct BillingDetailsDb {
+    pub user_id: Uuid,
+    pub legal_name: String,
+    pub tax_id: String,
+    pub address: String,
+    pub postal_code: String,
+    pub city: String,
+    pub country: String,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl From<BillingDetailsDb> for BillingDetails {
+    fn from(details_db: BillingDetailsDb) -> Self {
+        BillingDetails {
+            user_id: details_db.user_id,
+            legal_name: details_db.legal_name,
+            tax_id: details_db.tax_id,
+            address: details_db.address,
+            postal_code: details_db.postal_code,
+            city: details_db.city,
+            country: details_db.country,
+            updated_at: details_db.updated_at,
+        }
+    }
+}
+
+impl PostgresPersistence {
+    /// Attaches its lines to each invoice
+    async fn with_lines(&self, invoices: Vec<InvoiceDb>) -> AppResult<Vec<Invoice>> {
+        let ids: Vec<Uuid> = invoices.iter().map(|invoice| invoice.id).collect();
+
+        let mut lines = sqlx::query_as!(
+            InvoiceLineDb,
+            r#"
+                SELECT invoice_id, description, quantity, unit_amount, vat_rate, vat_exemption
+                FROM invoice_lines
+                WHERE invoice_id = ANY($1)
+                ORDER BY position
+            "#,
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(invoices
+            .into_iter()
+            .map(|invoice| {
+                let (own, rest): (Vec<_>, Vec<_>) =
+                    lines.drain(..).partition(|line| line.invoice_id == invoice.id);
+                lines = rest;
+
+                invoice.into_invoice(own.into_iter().map(InvoiceLine::from).collect())
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl InvoicePersistence for PostgresPersistence {
+    async fn create(&self, invoice: &Invoice) -> AppResult<Invoice> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        // Locks the row of the issuer and fiscal year until commit, a rollback gives the number back
+        let number = sqlx::query_scalar!(
+            r#"
+                INSERT INTO invoice_series (professional_id, fiscal_year, last_number) VALUES ($1, $2, 1)
+                ON CONFLICT (professional_id, fiscal_year) DO UPDATE SET last_number = invoice_series.last_number + 1
+                RETURNING last_number
+            "#,
+            invoice.professional_id,
+            invoice.fiscal_year
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!(
+            "INSERT INTO invoices (id, transaction_id, session_id, patient_id, professional_id, fiscal_year, number, issued_at,
+                issuer_name, issuer_tax_id, issuer_address, recipient_name, recipient_tax_id, recipient_address, currency)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+            invoice.id,
+            invoice.transaction_id,
+            invoice.session_id,
+            invoice.patient_id,
+            invoice.professional_id,
+            invoice.fiscal_year,
+            number,
+            invoice.issued_at,
+            invoice.issuer.name,
+            invoice.issuer.tax_id,
+            invoice.issuer.address,
+            invoice.recipient.name,
+            invoice.recipient.tax_id,
+            invoice.recipient.address,
+            invoice.currency
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_already_invoiced_error)?;
+
+        for (position, line) in invoice.lines.iter().enumerate() {
+            sqlx::query!(
+                "INSERT INTO invoice_lines (id, invoice_id, position, description, quantity, unit_amount, vat_rate, vat_exemption)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                Uuid::new_v4(),
+                invoice.id,
+                position as i32,
+                line.description,
+                line.quantity,
+                line.unit_amount,
+                line.vat_rate,
+                line.vat_exemption
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        let mut invoice = invoice.clone();
+        invoice.number = number;
+
+        Ok(invoice)
+    }
+
+    async fn read_single(&self, id: &Uuid) -> AppResult<Invoice> {
+        let invoice = sqlx::query_as!(
+            InvoiceDb,
+            r#"
+                SELECT id, transaction_id, session_id, patient_id, professional_id, fiscal_year, number, issued_at,
+                    issuer_name, issuer_tax_id, issuer_address, recipient_name, recipient_tax_id, recipient_address, currency
+                FROM invoices
+                WHERE id = $1
+                    AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))
+            "#,
+            id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound(format!("Invoice {} not found", id)))?;
+
+        Ok(self.with_lines(vec![invoice]).await?.remove(0))
+    }
+
+    async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Option<Invoice>> {
+        let invoice = sqlx::query_as!(
+            InvoiceDb,
+            r#"
+                SELECT id, transaction_id, session_id, patient_id, professional_id, fiscal_year, number, issued_at,
+                    issuer_name, issuer_tax_id, issuer_address, recipient_name, recipient_tax_id, recipient_address, currency
+                FROM invoices
+                WHERE transaction_id = $1
+                    AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))
+            "#,
+            transaction_id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        match invoice {
+            Some(invoice) => Ok(self.with_lines(vec![invoice]).await?.pop()),
+            None => Ok(None),
+        }
+    }
+
+    async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<Invoice>> {
+        let invoices = sqlx::query_as!(
+            InvoiceDb,
+            r#"
+                SELECT invoices.id, invoices.transaction_id, invoices.session_id, invoices.patient_id, invoices.professional_id,
+                    invoices.fiscal_year, invoices.number, invoices.issued_at,
+                    invoices.issuer_name, invoices.issuer_tax_id, invoices.issuer_address,
+                    invoices.recipient_name, invoices.recipient_tax_id, invoices.recipient_address, invoices.currency
+                FROM invoices
+                LEFT JOIN patients ON patients.id = invoices.patient_id
+                LEFT JOIN professionals ON professionals.id = invoices.professional_id
+                WHERE patients.user_id = $1 OR professionals.user_id = $1
+                ORDER BY invoices.fiscal_year DESC, invoices.number DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        self.with_lines(invoices).await
+    }
+
+    async fn is_party(&self, invoice_id: &Uuid, user_id: &Uuid) -> AppResult<bool> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM invoices
+                    LEFT JOIN patients ON patients.id = invoices.patient_id
+                    LEFT JOIN professionals ON professionals.id = invoices.professional_id
+                    WHERE invoices.id = $1 AND (patients.user_id = $2 OR professionals.user_id = $2)
+                ) AS "is_party!"
+            "#,
+            invoice_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    async fn read_invoice_source(&self, session_id: &Uuid) -> AppResult<InvoiceSourceDTO> {
+        let source = sqlx::query!(
+            r#"
+                SELECT
+                    sessions.patient_id,
+                    sessions.professional_id,
+                    sessions.session_date,
+                    sessions.session_duration,
+                    session_types.session_type_name AS "session_type_name?",
+                    COALESCE(patient_billing.legal_name, patient_users.username || ' ' || patient_users.usersurname) AS "patient_name?",
+                    patient_billing.tax_id AS "patient_tax_id?",
+                    patient_billing.address || ', ' || patient_billing.postal_code || ' ' || patient_billing.city || ', ' || patient_billing.country AS "patient_address?",
+                    COALESCE(professional_billing.legal_name, professional_users.username || ' ' || professional_users.usersurname) AS "professional_name!",
+                    professional_billing.tax_id AS "professional_tax_id?",
+                    professional_billing.address || ', ' || professional_billing.postal_code || ' ' || professional_billing.city || ', ' || professional_billing.country AS "professional_address?"
+                FROM sessions
+                JOIN patients ON patients.id = sessions.patient_id
+                LEFT JOIN users patient_users ON patient_users.id = patients.user_id
+                LEFT JOIN billing_details patient_billing ON patient_billing.user_id = patients.user_id
+                JOIN professionals ON professionals.id = sessions.professional_id
+                JOIN users professional_users ON professional_users.id = professionals.user_id
+                LEFT JOIN billing_details professional_billing ON professional_billing.user_id = professionals.user_id
+                LEFT JOIN session_types ON session_types.id = sessions.session_type_id
+                WHERE sessions.id = $1 AND ($2::uuid IS NULL OR sessions.organization_id = $2)
+            "#,
+            session_id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", session_id)))?;
+
+        Ok(InvoiceSourceDTO {
+            patient_id: source.patient_id,
+            professional_id: source.professional_id,
+            // Without billing details the invoice still gets the name of the user
+            issuer: BillingParty {
+                name: source.professional_name,
+                tax_id: source.professional_tax_id,
+                address: source.professional_address,
+            },
+            recipient: BillingParty {
+                name: source.patient_name.unwrap_or_default(),
+                tax_id: source.patient_tax_id,
+                address: source.patient_address,
+            },
+            session_type_name: source.session_type_name,
+            session_date: Some(source.session_date),
+            session_duration: source.session_duration,
+        })
+    }
+
+    async fn read_billing_details(&self, user_id: &Uuid) -> AppResult<Option<BillingDetails>> {
+        sqlx::query_as!(
+            BillingDetailsDb,
+            r#"
+                SELECT user_id, legal_name, tax_id, address, postal_code, city, country, updated_at
+                FROM billing_details
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|details| details.map(BillingDetails::from))
+    }
+
+    async fn upsert_billing_details(&self, details: &BillingDetails) -> AppResult<()> {
+        sqlx::query!(
+            "INSERT INTO billing_details (user_id, legal_name, tax_id, address, postal_code, city, country)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_id) DO UPDATE SET
+                legal_name = EXCLUDED.legal_name,
+                tax_id = EXCLUDED.tax_id,
+                address = EXCLUDED.address,
+                postal_code = EXCLUDED.postal_code,
+                city = EXCLUDED.city,
+                country = EXCLUDED.country,
+                updated_at = CURRENT_TIMESTAMP",
+            details.user_id,
+            details.legal_name,
+            details.tax_id,
+            details.address,
+            details.postal_code,
+            details.city,
+            details.country
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
+
+/// The transaction can only be invoiced once, surface a concurrent issue as a conflict
+fn map_already_invoiced_error(error: sqlx::Error) -> AppError {
+    let is_duplicate = error
+        .as_database_error()
+        .and_then(|db_error| db_error.code())
+        .is_some_and(|code| code == UNIQUE_VIOLATION);
+
+    if is_duplicate {
+        return AppError::Conflict("The transaction was already invoiced".into());
+    }
+
+    AppError::Database(error)
+}
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::PostgresPersistence,
+    app_error::{AppError, AppResult},
+    entities::login_throttle::{LoginFailures, LoginLockout, LoginSubjectKind},
+    use_cases::user::LoginThrottlePersistence,
+};
+
+// Failed logins as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct LoginFailuresDb {
+    pub subject_kind: String,
+    pub subject: String,
+    pub failures: i32,
+    pub last_failed_at: NaiveDateTime,
+}
+
+impl TryFrom<LoginFailuresDb> for LoginFailures {
+    type Error = AppError;
+
+    fn try_from(failures_db: LoginFailuresDb) -> Result<Self, Self::Error> {
+        Ok(LoginFailures {
+            kind: failures_db.subject_kind.parse().map_err(AppError::Internal)?,
+            subject: failures_db.subject,
+            failures: failures_db.failures,
+            last_failed_at: failures_db.last_failed_at,
+        })
+    }
+}
+
+// Lockout as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct LoginLockoutDb {
+    pub id: Uuid,
+    pub subject_kind: String,
+    pub subject: String,
+    pub failures: i32,
+    pub locked_until: NaiveDateTime,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl TryFrom<LoginLockoutDb> for LoginLockout {
+    type Error = AppError;
+
+    fn try_from(lockout_db: LoginLockoutDb) -> Result<Self, Self::Error> {
+        Ok(LoginLockout {
+            id: lockout_db.id,
+            kind: lockout_db.subject_kind.parse().map_err(AppError::Internal)?,
+            subject: lockout_db.subject,
+            failures: lockout_db.failures,
+            locked_until: lockout_db.locked_until,
+            created_at: lockout_db.created_at,
+        })
+    }
+}
+
+#[async_trait]
+impl LoginThrottlePersistence for PostgresPersistence {
+    async fn read_failures(&self, kind: LoginSubjectKind, subject: &str) -> AppResult<Option<LoginFailures>> {
+        sqlx::query_as!(
+            LoginFailuresDb,
+            r#"
+                SELECT subject_kind, subject, failures, last_failed_at
+                FROM login_failures
+                WHERE subject_kind = $1 AND subject = $2
+            "#,
+            kind.to_string(),
+            subject
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .map(LoginFailures::try_from)
+        .transpose()
+    }
+
+    async fn record_failure(
+        &self,
+        kind: LoginSubjectKind,
+        subject: &str,
+        now: NaiveDateTime,
+        forget_before: NaiveDateTime,
+    ) -> AppResult<LoginFailures> {
+        // A single statement so concurrent failures can't overwrite each other's count
+        sqlx::query_as!(
+            LoginFailuresDb,
+            r#"
+                INSERT INTO login_failures (subject_kind, subject, failures, last_failed_at)
+                VALUES ($1, $2, 1, $3)
+                ON CONFLICT (subject_kind, subject) DO UPDATE
+                SET failures = CASE
+                        WHEN login_failures.last_failed_at < $4 THEN 1
+                        ELSE login_failures.failures + 1
+                    END,
+                    last_failed_at = EXCLUDED.last_failed_at
+                RETURNING subject_kind, subject, failures, last_failed_at
+            "#,
+            kind.to_string(),
+            subject,
+            now,
+            forget_before
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .try_into()
+    }
+
+    async fn clear_failures(&self, kind: LoginSubjectKind, subject: &str) -> AppResult<()> {
+        sqlx::query!(
+            "DELETE FROM login_failures WHERE subject_kind = $1 AND subject = $2",
+            kind.to_string(),
+            subject
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn record_lockout(&self, lockout: &LoginLockout) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO login_lockouts (id, subject_kind, subject, failures, locked_until)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+            lockout.id,
+            lockout.kind.to_string(),
+            lockout.subject,
+            lockout.failures,
+            lockout.locked_until
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn list_lockouts(&self, since: NaiveDateTime) -> AppResult<Vec<LoginLockout>> {
+        sqlx::query_as!(
+            LoginLockoutDb,
+            r#"
+                SELECT id, subject_kind, subject, failures, locked_until, created_at
+                FROM login_lockouts
+                WHERE created_at >= $1
+                ORDER BY created_at DESC
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(LoginLockout::try_from)
+        .collect()
+    }
+}
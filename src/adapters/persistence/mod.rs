@@ -1,14 +1,32 @@
+use std::sync::Arc;
+
 use sqlx::PgPool;
 
+use crate::adapters::crypto::field_cipher::FieldCipher;
+
+pub mod account_deletion;
+pub mod audit;
 pub mod blog_post;
+pub mod cancellation;
+pub mod clinical_note;
+pub mod data_export;
+pub mod earning;
 pub mod email;
+pub mod field_encryption;
+pub mod invoice;
+pub mod login_throttle;
+pub mod organization;
 pub mod parent_consent;
 pub mod patient;
 pub mod professional;
+pub mod professional_availability;
 pub mod professional_language;
 pub mod professional_specialization;
+pub mod refresh_token;
 pub mod session;
 pub mod session_type;
+pub mod tenant;
+pub mod two_factor;
 pub mod user;
 pub mod user_token;
 pub mod transaction;
@@ -16,10 +34,11 @@ pub mod transaction;
 #[derive(Clone)]
 pub struct PostgresPersistence {
     pool: PgPool,
+    cipher: Arc<FieldCipher>, // of the clinical columns of patients and clinical notes
 }
 
 impl PostgresPersistence {
-    pub fn new(pool: PgPool) -> Self {
-        PostgresPersistence { pool }
+    pub fn new(pool: PgPool, cipher: Arc<FieldCipher>) -> Self {
+        PostgresPersistence { pool, cipher }
     }
 }
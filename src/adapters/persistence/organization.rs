@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, user::RoleDb},
+    app_error::{AppError, AppResult},
+    entities::{
+        organization::{Organization, OrganizationMember},
+        user::Role,
+    },
+    use_cases::organization::OrganizationPersistence,
+};
+
+// Organization struct as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct OrganizationDb {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<OrganizationDb> for Organization {
+    fn from(organization_db: OrganizationDb) -> Self {
+        Organization {
+            id: organization_db.id,
+            name: organization_db.name,
+            created_at: organization_db.created_at,
+        }
+    }
+}
+
+// Member of an organization with its user, as read from the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct OrganizationMemberDb {
+    pub user_id: Uuid,
+    pub username: String,
+    pub usersurname: String,
+    pub email: String,
+    pub role: RoleDb,
+    pub joined_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<OrganizationMemberDb> for OrganizationMember {
+    fn from(member_db: OrganizationMemberDb) -> Self {
+        OrganizationMember {
+            user_id: member_db.user_id,
+            username: member_db.username,
+            usersurname: member_db.usersurname,
+            email: member_db.email,
+            role: member_db.role.into(),
+            joined_at: member_db.joined_at,
+        }
+    }
+}
+
+#[async_trait]
+impl OrganizationPersistence for PostgresPersistence {
+    async fn create(&self, organization: &Organization, admin_user_id: &Uuid, from_organization_id: &Uuid) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        sqlx::query!(
+            "INSERT INTO organizations (id, name) VALUES ($1, $2)",
+            organization.id,
+            organization.name
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        let moved = sqlx::query!(
+            r#"
+                UPDATE organization_members
+                SET organization_id = $1, created_at = CURRENT_TIMESTAMP
+                WHERE user_id = $2 AND organization_id = $3
+            "#,
+            organization.id,
+            admin_user_id,
+            from_organization_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if moved.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        sqlx::query!(
+            "UPDATE users SET role_id = $2, credentials_version = credentials_version + 1 WHERE id = $1",
+            admin_user_id,
+            Role::Admin.to_id()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read(&self, id: &Uuid) -> AppResult<Organization> {
+        sqlx::query_as!(
+            OrganizationDb,
+            "SELECT id, name, created_at FROM organizations WHERE id = $1",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .map(Organization::from)
+        .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))
+    }
+
+    async fn is_platform_admin(&self, user_id: &Uuid) -> AppResult<bool> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM platform_admins WHERE user_id = $1) AS "exists!""#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    async fn read_members(&self, organization_id: &Uuid) -> AppResult<Vec<OrganizationMember>> {
+        let members = sqlx::query_as!(
+            OrganizationMemberDb,
+            r#"
+                SELECT u.id AS user_id, u.username, u.usersurname, u.email, u.role_id AS role, m.created_at AS joined_at
+                FROM organization_members m
+                JOIN users u ON u.id = m.user_id
+                WHERE m.organization_id = $1
+                ORDER BY u.usersurname, u.username
+            "#,
+            organization_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(members.into_iter().map(OrganizationMember::from).collect())
+    }
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, tenant},
     app_error::{AppError, AppResult},
     domain::entities::parent_consent::ParentConsent,
     application::use_cases::parent_consent::ParentConsentPersistence,
@@ -61,8 +61,10 @@ impl ParentConsentPersistence for PostgresPersistence {
     async fn read_by_patient(&self, patient_id: &Uuid) -> AppResult<Option<ParentConsent>> {
         let result = sqlx::query_as!(
             ParentConsentDb,
-            "SELECT * FROM parent_consents WHERE patient_id = $1",
-            patient_id
+            "SELECT * FROM parent_consents WHERE patient_id = $1
+                AND ($2::uuid IS NULL OR patient_id IN (SELECT id FROM patients WHERE organization_id = $2))",
+            patient_id,
+            tenant::current()?
         )
         .fetch_optional(&self.pool)
         .await
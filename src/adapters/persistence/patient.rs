@@ -4,9 +4,12 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::{
+        crypto::field_cipher::{FieldCipher, field_context},
+        persistence::{PostgresPersistence, clinical_note::map_clinical_notes_kept_error, tenant},
+    },
     app_error::{AppError, AppResult},
-    entities::{gender::Gender, patient::Patient, sexual_orientation::SexualOrientation},
+    entities::{gender::Gender, patient::Patient, session::SessionStatus, sexual_orientation::SexualOrientation},
     use_cases::patient::PatientPersistence,
 };
 
@@ -28,48 +31,85 @@ pub struct PatientDb {
     pub created_at: Option<chrono::NaiveDateTime>,
 }
 
-impl From<PatientDb> for Patient {
-    fn from(patient_db: PatientDb) -> Self {
-        Patient {
-            id: Some(patient_db.id),
-            user_id: patient_db.user_id,
-            gender: Gender::from_id(patient_db.gender_id).unwrap_or_default(),
-            sexual_orientation: SexualOrientation::from_id(patient_db.sexual_orientation_id)
-                .unwrap_or_default(),
-            birthdate: patient_db.birthdate,
-            phone: patient_db.phone,
-            emergency_contact_name: patient_db.emergency_contact_name,
-            emergency_contact_phone: patient_db.emergency_contact_phone,
-            insurance_policy_number: patient_db.insurance_policy_number,
-            medical_history: patient_db.medical_history,
-            current_medications: patient_db.current_medications,
-            allergies: patient_db.allergies,
-            created_at: patient_db.created_at,
-        }
+pub const INSURANCE_POLICY_NUMBER: &str = "patients.insurance_policy_number";
+pub const MEDICAL_HISTORY: &str = "patients.medical_history";
+pub const CURRENT_MEDICATIONS: &str = "patients.current_medications";
+pub const ALLERGIES: &str = "patients.allergies";
+
+/// Sensitive columns of a patient as stored in the db, encrypted
+pub struct EncryptedPatientFields {
+    pub insurance_policy_number: Option<String>,
+    pub medical_history: Option<String>,
+    pub current_medications: Option<String>,
+    pub allergies: Option<String>,
+}
+
+impl EncryptedPatientFields {
+    pub fn encrypt(cipher: &FieldCipher, id: &Uuid, patient: &Patient) -> AppResult<Self> {
+        Ok(Self {
+            insurance_policy_number: cipher.encrypt(
+                patient.insurance_policy_number.as_deref(),
+                &field_context(INSURANCE_POLICY_NUMBER, id),
+            )?,
+            medical_history: cipher.encrypt(patient.medical_history.as_deref(), &field_context(MEDICAL_HISTORY, id))?,
+            current_medications: cipher.encrypt(
+                patient.current_medications.as_deref(),
+                &field_context(CURRENT_MEDICATIONS, id),
+            )?,
+            allergies: cipher.encrypt(patient.allergies.as_deref(), &field_context(ALLERGIES, id))?,
+        })
+    }
+}
+
+impl PatientDb {
+    pub fn decrypt(self, cipher: &FieldCipher) -> AppResult<Patient> {
+        let id = self.id;
+
+        Ok(Patient {
+            id: Some(id),
+            user_id: self.user_id,
+            gender: Gender::from_id(self.gender_id).unwrap_or_default(),
+            sexual_orientation: SexualOrientation::from_id(self.sexual_orientation_id).unwrap_or_default(),
+            birthdate: self.birthdate,
+            phone: self.phone,
+            emergency_contact_name: self.emergency_contact_name,
+            emergency_contact_phone: self.emergency_contact_phone,
+            insurance_policy_number: cipher
+                .decrypt(self.insurance_policy_number, &field_context(INSURANCE_POLICY_NUMBER, &id))?,
+            medical_history: cipher.decrypt(self.medical_history, &field_context(MEDICAL_HISTORY, &id))?,
+            current_medications: cipher.decrypt(self.current_medications, &field_context(CURRENT_MEDICATIONS, &id))?,
+            allergies: cipher.decrypt(self.allergies, &field_context(ALLERGIES, &id))?,
+            created_at: self.created_at,
+        })
     }
 }
 
 #[async_trait]
 impl PatientPersistence for PostgresPersistence {
-    async fn create(&self, patient: &Patient) -> AppResult<()> {
+    async fn create(&self, patient: &Patient) -> AppResult<Uuid> {
         let uuid = Uuid::new_v4();
+        let organization_id = tenant::required()?;
+        let encrypted = EncryptedPatientFields::encrypt(&self.cipher, &uuid, patient)?;
 
         if let Some(uid) = patient.user_id {
-            let exists =
-                sqlx::query_scalar!("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)", uid)
-                    .fetch_one(&self.pool)
-                    .await
-                    .map_err(AppError::Database)?
-                    .unwrap_or(false);
+            let exists = sqlx::query_scalar!(
+                "SELECT EXISTS(SELECT 1 FROM organization_members WHERE user_id = $1 AND organization_id = $2)",
+                uid,
+                organization_id
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?
+            .unwrap_or(false);
 
             if !exists {
-                return Err(AppError::Internal(String::from("User does not exist")));
+                return Err(AppError::Internal(String::from("User is not a member of the organization")));
             }
         }
 
         sqlx::query!(
-                "INSERT INTO patients (id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies) 
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                "INSERT INTO patients (id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies, organization_id) 
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
                 uuid,
                 patient.user_id,
                 patient.gender.to_id(),
@@ -78,16 +118,17 @@ impl PatientPersistence for PostgresPersistence {
                 patient.phone,
                 patient.emergency_contact_name,
                 patient.emergency_contact_phone,
-                patient.insurance_policy_number,
-                patient.medical_history,
-                patient.current_medications,
-                patient.allergies
+                encrypted.insurance_policy_number,
+                encrypted.medical_history,
+                encrypted.current_medications,
+                encrypted.allergies,
+                organization_id
             )
             .execute(&self.pool)
             .await
             .map_err(AppError::Database)?;
 
-        Ok(())
+        Ok(uuid)
     }
 
     async fn read_all(&self) -> AppResult<Vec<Patient>> {
@@ -96,12 +137,14 @@ impl PatientPersistence for PostgresPersistence {
             r#"
                 SELECT id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies, created_at
                 FROM patients
-            "#
+                WHERE ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
         .map_err(AppError::Database)
-        .map(|patients| patients.into_iter().map(Patient::from).collect())
+        .and_then(|patients| patients.into_iter().map(|patient| patient.decrypt(&self.cipher)).collect())
     }
 
     async fn read_single(&self, id: &Uuid) -> AppResult<Patient> {
@@ -110,14 +153,15 @@ impl PatientPersistence for PostgresPersistence {
             r#"
                 SELECT id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies, created_at
                 FROM patients 
-                WHERE id = $1
+                WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
         .map_err(AppError::Database)
-        .map(Patient::from)
+        .and_then(|patient| patient.decrypt(&self.cipher))
     }
 
     async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Patient> {
@@ -126,14 +170,15 @@ impl PatientPersistence for PostgresPersistence {
             r#"
                 SELECT id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies, created_at
                 FROM patients 
-                WHERE user_id = $1
+                WHERE user_id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            user_id
+            user_id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
         .map_err(AppError::Database)
-        .map(Patient::from)
+        .and_then(|patient| patient.decrypt(&self.cipher))
     }
 
     async fn read_by_professional(&self, professional_id: &Uuid) -> AppResult<Vec<Patient>> {
@@ -146,32 +191,58 @@ impl PatientPersistence for PostgresPersistence {
                    p.medical_history, p.current_medications, p.allergies, p.created_at
             FROM patients p
             INNER JOIN sessions s ON p.id = s.patient_id
-            WHERE s.professional_id = $1
+            WHERE s.professional_id = $1 AND ($2::uuid IS NULL OR p.organization_id = $2)
         "#,
-            professional_id
+            professional_id,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
         .map_err(AppError::Database)
-        .map(|patients| patients.into_iter().map(Patient::from).collect())
+        .and_then(|patients| patients.into_iter().map(|patient| patient.decrypt(&self.cipher)).collect())
+    }
+
+    async fn is_patient_of(&self, patient_id: &Uuid, professional_user_id: &Uuid) -> AppResult<bool> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM sessions s
+                    INNER JOIN professionals pr ON pr.id = s.professional_id
+                    WHERE s.patient_id = $1 AND pr.user_id = $2 AND s.session_status_id = ANY($4)
+                        AND ($3::uuid IS NULL OR s.organization_id = $3)
+                ) AS "is_patient_of!"
+            "#,
+            patient_id,
+            professional_user_id,
+            tenant::current()?,
+            &[SessionStatus::Scheduled.to_id(), SessionStatus::InProgress.to_id(), SessionStatus::Completed.to_id()]
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)
     }
 
     async fn update(&self, patient: &Patient) -> AppResult<()> {
+        let id = patient.id.ok_or(AppError::InvalidPayload)?;
+        let encrypted = EncryptedPatientFields::encrypt(&self.cipher, &id, patient)?;
+
         sqlx::query!(
             "UPDATE patients 
                 SET gender_id = $2, sexual_orientation_id = $3, birthdate = $4, phone = $5, emergency_contact_name = $6, emergency_contact_phone = $7, insurance_policy_number = $8, medical_history = $9, current_medications = $10, allergies = $11
-                WHERE id = $1",
-            patient.id,
+                WHERE id = $1 AND ($12::uuid IS NULL OR organization_id = $12)",
+            id,
             patient.gender.to_id(),
             patient.sexual_orientation.to_id(),
             patient.birthdate,
             patient.phone,
             patient.emergency_contact_name,
             patient.emergency_contact_phone,
-            patient.insurance_policy_number,
-            patient.medical_history,
-            patient.current_medications,
-            patient.allergies
+            encrypted.insurance_policy_number,
+            encrypted.medical_history,
+            encrypted.current_medications,
+            encrypted.allergies,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -184,9 +255,10 @@ impl PatientPersistence for PostgresPersistence {
 
     async fn update_birthdate(&self, patient_id: &Uuid, birthdate: NaiveDate) -> AppResult<()> {
         sqlx::query!(
-            "UPDATE patients SET birthdate = $2 WHERE id = $1",
+            "UPDATE patients SET birthdate = $2 WHERE id = $1 AND ($3::uuid IS NULL OR organization_id = $3)",
             patient_id,
-            birthdate
+            birthdate,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -196,10 +268,14 @@ impl PatientPersistence for PostgresPersistence {
     }
 
     async fn delete(&self, id: &Uuid) -> AppResult<()> {
-        sqlx::query!("DELETE FROM patients WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(AppError::Database)?;
+        sqlx::query!(
+            "DELETE FROM patients WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)",
+            id,
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(map_clinical_notes_kept_error)?;
 
         Ok(())
     }
@@ -3,7 +3,7 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, clinical_note::map_clinical_notes_kept_error, tenant},
     app_error::{AppError, AppResult},
     dtos::professional::selector::ProfessionalSelectorDTO,
     entities::{gender::Gender, professional::Professional},
@@ -48,6 +48,21 @@ impl From<ProfessionalDb> for Professional {
 impl ProfessionalPersistence for PostgresPersistence {
     async fn create(&self, professional: &Professional) -> AppResult<()> {
         let uuid = Uuid::new_v4();
+        let organization_id = tenant::required()?;
+
+        // The foreign key doesn't tell the organization of the user
+        let is_member = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM organization_members WHERE user_id = $1 AND organization_id = $2) AS "is_member!""#,
+            professional.user_id,
+            organization_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        if !is_member {
+            return Err(AppError::NotFound(String::from("User not found in the organization")));
+        }
 
         // this is not needed since in professionals user_id is a proper foreign constraint, not as in patients
         // if let Some(uid) = professional.user_id {
@@ -64,8 +79,8 @@ impl ProfessionalPersistence for PostgresPersistence {
         // }
 
         sqlx::query!(
-                "INSERT INTO professionals (id, user_id, gender_id, birthdate, license_number, bio, education, experience_years, hourly_rate, accepts_insurance) 
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                "INSERT INTO professionals (id, user_id, gender_id, birthdate, license_number, bio, education, experience_years, hourly_rate, accepts_insurance, organization_id) 
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
                 uuid,
                 professional.user_id,
                 professional.gender.to_id(),
@@ -75,7 +90,8 @@ impl ProfessionalPersistence for PostgresPersistence {
                 professional.education,
                 professional.experience_years,
                 professional.hourly_rate,
-                professional.accepts_insurance
+                professional.accepts_insurance,
+                organization_id
             )
             .execute(&self.pool)
             .await
@@ -90,7 +106,9 @@ impl ProfessionalPersistence for PostgresPersistence {
             r#"
                 SELECT id, user_id, gender_id, birthdate, license_number, bio, education, experience_years, hourly_rate, accepts_insurance, created_at
                 FROM professionals
-            "#
+                WHERE ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -104,9 +122,10 @@ impl ProfessionalPersistence for PostgresPersistence {
             r#"
                 SELECT id, user_id, gender_id, birthdate, license_number, bio, education, experience_years, hourly_rate, accepts_insurance, created_at
                 FROM professionals 
-                WHERE id = $1
+                WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -120,9 +139,10 @@ impl ProfessionalPersistence for PostgresPersistence {
             r#"
                 SELECT id, user_id, gender_id, birthdate, license_number, bio, education, experience_years, hourly_rate, accepts_insurance, created_at
                 FROM professionals 
-                WHERE user_id = $1
+                WHERE user_id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            user_id
+            user_id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -134,7 +154,7 @@ impl ProfessionalPersistence for PostgresPersistence {
         sqlx::query!(
             "UPDATE professionals 
                 SET gender_id = $2, birthdate = $3, license_number = $4, bio = $5, education = $6, experience_years = $7, hourly_rate = $8, accepts_insurance = $9
-                WHERE id = $1",
+                WHERE id = $1 AND ($10::uuid IS NULL OR organization_id = $10)",
             professional.id,
             professional.gender.to_id(),
             professional.birthdate,
@@ -143,7 +163,8 @@ impl ProfessionalPersistence for PostgresPersistence {
             professional.education,
             professional.experience_years,
             professional.hourly_rate,
-            professional.accepts_insurance
+            professional.accepts_insurance,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -153,10 +174,14 @@ impl ProfessionalPersistence for PostgresPersistence {
     }
 
     async fn delete(&self, id: &Uuid) -> AppResult<()> {
-        sqlx::query!("DELETE FROM professionals WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(AppError::Database)?;
+        sqlx::query!(
+            "DELETE FROM professionals WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)",
+            id,
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(map_clinical_notes_kept_error)?;
 
         Ok(())
     }
@@ -170,7 +195,9 @@ impl ProfessionalPersistence for PostgresPersistence {
                     CONCAT(u.username, ' ', u.usersurname) as "name!"
                 FROM professionals p
                 INNER JOIN users u ON p.user_id = u.id
-            "#
+                WHERE ($1::uuid IS NULL OR p.organization_id = $1)
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -0,0 +1,225 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::{PostgresPersistence, tenant},
+    app_error::{AppError, AppResult},
+    entities::{
+        professional_availability::{
+            AvailabilityException, ProfessionalAvailability, TimeSlot, weekday_from_id,
+            weekday_to_id,
+        },
+        session::{DEFAULT_SESSION_DURATION_MINUTES, SessionStatus},
+    },
+    use_cases::professional_availability::ProfessionalAvailabilityPersistence,
+};
+
+// Availability rule as stored in the db.
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct ProfessionalAvailabilityDb {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub weekday: i32,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub slot_duration: i32,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<ProfessionalAvailabilityDb> for ProfessionalAvailability {
+    fn from(availability_db: ProfessionalAvailabilityDb) -> Self {
+        ProfessionalAvailability {
+            id: Some(availability_db.id),
+            professional_id: availability_db.professional_id,
+            weekday: weekday_from_id(availability_db.weekday).unwrap_or(Weekday::Mon), // the db constraint keeps this in range
+            start_time: availability_db.start_time,
+            end_time: availability_db.end_time,
+            slot_duration: availability_db.slot_duration,
+            created_at: availability_db.created_at,
+        }
+    }
+}
+
+// Availability exception as stored in the db.
+#[derive(sqlx::FromRow, Debug, Serialize)]
+pub struct AvailabilityExceptionDb {
+    pub id: Uuid,
+    pub professional_id: Uuid,
+    pub exception_date: NaiveDate,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub reason: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<AvailabilityExceptionDb> for AvailabilityException {
+    fn from(exception_db: AvailabilityExceptionDb) -> Self {
+        AvailabilityException {
+            id: Some(exception_db.id),
+            professional_id: exception_db.professional_id,
+            exception_date: exception_db.exception_date,
+            start_time: exception_db.start_time,
+            end_time: exception_db.end_time,
+            reason: exception_db.reason,
+            created_at: exception_db.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl ProfessionalAvailabilityPersistence for PostgresPersistence {
+    async fn create_rule(&self, rule: &ProfessionalAvailability) -> AppResult<()> {
+        let uuid = Uuid::new_v4();
+
+        sqlx::query!(
+            "INSERT INTO professional_availability (id, professional_id, weekday, start_time, end_time, slot_duration)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+            uuid,
+            rule.professional_id,
+            weekday_to_id(rule.weekday),
+            rule.start_time,
+            rule.end_time,
+            rule.slot_duration
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read_rules(&self, professional_id: &Uuid) -> AppResult<Vec<ProfessionalAvailability>> {
+        sqlx::query_as!(
+            ProfessionalAvailabilityDb,
+            r#"
+                SELECT id, professional_id, weekday, start_time, end_time, slot_duration, created_at
+                FROM professional_availability
+                WHERE professional_id = $1
+                    AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))
+                ORDER BY weekday, start_time
+            "#,
+            professional_id,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|rules| rules.into_iter().map(ProfessionalAvailability::from).collect())
+    }
+
+    async fn delete_rule(&self, professional_id: &Uuid, id: &Uuid) -> AppResult<()> {
+        sqlx::query!(
+            "DELETE FROM professional_availability WHERE id = $1 AND professional_id = $2",
+            id,
+            professional_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn create_exception(&self, exception: &AvailabilityException) -> AppResult<()> {
+        let uuid = Uuid::new_v4();
+
+        sqlx::query!(
+            "INSERT INTO professional_availability_exceptions (id, professional_id, exception_date, start_time, end_time, reason)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+            uuid,
+            exception.professional_id,
+            exception.exception_date,
+            exception.start_time,
+            exception.end_time,
+            exception.reason
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read_exceptions(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> AppResult<Vec<AvailabilityException>> {
+        sqlx::query_as!(
+            AvailabilityExceptionDb,
+            r#"
+                SELECT id, professional_id, exception_date, start_time, end_time, reason, created_at
+                FROM professional_availability_exceptions
+                WHERE professional_id = $1 AND exception_date BETWEEN $2 AND $3
+                    AND ($4::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $4))
+                ORDER BY exception_date, start_time
+            "#,
+            professional_id,
+            from,
+            to,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|exceptions| exceptions.into_iter().map(AvailabilityException::from).collect())
+    }
+
+    async fn delete_exception(&self, professional_id: &Uuid, id: &Uuid) -> AppResult<()> {
+        sqlx::query!(
+            "DELETE FROM professional_availability_exceptions WHERE id = $1 AND professional_id = $2",
+            id,
+            professional_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read_booked(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> AppResult<Vec<TimeSlot>> {
+        let sessions = sqlx::query!(
+            r#"
+                SELECT session_date, session_duration
+                FROM sessions
+                WHERE professional_id = $1
+                    AND session_status_id <> $2
+                    AND session_date < $4
+                    AND session_date + make_interval(mins => COALESCE(session_duration, $5)) > $3
+                    AND ($6::uuid IS NULL OR organization_id = $6)
+            "#,
+            professional_id,
+            SessionStatus::Cancelled.to_id(),
+            from,
+            to,
+            DEFAULT_SESSION_DURATION_MINUTES,
+            tenant::current()?
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| TimeSlot {
+                start: session.session_date,
+                end: session.session_date
+                    + chrono::Duration::minutes(
+                        session
+                            .session_duration
+                            .unwrap_or(DEFAULT_SESSION_DURATION_MINUTES)
+                            .into(),
+                    ),
+            })
+            .collect())
+    }
+}
@@ -3,7 +3,7 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, tenant},
     app_error::{AppError, AppResult},
     entities::professional_language::ProfessionalLanguage,
     use_cases::professional_language::ProfessionalLanguagePersistence,
@@ -34,17 +34,24 @@ impl ProfessionalLanguagePersistence for PostgresPersistence {
     async fn create(&self, professional_language: &ProfessionalLanguage) -> AppResult<()> {
         let uuid = Uuid::new_v4();
 
-        sqlx::query!(
+        // Only for professionals of the organization
+        let result = sqlx::query!(
             "INSERT INTO professionals_languages (id, professional_id, p_language) 
-                    VALUES ($1, $2, $3)",
+                    SELECT $1, $2, $3
+                    WHERE $4::uuid IS NULL OR EXISTS (SELECT 1 FROM professionals WHERE id = $2 AND organization_id = $4)",
             uuid,
             professional_language.professional_id,
-            professional_language.name
+            professional_language.name,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(String::from("Professional not found")));
+        }
+
         Ok(())
     }
 
@@ -54,7 +61,9 @@ impl ProfessionalLanguagePersistence for PostgresPersistence {
             r#"
                 SELECT id, professional_id, p_language as name, created_at
                 FROM professionals_languages
-            "#
+                WHERE ($1::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $1))
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -74,8 +83,10 @@ impl ProfessionalLanguagePersistence for PostgresPersistence {
                 SELECT id, professional_id, p_language as name, created_at
                 FROM professionals_languages 
                 WHERE id = $1
+                    AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -87,9 +98,11 @@ impl ProfessionalLanguagePersistence for PostgresPersistence {
         sqlx::query!(
             "UPDATE professionals_languages 
                 SET p_language = $2
-                WHERE id = $1",
+                WHERE id = $1
+                    AND ($3::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $3))",
             professional_language.id,
-            professional_language.name
+            professional_language.name,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -99,10 +112,15 @@ impl ProfessionalLanguagePersistence for PostgresPersistence {
     }
 
     async fn delete(&self, id: Uuid) -> AppResult<()> {
-        sqlx::query!("DELETE FROM professionals_languages WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(AppError::Database)?;
+        sqlx::query!(
+            "DELETE FROM professionals_languages WHERE id = $1
+                AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))",
+            id,
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
 
         Ok(())
     }
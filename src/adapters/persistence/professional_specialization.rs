@@ -3,7 +3,7 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, tenant},
     app_error::{AppError, AppResult},
     entities::professional_specialization::ProfessionalSpecialization,
     use_cases::professional_specialization::ProfessionalSpecializationPersistence,
@@ -37,17 +37,24 @@ impl ProfessionalSpecializationPersistence for PostgresPersistence {
     ) -> AppResult<()> {
         let uuid = Uuid::new_v4();
 
-        sqlx::query!(
+        // Only for professionals of the organization
+        let result = sqlx::query!(
             "INSERT INTO professionals_specializations (id, professional_id, s_name) 
-                    VALUES ($1, $2, $3)",
+                    SELECT $1, $2, $3
+                    WHERE $4::uuid IS NULL OR EXISTS (SELECT 1 FROM professionals WHERE id = $2 AND organization_id = $4)",
             uuid,
             professional_specialization.professional_id,
-            professional_specialization.name
+            professional_specialization.name,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(String::from("Professional not found")));
+        }
+
         Ok(())
     }
 
@@ -57,7 +64,9 @@ impl ProfessionalSpecializationPersistence for PostgresPersistence {
             r#"
                 SELECT id, professional_id, s_name as name, created_at
                 FROM professionals_specializations
-            "#
+                WHERE ($1::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $1))
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -77,8 +86,10 @@ impl ProfessionalSpecializationPersistence for PostgresPersistence {
                 SELECT id, professional_id, s_name as name, created_at
                 FROM professionals_specializations 
                 WHERE id = $1
+                    AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -93,9 +104,11 @@ impl ProfessionalSpecializationPersistence for PostgresPersistence {
         sqlx::query!(
             "UPDATE professionals_specializations 
                 SET s_name = $2
-                WHERE id = $1",
+                WHERE id = $1
+                    AND ($3::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $3))",
             professional_specialization.id,
-            professional_specialization.name
+            professional_specialization.name,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -106,8 +119,10 @@ impl ProfessionalSpecializationPersistence for PostgresPersistence {
 
     async fn delete(&self, id: Uuid) -> AppResult<()> {
         sqlx::query!(
-            "DELETE FROM professionals_specializations WHERE id = $1",
-            id
+            "DELETE FROM professionals_specializations WHERE id = $1
+                AND ($2::uuid IS NULL OR professional_id IN (SELECT id FROM professionals WHERE organization_id = $2))",
+            id,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
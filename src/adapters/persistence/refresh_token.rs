@@ -0,0 +1,148 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::PostgresPersistence,
+    app_error::{AppError, AppResult},
+    entities::refresh_token::RefreshToken,
+    use_cases::user::RefreshTokenPersistence,
+};
+
+// Refresh token as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct RefreshTokenDb {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<RefreshTokenDb> for RefreshToken {
+    fn from(token_db: RefreshTokenDb) -> Self {
+        RefreshToken {
+            id: token_db.id,
+            user_id: token_db.user_id,
+            family_id: token_db.family_id,
+            token_hash: token_db.token_hash,
+            expires_at: token_db.expires_at,
+            revoked_at: token_db.revoked_at,
+            created_at: token_db.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl RefreshTokenPersistence for PostgresPersistence {
+    async fn create(&self, token: &RefreshToken) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO refresh_tokens (id, user_id, family_id, token_hash, expires_at)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+            token.id,
+            token.user_id,
+            token.family_id,
+            token.token_hash,
+            token.expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn read_by_hash(&self, token_hash: &str) -> AppResult<Option<RefreshToken>> {
+        sqlx::query_as!(
+            RefreshTokenDb,
+            r#"
+                SELECT id, user_id, family_id, token_hash, expires_at, revoked_at, created_at
+                FROM refresh_tokens
+                WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|token| token.map(RefreshToken::from))
+    }
+
+    async fn rotate(&self, current: &Uuid, next: &RefreshToken) -> AppResult<bool> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        // Only one of two concurrent refreshes with the same token gets to revoke it
+        let revoked = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = NOW() AT TIME ZONE 'UTC' WHERE id = $1 AND revoked_at IS NULL",
+            current
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected();
+
+        if revoked == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+                INSERT INTO refresh_tokens (id, user_id, family_id, token_hash, expires_at)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+            next.id,
+            next.user_id,
+            next.family_id,
+            next.token_hash,
+            next.expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(true)
+    }
+
+    async fn revoke(&self, id: &Uuid) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = NOW() AT TIME ZONE 'UTC' WHERE id = $1 AND revoked_at IS NULL",
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn revoke_family(&self, family_id: &Uuid) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = NOW() AT TIME ZONE 'UTC' WHERE family_id = $1 AND revoked_at IS NULL",
+            family_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn revoke_all(&self, user_id: &Uuid) -> AppResult<u64> {
+        let revoked = sqlx::query!(
+            "UPDATE refresh_tokens SET revoked_at = NOW() AT TIME ZONE 'UTC' WHERE user_id = $1 AND revoked_at IS NULL",
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected();
+
+        Ok(revoked)
+    }
+}
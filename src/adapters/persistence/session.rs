@@ -3,12 +3,17 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::{
+        persistence::{PostgresPersistence, clinical_note::map_clinical_notes_kept_error, tenant},
+    },
     app_error::{AppError, AppResult},
-    entities::session::{Session, SessionStatus},
+    entities::session::{DEFAULT_SESSION_DURATION_MINUTES, Session, SessionStatus},
     use_cases::session::SessionPersistence,
 };
 
+/// SQLSTATE raised by postgres when an exclusion constraint is violated
+const EXCLUSION_VIOLATION: &str = "23P01";
+
 // Session struct as stored in the db.
 #[derive(sqlx::FromRow, Debug, Serialize)]
 pub struct SessionDb {
@@ -19,7 +24,9 @@ pub struct SessionDb {
     pub session_status_id: i32,
     pub session_date: Option<chrono::NaiveDateTime>,
     pub videocall_url: Option<String>,
-    pub notes: Option<String>,
+    pub videocall_provider: Option<String>,
+    pub videocall_host_url: Option<String>,
+    pub videocall_meeting_id: Option<String>,
     pub completed: Option<bool>,
     pub session_duration: Option<i32>,
     pub created_at: Option<chrono::NaiveDateTime>,
@@ -36,7 +43,11 @@ impl From<SessionDb> for Session {
                 .unwrap_or_default(),
             session_date: session_db.session_date,
             videocall_url: session_db.videocall_url,
-            notes: session_db.notes,
+            videocall_provider: session_db
+                .videocall_provider
+                .and_then(|provider| provider.parse().ok()),
+            videocall_host_url: session_db.videocall_host_url,
+            videocall_meeting_id: session_db.videocall_meeting_id,
             completed: session_db.completed.unwrap_or(false),
             session_duration: session_db.session_duration,
             created_at: session_db.created_at,
@@ -46,12 +57,30 @@ impl From<SessionDb> for Session {
 
 #[async_trait]
 impl SessionPersistence for PostgresPersistence {
-    async fn create(&self, session: &Session) -> AppResult<()> {
+    async fn create(&self, session: &Session) -> AppResult<Uuid> {
         let uuid = Uuid::new_v4();
+        let organization_id = tenant::required()?;
+
+        let same_organization = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (SELECT 1 FROM patients WHERE id = $1 AND organization_id = $3)
+                    AND EXISTS (SELECT 1 FROM professionals WHERE id = $2 AND organization_id = $3) AS "same_organization!"
+            "#,
+            session.patient_id,
+            session.professional_id,
+            organization_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        if !same_organization {
+            return Err(AppError::NotFound(String::from("Patient or professional not found in the organization")));
+        }
 
         sqlx::query!(
-            "INSERT INTO sessions (id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, notes, completed, session_duration) 
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            "INSERT INTO sessions (id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, videocall_provider, videocall_host_url, videocall_meeting_id, completed, session_duration, organization_id) 
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
             uuid,
             session.patient_id,
             session.professional_id,
@@ -59,24 +88,29 @@ impl SessionPersistence for PostgresPersistence {
             session.session_status.to_id(),
             session.session_date,
             session.videocall_url,
-            session.notes,
+            session.videocall_provider.map(|provider| provider.to_string()),
+            session.videocall_host_url,
+            session.videocall_meeting_id,
             session.completed,
-            session.session_duration
+            session.session_duration,
+            organization_id
         )
         .execute(&self.pool)
         .await
-        .map_err(AppError::Database)?;
+        .map_err(map_overlap_error)?;
 
-        Ok(())
+        Ok(uuid)
     }
 
     async fn read_all(&self) -> AppResult<Vec<Session>> {
         sqlx::query_as!(
             SessionDb,
             r#"
-                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, notes, completed, session_duration, created_at
+                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, videocall_provider, videocall_host_url, videocall_meeting_id, completed, session_duration, created_at
                 FROM sessions
-            "#
+                WHERE ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -88,11 +122,12 @@ impl SessionPersistence for PostgresPersistence {
         sqlx::query_as!(
             SessionDb,
             r#"
-                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, notes, completed, session_duration, created_at
+                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, videocall_provider, videocall_host_url, videocall_meeting_id, completed, session_duration, created_at
                 FROM sessions
-                WHERE patient_id = $1
+                WHERE patient_id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            patient_id
+            patient_id,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -104,11 +139,12 @@ impl SessionPersistence for PostgresPersistence {
         sqlx::query_as!(
             SessionDb,
             r#"
-                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, notes, completed, session_duration, created_at
+                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, videocall_provider, videocall_host_url, videocall_meeting_id, completed, session_duration, created_at
                 FROM sessions
-                WHERE professional_id = $1
+                WHERE professional_id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            professional_id
+            professional_id,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -120,11 +156,12 @@ impl SessionPersistence for PostgresPersistence {
         sqlx::query_as!(
             SessionDb,
             r#"
-                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, notes, completed, session_duration, created_at
+                SELECT id, patient_id, professional_id, session_type_id, session_status_id, session_date, videocall_url, videocall_provider, videocall_host_url, videocall_meeting_id, completed, session_duration, created_at
                 FROM sessions 
-                WHERE id = $1
+                WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -132,35 +169,144 @@ impl SessionPersistence for PostgresPersistence {
         .map(Session::from)
     }
 
+    async fn is_professional_user(&self, professional_id: &Uuid, user_id: &Uuid) -> AppResult<bool> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM professionals WHERE id = $1 AND user_id = $2 AND ($3::uuid IS NULL OR organization_id = $3)
+                ) AS "is_professional_user!"
+            "#,
+            professional_id,
+            user_id,
+            tenant::current()?
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
     async fn update(&self, session: &Session) -> AppResult<()> {
+        let id = session.id.ok_or(AppError::InvalidPayload)?;
+
+        // The updates coming from the api don't know the provider nor the meeting, they're kept for as long as the link doesn't change
         sqlx::query!(
             "UPDATE sessions 
-                SET patient_id = $2, professional_id = $3, session_type_id = $4, session_status_id = $5, session_date = $6, videocall_url = $7, notes = $8, completed = $9, session_duration = $10
-                WHERE id = $1",
-            session.id,
+                SET patient_id = $2, professional_id = $3, session_type_id = $4, session_date = $5, videocall_url = $6, completed = $7, session_duration = $8,
+                    videocall_provider = CASE WHEN videocall_url IS NOT DISTINCT FROM $6 THEN COALESCE($9, videocall_provider) ELSE $9 END,
+                    videocall_host_url = CASE WHEN videocall_url IS NOT DISTINCT FROM $6 THEN COALESCE($10, videocall_host_url) ELSE $10 END,
+                    videocall_meeting_id = CASE WHEN videocall_url IS NOT DISTINCT FROM $6 THEN COALESCE($11, videocall_meeting_id) ELSE $11 END
+                WHERE id = $1 AND ($12::uuid IS NULL OR (
+                    organization_id = $12
+                    AND EXISTS (SELECT 1 FROM patients WHERE id = $2 AND organization_id = $12)
+                    AND EXISTS (SELECT 1 FROM professionals WHERE id = $3 AND organization_id = $12)
+                ))",
+            id,
             session.patient_id,
             session.professional_id,
             session.session_type_id,
-            session.session_status.to_id(),
             session.session_date,
             session.videocall_url,
-            session.notes,
             session.completed,
-            session.session_duration
+            session.session_duration,
+            session.videocall_provider.map(|provider| provider.to_string()),
+            session.videocall_host_url,
+            session.videocall_meeting_id,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
-        .map_err(AppError::Database)?;
+        .map_err(map_overlap_error)?;
 
         Ok(())
     }
 
+    async fn transition(&self, id: &Uuid, from: &[SessionStatus], to: &SessionStatus) -> AppResult<Option<SessionStatus>> {
+        let from: Vec<i32> = from.iter().map(SessionStatus::to_id).collect();
+
+        // The row lock makes a concurrent transition wait and recheck the status it expects
+        let previous = sqlx::query_scalar!(
+            r#"
+                WITH previous AS (
+                    SELECT id, session_status_id FROM sessions
+                    WHERE id = $1 AND session_status_id = ANY($2) AND ($4::uuid IS NULL OR organization_id = $4)
+                    FOR UPDATE
+                )
+                UPDATE sessions SET session_status_id = $3
+                FROM previous
+                WHERE sessions.id = previous.id
+                RETURNING previous.session_status_id
+            "#,
+            id,
+            &from,
+            to.to_id(),
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(previous.and_then(SessionStatus::from_id))
+    }
+
+    async fn has_overlapping(
+        &self,
+        professional_id: &Uuid,
+        from: chrono::NaiveDateTime,
+        to: chrono::NaiveDateTime,
+        exclude_id: Option<&Uuid>,
+    ) -> AppResult<bool> {
+        let overlapping = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM sessions
+                    WHERE professional_id = $1
+                        AND session_status_id <> $2
+                        AND ($5::uuid IS NULL OR id <> $5)
+                        AND session_date < $4
+                        AND session_date + make_interval(mins => COALESCE(session_duration, $6)) > $3
+                ) AS "overlapping!"
+            "#,
+            professional_id,
+            SessionStatus::Cancelled.to_id(),
+            from,
+            to,
+            exclude_id,
+            DEFAULT_SESSION_DURATION_MINUTES
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(overlapping)
+    }
+
     async fn delete(&self, id: &Uuid) -> AppResult<()> {
-        sqlx::query!("DELETE FROM sessions WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(AppError::Database)?;
+        sqlx::query!(
+            "DELETE FROM sessions WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)",
+            id,
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(map_clinical_notes_kept_error)?;
 
         Ok(())
     }
 }
+
+/// The overlap constraint is the last line of defense against concurrent bookings, surface it as a conflict
+fn map_overlap_error(error: sqlx::Error) -> AppError {
+    let is_overlap = error
+        .as_database_error()
+        .and_then(|db_error| db_error.code())
+        .is_some_and(|code| code == EXCLUSION_VIOLATION);
+
+    if is_overlap {
+        return AppError::Conflict(
+            "The professional already has a session at the requested time".into(),
+        );
+    }
+
+    AppError::Database(error)
+}
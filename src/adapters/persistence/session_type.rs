@@ -3,7 +3,7 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, tenant},
     app_error::{AppError, AppResult},
     entities::session_type::SessionType,
     use_cases::session_type::SessionTypePersistence,
@@ -33,10 +33,11 @@ impl SessionTypePersistence for PostgresPersistence {
         let uuid = Uuid::new_v4();
 
         sqlx::query!(
-            "INSERT INTO session_types (id, session_type_name) 
-                    VALUES ($1, $2)",
+            "INSERT INTO session_types (id, session_type_name, organization_id) 
+                    VALUES ($1, $2, $3)",
             uuid,
-            name
+            name,
+            tenant::required()?
         )
         .execute(&self.pool)
         .await
@@ -51,7 +52,9 @@ impl SessionTypePersistence for PostgresPersistence {
             r#"
                 SELECT id, session_type_name, created_at
                 FROM session_types
-            "#
+                WHERE ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -65,9 +68,10 @@ impl SessionTypePersistence for PostgresPersistence {
             r#"
                 SELECT id, session_type_name, created_at
                 FROM session_types 
-                WHERE id = $1
+                WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
             "#,
-            id
+            id,
+            tenant::current()?
         )
         .fetch_one(&self.pool)
         .await
@@ -79,9 +83,10 @@ impl SessionTypePersistence for PostgresPersistence {
         sqlx::query!(
             "UPDATE session_types 
                 SET session_type_name = $2
-                WHERE id = $1",
+                WHERE id = $1 AND ($3::uuid IS NULL OR organization_id = $3)",
             id,
-            name
+            name,
+            tenant::current()?
         )
         .execute(&self.pool)
         .await
@@ -91,10 +96,14 @@ impl SessionTypePersistence for PostgresPersistence {
     }
 
     async fn delete(&self, id: Uuid) -> AppResult<()> {
-        sqlx::query!("DELETE FROM session_types WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(AppError::Database)?;
+        sqlx::query!(
+            "DELETE FROM session_types WHERE id = $1 AND ($2::uuid IS NULL OR organization_id = $2)",
+            id,
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
 
         Ok(())
     }
@@ -0,0 +1,67 @@
+use std::future::Future;
+
+use uuid::Uuid;
+
+use crate::app_error::{AppError, AppResult};
+
+/// What the queries of a task can see
+#[derive(Debug, Clone, Copy)]
+enum Tenant {
+    Organization(Uuid),
+    Unscoped,
+}
+
+tokio::task_local! {
+    static TENANT: Tenant;
+}
+
+/// Runs the future with every query of it scoped to the organization, the auth middleware wraps each request in it
+pub async fn scope<F: Future>(organization_id: Uuid, future: F) -> F::Output {
+    TENANT.scope(Tenant::Organization(organization_id), future).await
+}
+
+/// Runs the future with its queries seeing the rows of every organization. Only for what runs on behalf of no user
+/// of an organization: the payment webhooks and the background jobs
+pub async fn unscoped<F: Future>(future: F) -> F::Output {
+    TENANT.scope(Tenant::Unscoped, future).await
+}
+
+/// Organization the queries are scoped to, none inside `unscoped`. Fails outside of both, so a query that was never
+/// given a tenant doesn't see every organization
+pub fn current() -> AppResult<Option<Uuid>> {
+    match TENANT.try_with(|tenant| *tenant) {
+        Ok(Tenant::Organization(organization_id)) => Ok(Some(organization_id)),
+        Ok(Tenant::Unscoped) => Ok(None),
+        Err(_) => Err(AppError::Internal(String::from("Query run outside of a tenant scope"))),
+    }
+}
+
+/// Organization the new rows belong to
+pub fn required() -> AppResult<Uuid> {
+    current()?.ok_or_else(|| AppError::Internal(String::from("No organization to create the row in")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn the_organization_is_only_set_inside_the_scope() {
+        let organization_id = Uuid::new_v4();
+
+        assert!(current().is_err());
+        assert!(required().is_err());
+
+        let inside = scope(organization_id, async { (current().ok(), required().ok()) }).await;
+        assert_eq!(inside, (Some(Some(organization_id)), Some(organization_id)));
+
+        assert!(current().is_err());
+    }
+
+    #[tokio::test]
+    async fn unscoped_queries_see_every_organization_but_create_nothing() {
+        let inside = unscoped(async { (current().ok(), required().is_err()) }).await;
+
+        assert_eq!(inside, (Some(None), true));
+    }
+}
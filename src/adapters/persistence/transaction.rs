@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use sqlx::query;
+use uuid::Uuid;
 
 
 use crate::{
@@ -9,22 +10,24 @@ use crate::{
     domain::entities::transaction::{Transaction, TransactionStatus},
 };
 
-use super::PostgresPersistence;
+use super::{PostgresPersistence, tenant};
 
 #[async_trait]
 impl TransactionPersistence for PostgresPersistence {
     async fn create(&self, transaction: &Transaction) -> AppResult<Transaction> {
         query!(
             r#"
-            INSERT INTO transactions (id, payment_intent_id, session_id, amount, currency, status, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO transactions (id, payment_intent_id, session_id, booked_session_id, amount, currency, status, refunded_amount, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
             transaction.id,
             transaction.payment_intent_id,
             transaction.session_id,
+            transaction.booked_session_id,
             transaction.amount,
             transaction.currency,
             transaction.status.to_string(),
+            transaction.refunded_amount,
             transaction.created_at.naive_utc(),
             transaction.updated_at.naive_utc()
         )
@@ -42,11 +45,12 @@ impl TransactionPersistence for PostgresPersistence {
         query!(
             r#"
             UPDATE transactions
-            SET payment_intent_id = $1, status = $2, updated_at = $3
-            WHERE id = $4
+            SET payment_intent_id = $1, status = $2, refunded_amount = $3, updated_at = $4
+            WHERE id = $5
             "#,
             transaction.payment_intent_id,
             transaction.status.to_string(),
+            transaction.refunded_amount,
             transaction.updated_at.naive_utc(),
             transaction.id
         )
@@ -63,7 +67,7 @@ impl TransactionPersistence for PostgresPersistence {
     async fn get_by_session_id(&self, session_id: &str) -> AppResult<Transaction> {
         let rec = query!(
             r#"
-            SELECT id, payment_intent_id, session_id, amount, currency, status, created_at, updated_at
+            SELECT id, payment_intent_id, session_id, booked_session_id, amount, currency, status, refunded_amount, created_at, updated_at
             FROM transactions
             WHERE session_id = $1
             "#,
@@ -81,13 +85,52 @@ impl TransactionPersistence for PostgresPersistence {
                 id: row.id,
                 payment_intent_id: row.payment_intent_id,
                 session_id: row.session_id,
+                booked_session_id: row.booked_session_id,
                 amount: row.amount,
                 currency: row.currency,
                 status: TransactionStatus::from(row.status),
+                refunded_amount: row.refunded_amount,
                 created_at: row.created_at.expect("created_at cannot be null").and_utc(),
                 updated_at: row.updated_at.expect("updated_at cannot be null").and_utc(),
             }),
             None => Err(AppError::NotFound(format!("Transaction with session_id {} not found", session_id))),
         }
     }
+
+    async fn get_by_booked_session_id(&self, booked_session_id: &Uuid) -> AppResult<Transaction> {
+        let rec = query!(
+            r#"
+            SELECT id, payment_intent_id, session_id, booked_session_id, amount, currency, status, refunded_amount, created_at, updated_at
+            FROM transactions
+            WHERE booked_session_id = $1
+                AND ($2::uuid IS NULL OR booked_session_id IN (SELECT id FROM sessions WHERE organization_id = $2))
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            booked_session_id,
+            tenant::current()?
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch transaction: {:?}", e);
+            AppError::Database(e)
+        })?;
+
+        match rec {
+            Some(row) => Ok(Transaction {
+                id: row.id,
+                payment_intent_id: row.payment_intent_id,
+                session_id: row.session_id,
+                booked_session_id: row.booked_session_id,
+                amount: row.amount,
+                currency: row.currency,
+                status: TransactionStatus::from(row.status),
+                refunded_amount: row.refunded_amount,
+                created_at: row.created_at.expect("created_at cannot be null").and_utc(),
+                updated_at: row.updated_at.expect("updated_at cannot be null").and_utc(),
+            }),
+            None => Err(AppError::NotFound(format!("Transaction for booked session {} not found", booked_session_id))),
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::{
+    adapters::persistence::PostgresPersistence,
+    app_error::{AppError, AppResult},
+    entities::{two_factor::TwoFactor, user::Role},
+    use_cases::user::TwoFactorPersistence,
+};
+
+// Two factor enrollment as stored in the db.
+#[derive(sqlx::FromRow, Debug)]
+pub struct TwoFactorDb {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub enabled_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<TwoFactorDb> for TwoFactor {
+    fn from(two_factor_db: TwoFactorDb) -> Self {
+        TwoFactor {
+            user_id: two_factor_db.user_id,
+            secret: two_factor_db.secret,
+            enabled_at: two_factor_db.enabled_at,
+            created_at: two_factor_db.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl TwoFactorPersistence for PostgresPersistence {
+    async fn read(&self, user_id: &Uuid) -> AppResult<Option<TwoFactor>> {
+        sqlx::query_as!(
+            TwoFactorDb,
+            r#"
+                SELECT user_id, secret, enabled_at, created_at
+                FROM user_two_factor
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+        .map(|two_factor| two_factor.map(TwoFactor::from))
+    }
+
+    async fn save_pending_secret(&self, user_id: &Uuid, secret: &str) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO user_two_factor (user_id, secret)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id) DO UPDATE
+                SET secret = EXCLUDED.secret, created_at = NOW() AT TIME ZONE 'UTC'
+                WHERE user_two_factor.enabled_at IS NULL
+            "#,
+            user_id,
+            secret
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn enable(&self, user_id: &Uuid, recovery_code_hashes: &[String]) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        sqlx::query!(
+            "UPDATE user_two_factor SET enabled_at = NOW() AT TIME ZONE 'UTC' WHERE user_id = $1",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM two_factor_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        for code_hash in recovery_code_hashes {
+            sqlx::query!(
+                "INSERT INTO two_factor_recovery_codes (id, user_id, code_hash) VALUES ($1, $2, $3)",
+                Uuid::new_v4(),
+                user_id,
+                code_hash
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn disable(&self, user_id: &Uuid) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM two_factor_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query!("DELETE FROM user_two_factor WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn use_recovery_code(&self, user_id: &Uuid, code_hash: &str) -> AppResult<bool> {
+        // Only one of two concurrent logins with the same code gets to use it
+        let used = sqlx::query!(
+            r#"
+                UPDATE two_factor_recovery_codes
+                SET used_at = NOW() AT TIME ZONE 'UTC'
+                WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            code_hash
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected();
+
+        Ok(used > 0)
+    }
+
+    async fn is_required(&self, role: &Role) -> AppResult<bool> {
+        let required = sqlx::query_scalar!(
+            "SELECT required FROM two_factor_policies WHERE role_id = $1",
+            role.to_id()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(required.unwrap_or(false))
+    }
+
+    async fn set_required(&self, role: &Role, required: bool) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO two_factor_policies (role_id, required)
+                VALUES ($1, $2)
+                ON CONFLICT (role_id) DO UPDATE
+                SET required = EXCLUDED.required, updated_at = NOW() AT TIME ZONE 'UTC'
+            "#,
+            role.to_id(),
+            required
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
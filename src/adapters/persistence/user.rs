@@ -5,9 +5,12 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    adapters::persistence::PostgresPersistence,
+    adapters::persistence::{PostgresPersistence, patient::EncryptedPatientFields, tenant},
     app_error::{AppError, AppResult},
-    entities::user::{Role, User},
+    entities::{
+        user::{Role, User},
+        user_token::TokenPurpose,
+    },
     use_cases::user::{UserPersistence, OnboardingDto},
 };
 
@@ -49,6 +52,7 @@ pub enum RoleDb {
     Patient,
     Professional,
     Admin,
+    Receptionist,
 }
 
 impl From<RoleDb> for Role {
@@ -57,6 +61,7 @@ impl From<RoleDb> for Role {
             RoleDb::Patient => Role::Patient,
             RoleDb::Professional => Role::Professional,
             RoleDb::Admin => Role::Admin,
+            RoleDb::Receptionist => Role::Receptionist,
         }
     }
 }
@@ -77,6 +82,7 @@ impl RoleDb {
             Role::Patient => RoleDb::Patient,
             Role::Professional => RoleDb::Professional,
             Role::Admin => RoleDb::Admin,
+            Role::Receptionist => RoleDb::Receptionist,
         })
     }
 }
@@ -95,12 +101,25 @@ impl UserPersistence for PostgresPersistence {
         usersurname: &str,
         email: &str,
         password_hash: &str,
+        organization_id: &Uuid,
     ) -> AppResult<User> {
         use crate::entities::patient::Patient;
         let uuid = Uuid::new_v4();
 
         let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
 
+        let organization_exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM organizations WHERE id = $1) AS "exists!""#,
+            organization_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if !organization_exists {
+            return Err(AppError::NotFound(String::from("Organization not found")));
+        }
+
         let user = sqlx::query_as!(
             UserDb,
             "INSERT INTO users (id, role_id, username, usersurname, email, password_hash) 
@@ -118,11 +137,21 @@ impl UserPersistence for PostgresPersistence {
         .map_err(AppError::Database)
         .map(User::from)?;
 
+        sqlx::query!(
+            "INSERT INTO organization_members (organization_id, user_id) VALUES ($1, $2)",
+            organization_id,
+            user.id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
         let patient = Patient::from(&user);
+        let encrypted = EncryptedPatientFields::encrypt(&self.cipher, &uuid, &patient)?;
 
         sqlx::query!(
-        "INSERT INTO patients (id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies) 
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        "INSERT INTO patients (id, user_id, gender_id, sexual_orientation_id, birthdate, phone, emergency_contact_name, emergency_contact_phone, insurance_policy_number, medical_history, current_medications, allergies, organization_id) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
             uuid,
             patient.user_id,
             patient.gender.to_id(),
@@ -131,10 +160,11 @@ impl UserPersistence for PostgresPersistence {
             patient.phone,
             patient.emergency_contact_name,
             patient.emergency_contact_phone,
-            patient.insurance_policy_number,
-            patient.medical_history,
-            patient.current_medications,
-            patient.allergies
+            encrypted.insurance_policy_number,
+            encrypted.medical_history,
+            encrypted.current_medications,
+            encrypted.allergies,
+            organization_id
         )
         .execute(&mut *tx)
         .await
@@ -145,7 +175,7 @@ impl UserPersistence for PostgresPersistence {
         Ok(user)
     }
 
-    async fn get_user_by_email(&self, email: &str) -> AppResult<User> {
+    async fn get_user_by_email(&self, email: &str) -> AppResult<Option<User>> {
         sqlx::query_as!(
             UserDb,
             "SELECT id, role_id as role, username, usersurname, email, verified, needs_onboarding, password_hash, profile_picture_url, created_at 
@@ -153,10 +183,10 @@ impl UserPersistence for PostgresPersistence {
             WHERE email = $1",
             email
         )
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await
         .map_err(AppError::Database)
-        .map(User::from)
+        .map(|user| user.map(User::from))
     }
 
     async fn get_user_by_id(&self, user_id: &Uuid) -> AppResult<User> {
@@ -174,11 +204,44 @@ impl UserPersistence for PostgresPersistence {
         .map(User::from)
     }
 
+    async fn get_organization_id(&self, user_id: &Uuid) -> AppResult<Uuid> {
+        sqlx::query_scalar!(
+            "SELECT organization_id FROM organization_members WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound("User is not a member of any organization".to_string()))
+    }
+
+    async fn get_credentials_version(&self, user_id: &Uuid) -> AppResult<i32> {
+        sqlx::query_scalar!("SELECT credentials_version FROM users WHERE id = $1", user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::Database)?
+            .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))
+    }
+
+    async fn revoke_access_tokens(&self, user_id: &Uuid) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE users SET credentials_version = credentials_version + 1 WHERE id = $1",
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
     async fn get_all_users(&self) -> AppResult<Vec<User>> {
         sqlx::query_as!(
             UserDb,
             r#"SELECT id, role_id as role, username, usersurname, email, verified, needs_onboarding, ''::text as "password_hash!", profile_picture_url, created_at
-                FROM users"#
+                FROM users
+                WHERE ($1::uuid IS NULL OR id IN (SELECT user_id FROM organization_members WHERE organization_id = $1))"#,
+            tenant::current()?
         )
         .fetch_all(&self.pool)
         .await
@@ -323,4 +386,61 @@ impl UserPersistence for PostgresPersistence {
 
         Ok(())
     }
+
+    /// Transaction that sets the new password hash and deletes the pending reset and email change tokens of the user,
+    /// they were sent under the old password. The access tokens issued under it stop working too
+    async fn update_password(&self, user_id: &Uuid, password_hash: &str) -> AppResult<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        sqlx::query!(
+            r#"
+                UPDATE users
+                SET password_hash = $2, credentials_version = credentials_version + 1
+                WHERE id = $1
+            "#,
+            user_id,
+            password_hash
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM user_tokens
+                WHERE user_id = $1 AND purpose <> $2
+            "#,
+            user_id,
+            TokenPurpose::Verification.to_string()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    async fn update_role(&self, user_id: &Uuid, role: &Role) -> AppResult<()> {
+        let result = sqlx::query!(
+            r#"
+                UPDATE users
+                SET role_id = $2
+                WHERE id = $1 AND ($3::uuid IS NULL OR id IN (SELECT user_id FROM organization_members WHERE organization_id = $3))
+            "#,
+            user_id,
+            role.to_id(),
+            tenant::current()?
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
 }
@@ -6,16 +6,23 @@ use uuid::Uuid;
 use crate::{
     adapters::persistence::PostgresPersistence,
     app_error::{AppError, AppResult},
-    entities::{email::EmailKind, user_token::UserToken},
+    entities::{
+        email::EmailKind,
+        user_token::{TokenPurpose, UserToken},
+    },
     use_cases::user_token::UserTokenPersistence,
 };
 
+const UNIQUE_VIOLATION: &str = "23505";
+
 // User struct as stored in the db.
 #[derive(sqlx::FromRow, Debug, Serialize)]
 pub struct UserTokenDb {
     pub id: Uuid,
     pub user_id: Uuid,
     pub token: String,
+    pub purpose: String,
+    pub new_email: Option<String>,
     pub expires_at: Option<NaiveDateTime>,
     pub created_at: Option<NaiveDateTime>,
 }
@@ -26,6 +33,8 @@ impl From<UserTokenDb> for UserToken {
             id: user_token_db.id,
             user_id: user_token_db.user_id,
             token: user_token_db.token,
+            purpose: user_token_db.purpose.parse().unwrap_or_default(),
+            new_email: user_token_db.new_email,
             expires_at: user_token_db.expires_at,
             created_at: user_token_db.created_at,
         }
@@ -39,6 +48,7 @@ impl UserTokenPersistence for PostgresPersistence {
         &self,
         user_id: Uuid,
         token: String,
+        purpose: TokenPurpose,
         expires_at: NaiveDateTime,
     ) -> AppResult<UserToken> {
         let uuid = Uuid::new_v4();
@@ -46,13 +56,14 @@ impl UserTokenPersistence for PostgresPersistence {
         let token = sqlx::query_as!(
             UserTokenDb,
             r#"
-            INSERT INTO user_tokens (id, user_id, token, expires_at)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, user_id, token, expires_at, created_at
+            INSERT INTO user_tokens (id, user_id, token, purpose, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, token, purpose, new_email, expires_at, created_at
             "#,
             uuid,
             user_id,
             token,
+            purpose.to_string(),
             expires_at
         )
         .fetch_one(&self.pool)
@@ -62,7 +73,7 @@ impl UserTokenPersistence for PostgresPersistence {
         Ok(token.into())
     }
 
-    /// Checks if the given user has a token already created, returns the token if exists, returns error if the user does
+    /// Checks if the given user has a verification token already created, returns the token if exists, returns error if the user does
     /// not exist, returns none if the user does not have a token or it has expired
     async fn check_user_token(&self, user_id: &Uuid) -> AppResult<Option<UserToken>> {
         let now = chrono::Utc::now().naive_utc();
@@ -70,15 +81,16 @@ impl UserTokenPersistence for PostgresPersistence {
         let token = sqlx::query_as!(
             UserTokenDb,
             r#"
-                SELECT ut.id, ut.user_id, ut.token, ut.expires_at, ut.created_at
+                SELECT ut.id, ut.user_id, ut.token, ut.purpose, ut.new_email, ut.expires_at, ut.created_at
                 FROM user_tokens ut
                 INNER JOIN users u ON ut.user_id = u.id
-                WHERE ut.user_id = $1 AND ut.expires_at > $2
+                WHERE ut.user_id = $1 AND ut.expires_at > $2 AND ut.purpose = $3
                 ORDER BY ut.created_at DESC
                 LIMIT 1
             "#,
             user_id,
-            now
+            now,
+            TokenPurpose::Verification.to_string()
         )
         .fetch_optional(&self.pool)
         .await
@@ -154,12 +166,13 @@ impl UserTokenPersistence for PostgresPersistence {
         let user_token = sqlx::query_as!(
             UserTokenDb,
             r#"
-                SELECT id, user_id, token, expires_at, created_at
+                SELECT id, user_id, token, purpose, new_email, expires_at, created_at
                 FROM user_tokens
-                WHERE token = $1 AND expires_at > $2
+                WHERE token = $1 AND expires_at > $2 AND purpose = $3
             "#,
             token,
-            now
+            now,
+            TokenPurpose::Verification.to_string()
         )
         .fetch_one(&mut *tx)
         .await
@@ -195,4 +208,247 @@ impl UserTokenPersistence for PostgresPersistence {
 
         Ok(())
     }
+
+    /// Fetches the id of the user with the given email, none if there is no such user
+    async fn get_user_id_by_email(&self, email: &str) -> AppResult<Option<Uuid>> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT id
+                FROM users
+                WHERE email = $1
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Adds a password reset email to the database with the given params
+    async fn add_password_reset_email(&self, from: &str, to: &str, body: &str) -> AppResult<()> {
+        let uuid = Uuid::new_v4();
+        sqlx::query!(
+            r#"
+            INSERT INTO emails (id, from_mail, to_mail, mail_subject, mail_body, email_kind)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            uuid,
+            from,
+            to,
+            "Reset your Password",
+            body,
+            EmailKind::PasswordReset.to_id()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Transaction that checks the given password reset token is valid, sets the new password hash of its user
+    /// and deletes every reset and email change token of that user so none of the links sent can be used again.
+    /// The refresh tokens and the access tokens issued under the old password stop working too
+    async fn reset_password(&self, token: &str, password_hash: &str) -> AppResult<Uuid> {
+        let now = chrono::Utc::now().naive_utc();
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let user_id = sqlx::query_scalar!(
+            r#"
+                SELECT user_id
+                FROM user_tokens
+                WHERE token = $1 AND expires_at > $2 AND purpose = $3
+            "#,
+            token,
+            now,
+            TokenPurpose::PasswordReset.to_string()
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::Unauthorized(String::from("Invalid or expired password reset token")))?;
+
+        sqlx::query!(
+            r#"
+                UPDATE users
+                SET password_hash = $2, credentials_version = credentials_version + 1
+                WHERE id = $1
+            "#,
+            user_id,
+            password_hash
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM user_tokens
+                WHERE user_id = $1 AND purpose <> $2
+            "#,
+            user_id,
+            TokenPurpose::Verification.to_string()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        delete_refresh_tokens(&mut tx, &user_id).await?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(user_id)
+    }
+
+    async fn get_user_password_hash(&self, user_id: &Uuid) -> AppResult<String> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT password_hash
+                FROM users
+                WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    async fn add_email_change_token(
+        &self,
+        user_id: Uuid,
+        token: String,
+        new_email: &str,
+        expires_at: NaiveDateTime,
+    ) -> AppResult<UserToken> {
+        let uuid = Uuid::new_v4();
+
+        let token = sqlx::query_as!(
+            UserTokenDb,
+            r#"
+            INSERT INTO user_tokens (id, user_id, token, purpose, new_email, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, token, purpose, new_email, expires_at, created_at
+            "#,
+            uuid,
+            user_id,
+            token,
+            TokenPurpose::EmailChange.to_string(),
+            new_email,
+            expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(token.into())
+    }
+
+    /// Adds an email change verification email to the database with the given params
+    async fn add_email_change_email(&self, from: &str, to: &str, body: &str) -> AppResult<()> {
+        let uuid = Uuid::new_v4();
+        sqlx::query!(
+            r#"
+            INSERT INTO emails (id, from_mail, to_mail, mail_subject, mail_body, email_kind)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            uuid,
+            from,
+            to,
+            "Confirm your new Email",
+            body,
+            EmailKind::EmailChange.to_id()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Transaction that checks the given email change token is valid, swaps the email of its user for the verified one
+    /// and deletes every pending token of that user, they were all sent for the old address. The user is logged out of
+    /// every device
+    async fn change_email(&self, token: &str) -> AppResult<Uuid> {
+        let now = chrono::Utc::now().naive_utc();
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let user_token = sqlx::query_as!(
+            UserTokenDb,
+            r#"
+                SELECT id, user_id, token, purpose, new_email, expires_at, created_at
+                FROM user_tokens
+                WHERE token = $1 AND expires_at > $2 AND purpose = $3
+            "#,
+            token,
+            now,
+            TokenPurpose::EmailChange.to_string()
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .ok_or_else(|| AppError::Unauthorized(String::from("Invalid or expired email change token")))?;
+
+        let new_email = user_token
+            .new_email
+            .ok_or_else(|| AppError::Internal("Email change token without an email".into()))?;
+
+        // Opening the link proves the new address is owned, so the user stays (or becomes) verified
+        sqlx::query!(
+            r#"
+                UPDATE users
+                SET email = $2, verified = true, credentials_version = credentials_version + 1
+                WHERE id = $1
+            "#,
+            user_token.user_id,
+            new_email
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_email_in_use_error)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM user_tokens
+                WHERE user_id = $1
+            "#,
+            user_token.user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        delete_refresh_tokens(&mut tx, &user_token.user_id).await?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(user_token.user_id)
+    }
+}
+
+/// Logs the user out of every device along with the change of its credentials, they can't be used apart
+async fn delete_refresh_tokens(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, user_id: &Uuid) -> AppResult<()> {
+    sqlx::query!("DELETE FROM refresh_tokens WHERE user_id = $1", user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+    Ok(())
+}
+
+/// Someone else may have registered the address after the change was requested, surface it as a conflict
+fn map_email_in_use_error(error: sqlx::Error) -> AppError {
+    let is_duplicate = error
+        .as_database_error()
+        .and_then(|db_error| db_error.code())
+        .is_some_and(|code| code == UNIQUE_VIOLATION);
+
+    if is_duplicate {
+        return AppError::Conflict("Email already in use".into());
+    }
+
+    AppError::Database(error)
 }
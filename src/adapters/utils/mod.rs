@@ -1 +1,3 @@
+pub mod recovery_code;
+pub mod refresh_token;
 pub mod verification_token;
@@ -0,0 +1,50 @@
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Codes look like `a1b2c-3d4e5` so they are easy to type
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::rng().fill(&mut bytes);
+            let code = hex::encode(bytes);
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Ignores the dash, spaces and case so the code can be typed any way
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_recovery_codes_are_unique() {
+        let codes = generate_recovery_codes();
+
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(codes.iter().all(|code| code.len() == 11 && code.chars().nth(5) == Some('-')));
+        let mut unique = codes.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), codes.len());
+    }
+
+    #[test]
+    fn test_hash_recovery_code_ignores_format() {
+        assert_eq!(hash_recovery_code("a1b2c-3d4e5"), hash_recovery_code(" A1B2C3D4E5 "));
+        assert_ne!(hash_recovery_code("a1b2c-3d4e5"), hash_recovery_code("a1b2c-3d4e6"));
+    }
+}
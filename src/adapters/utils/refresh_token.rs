@@ -0,0 +1,33 @@
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+pub fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// The refresh tokens are random enough that a plain sha256 is safe to store and fast to look up
+pub fn hash_refresh_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_refresh_token_length() {
+        // 32 bytes = 64 hex characters
+        assert_eq!(generate_refresh_token().len(), 64);
+    }
+
+    #[test]
+    fn test_hash_refresh_token_is_stable() {
+        let token = generate_refresh_token();
+
+        assert_eq!(hash_refresh_token(&token), hash_refresh_token(&token));
+        assert_ne!(hash_refresh_token(&token), token);
+        assert_eq!(hash_refresh_token(&token).len(), 64);
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::videocall::{Meeting, VideoCallProvider},
+    infra::config::AppConfig,
+    use_cases::session::VideoCallService,
+};
+
+/// Self-hosted Jitsi Meet with token authentication, the rooms are created by Jitsi when the first participant
+/// joins so the links are signed here without calling any api
+pub struct JitsiService {
+    config: Arc<AppConfig>,
+}
+
+impl JitsiService {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl JitsiService {
+    fn room_url(&self, room: &str, moderator: bool, end_date: chrono::NaiveDateTime) -> AppResult<String> {
+        let claims = JitsiClaims {
+            aud: String::from("jitsi"),
+            iss: self.config.jitsi_app_id.clone(),
+            sub: self.config.jitsi_domain.clone(),
+            room: room.to_string(),
+            exp: end_date.and_utc().timestamp(),
+            context: JitsiContext { user: JitsiUser { moderator } },
+        };
+
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.config.jitsi_app_secret.as_bytes()),
+        )
+        .map_err(|e| AppError::Internal(format!("Failed to sign Jitsi token: {}", e)))?;
+
+        Ok(format!("https://{}/{}?jwt={}", self.config.jitsi_domain, room, token))
+    }
+}
+
+/// Claims checked by the Jitsi token auth (prosody `token_verification`)
+#[derive(Debug, Serialize, Deserialize)]
+struct JitsiClaims {
+    aud: String,
+    iss: String,
+    sub: String,
+    room: String,
+    exp: i64,
+    context: JitsiContext,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JitsiContext {
+    user: JitsiUser,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JitsiUser {
+    moderator: bool,
+}
+
+#[async_trait]
+impl VideoCallService for JitsiService {
+    fn provider(&self) -> VideoCallProvider {
+        VideoCallProvider::Jitsi
+    }
+
+    #[instrument(skip(self))]
+    async fn create_meeting(&self, end_date: chrono::NaiveDateTime) -> AppResult<Meeting> {
+        let room = Uuid::new_v4().simple().to_string();
+
+        let meeting = Meeting {
+            room_url: self.room_url(&room, false, end_date)?,
+            host_room_url: self.room_url(&room, true, end_date)?,
+            id: room,
+        };
+
+        info!("Created Jitsi room {}", meeting.id);
+
+        Ok(meeting)
+    }
+
+    /// Jitsi drops the rooms once they are empty, there is nothing stored to delete.
+    /// The links keep working until their token expires at the end of the session
+    async fn delete_meeting(&self, _meeting_id: &str) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use jsonwebtoken::{DecodingKey, Validation, decode};
+
+    use crate::infra::config::PaymentGatewayKind;
+
+    use super::*;
+
+    fn config() -> Arc<AppConfig> {
+        Arc::new(AppConfig {
+            jwt_secret: String::new(),
+            field_encryption_keys: String::new(),
+            resend_key: String::new(),
+            resend_from_email: String::new(),
+            base_frontend_url: String::new(),
+            payment_gateway: PaymentGatewayKind::Fake,
+            stripe_secret_key: String::new(),
+            stripe_webhook_secret: String::new(),
+            videocall_provider: VideoCallProvider::Jitsi,
+            whereby_key: String::new(),
+            jitsi_domain: String::from("meet.example.com"),
+            jitsi_app_id: String::from("mipsicored"),
+            jitsi_app_secret: String::from("secret"),
+            videocall_join_minutes_before: 5,
+            videocall_join_minutes_after: 15,
+            cancellation_full_refund_hours: 24,
+            cancellation_late_refund_percent: 0,
+            platform_commission_percent: 15,
+            access_token_ttl: chrono::Duration::minutes(15),
+            refresh_token_ttl: chrono::Duration::days(30),
+            password_reset_token_ttl: chrono::Duration::minutes(30),
+            pre_auth_token_ttl: chrono::Duration::seconds(300),
+            account_deletion_grace_period: chrono::Duration::days(30),
+            totp_issuer: String::from("Mipsicored"),
+            login_max_failures: 10,
+            login_max_failures_per_ip: 50,
+            login_lockout_minutes: 15,
+            trust_proxy_headers: false,
+            rate_limit_public: 60,
+            rate_limit_public_per_minute: 60,
+            rate_limit_protected: 120,
+            rate_limit_protected_per_minute: 120,
+            rate_limit_auth: 10,
+            rate_limit_auth_per_minute: 5,
+            rate_limit_email: 3,
+            rate_limit_email_per_minute: 1,
+        })
+    }
+
+    fn decode_url(url: &str) -> (String, JitsiClaims) {
+        let (room_url, token) = url.split_once("?jwt=").unwrap();
+        let room = room_url.strip_prefix("https://meet.example.com/").unwrap();
+
+        let mut validation = Validation::default();
+        validation.set_audience(&["jitsi"]);
+        let claims = decode::<JitsiClaims>(token, &DecodingKey::from_secret(b"secret"), &validation)
+            .unwrap()
+            .claims;
+
+        (room.to_string(), claims)
+    }
+
+    #[tokio::test]
+    async fn create_meeting_signs_room_urls() {
+        let service = JitsiService::new(config());
+        let end_date = chrono::Utc::now().naive_utc() + chrono::Duration::hours(1);
+
+        let meeting = service.create_meeting(end_date).await.unwrap();
+
+        let (room, claims) = decode_url(&meeting.room_url);
+        assert_eq!(room, meeting.id);
+        assert_eq!(claims.room, room);
+        assert_eq!(claims.iss, "mipsicored");
+        assert_eq!(claims.sub, "meet.example.com");
+        assert_eq!(claims.exp, end_date.and_utc().timestamp());
+        assert!(!claims.context.user.moderator);
+
+        let (host_room, host_claims) = decode_url(&meeting.host_room_url);
+        assert_eq!(host_room, meeting.id);
+        assert!(host_claims.context.user.moderator);
+    }
+
+    #[tokio::test]
+    async fn create_meeting_uses_a_new_room_each_time() {
+        let service = JitsiService::new(config());
+        let end_date = chrono::Utc::now().naive_utc() + chrono::Duration::hours(1);
+
+        let first = service.create_meeting(end_date).await.unwrap();
+        let second = service.create_meeting(end_date).await.unwrap();
+
+        assert_ne!(first.id, second.id);
+    }
+}
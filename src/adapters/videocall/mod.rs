@@ -1 +1,3 @@
+pub mod jitsi;
+pub mod noop;
 pub mod whereby;
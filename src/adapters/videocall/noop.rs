@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    app_error::AppResult,
+    entities::videocall::{Meeting, VideoCallProvider},
+    infra::config::AppConfig,
+    use_cases::session::VideoCallService,
+};
+
+/// Local development provider, hands out placeholder links on the frontend without creating any room
+pub struct NoopVideoCallService {
+    config: Arc<AppConfig>,
+}
+
+impl NoopVideoCallService {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        warn!("Using the noop videocall provider, the session links lead nowhere");
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl VideoCallService for NoopVideoCallService {
+    fn provider(&self) -> VideoCallProvider {
+        VideoCallProvider::Noop
+    }
+
+    async fn create_meeting(&self, _end_date: chrono::NaiveDateTime) -> AppResult<Meeting> {
+        let id = Uuid::new_v4().to_string();
+        let room_url = format!("{}/videocall/{}", self.config.base_frontend_url, id);
+
+        Ok(Meeting { host_room_url: format!("{}?host=true", room_url), room_url, id })
+    }
+
+    async fn delete_meeting(&self, _meeting_id: &str) -> AppResult<()> {
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use tracing::{info, instrument};
 
 use crate::{
     app_error::{AppError, AppResult},
+    entities::videocall::{Meeting, VideoCallProvider},
     infra::config::AppConfig,
     use_cases::session::VideoCallService,
 };
@@ -28,18 +29,25 @@ impl WherebyService {
 #[serde(rename_all = "camelCase")]
 struct CreateMeetingRequest {
     end_date: String,
+    fields: Vec<String>, // the host url is only returned when asked for
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CreateMeetingResponse {
+    meeting_id: String,
     room_url: String,
+    host_room_url: String,
 }
 
 #[async_trait]
 impl VideoCallService for WherebyService {
+    fn provider(&self) -> VideoCallProvider {
+        VideoCallProvider::Whereby
+    }
+
     #[instrument(skip(self))]
-    async fn create_meeting(&self, end_date: chrono::NaiveDateTime) -> AppResult<String> {
+    async fn create_meeting(&self, end_date: chrono::NaiveDateTime) -> AppResult<Meeting> {
         info!("Sending request to Whereby API...");
 
         let url = "https://api.whereby.dev/v1/meetings";
@@ -50,6 +58,7 @@ impl VideoCallService for WherebyService {
 
         let request_body = CreateMeetingRequest {
             end_date: end_date_str,
+            fields: vec![String::from("hostRoomUrl")],
         };
 
         let response = self
@@ -80,6 +89,44 @@ impl VideoCallService for WherebyService {
 
         info!("Successfully created Whereby meeting: {}", result.room_url);
 
-        Ok(result.room_url)
+        Ok(Meeting {
+            id: result.meeting_id,
+            room_url: result.room_url,
+            host_room_url: result.host_room_url,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_meeting(&self, meeting_id: &str) -> AppResult<()> {
+        info!("Deleting Whereby meeting {}", meeting_id);
+
+        let url = format!("https://api.whereby.dev/v1/meetings/{}", meeting_id);
+
+        let response = self
+            .client
+            .delete(url)
+            .header("Authorization", format!("Bearer {}", self.config.whereby_key))
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to send request to Whereby: {}", e)))?;
+
+        // Already gone, e.g. it expired
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AppError::Internal(format!(
+                "Whereby API error ({}): {}",
+                status, error_text
+            )));
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,249 @@
+use std::io::{Cursor, Write};
+
+use serde_json::{Value, json};
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::user::personal_data::PersonalDataDTO,
+    entities::{
+        clinical_note::ClinicalNote, email::Email, parent_consent::ParentConsent, patient::Patient, session::Session,
+        user::User,
+    },
+    use_cases::data_export::DataExportArchiver,
+};
+
+/// One pretty printed JSON file per kind of data, so the archive can be read by people as well as imported
+#[derive(Default)]
+pub struct ZipDataExportArchiver;
+
+impl DataExportArchiver for ZipDataExportArchiver {
+    fn archive(&self, data: &PersonalDataDTO) -> AppResult<Vec<u8>> {
+        let files = [
+            ("user.json", user_json(&data.user)),
+            ("onboarding.json", json!({ "onboarding": data.onboarding, "consent": data.consent })),
+            (
+                "patient.json",
+                json!({
+                    "patient": data.patient.as_ref().map(patient_json),
+                    "parent_consent": data.parent_consent.as_ref().map(parent_consent_json),
+                }),
+            ),
+            ("sessions.json", data.sessions.iter().map(session_json).collect()),
+            ("clinical_notes.json", data.clinical_notes.iter().map(clinical_note_json).collect()),
+            ("transactions.json", json!(data.transactions)),
+            ("emails.json", data.emails.iter().map(email_json).collect()),
+        ];
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, content) in files {
+            let content = serde_json::to_vec_pretty(&content).map_err(|e| AppError::Internal(e.to_string()))?;
+
+            zip.start_file(name, options).map_err(zip_error)?;
+            zip.write_all(&content).map_err(|e| AppError::Internal(e.to_string()))?;
+        }
+
+        zip.finish().map(Cursor::into_inner).map_err(zip_error)
+    }
+}
+
+fn user_json(user: &User) -> Value {
+    json!({
+        "id": user.id,
+        "role": user.role.to_string(),
+        "name": user.username,
+        "surname": user.usersurname,
+        "email": user.email,
+        "verified": user.verified,
+        "profile_picture_url": user.profile_picture_url,
+        "created_at": user.created_at,
+    })
+}
+
+fn patient_json(patient: &Patient) -> Value {
+    json!({
+        "id": patient.id,
+        "gender": patient.gender.to_string(),
+        "sexual_orientation": patient.sexual_orientation.to_string(),
+        "birthdate": patient.birthdate,
+        "phone": patient.phone,
+        "emergency_contact_name": patient.emergency_contact_name,
+        "emergency_contact_phone": patient.emergency_contact_phone,
+        "insurance_policy_number": patient.insurance_policy_number,
+        "medical_history": patient.medical_history,
+        "current_medications": patient.current_medications,
+        "allergies": patient.allergies,
+        "created_at": patient.created_at,
+    })
+}
+
+fn parent_consent_json(consent: &ParentConsent) -> Value {
+    json!({
+        "guardian_name": consent.guardian_name,
+        "guardian_id_document": consent.guardian_id_document,
+        "signature_data": consent.signature_data,
+        "consent_certificate_id": consent.consent_certificate_id,
+        "signed_at": consent.signed_at,
+    })
+}
+
+/// Without the host link of the videocall, it belongs to the professional
+fn session_json(session: &Session) -> Value {
+    json!({
+        "id": session.id,
+        "professional_id": session.professional_id,
+        "session_type_id": session.session_type_id,
+        "status": session.session_status.to_string(),
+        "date": session.session_date,
+        "duration": session.session_duration,
+        "videocall_url": session.videocall_url,
+        "completed": session.completed,
+        "created_at": session.created_at,
+    })
+}
+
+/// The current version only, the history of the edits belongs to the professional
+fn clinical_note_json(note: &ClinicalNote) -> Value {
+    json!({
+        "id": note.id,
+        "session_id": note.session_id,
+        "subjective": note.current.sections.subjective,
+        "objective": note.current.sections.objective,
+        "assessment": note.current.sections.assessment,
+        "plan": note.current.sections.plan,
+        "created_at": note.created_at,
+        "updated_at": note.current.created_at,
+    })
+}
+
+fn email_json(email: &Email) -> Value {
+    json!({
+        "from": email.from_mail,
+        "to": email.to_mail,
+        "subject": email.mail_subject,
+        "body": email.mail_body,
+        "kind": email.email_kind.to_string(),
+        "sent_at": email.created_at,
+    })
+}
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::Internal(format!("Data export archive failed: {}", e))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use uuid::Uuid;
+
+    use crate::entities::{
+        clinical_note::{NoteVisibility, SoapSections},
+        email::EmailKind,
+        gender::Gender,
+        session::SessionStatus,
+        sexual_orientation::SexualOrientation,
+        user::Role,
+    };
+
+    use super::*;
+
+    fn personal_data() -> PersonalDataDTO {
+        let patient_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+
+        PersonalDataDTO {
+            user: User {
+                id: Uuid::new_v4(),
+                role: Role::Patient,
+                username: String::from("Ana"),
+                usersurname: String::from("García"),
+                email: String::from("ana@example.com"),
+                verified: Some(true),
+                needs_onboarding: Some(false),
+                password_hash: String::from("$argon2id$secret"),
+                profile_picture_url: None,
+                created_at: None,
+            },
+            onboarding: None,
+            consent: None,
+            patient: Some(Patient {
+                id: Some(patient_id),
+                user_id: None,
+                gender: Gender::default(),
+                sexual_orientation: SexualOrientation::default(),
+                birthdate: None,
+                phone: String::from("600000000"),
+                emergency_contact_name: None,
+                emergency_contact_phone: None,
+                insurance_policy_number: None,
+                medical_history: Some(String::from("Anxiety")),
+                current_medications: None,
+                allergies: None,
+                created_at: None,
+            }),
+            parent_consent: None,
+            sessions: vec![Session {
+                id: Some(session_id),
+                patient_id,
+                professional_id: Uuid::new_v4(),
+                session_type_id: None,
+                session_status: SessionStatus::Completed,
+                session_date: None,
+                videocall_url: Some(String::from("https://meet.example.com/room")),
+                videocall_provider: None,
+                videocall_host_url: Some(String::from("https://meet.example.com/room?host")),
+                videocall_meeting_id: None,
+                completed: true,
+                session_duration: Some(60),
+                created_at: None,
+            }],
+            clinical_notes: vec![ClinicalNote::new(
+                session_id,
+                Uuid::new_v4(),
+                NoteVisibility::Patient,
+                SoapSections { plan: Some(String::from("Follow up in two weeks")), ..SoapSections::default() },
+            )],
+            transactions: vec![],
+            emails: vec![Email {
+                id: Uuid::new_v4(),
+                from_mail: String::from("noreply@example.com"),
+                to_mail: String::from("ana@example.com"),
+                mail_subject: String::from("Verify your email"),
+                mail_body: String::from("..."),
+                email_kind: EmailKind::Verification,
+                created_at: None,
+            }],
+        }
+    }
+
+    fn read_file(archive: &[u8], name: &str) -> Value {
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        let mut content = String::new();
+        zip.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+
+        serde_json::from_str(&content).unwrap()
+    }
+
+    #[test]
+    fn archive_works() {
+        let archive = ZipDataExportArchiver.archive(&personal_data()).unwrap();
+
+        assert_eq!(read_file(&archive, "user.json")["email"], "ana@example.com");
+        assert_eq!(read_file(&archive, "patient.json")["patient"]["medical_history"], "Anxiety");
+        assert_eq!(read_file(&archive, "clinical_notes.json")[0]["plan"], "Follow up in two weeks");
+        assert_eq!(read_file(&archive, "emails.json")[0]["kind"], "Verification");
+        assert_eq!(read_file(&archive, "transactions.json"), json!([]));
+        assert_eq!(read_file(&archive, "onboarding.json")["onboarding"], Value::Null);
+    }
+
+    #[test]
+    fn archive_leaves_the_secrets_out() {
+        let archive = ZipDataExportArchiver.archive(&personal_data()).unwrap();
+
+        assert!(!read_file(&archive, "user.json").to_string().contains("argon2"));
+        assert!(!read_file(&archive, "sessions.json").to_string().contains("host"));
+    }
+}
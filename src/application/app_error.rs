@@ -22,6 +22,15 @@ pub enum AppError {
 
     #[error("External Service Error: {0}")]
     ExternalServiceError(String),
+
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(i64),
 }
 
 pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,2 @@
+pub mod payout;
+pub mod summary;
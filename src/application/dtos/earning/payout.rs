@@ -0,0 +1,14 @@
+use uuid::Uuid;
+
+/// What is owed to a professional for a batch of ledger entries, amounts are in cents
+#[derive(Debug, Clone)]
+pub struct PayoutDTO {
+    pub professional_id: Uuid,
+    pub professional_name: String,
+    pub professional_email: String,
+    pub entries: i64,
+    pub gross_amount: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub currency: String,
+}
@@ -0,0 +1,28 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+/// Earnings of a professional over a period, amounts are in cents
+#[derive(Debug, Clone)]
+pub struct EarningsSummaryDTO {
+    pub professional_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub currency: String,
+    pub totals: EarningsTotalsDTO,
+    pub months: Vec<EarningsMonthDTO>, // oldest first, only the months with entries
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EarningsTotalsDTO {
+    pub sessions: i64, // paid sessions, refunds don't substract from it
+    pub gross_amount: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub paid_out_amount: i64, // part of the net amount already paid to the professional
+}
+
+#[derive(Debug, Clone)]
+pub struct EarningsMonthDTO {
+    pub month: String, // e.g. 2026-01
+    pub totals: EarningsTotalsDTO,
+}
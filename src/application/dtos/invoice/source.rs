@@ -0,0 +1,15 @@
+use uuid::Uuid;
+
+use crate::entities::invoice::BillingParty;
+
+/// Data of a booked session needed to invoice it
+#[derive(Debug, Clone)]
+pub struct InvoiceSourceDTO {
+    pub patient_id: Uuid,
+    pub professional_id: Uuid,
+    pub issuer: BillingParty,    // billing details of the professional, or its name when it has none
+    pub recipient: BillingParty, // billing details of the patient, or its name when it has none
+    pub session_type_name: Option<String>,
+    pub session_date: Option<chrono::NaiveDateTime>,
+    pub session_duration: Option<i32>,
+}
@@ -1 +1,6 @@
+pub mod earning;
+pub mod invoice;
+pub mod payment;
 pub mod professional;
+pub mod session;
+pub mod user;
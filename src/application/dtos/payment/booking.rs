@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+/// Session requested by a patient through the self-service booking
+#[derive(Debug, Clone)]
+pub struct BookingRequestDTO {
+    pub patient_id: Uuid,
+    pub professional_id: Uuid,
+    pub session_type_id: Uuid,
+    pub session_date: chrono::NaiveDateTime, // start of a free availability slot, the session lasts as long as it
+    pub success_url: String,
+    pub cancel_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct BookingCheckoutDTO {
+    pub session_id: Uuid,
+    pub client_secret: String,
+    pub amount: i64, // cents
+    pub currency: String,
+}
@@ -0,0 +1,6 @@
+/// Outcome of a session cancellation
+#[derive(Debug, Clone)]
+pub struct CancellationDTO {
+    pub refunded_amount: i64, // cents, 0 when nothing was paid or the policy doesn't allow a refund
+    pub currency: Option<String>,
+}
@@ -0,0 +1,2 @@
+pub mod cancellation;
+pub mod parties;
@@ -0,0 +1,10 @@
+use uuid::Uuid;
+
+/// Users on both sides of a session, the patient may not have an account (e.g. a minor managed by a professional)
+#[derive(Debug, Clone, Default)]
+pub struct SessionPartiesDTO {
+    pub patient_user_id: Option<Uuid>,
+    pub patient_email: Option<String>,
+    pub professional_user_id: Option<Uuid>,
+    pub professional_email: Option<String>,
+}
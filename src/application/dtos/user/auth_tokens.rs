@@ -0,0 +1,9 @@
+use chrono::NaiveDateTime;
+
+/// Tokens handed out on login, registration and refresh
+#[derive(Debug, Clone)]
+pub struct AuthTokensDTO {
+    pub access_token: String,
+    pub refresh_token: String, // raw token, only its hash is stored
+    pub refresh_token_expires_at: NaiveDateTime,
+}
@@ -0,0 +1,11 @@
+use crate::dtos::user::auth_tokens::AuthTokensDTO;
+
+/// Result of checking the password, users with two factor authentication need a second step
+#[derive(Debug, Clone)]
+pub enum LoginOutcomeDTO {
+    Authenticated(AuthTokensDTO),
+    /// The pre auth token is exchanged for the tokens along with a TOTP or recovery code
+    TwoFactorRequired { pre_auth_token: String },
+    /// The role of the user requires two factor authentication, it has to be enrolled with the pre auth token first
+    TwoFactorEnrollmentRequired { pre_auth_token: String },
+}
@@ -0,0 +1,4 @@
+pub mod auth_tokens;
+pub mod login;
+pub mod personal_data;
+pub mod two_factor;
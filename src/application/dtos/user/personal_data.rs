@@ -0,0 +1,24 @@
+use crate::entities::{
+    clinical_note::ClinicalNote,
+    email::Email,
+    onboarding::{UserConsent, UserOnboarding},
+    parent_consent::ParentConsent,
+    patient::Patient,
+    session::Session,
+    transaction::Transaction,
+    user::User,
+};
+
+/// Everything stored about a user, decrypted, as handed over in its data export
+#[derive(Debug)]
+pub struct PersonalDataDTO {
+    pub user: User,
+    pub onboarding: Option<UserOnboarding>,
+    pub consent: Option<UserConsent>,
+    pub patient: Option<Patient>, // None for the users without a patient profile
+    pub parent_consent: Option<ParentConsent>,
+    pub sessions: Vec<Session>,
+    pub clinical_notes: Vec<ClinicalNote>, // of its sessions, only the ones shared with the patient
+    pub transactions: Vec<Transaction>, // payments of its sessions
+    pub emails: Vec<Email>,             // sent to its current address
+}
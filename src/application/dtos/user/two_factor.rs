@@ -0,0 +1,15 @@
+use crate::dtos::user::auth_tokens::AuthTokensDTO;
+
+/// What the authenticator app needs, the uri is usually shown as a QR code
+#[derive(Debug, Clone)]
+pub struct TwoFactorEnrollmentDTO {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// Enrollment finished during the login, the user gets logged in as well
+#[derive(Debug, Clone)]
+pub struct TwoFactorLoginActivationDTO {
+    pub tokens: AuthTokensDTO,
+    pub recovery_codes: Vec<String>, // raw codes, only shown once
+}
@@ -0,0 +1,338 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::session::cancellation::CancellationDTO,
+    entities::{account_deletion::AccountDeletion, user::Role},
+    use_cases::cancellation::CancellationUseCases,
+};
+
+#[async_trait]
+pub trait AccountDeletionPersistence: Send + Sync {
+    /// Fails with a Conflict if the user already has one
+    async fn create(&self, deletion: &AccountDeletion) -> AppResult<()>;
+
+    async fn read(&self, user_id: &Uuid) -> AppResult<Option<AccountDeletion>>;
+
+    /// Only while it hasn't been carried out, NotFound otherwise
+    async fn delete_pending(&self, user_id: &Uuid) -> AppResult<()>;
+
+    /// The pending deletions whose grace period is over
+    async fn read_due(&self) -> AppResult<Vec<AccountDeletion>>;
+
+    /// Scheduled, paid or not, sessions yet to start where the user is the patient or the professional
+    async fn read_future_sessions(&self, user_id: &Uuid) -> AppResult<Vec<Uuid>>;
+
+    /// Removes or pseudonymizes the personal data of the user and marks the deletion as completed. The minimum
+    /// clinical record (the clinical fields of the patient, the sessions and their notes, the parental consents without
+    /// the guardian data), the invoices and the payments are kept, tied to the pseudonymized user. The issued access
+    /// tokens stop working
+    async fn erase(&self, user_id: &Uuid) -> AppResult<()>;
+}
+
+/// Cancels the sessions of a user that is leaving, refunding and notifying like any other cancellation
+#[async_trait]
+pub trait SessionCanceller: Send + Sync {
+    async fn cancel_session(&self, session_id: &Uuid, user_id: &Uuid, role: &Role) -> AppResult<CancellationDTO>;
+}
+
+#[async_trait]
+impl SessionCanceller for CancellationUseCases {
+    async fn cancel_session(&self, session_id: &Uuid, user_id: &Uuid, role: &Role) -> AppResult<CancellationDTO> {
+        CancellationUseCases::cancel_session(self, session_id, user_id, role).await
+    }
+}
+
+#[derive(Clone)]
+pub struct AccountDeletionUseCases {
+    persistence: Arc<dyn AccountDeletionPersistence>,
+    canceller: Arc<dyn SessionCanceller>,
+    grace_period: Duration,
+}
+
+impl AccountDeletionUseCases {
+    pub fn new(
+        persistence: Arc<dyn AccountDeletionPersistence>,
+        canceller: Arc<dyn SessionCanceller>,
+        grace_period: Duration,
+    ) -> Self {
+        Self {
+            persistence,
+            canceller,
+            grace_period,
+        }
+    }
+
+    /// Schedules the erasure of the account of the user at the end of the grace period
+    #[instrument(skip(self))]
+    pub async fn request(&self, user_id: &Uuid) -> AppResult<AccountDeletion> {
+        info!("Attempting request account deletion...");
+
+        let deletion = AccountDeletion::new(*user_id, *user_id, Utc::now().naive_utc() + self.grace_period);
+        self.persistence.create(&deletion).await?;
+
+        info!("Account deletion scheduled.");
+
+        Ok(deletion)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read(&self, user_id: &Uuid) -> AppResult<Option<AccountDeletion>> {
+        info!("Attempting read account deletion...");
+
+        self.persistence.read(user_id).await
+    }
+
+    /// Keeps the account, only possible during the grace period
+    #[instrument(skip(self))]
+    pub async fn cancel(&self, user_id: &Uuid) -> AppResult<()> {
+        info!("Attempting cancel account deletion...");
+
+        self.persistence.delete_pending(user_id).await?;
+
+        info!("Account deletion cancelled.");
+
+        Ok(())
+    }
+
+    /// Admin override, erases the account right away whether the user asked for it or not
+    #[instrument(skip(self))]
+    pub async fn erase_now(&self, user_id: &Uuid, admin_user_id: &Uuid) -> AppResult<()> {
+        info!("Attempting erase account...");
+
+        let deletion = match self.persistence.read(user_id).await? {
+            Some(deletion) if deletion.is_completed() => {
+                return Err(AppError::Conflict(String::from("Account already erased")));
+            }
+            Some(deletion) => deletion,
+            None => {
+                let deletion = AccountDeletion::new(*user_id, *admin_user_id, Utc::now().naive_utc());
+                self.persistence.create(&deletion).await?;
+                deletion
+            }
+        };
+
+        self.erase(&deletion).await
+    }
+
+    /// Erases the accounts whose grace period is over, returns how many. A failed one is retried on the next run
+    #[instrument(skip(self))]
+    pub async fn erase_due(&self) -> AppResult<usize> {
+        let mut erased = 0;
+
+        for deletion in self.persistence.read_due().await? {
+            match self.erase(&deletion).await {
+                Ok(()) => erased += 1,
+                Err(e) => error!("Failed to erase the account of user {}: {:?}", deletion.user_id, e),
+            }
+        }
+
+        Ok(erased)
+    }
+
+    /// The future sessions are cancelled first, while the user can still be refunded and notified. If one can't be
+    /// cancelled nothing is erased
+    async fn erase(&self, deletion: &AccountDeletion) -> AppResult<()> {
+        for session_id in self.persistence.read_future_sessions(&deletion.user_id).await? {
+            // The user is a party of the session, its role doesn't matter
+            match self
+                .canceller
+                .cancel_session(&session_id, &deletion.user_id, &Role::default())
+                .await
+            {
+                Ok(_) => {}
+                Err(AppError::Conflict(reason)) => warn!("Session {} not cancelled: {}", session_id, reason),
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.persistence.erase(&deletion.user_id).await?;
+
+        info!("Account erased.");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use super::*;
+
+    const USER_ID: Uuid = Uuid::from_u128(1);
+    const ADMIN_USER_ID: Uuid = Uuid::from_u128(2);
+    const SESSION_ID: Uuid = Uuid::from_u128(3);
+    /// Session whose refund fails
+    const FAILING_SESSION_ID: Uuid = Uuid::from_u128(4);
+
+    #[derive(Default)]
+    struct MockAccountDeletionPersistence {
+        deletions: Mutex<HashMap<Uuid, AccountDeletion>>,
+        future_sessions: Vec<Uuid>,
+        erased: Mutex<Vec<Uuid>>,
+    }
+
+    impl MockAccountDeletionPersistence {
+        fn with_sessions(future_sessions: Vec<Uuid>) -> Self {
+            Self { future_sessions, ..Default::default() }
+        }
+    }
+
+    #[async_trait]
+    impl AccountDeletionPersistence for MockAccountDeletionPersistence {
+        async fn create(&self, deletion: &AccountDeletion) -> AppResult<()> {
+            let mut deletions = self.deletions.lock().unwrap();
+            if deletions.contains_key(&deletion.user_id) {
+                return Err(AppError::Conflict(String::from("Account deletion already requested")));
+            }
+
+            deletions.insert(deletion.user_id, deletion.clone());
+            Ok(())
+        }
+
+        async fn read(&self, user_id: &Uuid) -> AppResult<Option<AccountDeletion>> {
+            Ok(self.deletions.lock().unwrap().get(user_id).cloned())
+        }
+
+        async fn delete_pending(&self, user_id: &Uuid) -> AppResult<()> {
+            let mut deletions = self.deletions.lock().unwrap();
+            match deletions.get(user_id) {
+                Some(deletion) if !deletion.is_completed() => {
+                    deletions.remove(user_id);
+                    Ok(())
+                }
+                _ => Err(AppError::NotFound(String::from("No pending account deletion"))),
+            }
+        }
+
+        async fn read_due(&self) -> AppResult<Vec<AccountDeletion>> {
+            let now = Utc::now().naive_utc();
+
+            Ok(self
+                .deletions
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|deletion| !deletion.is_completed() && deletion.scheduled_for <= now)
+                .cloned()
+                .collect())
+        }
+
+        async fn read_future_sessions(&self, _user_id: &Uuid) -> AppResult<Vec<Uuid>> {
+            Ok(self.future_sessions.clone())
+        }
+
+        async fn erase(&self, user_id: &Uuid) -> AppResult<()> {
+            self.erased.lock().unwrap().push(*user_id);
+            if let Some(deletion) = self.deletions.lock().unwrap().get_mut(user_id) {
+                deletion.completed_at = Some(Utc::now().naive_utc());
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSessionCanceller {
+        cancelled: Mutex<Vec<Uuid>>,
+    }
+
+    #[async_trait]
+    impl SessionCanceller for MockSessionCanceller {
+        async fn cancel_session(&self, session_id: &Uuid, _user_id: &Uuid, _role: &Role) -> AppResult<CancellationDTO> {
+            if *session_id == FAILING_SESSION_ID {
+                return Err(AppError::ExternalServiceError(String::from("Refund failed")));
+            }
+
+            self.cancelled.lock().unwrap().push(*session_id);
+            Ok(CancellationDTO { refunded_amount: 0, currency: None })
+        }
+    }
+
+    fn use_cases(
+        persistence: Arc<MockAccountDeletionPersistence>,
+        canceller: Arc<MockSessionCanceller>,
+    ) -> AccountDeletionUseCases {
+        AccountDeletionUseCases::new(persistence, canceller, Duration::days(30))
+    }
+
+    #[tokio::test]
+    async fn request_waits_for_the_grace_period() {
+        let persistence = Arc::new(MockAccountDeletionPersistence::with_sessions(vec![SESSION_ID]));
+        let canceller = Arc::new(MockSessionCanceller::default());
+        let use_cases = use_cases(persistence.clone(), canceller.clone());
+
+        let deletion = use_cases.request(&USER_ID).await.unwrap();
+
+        assert!(deletion.scheduled_for > Utc::now().naive_utc() + Duration::days(29));
+        assert!(matches!(use_cases.request(&USER_ID).await, Err(AppError::Conflict(_))));
+
+        assert_eq!(use_cases.erase_due().await.unwrap(), 0);
+        assert!(persistence.erased.lock().unwrap().is_empty());
+        assert!(canceller.cancelled.lock().unwrap().is_empty());
+
+        use_cases.cancel(&USER_ID).await.unwrap();
+        assert!(use_cases.read(&USER_ID).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn erase_due_cancels_the_future_sessions_and_erases() {
+        let persistence = Arc::new(MockAccountDeletionPersistence::with_sessions(vec![SESSION_ID]));
+        let canceller = Arc::new(MockSessionCanceller::default());
+        let use_cases = use_cases(persistence.clone(), canceller.clone());
+
+        persistence
+            .create(&AccountDeletion::new(USER_ID, USER_ID, Utc::now().naive_utc() - Duration::minutes(1)))
+            .await
+            .unwrap();
+
+        assert_eq!(use_cases.erase_due().await.unwrap(), 1);
+        assert_eq!(*canceller.cancelled.lock().unwrap(), vec![SESSION_ID]);
+        assert_eq!(*persistence.erased.lock().unwrap(), vec![USER_ID]);
+        assert!(matches!(use_cases.cancel(&USER_ID).await, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn erase_now_skips_the_grace_period() {
+        let persistence = Arc::new(MockAccountDeletionPersistence::default());
+        let use_cases = use_cases(persistence.clone(), Arc::new(MockSessionCanceller::default()));
+
+        use_cases.request(&USER_ID).await.unwrap();
+        use_cases.erase_now(&USER_ID, &ADMIN_USER_ID).await.unwrap();
+
+        assert_eq!(*persistence.erased.lock().unwrap(), vec![USER_ID]);
+        assert!(matches!(
+            use_cases.erase_now(&USER_ID, &ADMIN_USER_ID).await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn erase_now_without_request_records_the_admin() {
+        let persistence = Arc::new(MockAccountDeletionPersistence::default());
+        let use_cases = use_cases(persistence.clone(), Arc::new(MockSessionCanceller::default()));
+
+        use_cases.erase_now(&USER_ID, &ADMIN_USER_ID).await.unwrap();
+
+        let deletion = use_cases.read(&USER_ID).await.unwrap().unwrap();
+        assert_eq!(deletion.requested_by, ADMIN_USER_ID);
+        assert!(deletion.is_completed());
+    }
+
+    #[tokio::test]
+    async fn failed_cancellation_erases_nothing() {
+        let persistence = Arc::new(MockAccountDeletionPersistence::with_sessions(vec![FAILING_SESSION_ID]));
+        let use_cases = use_cases(persistence.clone(), Arc::new(MockSessionCanceller::default()));
+
+        let result = use_cases.erase_now(&USER_ID, &ADMIN_USER_ID).await;
+
+        assert!(matches!(result, Err(AppError::ExternalServiceError(_))));
+        assert!(persistence.erased.lock().unwrap().is_empty());
+        assert!(!use_cases.read(&USER_ID).await.unwrap().unwrap().is_completed());
+    }
+}
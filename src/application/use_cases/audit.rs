@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::{info, instrument};
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::audit_event::{AuditEvent, AuditEventFilter},
+};
+
+/// Most events returned by a single query
+pub const MAX_AUDIT_EVENTS: i64 = 500;
+
+#[async_trait]
+pub trait AuditPersistence: Send + Sync {
+    /// Appends the events, there is no way to change or remove them
+    async fn record(&self, events: &[AuditEvent]) -> AppResult<()>;
+
+    /// Most recent first
+    async fn read(&self, filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>>;
+}
+
+#[derive(Clone)]
+pub struct AuditUseCases {
+    persistence: Arc<dyn AuditPersistence>,
+}
+
+impl AuditUseCases {
+    pub fn new(persistence: Arc<dyn AuditPersistence>) -> Self {
+        Self { persistence }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read(&self, filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>> {
+        info!("Attempting read audit events...");
+
+        if !(1..=MAX_AUDIT_EVENTS).contains(&filter.limit) || filter.offset < 0 {
+            return Err(AppError::InvalidPayload);
+        }
+
+        if let (Some(from), Some(to)) = (filter.from, filter.to)
+            && from > to
+        {
+            return Err(AppError::InvalidPayload);
+        }
+
+        self.persistence.read(filter).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockAuditPersistence {
+        filters: Mutex<Vec<AuditEventFilter>>,
+    }
+
+    #[async_trait]
+    impl AuditPersistence for MockAuditPersistence {
+        async fn record(&self, _events: &[AuditEvent]) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read(&self, filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>> {
+            self.filters.lock().unwrap().push(filter.clone());
+
+            Ok(vec![])
+        }
+    }
+
+    fn filter(limit: i64) -> AuditEventFilter {
+        AuditEventFilter {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn read_works() {
+        let persistence = Arc::new(MockAuditPersistence::default());
+        let use_cases = AuditUseCases::new(persistence.clone());
+
+        use_cases.read(&filter(50)).await.unwrap();
+
+        assert_eq!(persistence.filters.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn read_over_the_limit_fails() {
+        let use_cases = AuditUseCases::new(Arc::new(MockAuditPersistence::default()));
+
+        let result = use_cases.read(&filter(MAX_AUDIT_EVENTS + 1)).await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+
+    #[tokio::test]
+    async fn read_with_inverted_range_fails() {
+        let use_cases = AuditUseCases::new(Arc::new(MockAuditPersistence::default()));
+        let now = chrono::Utc::now().naive_utc();
+
+        let result = use_cases
+            .read(&AuditEventFilter {
+                from: Some(now),
+                to: Some(now - chrono::Duration::days(1)),
+                ..filter(50)
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+}
@@ -0,0 +1,871 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::session::{cancellation::CancellationDTO, parties::SessionPartiesDTO},
+    entities::{
+        cancellation_policy::CancellationPolicy,
+        email::EmailKind,
+        permission::Permission,
+        session::{Session, SessionStatus},
+        transaction::TransactionStatus,
+        user::Role,
+    },
+    use_cases::{
+        earning::{EarningPersistence, record_refund},
+        email::EmailPersistence,
+        payment::{PaymentGateway, TransactionPersistence, refund_idempotency_key},
+        session::{SessionPersistence, VideoCallService, delete_meeting},
+    },
+};
+
+#[async_trait]
+pub trait CancellationPersistence: Send + Sync {
+    async fn read_session_parties(&self, session_id: &Uuid) -> AppResult<SessionPartiesDTO>;
+}
+
+#[async_trait]
+pub trait CancellationEmailService: Send + Sync {
+    /// Returns the 'from' email and the email body
+    async fn send_cancellation_email(
+        &self,
+        to: &[String],
+        session_date: Option<NaiveDateTime>,
+        refunded_amount: i64,
+        currency: Option<&str>,
+    ) -> AppResult<(String, String)>;
+}
+
+#[derive(Clone)]
+pub struct CancellationUseCases {
+    session_persistence: Arc<dyn SessionPersistence>,
+    transaction_persistence: Arc<dyn TransactionPersistence>,
+    payment_gateway: Arc<dyn PaymentGateway>,
+    persistence: Arc<dyn CancellationPersistence>,
+    email_service: Arc<dyn CancellationEmailService>,
+    email_persistence: Arc<dyn EmailPersistence>,
+    earning_persistence: Arc<dyn EarningPersistence>,
+    videocall_service: Arc<dyn VideoCallService>,
+    policy: CancellationPolicy,
+}
+
+impl CancellationUseCases {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        session_persistence: Arc<dyn SessionPersistence>,
+        transaction_persistence: Arc<dyn TransactionPersistence>,
+        payment_gateway: Arc<dyn PaymentGateway>,
+        persistence: Arc<dyn CancellationPersistence>,
+        email_service: Arc<dyn CancellationEmailService>,
+        email_persistence: Arc<dyn EmailPersistence>,
+        earning_persistence: Arc<dyn EarningPersistence>,
+        videocall_service: Arc<dyn VideoCallService>,
+        policy: CancellationPolicy,
+    ) -> Self {
+        Self {
+            session_persistence,
+            transaction_persistence,
+            payment_gateway,
+            persistence,
+            email_service,
+            email_persistence,
+            earning_persistence,
+            videocall_service,
+            policy,
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn cancel_session(
+        &self,
+        session_id: &Uuid,
+        user_id: &Uuid,
+        role: &Role,
+    ) -> AppResult<CancellationDTO> {
+        // Flow of this should be:
+        // 0 - Check the session can still be cancelled
+        // 1 - Check the requesting user is the patient, the professional or an admin
+        // 2 - Mark the session as cancelled, only one of concurrent cancellations gets past this
+        // 3 - If the session was paid, refund what the policy allows, otherwise close its checkout
+        // 4 - Delete the meeting of the session
+        // 5 - Notify both parties, a failed notification doesn't undo the cancellation
+
+        info!("Attempting to cancel session...");
+
+        let mut session = self.session_persistence.read_single(session_id).await?;
+
+        if matches!(
+            session.session_status,
+            SessionStatus::Cancelled | SessionStatus::Completed
+        ) {
+            return Err(AppError::Conflict(format!(
+                "Session is already {}",
+                session.session_status
+            )));
+        }
+
+        let parties = self.persistence.read_session_parties(session_id).await?;
+
+        let cancelled_by_patient = parties.patient_user_id.as_ref() == Some(user_id);
+        let cancelled_by_professional = parties.professional_user_id.as_ref() == Some(user_id);
+
+        if !role.can(Permission::ManageSessions) && !cancelled_by_patient && !cancelled_by_professional {
+            return Err(AppError::Unauthorized(String::from(
+                "Only the session patient, professional or staff can cancel it",
+            )));
+        }
+
+        let previous = self
+            .session_persistence
+            .transition(
+                session_id,
+                &[SessionStatus::Scheduled, SessionStatus::InProgress, SessionStatus::PendingPayment],
+                &SessionStatus::Cancelled,
+            )
+            .await?
+            .ok_or_else(|| AppError::Conflict(String::from("Session is already cancelled or completed")))?;
+
+        let cancellation = if matches!(previous, SessionStatus::PendingPayment) {
+            self.close_checkout(session_id).await
+        } else {
+            match self.refund(&session, cancelled_by_patient).await {
+                Ok(cancellation) => cancellation,
+                Err(e) => {
+                    // Nothing was refunded, the session is put back so the cancellation can be retried
+                    self.session_persistence
+                        .transition(session_id, &[SessionStatus::Cancelled], &previous)
+                        .await?;
+                    return Err(e);
+                }
+            }
+        };
+
+        session.session_status = SessionStatus::Cancelled;
+
+        info!("Session cancelled.");
+
+        if let Err(e) = delete_meeting(self.videocall_service.as_ref(), &session).await {
+            warn!("Failed to delete the meeting of the cancelled session: {:?}", e);
+        }
+
+        if let Err(e) = self.notify(&session, &parties, &cancellation).await {
+            warn!("Failed to notify the session cancellation: {:?}", e);
+        }
+
+        Ok(cancellation)
+    }
+
+    /// Closes the checkout of a booking that wasn't paid yet. A payment going through anyway is refunded once confirmed
+    async fn close_checkout(&self, session_id: &Uuid) -> CancellationDTO {
+        let mut transaction = match self.transaction_persistence.get_by_booked_session_id(session_id).await {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                warn!("No checkout to close for session {}: {:?}", session_id, e);
+                return CancellationDTO { refunded_amount: 0, currency: None };
+            }
+        };
+
+        if matches!(transaction.status, TransactionStatus::Pending | TransactionStatus::Failed) {
+            match self.payment_gateway.expire_checkout(&transaction.session_id).await {
+                Ok(()) => {
+                    transaction.expire();
+                    if let Err(e) = self.transaction_persistence.update(&transaction).await {
+                        warn!("Failed to expire transaction {}: {:?}", transaction.id, e);
+                    }
+                }
+                Err(e) => warn!("Failed to expire the checkout of transaction {}: {:?}", transaction.id, e),
+            }
+        }
+
+        CancellationDTO { refunded_amount: 0, currency: transaction.currency }
+    }
+
+    /// Refunds the payment of the session, the policy only applies when the patient cancels
+    async fn refund(&self, session: &Session, cancelled_by_patient: bool) -> AppResult<CancellationDTO> {
+        let session_id = session.id.unwrap_or_default();
+
+        let mut transaction = match self
+            .transaction_persistence
+            .get_by_booked_session_id(&session_id)
+            .await
+        {
+            Ok(transaction) => transaction,
+            Err(AppError::NotFound(_)) => {
+                info!("Session was not paid, nothing to refund");
+                return Ok(CancellationDTO { refunded_amount: 0, currency: None });
+            }
+            Err(e) => return Err(e),
+        };
+
+        let (TransactionStatus::Completed, Some(payment_intent_id)) =
+            (&transaction.status, transaction.payment_intent_id.clone())
+        else {
+            info!("Transaction {} is {}, nothing to refund", transaction.id, transaction.status);
+            return Ok(CancellationDTO { refunded_amount: 0, currency: transaction.currency });
+        };
+
+        let paid = transaction.amount.unwrap_or_default();
+        let refunded_amount = match (cancelled_by_patient, session.session_date) {
+            (true, Some(session_date)) => {
+                self.policy
+                    .refund_amount(paid, session_date, chrono::Utc::now().naive_utc())
+            }
+            _ => paid,
+        };
+
+        if refunded_amount > 0 {
+            info!("Refunding {} of transaction {}", refunded_amount, transaction.id);
+
+            self.payment_gateway
+                .refund(&payment_intent_id, refunded_amount, &refund_idempotency_key(&session_id))
+                .await
+                .map_err(|e| {
+                    error!("Failed to refund transaction {}: {:?}", transaction.id, e);
+                    e
+                })?;
+
+            transaction.refund(refunded_amount);
+            self.transaction_persistence.update(&transaction).await?;
+
+            // The patient already got the money back, the ledger can be fixed afterwards
+            if let Err(e) = record_refund(self.earning_persistence.as_ref(), &transaction, refunded_amount).await {
+                error!("Failed to take the refund of transaction {} out of the earnings: {:?}", transaction.id, e);
+            }
+        }
+
+        Ok(CancellationDTO { refunded_amount, currency: transaction.currency })
+    }
+
+    async fn notify(
+        &self,
+        session: &Session,
+        parties: &SessionPartiesDTO,
+        cancellation: &CancellationDTO,
+    ) -> AppResult<()> {
+        let recipients = [&parties.patient_email, &parties.professional_email];
+
+        // One email per party so they don't see each other's address
+        for to in recipients.into_iter().flatten() {
+            let (from, body) = self
+                .email_service
+                .send_cancellation_email(
+                    std::slice::from_ref(to),
+                    session.session_date,
+                    cancellation.refunded_amount,
+                    cancellation.currency.as_deref(),
+                )
+                .await?;
+
+            self.email_persistence
+                .add_email(
+                    from,
+                    to.clone(),
+                    String::from("Session Cancelled"),
+                    body,
+                    EmailKind::SessionCancelled,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use chrono::NaiveDateTime;
+
+    use crate::{
+        dtos::earning::payout::PayoutDTO,
+        entities::{
+            earning::{Earning, EarningKind, PlatformCommission},
+            transaction::Transaction,
+            videocall::{Meeting, VideoCallProvider},
+        },
+        use_cases::payment::{CheckoutReference, CheckoutStatus, PaymentEvent},
+    };
+
+    use super::*;
+
+    const PATIENT_USER_ID: Uuid = Uuid::from_u128(1);
+    const PROFESSIONAL_USER_ID: Uuid = Uuid::from_u128(2);
+    const OTHER_USER_ID: Uuid = Uuid::from_u128(3);
+    /// Session that was never paid
+    const UNPAID_SESSION_ID: Uuid = Uuid::from_u128(4);
+    /// Session booked by the patient whose checkout is still open
+    const PENDING_SESSION_ID: Uuid = Uuid::from_u128(5);
+    const PAID: i64 = 6000;
+
+    fn policy() -> CancellationPolicy {
+        CancellationPolicy { full_refund_notice_hours: 24, late_refund_percent: 0 }
+    }
+
+    struct MockSessionPersistence {
+        session_date: NaiveDateTime,
+        status_id: i32,
+        statuses: Mutex<Vec<i32>>,
+    }
+
+    impl MockSessionPersistence {
+        fn in_hours(hours: i64) -> Self {
+            Self {
+                session_date: chrono::Utc::now().naive_utc() + chrono::Duration::hours(hours),
+                status_id: SessionStatus::Scheduled.to_id(),
+                statuses: Mutex::new(vec![]),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SessionPersistence for MockSessionPersistence {
+        async fn create(&self, _session: &Session) -> AppResult<Uuid> {
+            Ok(Uuid::new_v4())
+        }
+
+        async fn read_all(&self) -> AppResult<Vec<Session>> {
+            Ok(vec![])
+        }
+
+        async fn read_patient(&self, _patient_id: &Uuid) -> AppResult<Vec<Session>> {
+            Ok(vec![])
+        }
+
+        async fn read_professional(&self, _professional_id: &Uuid) -> AppResult<Vec<Session>> {
+            Ok(vec![])
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<Session> {
+            Ok(Session {
+                id: Some(*id),
+                patient_id: Uuid::new_v4(),
+                professional_id: Uuid::new_v4(),
+                session_type_id: None,
+                session_status: SessionStatus::from_id(self.status_id).unwrap_or_default(),
+                session_date: Some(self.session_date),
+                videocall_url: Some(String::from("https://whereby.com/room")),
+                videocall_provider: Some(VideoCallProvider::Whereby),
+                videocall_host_url: Some(String::from("https://whereby.com/room?roomKey=host")),
+                videocall_meeting_id: Some(String::from("meeting-1")),
+                completed: false,
+                session_duration: Some(60),
+                created_at: None,
+            })
+        }
+
+        async fn is_professional_user(&self, _professional_id: &Uuid, _user_id: &Uuid) -> AppResult<bool> {
+            Ok(false)
+        }
+
+        async fn update(&self, session: &Session) -> AppResult<()> {
+            self.statuses.lock().unwrap().push(session.session_status.to_id());
+
+            Ok(())
+        }
+
+        async fn transition(
+            &self,
+            _id: &Uuid,
+            from: &[SessionStatus],
+            to: &SessionStatus,
+        ) -> AppResult<Option<SessionStatus>> {
+            let mut statuses = self.statuses.lock().unwrap();
+
+            let current = statuses.last().copied().unwrap_or(self.status_id);
+            if !from.iter().any(|status| status.to_id() == current) {
+                return Ok(None);
+            }
+
+            statuses.push(to.to_id());
+
+            Ok(SessionStatus::from_id(current))
+        }
+
+        async fn has_overlapping(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+            _exclude_id: Option<&Uuid>,
+        ) -> AppResult<bool> {
+            Ok(false)
+        }
+
+        async fn delete(&self, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockTransactionPersistence {
+        updates: Mutex<Vec<(TransactionStatus, i64)>>,
+    }
+
+    #[async_trait]
+    impl TransactionPersistence for MockTransactionPersistence {
+        async fn create(&self, transaction: &Transaction) -> AppResult<Transaction> {
+            Ok(transaction.clone())
+        }
+
+        async fn update(&self, transaction: &Transaction) -> AppResult<Transaction> {
+            self.updates
+                .lock()
+                .unwrap()
+                .push((transaction.status.clone(), transaction.refunded_amount));
+
+            Ok(transaction.clone())
+        }
+
+        async fn get_by_session_id(&self, _session_id: &str) -> AppResult<Transaction> {
+            Err(AppError::NotFound("Transaction not found".into()))
+        }
+
+        async fn get_by_booked_session_id(&self, booked_session_id: &Uuid) -> AppResult<Transaction> {
+            if *booked_session_id == UNPAID_SESSION_ID {
+                return Err(AppError::NotFound("Transaction not found".into()));
+            }
+
+            let mut transaction = Transaction::new(String::from("cs_test"), Some(PAID), Some("eur".into()));
+            transaction.booked_session_id = Some(*booked_session_id);
+
+            if *booked_session_id != PENDING_SESSION_ID {
+                transaction.payment_intent_id = Some(String::from("pi_test"));
+                transaction.status = TransactionStatus::Completed;
+            }
+
+            Ok(transaction)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockPaymentGateway {
+        refunds: Mutex<Vec<i64>>,
+        idempotency_keys: Mutex<Vec<String>>,
+        expired: Mutex<Vec<String>>,
+        refusing: bool, // every refund fails
+    }
+
+    #[async_trait]
+    impl PaymentGateway for MockPaymentGateway {
+        async fn create_checkout_session(
+            &self,
+            _amount: i64,
+            _currency: &str,
+            _success_url: &str,
+            _cancel_url: &str,
+            _reference: CheckoutReference,
+        ) -> AppResult<(String, String)> {
+            Ok((String::from("secret"), String::from("cs_test")))
+        }
+
+        async fn checkout_status(&self, _session_id: &str) -> AppResult<CheckoutStatus> {
+            Ok(CheckoutStatus::Open)
+        }
+
+        fn webhook_event(&self, _payload: &str, _signature: &str) -> AppResult<PaymentEvent> {
+            Ok(PaymentEvent::Ignored)
+        }
+
+        async fn checkout_session_for_payment_intent(
+            &self,
+            _payment_intent_id: &str,
+        ) -> AppResult<Option<String>> {
+            Ok(None)
+        }
+
+        async fn expire_checkout(&self, session_id: &str) -> AppResult<()> {
+            self.expired.lock().unwrap().push(session_id.to_string());
+
+            Ok(())
+        }
+
+        async fn refund(&self, _payment_intent_id: &str, amount: i64, idempotency_key: &str) -> AppResult<String> {
+            if self.refusing {
+                return Err(AppError::ExternalServiceError(String::from("Refund declined")));
+            }
+
+            self.refunds.lock().unwrap().push(amount);
+            self.idempotency_keys.lock().unwrap().push(idempotency_key.to_string());
+
+            Ok(String::from("re_test"))
+        }
+    }
+
+    struct MockCancellationPersistence;
+
+    #[async_trait]
+    impl CancellationPersistence for MockCancellationPersistence {
+        async fn read_session_parties(&self, _session_id: &Uuid) -> AppResult<SessionPartiesDTO> {
+            Ok(SessionPartiesDTO {
+                patient_user_id: Some(PATIENT_USER_ID),
+                patient_email: Some(String::from("patient@example.com")),
+                professional_user_id: Some(PROFESSIONAL_USER_ID),
+                professional_email: Some(String::from("professional@example.com")),
+            })
+        }
+    }
+
+    struct MockEmailService;
+
+    #[async_trait]
+    impl CancellationEmailService for MockEmailService {
+        async fn send_cancellation_email(
+            &self,
+            _to: &[String],
+            _session_date: Option<NaiveDateTime>,
+            _refunded_amount: i64,
+            _currency: Option<&str>,
+        ) -> AppResult<(String, String)> {
+            Ok((String::from("noreply@example.com"), String::from("body")))
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEmailPersistence {
+        sent_to: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl EmailPersistence for MockEmailPersistence {
+        async fn add_email(
+            &self,
+            _from: String,
+            to: String,
+            _subject: String,
+            _body: String,
+            kind: EmailKind,
+        ) -> AppResult<()> {
+            assert_eq!(kind, EmailKind::SessionCancelled);
+            self.sent_to.lock().unwrap().push(to);
+
+            Ok(())
+        }
+    }
+
+    /// Every transaction has its payment recorded with a 20% commission, records the entries created
+    #[derive(Default)]
+    struct MockEarningPersistence {
+        created: Mutex<Vec<Earning>>,
+    }
+
+    #[async_trait]
+    impl EarningPersistence for MockEarningPersistence {
+        async fn create(&self, earning: &Earning) -> AppResult<()> {
+            self.created.lock().unwrap().push(earning.clone());
+            Ok(())
+        }
+
+        async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Vec<Earning>> {
+            let mut transaction = Transaction::new(String::from("cs_test"), Some(PAID), Some("eur".into()));
+            transaction.id = *transaction_id;
+
+            Ok(vec![Earning::for_session(
+                &transaction,
+                Uuid::new_v4(),
+                &PlatformCommission { percent: 20 },
+                String::from("eur"),
+            )])
+        }
+
+        async fn read_by_professional(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> AppResult<Vec<Earning>> {
+            Ok(vec![])
+        }
+
+        async fn read_pending_payouts(&self, _until: NaiveDateTime) -> AppResult<Vec<PayoutDTO>> {
+            Ok(vec![])
+        }
+
+        async fn settle_payouts(
+            &self,
+            _until: NaiveDateTime,
+            _paid_out_at: NaiveDateTime,
+        ) -> AppResult<Vec<PayoutDTO>> {
+            Ok(vec![])
+        }
+    }
+
+    /// Records the meetings deleted
+    #[derive(Default)]
+    struct MockVideoCallService {
+        deleted: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl VideoCallService for MockVideoCallService {
+        async fn create_meeting(&self, _end_date: NaiveDateTime) -> AppResult<Meeting> {
+            Err(AppError::Internal("Not expected".into()))
+        }
+
+        async fn delete_meeting(&self, meeting_id: &str) -> AppResult<()> {
+            self.deleted.lock().unwrap().push(meeting_id.to_string());
+            Ok(())
+        }
+
+        fn provider(&self) -> VideoCallProvider {
+            VideoCallProvider::Whereby
+        }
+    }
+
+    struct Mocks {
+        sessions: Arc<MockSessionPersistence>,
+        transactions: Arc<MockTransactionPersistence>,
+        gateway: Arc<MockPaymentGateway>,
+        emails: Arc<MockEmailPersistence>,
+        earnings: Arc<MockEarningPersistence>,
+        videocalls: Arc<MockVideoCallService>,
+    }
+
+    fn use_cases(sessions: MockSessionPersistence) -> (CancellationUseCases, Mocks) {
+        use_cases_with_gateway(sessions, MockPaymentGateway::default())
+    }
+
+    fn use_cases_with_gateway(
+        sessions: MockSessionPersistence,
+        gateway: MockPaymentGateway,
+    ) -> (CancellationUseCases, Mocks) {
+        let mocks = Mocks {
+            sessions: Arc::new(sessions),
+            transactions: Arc::new(MockTransactionPersistence::default()),
+            gateway: Arc::new(gateway),
+            emails: Arc::new(MockEmailPersistence::default()),
+            earnings: Arc::new(MockEarningPersistence::default()),
+            videocalls: Arc::new(MockVideoCallService::default()),
+        };
+
+        let use_cases = CancellationUseCases::new(
+            mocks.sessions.clone(),
+            mocks.transactions.clone(),
+            mocks.gateway.clone(),
+            Arc::new(MockCancellationPersistence),
+            Arc::new(MockEmailService),
+            mocks.emails.clone(),
+            mocks.earnings.clone(),
+            mocks.videocalls.clone(),
+            policy(),
+        );
+
+        (use_cases, mocks)
+    }
+
+    #[test]
+    fn policy_refunds_depending_on_notice() {
+        let session_date = chrono::Utc::now().naive_utc();
+        let partial = CancellationPolicy { full_refund_notice_hours: 24, late_refund_percent: 50 };
+
+        assert_eq!(policy().refund_amount(PAID, session_date, session_date - chrono::Duration::hours(25)), PAID);
+        assert_eq!(policy().refund_amount(PAID, session_date, session_date - chrono::Duration::hours(2)), 0);
+        assert_eq!(partial.refund_amount(PAID, session_date, session_date - chrono::Duration::hours(2)), PAID / 2);
+    }
+
+    #[tokio::test]
+    async fn patient_cancel_early_refunds_everything() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(48));
+        let session_id = Uuid::new_v4();
+
+        let res = use_cases
+            .cancel_session(&session_id, &PATIENT_USER_ID, &Role::Patient)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, PAID);
+        assert_eq!(*mocks.gateway.refunds.lock().unwrap(), vec![PAID]);
+        assert_eq!(
+            *mocks.gateway.idempotency_keys.lock().unwrap(),
+            vec![refund_idempotency_key(&session_id)]
+        );
+        assert_eq!(
+            *mocks.transactions.updates.lock().unwrap(),
+            vec![(TransactionStatus::Refunded, PAID)]
+        );
+        assert_eq!(
+            *mocks.sessions.statuses.lock().unwrap(),
+            vec![SessionStatus::Cancelled.to_id()]
+        );
+        assert_eq!(mocks.emails.sent_to.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn refund_is_taken_out_of_earnings() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(48));
+
+        use_cases
+            .cancel_session(&Uuid::new_v4(), &PATIENT_USER_ID, &Role::Patient)
+            .await
+            .unwrap();
+
+        let created = mocks.earnings.created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].kind, EarningKind::Refund);
+        assert_eq!(created[0].gross_amount, -PAID);
+        assert_eq!(created[0].net_amount, -(PAID - PAID / 5));
+    }
+
+    #[tokio::test]
+    async fn patient_cancel_late_refunds_nothing() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(2));
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &PATIENT_USER_ID, &Role::Patient)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, 0);
+        assert!(mocks.gateway.refunds.lock().unwrap().is_empty());
+        assert!(mocks.transactions.updates.lock().unwrap().is_empty());
+        assert_eq!(
+            *mocks.sessions.statuses.lock().unwrap(),
+            vec![SessionStatus::Cancelled.to_id()]
+        );
+    }
+
+    #[tokio::test]
+    async fn patient_cancel_late_refunds_partially() {
+        let (mut use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(2));
+        use_cases.policy.late_refund_percent = 50;
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &PATIENT_USER_ID, &Role::Patient)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, PAID / 2);
+        assert_eq!(
+            *mocks.transactions.updates.lock().unwrap(),
+            vec![(TransactionStatus::PartiallyRefunded, PAID / 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn professional_cancel_late_refunds_everything() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(2));
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &PROFESSIONAL_USER_ID, &Role::Professional)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, PAID);
+        assert_eq!(*mocks.gateway.refunds.lock().unwrap(), vec![PAID]);
+    }
+
+    #[tokio::test]
+    async fn unpaid_cancel_works() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(48));
+
+        let res = use_cases
+            .cancel_session(&UNPAID_SESSION_ID, &OTHER_USER_ID, &Role::Admin)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, 0);
+        assert!(mocks.gateway.refunds.lock().unwrap().is_empty());
+        assert_eq!(
+            *mocks.sessions.statuses.lock().unwrap(),
+            vec![SessionStatus::Cancelled.to_id()]
+        );
+    }
+
+    #[tokio::test]
+    async fn receptionist_cancel_late_refunds_everything() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(2));
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &OTHER_USER_ID, &Role::Receptionist)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, PAID);
+        assert_eq!(*mocks.gateway.refunds.lock().unwrap(), vec![PAID]);
+    }
+
+    #[tokio::test]
+    async fn unrelated_user_cancel_fails() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(48));
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &OTHER_USER_ID, &Role::Professional)
+            .await;
+
+        assert!(matches!(res, Err(AppError::Unauthorized(_))));
+        assert!(mocks.gateway.refunds.lock().unwrap().is_empty());
+        assert!(mocks.sessions.statuses.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_twice_fails() {
+        let mut sessions = MockSessionPersistence::in_hours(48);
+        sessions.status_id = SessionStatus::Cancelled.to_id();
+        let (use_cases, mocks) = use_cases(sessions);
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &PATIENT_USER_ID, &Role::Patient)
+            .await;
+
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        assert!(mocks.gateway.refunds.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_pending_payment_expires_checkout() {
+        let mut sessions = MockSessionPersistence::in_hours(48);
+        sessions.status_id = SessionStatus::PendingPayment.to_id();
+        let (use_cases, mocks) = use_cases(sessions);
+
+        let res = use_cases
+            .cancel_session(&PENDING_SESSION_ID, &PATIENT_USER_ID, &Role::Patient)
+            .await
+            .unwrap();
+
+        assert_eq!(res.refunded_amount, 0);
+        assert_eq!(*mocks.gateway.expired.lock().unwrap(), vec![String::from("cs_test")]);
+        assert!(mocks.gateway.refunds.lock().unwrap().is_empty());
+        assert_eq!(
+            *mocks.transactions.updates.lock().unwrap(),
+            vec![(TransactionStatus::Expired, 0)]
+        );
+        assert_eq!(
+            *mocks.sessions.statuses.lock().unwrap(),
+            vec![SessionStatus::Cancelled.to_id()]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_refund_keeps_session() {
+        let gateway = MockPaymentGateway { refusing: true, ..Default::default() };
+        let (use_cases, mocks) = use_cases_with_gateway(MockSessionPersistence::in_hours(48), gateway);
+
+        let res = use_cases
+            .cancel_session(&Uuid::new_v4(), &PATIENT_USER_ID, &Role::Patient)
+            .await;
+
+        assert!(matches!(res, Err(AppError::ExternalServiceError(_))));
+        assert_eq!(
+            *mocks.sessions.statuses.lock().unwrap(),
+            vec![SessionStatus::Cancelled.to_id(), SessionStatus::Scheduled.to_id()]
+        );
+        assert!(mocks.emails.sent_to.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_deletes_meeting() {
+        let (use_cases, mocks) = use_cases(MockSessionPersistence::in_hours(48));
+
+        use_cases
+            .cancel_session(&Uuid::new_v4(), &PROFESSIONAL_USER_ID, &Role::Professional)
+            .await
+            .unwrap();
+
+        assert_eq!(*mocks.videocalls.deleted.lock().unwrap(), vec![String::from("meeting-1")]);
+    }
+}
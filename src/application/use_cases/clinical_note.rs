@@ -0,0 +1,448 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::session::parties::SessionPartiesDTO,
+    entities::{
+        audit_event::{AuditAction, AuditEvent, AuditResource},
+        clinical_note::{ClinicalNote, ClinicalNoteVersion, NoteVisibility, SoapSections},
+        permission::{Actor, Permission},
+    },
+    use_cases::{audit::AuditPersistence, cancellation::CancellationPersistence},
+};
+
+#[async_trait]
+pub trait ClinicalNotePersistence: Send + Sync {
+    /// Stores the note with its first version
+    async fn create(&self, note: &ClinicalNote) -> AppResult<()>;
+
+    /// The note with its latest version
+    async fn read_single(&self, id: &Uuid) -> AppResult<ClinicalNote>;
+
+    /// The notes of the session with their latest version, oldest first
+    async fn read_by_session(&self, session_id: &Uuid) -> AppResult<Vec<ClinicalNote>>;
+
+    /// Every version of the note, oldest first
+    async fn read_versions(&self, note_id: &Uuid) -> AppResult<Vec<ClinicalNoteVersion>>;
+
+    /// Fails with a Conflict when the note already has a version with that number, i.e. someone else edited it first
+    async fn create_version(&self, version: &ClinicalNoteVersion) -> AppResult<()>;
+}
+
+#[derive(Clone)]
+pub struct ClinicalNoteUseCases {
+    persistence: Arc<dyn ClinicalNotePersistence>,
+    parties_persistence: Arc<dyn CancellationPersistence>,
+    audit_persistence: Arc<dyn AuditPersistence>,
+}
+
+impl ClinicalNoteUseCases {
+    pub fn new(
+        persistence: Arc<dyn ClinicalNotePersistence>,
+        parties_persistence: Arc<dyn CancellationPersistence>,
+        audit_persistence: Arc<dyn AuditPersistence>,
+    ) -> Self {
+        Self {
+            persistence,
+            parties_persistence,
+            audit_persistence,
+        }
+    }
+
+    #[instrument(skip(self, sections))]
+    pub async fn create(
+        &self,
+        actor: &Actor,
+        session_id: &Uuid,
+        visibility: NoteVisibility,
+        sections: SoapSections,
+    ) -> AppResult<ClinicalNote> {
+        info!("Attempting create clinical note...");
+
+        if sections.is_empty() {
+            return Err(AppError::InvalidPayload);
+        }
+
+        self.ensure_can_write(actor, session_id).await?;
+
+        let note = ClinicalNote::new(*session_id, actor.user_id, visibility, sections);
+
+        self.persistence.create(&note).await?;
+
+        self.audit(actor, AuditAction::Create, &[note.id]).await?;
+
+        info!("Clinical note created.");
+
+        Ok(note)
+    }
+
+    /// Edits never overwrite a note, they add a version and the previous ones are kept as they were
+    #[instrument(skip(self, sections))]
+    pub async fn revise(
+        &self,
+        actor: &Actor,
+        note_id: &Uuid,
+        visibility: NoteVisibility,
+        sections: SoapSections,
+    ) -> AppResult<ClinicalNoteVersion> {
+        info!("Attempting revise clinical note...");
+
+        if sections.is_empty() {
+            return Err(AppError::InvalidPayload);
+        }
+
+        let note = self.persistence.read_single(note_id).await?;
+
+        self.ensure_can_write(actor, &note.session_id).await?;
+
+        if note.current.visibility == visibility && note.current.sections == sections {
+            return Err(AppError::Conflict(String::from("The note already has this content")));
+        }
+
+        let version = note.revise(actor.user_id, visibility, sections);
+
+        self.persistence.create_version(&version).await?;
+
+        self.audit(actor, AuditAction::Update, &[note.id]).await?;
+
+        info!("Clinical note revised.");
+
+        Ok(version)
+    }
+
+    /// The patient of the session only gets the notes shared with them
+    #[instrument(skip(self))]
+    pub async fn read_by_session(&self, actor: &Actor, session_id: &Uuid) -> AppResult<Vec<ClinicalNote>> {
+        let parties = self.parties_persistence.read_session_parties(session_id).await?;
+
+        let notes = self.persistence.read_by_session(session_id).await?;
+
+        let notes: Vec<ClinicalNote> = if can_read_every_note(actor, &parties) {
+            notes
+        } else if actor.is(parties.patient_user_id.as_ref()) {
+            notes.into_iter().filter(ClinicalNote::is_visible_to_patient).collect()
+        } else {
+            return Err(AppError::Unauthorized(String::from(
+                "Only the session patient, professional or an admin can read its notes",
+            )));
+        };
+
+        let ids: Vec<Uuid> = notes.iter().map(|note| note.id).collect();
+        self.audit(actor, AuditAction::Read, &ids).await?;
+
+        Ok(notes)
+    }
+
+    /// The history of the edits is kept from the patient, even for the notes shared with them
+    #[instrument(skip(self))]
+    pub async fn read_versions(&self, actor: &Actor, note_id: &Uuid) -> AppResult<Vec<ClinicalNoteVersion>> {
+        let note = self.persistence.read_single(note_id).await?;
+        let parties = self.parties_persistence.read_session_parties(&note.session_id).await?;
+
+        if !can_read_every_note(actor, &parties) {
+            return Err(AppError::Unauthorized(String::from(
+                "Only the session professional or an admin can read the versions of a note",
+            )));
+        }
+
+        let versions = self.persistence.read_versions(note_id).await?;
+
+        self.audit(actor, AuditAction::Read, &[note.id]).await?;
+
+        Ok(versions)
+    }
+
+    /// Only the professional of the session writes its notes, not even the admins, they didn't attend it
+    async fn ensure_can_write(&self, actor: &Actor, session_id: &Uuid) -> AppResult<()> {
+        let parties = self.parties_persistence.read_session_parties(session_id).await?;
+
+        if !actor.is(parties.professional_user_id.as_ref()) {
+            return Err(AppError::Unauthorized(String::from(
+                "Only the session professional can write its notes",
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn audit(&self, actor: &Actor, action: AuditAction, ids: &[Uuid]) -> AppResult<()> {
+        let events: Vec<AuditEvent> = ids
+            .iter()
+            .map(|id| AuditEvent::new(actor, action, AuditResource::ClinicalNote, *id))
+            .collect();
+
+        self.audit_persistence.record(&events).await
+    }
+}
+
+fn can_read_every_note(actor: &Actor, parties: &SessionPartiesDTO) -> bool {
+    actor.can(Permission::ReadClinicalNotes) || actor.is(parties.professional_user_id.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::entities::{audit_event::AuditEventFilter, user::Role};
+
+    use super::*;
+
+    const PATIENT_USER_ID: Uuid = Uuid::from_u128(1);
+    const PROFESSIONAL_USER_ID: Uuid = Uuid::from_u128(2);
+    const SESSION_ID: Uuid = Uuid::from_u128(3);
+
+    #[derive(Default)]
+    struct MockClinicalNotePersistence {
+        notes: Mutex<Vec<ClinicalNote>>,
+        versions: Mutex<Vec<ClinicalNoteVersion>>,
+    }
+
+    impl MockClinicalNotePersistence {
+        fn with(notes: Vec<ClinicalNote>) -> Self {
+            let versions = notes.iter().map(|note| note.current.clone()).collect();
+
+            Self {
+                notes: Mutex::new(notes),
+                versions: Mutex::new(versions),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ClinicalNotePersistence for MockClinicalNotePersistence {
+        async fn create(&self, note: &ClinicalNote) -> AppResult<()> {
+            self.notes.lock().unwrap().push(note.clone());
+            self.versions.lock().unwrap().push(note.current.clone());
+
+            Ok(())
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<ClinicalNote> {
+            self.notes
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|note| note.id == *id)
+                .cloned()
+                .ok_or(AppError::NotFound(String::from("Clinical note not found")))
+        }
+
+        async fn read_by_session(&self, session_id: &Uuid) -> AppResult<Vec<ClinicalNote>> {
+            Ok(self
+                .notes
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|note| note.session_id == *session_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn read_versions(&self, note_id: &Uuid) -> AppResult<Vec<ClinicalNoteVersion>> {
+            Ok(self
+                .versions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|version| version.note_id == *note_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn create_version(&self, version: &ClinicalNoteVersion) -> AppResult<()> {
+            let mut versions = self.versions.lock().unwrap();
+
+            if versions.iter().any(|stored| stored.note_id == version.note_id && stored.version == version.version) {
+                return Err(AppError::Conflict(String::from("The note was edited by someone else")));
+            }
+            versions.push(version.clone());
+
+            for note in self.notes.lock().unwrap().iter_mut().filter(|note| note.id == version.note_id) {
+                note.current = version.clone();
+            }
+
+            Ok(())
+        }
+    }
+
+    struct MockPartiesPersistence;
+
+    #[async_trait]
+    impl CancellationPersistence for MockPartiesPersistence {
+        async fn read_session_parties(&self, _session_id: &Uuid) -> AppResult<SessionPartiesDTO> {
+            Ok(SessionPartiesDTO {
+                patient_user_id: Some(PATIENT_USER_ID),
+                patient_email: Some(String::from("patient@example.com")),
+                professional_user_id: Some(PROFESSIONAL_USER_ID),
+                professional_email: Some(String::from("professional@example.com")),
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct MockAuditPersistence {
+        events: Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait]
+    impl AuditPersistence for MockAuditPersistence {
+        async fn record(&self, events: &[AuditEvent]) -> AppResult<()> {
+            self.events.lock().unwrap().extend_from_slice(events);
+
+            Ok(())
+        }
+
+        async fn read(&self, _filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>> {
+            Ok(self.events.lock().unwrap().clone())
+        }
+    }
+
+    fn professional() -> Actor {
+        Actor::new(PROFESSIONAL_USER_ID, Role::Professional)
+    }
+
+    fn patient() -> Actor {
+        Actor::new(PATIENT_USER_ID, Role::Patient)
+    }
+
+    fn sections(subjective: &str) -> SoapSections {
+        SoapSections {
+            subjective: Some(String::from(subjective)),
+            ..SoapSections::default()
+        }
+    }
+
+    fn note(visibility: NoteVisibility, subjective: &str) -> ClinicalNote {
+        ClinicalNote::new(SESSION_ID, PROFESSIONAL_USER_ID, visibility, sections(subjective))
+    }
+
+    fn use_cases(persistence: Arc<MockClinicalNotePersistence>, audit: Arc<MockAuditPersistence>) -> ClinicalNoteUseCases {
+        ClinicalNoteUseCases::new(persistence, Arc::new(MockPartiesPersistence), audit)
+    }
+
+    #[tokio::test]
+    async fn create_works() {
+        let persistence = Arc::new(MockClinicalNotePersistence::default());
+        let audit = Arc::new(MockAuditPersistence::default());
+        let use_cases = use_cases(persistence.clone(), audit.clone());
+
+        let note = use_cases
+            .create(&professional(), &SESSION_ID, NoteVisibility::Professional, sections("Sleeps badly"))
+            .await
+            .unwrap();
+
+        assert_eq!(note.current.version, 1);
+        assert_eq!(note.author_user_id, Some(PROFESSIONAL_USER_ID));
+        assert_eq!(persistence.notes.lock().unwrap().len(), 1);
+        assert_eq!(audit.events.lock().unwrap()[0].resource_type, AuditResource::ClinicalNote);
+    }
+
+    #[tokio::test]
+    async fn create_by_someone_else_than_the_professional_fails() {
+        let persistence = Arc::new(MockClinicalNotePersistence::default());
+        let use_cases = use_cases(persistence.clone(), Arc::new(MockAuditPersistence::default()));
+
+        for actor in [patient(), Actor::new(Uuid::new_v4(), Role::Admin), Actor::new(Uuid::new_v4(), Role::Professional)] {
+            let result = use_cases
+                .create(&actor, &SESSION_ID, NoteVisibility::Professional, sections("Sleeps badly"))
+                .await;
+
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        }
+        assert!(persistence.notes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn create_without_content_fails() {
+        let use_cases = use_cases(
+            Arc::new(MockClinicalNotePersistence::default()),
+            Arc::new(MockAuditPersistence::default()),
+        );
+
+        let result = use_cases
+            .create(&professional(), &SESSION_ID, NoteVisibility::Patient, sections("  "))
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+
+    #[tokio::test]
+    async fn revise_keeps_the_previous_versions() {
+        let original = note(NoteVisibility::Professional, "Sleeps badly");
+        let persistence = Arc::new(MockClinicalNotePersistence::with(vec![original.clone()]));
+        let use_cases = use_cases(persistence.clone(), Arc::new(MockAuditPersistence::default()));
+
+        let version = use_cases
+            .revise(&professional(), &original.id, NoteVisibility::Patient, sections("Sleeps better"))
+            .await
+            .unwrap();
+
+        assert_eq!(version.version, 2);
+        let versions = use_cases.read_versions(&professional(), &original.id).await.unwrap();
+        let subjectives: Vec<Option<String>> = versions.into_iter().map(|version| version.sections.subjective).collect();
+        assert_eq!(subjectives, vec![Some(String::from("Sleeps badly")), Some(String::from("Sleeps better"))]);
+    }
+
+    #[tokio::test]
+    async fn revise_without_changes_fails() {
+        let original = note(NoteVisibility::Professional, "Sleeps badly");
+        let persistence = Arc::new(MockClinicalNotePersistence::with(vec![original.clone()]));
+        let use_cases = use_cases(persistence.clone(), Arc::new(MockAuditPersistence::default()));
+
+        let result = use_cases
+            .revise(&professional(), &original.id, NoteVisibility::Professional, sections("Sleeps badly"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(persistence.versions.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn patient_only_reads_the_shared_notes() {
+        let shared = note(NoteVisibility::Patient, "Keep a sleep diary");
+        let private = note(NoteVisibility::Professional, "Suspected insomnia");
+        let persistence = Arc::new(MockClinicalNotePersistence::with(vec![shared.clone(), private]));
+        let audit = Arc::new(MockAuditPersistence::default());
+        let use_cases = use_cases(persistence, audit.clone());
+
+        let notes = use_cases.read_by_session(&patient(), &SESSION_ID).await.unwrap();
+
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].id, shared.id);
+        assert_eq!(audit.events.lock().unwrap().len(), 1);
+
+        let notes = use_cases.read_by_session(&professional(), &SESSION_ID).await.unwrap();
+        assert_eq!(notes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn patient_cant_read_the_versions() {
+        let shared = note(NoteVisibility::Patient, "Keep a sleep diary");
+        let use_cases = use_cases(
+            Arc::new(MockClinicalNotePersistence::with(vec![shared.clone()])),
+            Arc::new(MockAuditPersistence::default()),
+        );
+
+        let result = use_cases.read_versions(&patient(), &shared.id).await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn receptionist_cant_read_the_notes() {
+        let use_cases = use_cases(
+            Arc::new(MockClinicalNotePersistence::with(vec![note(NoteVisibility::Patient, "Keep a sleep diary")])),
+            Arc::new(MockAuditPersistence::default()),
+        );
+
+        let result = use_cases
+            .read_by_session(&Actor::new(Uuid::new_v4(), Role::Receptionist), &SESSION_ID)
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::user::personal_data::PersonalDataDTO,
+    entities::data_export::{DataExport, DataExportStatus},
+};
+
+/// Days a ready archive can be downloaded for, it is deleted afterwards
+pub const DATA_EXPORT_TTL_DAYS: i64 = 7;
+
+#[async_trait]
+pub trait DataExportPersistence: Send + Sync {
+    async fn create(&self, export: &DataExport) -> AppResult<()>;
+
+    async fn read_single(&self, id: &Uuid) -> AppResult<DataExport>;
+
+    /// Newest first
+    async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<DataExport>>;
+
+    /// Stores the archive and marks the export as ready
+    async fn complete(&self, id: &Uuid, archive: &[u8], expires_at: NaiveDateTime) -> AppResult<()>;
+
+    async fn fail(&self, id: &Uuid) -> AppResult<()>;
+
+    async fn read_archive(&self, id: &Uuid) -> AppResult<Vec<u8>>;
+
+    /// Deletes the expired exports with their archives and returns how many there were
+    async fn delete_expired(&self) -> AppResult<u64>;
+
+    async fn read_personal_data(&self, user_id: &Uuid) -> AppResult<PersonalDataDTO>;
+}
+
+pub trait DataExportArchiver: Send + Sync {
+    fn archive(&self, data: &PersonalDataDTO) -> AppResult<Vec<u8>>;
+}
+
+#[derive(Clone)]
+pub struct DataExportUseCases {
+    persistence: Arc<dyn DataExportPersistence>,
+    archiver: Arc<dyn DataExportArchiver>,
+}
+
+impl DataExportUseCases {
+    pub fn new(persistence: Arc<dyn DataExportPersistence>, archiver: Arc<dyn DataExportArchiver>) -> Self {
+        Self { persistence, archiver }
+    }
+
+    /// Starts generating a copy of the personal data of the user, the export is returned while still pending.
+    /// Only one export per user is generated at a time
+    #[instrument(skip(self))]
+    pub async fn request(&self, user_id: &Uuid) -> AppResult<DataExport> {
+        info!("Attempting request data export...");
+
+        let now = Utc::now().naive_utc();
+        let exports = self.persistence.read_by_user(user_id).await?;
+        if exports.iter().any(|export| export.is_generating(now)) {
+            return Err(AppError::Conflict("A data export is already being generated".into()));
+        }
+
+        let export = DataExport::new(*user_id);
+        self.persistence.create(&export).await?;
+
+        let use_cases = self.clone();
+        let (id, user_id) = (export.id, *user_id);
+        tokio::spawn(async move { use_cases.generate(&id, &user_id).await });
+
+        info!("Data export requested.");
+
+        Ok(export)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<DataExport>> {
+        info!("Attempting read data exports by user...");
+
+        let now = Utc::now().naive_utc();
+        let exports = self.persistence.read_by_user(user_id).await?;
+
+        Ok(exports.into_iter().filter(|export| !export.is_expired(now)).collect())
+    }
+
+    /// The archive of a ready export, only its user can get it and only until it expires
+    #[instrument(skip(self))]
+    pub async fn download(&self, id: &Uuid, user_id: &Uuid) -> AppResult<Vec<u8>> {
+        info!("Attempting download data export...");
+
+        let export = self.persistence.read_single(id).await?;
+
+        // Same answer as a missing export, so ids can't be probed
+        if export.user_id != *user_id || export.is_expired(Utc::now().naive_utc()) {
+            return Err(AppError::NotFound(format!("Data export {} not found", id)));
+        }
+
+        if export.status != DataExportStatus::Ready {
+            return Err(AppError::Conflict(format!("Data export {} is {}", id, export.status)));
+        }
+
+        self.persistence.read_archive(id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_expired(&self) -> AppResult<u64> {
+        self.persistence.delete_expired().await
+    }
+
+    /// Runs in the background, a failure is only logged and leaves the export as failed so it can be requested again
+    async fn generate(&self, id: &Uuid, user_id: &Uuid) {
+        if let Err(e) = self.store_archive(id, user_id).await {
+            error!("Failed to generate data export {}: {:?}", id, e);
+
+            if let Err(e) = self.persistence.fail(id).await {
+                error!("Failed to mark data export {} as failed: {:?}", id, e);
+            }
+        }
+    }
+
+    async fn store_archive(&self, id: &Uuid, user_id: &Uuid) -> AppResult<()> {
+        let data = self.persistence.read_personal_data(user_id).await?;
+        let archive = self.archiver.archive(&data)?;
+        let expires_at = Utc::now().naive_utc() + chrono::Duration::days(DATA_EXPORT_TTL_DAYS);
+
+        self.persistence.complete(id, &archive, expires_at).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use async_trait::async_trait;
+
+    use crate::entities::user::{Role, User};
+
+    use super::*;
+
+    const USER_ID: Uuid = Uuid::from_u128(1);
+    const OTHER_USER_ID: Uuid = Uuid::from_u128(2);
+
+    #[derive(Default)]
+    struct MockDataExportPersistence {
+        exports: Mutex<Vec<DataExport>>,
+        archives: Mutex<HashMap<Uuid, Vec<u8>>>,
+    }
+
+    impl MockDataExportPersistence {
+        fn with_export(export: DataExport) -> Self {
+            let persistence = Self::default();
+            persistence.exports.lock().unwrap().push(export);
+            persistence
+        }
+
+        fn status(&self, id: &Uuid) -> DataExportStatus {
+            self.exports.lock().unwrap().iter().find(|export| export.id == *id).unwrap().status
+        }
+    }
+
+    #[async_trait]
+    impl DataExportPersistence for MockDataExportPersistence {
+        async fn create(&self, export: &DataExport) -> AppResult<()> {
+            self.exports.lock().unwrap().push(DataExport {
+                created_at: Some(Utc::now().naive_utc()),
+                ..export.clone()
+            });
+            Ok(())
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<DataExport> {
+            self.exports
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|export| export.id == *id)
+                .cloned()
+                .ok_or_else(|| AppError::NotFound(format!("Data export {} not found", id)))
+        }
+
+        async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<DataExport>> {
+            Ok(self
+                .exports
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|export| export.user_id == *user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn complete(&self, id: &Uuid, archive: &[u8], expires_at: NaiveDateTime) -> AppResult<()> {
+            let mut exports = self.exports.lock().unwrap();
+            let export = exports.iter_mut().find(|export| export.id == *id).unwrap();
+            export.status = DataExportStatus::Ready;
+            export.expires_at = Some(expires_at);
+            self.archives.lock().unwrap().insert(*id, archive.to_vec());
+            Ok(())
+        }
+
+        async fn fail(&self, id: &Uuid) -> AppResult<()> {
+            let mut exports = self.exports.lock().unwrap();
+            exports.iter_mut().find(|export| export.id == *id).unwrap().status = DataExportStatus::Failed;
+            Ok(())
+        }
+
+        async fn read_archive(&self, id: &Uuid) -> AppResult<Vec<u8>> {
+            self.archives
+                .lock()
+                .unwrap()
+                .get(id)
+                .cloned()
+                .ok_or_else(|| AppError::NotFound(format!("Data export {} not found", id)))
+        }
+
+        async fn delete_expired(&self) -> AppResult<u64> {
+            Ok(0)
+        }
+
+        async fn read_personal_data(&self, user_id: &Uuid) -> AppResult<PersonalDataDTO> {
+            Ok(PersonalDataDTO {
+                user: User {
+                    id: *user_id,
+                    role: Role::Patient,
+                    username: String::from("john"),
+                    usersurname: String::from("doe"),
+                    email: String::from("john@example.com"),
+                    verified: Some(true),
+                    needs_onboarding: Some(false),
+                    password_hash: String::new(),
+                    profile_picture_url: None,
+                    created_at: None,
+                },
+                onboarding: None,
+                consent: None,
+                patient: None,
+                parent_consent: None,
+                sessions: vec![],
+                clinical_notes: vec![],
+                transactions: vec![],
+                emails: vec![],
+            })
+        }
+    }
+
+    struct MockArchiver {
+        fails: bool,
+    }
+
+    impl DataExportArchiver for MockArchiver {
+        fn archive(&self, data: &PersonalDataDTO) -> AppResult<Vec<u8>> {
+            if self.fails {
+                return Err(AppError::Internal("Archive failed".into()));
+            }
+
+            Ok(data.user.email.as_bytes().to_vec())
+        }
+    }
+
+    fn use_cases(persistence: Arc<MockDataExportPersistence>, fails: bool) -> DataExportUseCases {
+        DataExportUseCases::new(persistence, Arc::new(MockArchiver { fails }))
+    }
+
+    fn ready_export(user_id: Uuid, expires_at: NaiveDateTime) -> DataExport {
+        DataExport {
+            status: DataExportStatus::Ready,
+            expires_at: Some(expires_at),
+            ..DataExport::new(user_id)
+        }
+    }
+
+    #[tokio::test]
+    async fn generate_stores_the_archive() {
+        let export = DataExport::new(USER_ID);
+        let persistence = Arc::new(MockDataExportPersistence::with_export(export.clone()));
+        let use_cases = use_cases(persistence.clone(), false);
+
+        use_cases.generate(&export.id, &USER_ID).await;
+
+        assert_eq!(persistence.status(&export.id), DataExportStatus::Ready);
+        assert_eq!(
+            use_cases.download(&export.id, &USER_ID).await.unwrap(),
+            b"john@example.com".to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn generate_failure_marks_the_export_as_failed() {
+        let export = DataExport::new(USER_ID);
+        let persistence = Arc::new(MockDataExportPersistence::with_export(export.clone()));
+        let use_cases = use_cases(persistence.clone(), true);
+
+        use_cases.generate(&export.id, &USER_ID).await;
+
+        assert_eq!(persistence.status(&export.id), DataExportStatus::Failed);
+        assert!(matches!(use_cases.download(&export.id, &USER_ID).await, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn request_while_generating_conflicts() {
+        let persistence = Arc::new(MockDataExportPersistence::default());
+        let use_cases = use_cases(persistence.clone(), false);
+
+        persistence.create(&DataExport::new(USER_ID)).await.unwrap();
+
+        let result = use_cases.request(&USER_ID).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(persistence.exports.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn request_after_an_interrupted_generation_works() {
+        let stale = DataExport {
+            created_at: Some(Utc::now().naive_utc() - chrono::Duration::hours(1)),
+            ..DataExport::new(USER_ID)
+        };
+        let persistence = Arc::new(MockDataExportPersistence::with_export(stale));
+        let use_cases = use_cases(persistence.clone(), false);
+
+        let export = use_cases.request(&USER_ID).await.unwrap();
+
+        assert_eq!(export.status, DataExportStatus::Pending);
+        assert_eq!(persistence.exports.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn download_of_another_user_is_not_found() {
+        let export = ready_export(USER_ID, Utc::now().naive_utc() + chrono::Duration::days(1));
+        let persistence = Arc::new(MockDataExportPersistence::with_export(export.clone()));
+        persistence.archives.lock().unwrap().insert(export.id, b"zip".to_vec());
+
+        let result = use_cases(persistence, false).download(&export.id, &OTHER_USER_ID).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn expired_export_is_gone() {
+        let export = ready_export(USER_ID, Utc::now().naive_utc() - chrono::Duration::minutes(1));
+        let persistence = Arc::new(MockDataExportPersistence::with_export(export.clone()));
+        persistence.archives.lock().unwrap().insert(export.id, b"zip".to_vec());
+        let use_cases = use_cases(persistence, false);
+
+        assert!(matches!(use_cases.download(&export.id, &USER_ID).await, Err(AppError::NotFound(_))));
+        assert!(use_cases.read_by_user(&USER_ID).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,485 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::earning::{
+        payout::PayoutDTO,
+        summary::{EarningsMonthDTO, EarningsSummaryDTO, EarningsTotalsDTO},
+    },
+    entities::{
+        earning::{Earning, EarningKind, PlatformCommission},
+        transaction::Transaction,
+    },
+    use_cases::{payment::BOOKING_CURRENCY, professional::ProfessionalPersistence},
+};
+
+#[async_trait]
+pub trait EarningPersistence: Send + Sync {
+    /// Fails with a Conflict if the payment of the transaction was already recorded
+    async fn create(&self, earning: &Earning) -> AppResult<()>;
+
+    async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Vec<Earning>>;
+
+    /// Entries of the professional earned in [from, to), oldest first
+    async fn read_by_professional(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> AppResult<Vec<Earning>>;
+
+    /// Not paid out entries earned before `until`, grouped by professional
+    async fn read_pending_payouts(&self, until: NaiveDateTime) -> AppResult<Vec<PayoutDTO>>;
+
+    /// Marks the not paid out entries earned before `until` as paid out and returns them grouped by professional
+    async fn settle_payouts(
+        &self,
+        until: NaiveDateTime,
+        paid_out_at: NaiveDateTime,
+    ) -> AppResult<Vec<PayoutDTO>>;
+}
+
+#[derive(Clone)]
+pub struct EarningUseCases {
+    persistence: Arc<dyn EarningPersistence>,
+    professional_persistence: Arc<dyn ProfessionalPersistence>,
+}
+
+impl EarningUseCases {
+    pub fn new(
+        persistence: Arc<dyn EarningPersistence>,
+        professional_persistence: Arc<dyn ProfessionalPersistence>,
+    ) -> Self {
+        Self {
+            persistence,
+            professional_persistence,
+        }
+    }
+
+    /// Earnings of the professional between both dates, included
+    #[instrument(skip(self))]
+    pub async fn read_summary(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> AppResult<EarningsSummaryDTO> {
+        info!("Attempting read earnings summary...");
+
+        if from > to {
+            return Err(AppError::InvalidPayload);
+        }
+
+        let earnings = self
+            .persistence
+            .read_by_professional(professional_id, start_of(from), start_of(to.succ_opt().unwrap_or(to)))
+            .await?;
+
+        Ok(summarize(*professional_id, from, to, &earnings))
+    }
+
+    /// Same as `read_summary` for the professional profile of the user
+    #[instrument(skip(self))]
+    pub async fn read_summary_by_user(
+        &self,
+        user_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> AppResult<EarningsSummaryDTO> {
+        let professional = self.professional_persistence.read_by_user(user_id).await?;
+        let professional_id = professional
+            .id
+            .ok_or_else(|| AppError::NotFound(String::from("Professional not found")))?;
+
+        self.read_summary(&professional_id, from, to).await
+    }
+
+    /// What is owed to each professional for the entries earned up to `until`, included
+    #[instrument(skip(self))]
+    pub async fn read_pending_payouts(&self, until: NaiveDate) -> AppResult<Vec<PayoutDTO>> {
+        info!("Attempting read pending payouts...");
+
+        self.persistence
+            .read_pending_payouts(start_of(until.succ_opt().unwrap_or(until)))
+            .await
+    }
+
+    /// Marks what is owed up to `until` as paid out. Only closed days can be settled, so the
+    /// entries settled are the same ones a previous export of the same day listed
+    #[instrument(skip(self))]
+    pub async fn settle_payouts(&self, until: NaiveDate) -> AppResult<Vec<PayoutDTO>> {
+        info!("Attempting settle payouts...");
+
+        let now = chrono::Utc::now().naive_utc();
+        if until >= now.date() {
+            return Err(AppError::Unavailable(String::from(
+                "Only the days that already ended can be settled",
+            )));
+        }
+
+        let payouts = self
+            .persistence
+            .settle_payouts(start_of(until.succ_opt().unwrap_or(until)), now)
+            .await?;
+
+        info!("Payouts settled for {} professionals.", payouts.len());
+
+        Ok(payouts)
+    }
+}
+
+/// Records what the professional earns from a paid transaction, safe to call more than once
+pub(crate) async fn record_earning(
+    persistence: &dyn EarningPersistence,
+    transaction: &Transaction,
+    professional_id: Uuid,
+    commission: &PlatformCommission,
+) -> AppResult<()> {
+    let recorded = persistence.read_by_transaction(&transaction.id).await?;
+    if recorded.iter().any(|earning| earning.kind == EarningKind::Session) {
+        return Ok(());
+    }
+
+    let currency = transaction
+        .currency
+        .clone()
+        .unwrap_or_else(|| BOOKING_CURRENCY.to_string());
+    let earning = Earning::for_session(transaction, professional_id, commission, currency);
+
+    match persistence.create(&earning).await {
+        Ok(()) => {
+            info!("Earning recorded for transaction {}", transaction.id);
+            Ok(())
+        }
+        // Recorded concurrently by another delivery of the same payment
+        Err(AppError::Conflict(_)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Takes a refund out of the earnings of the professional, nothing to do if the payment was never recorded
+pub(crate) async fn record_refund(
+    persistence: &dyn EarningPersistence,
+    transaction: &Transaction,
+    refunded_amount: i64,
+) -> AppResult<()> {
+    let recorded = persistence.read_by_transaction(&transaction.id).await?;
+
+    let Some(earning) = recorded
+        .iter()
+        .find(|earning| earning.kind == EarningKind::Session)
+    else {
+        info!("No earning recorded for transaction {}, nothing to reverse", transaction.id);
+        return Ok(());
+    };
+
+    persistence.create(&earning.reversal(refunded_amount)).await?;
+
+    info!("Refund of transaction {} taken out of the earnings", transaction.id);
+
+    Ok(())
+}
+
+fn start_of(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(chrono::NaiveTime::MIN)
+}
+
+fn summarize(
+    professional_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+    earnings: &[Earning],
+) -> EarningsSummaryDTO {
+    let mut totals = EarningsTotalsDTO::default();
+    let mut months: Vec<EarningsMonthDTO> = vec![];
+
+    for earning in earnings {
+        let month = earning.earned_at.format("%Y-%m").to_string();
+
+        if months.last().is_none_or(|last| last.month != month) {
+            months.push(EarningsMonthDTO { month, totals: EarningsTotalsDTO::default() });
+        }
+
+        // Never panics, a month was just pushed if there was none
+        let month_totals = &mut months.last_mut().unwrap().totals;
+        add(month_totals, earning);
+        add(&mut totals, earning);
+    }
+
+    EarningsSummaryDTO {
+        professional_id,
+        from,
+        to,
+        currency: earnings
+            .first()
+            .map(|earning| earning.currency.clone())
+            .unwrap_or_else(|| BOOKING_CURRENCY.to_string()),
+        totals,
+        months,
+    }
+}
+
+fn add(totals: &mut EarningsTotalsDTO, earning: &Earning) {
+    if earning.kind == EarningKind::Session {
+        totals.sessions += 1;
+    }
+    totals.gross_amount += earning.gross_amount;
+    totals.commission_amount += earning.commission_amount;
+    totals.net_amount += earning.net_amount;
+    if earning.paid_out_at.is_some() {
+        totals.paid_out_amount += earning.net_amount;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::{
+        dtos::professional::selector::ProfessionalSelectorDTO,
+        entities::{gender::Gender, professional::Professional},
+    };
+
+    use super::*;
+
+    const PROFESSIONAL_ID: Uuid = Uuid::from_u128(1);
+
+    #[derive(Default)]
+    struct MockEarningPersistence {
+        earnings: Mutex<Vec<Earning>>,
+    }
+
+    #[async_trait]
+    impl EarningPersistence for MockEarningPersistence {
+        async fn create(&self, earning: &Earning) -> AppResult<()> {
+            self.earnings.lock().unwrap().push(earning.clone());
+            Ok(())
+        }
+
+        async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Vec<Earning>> {
+            Ok(self
+                .earnings
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|earning| earning.transaction_id == *transaction_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn read_by_professional(
+            &self,
+            professional_id: &Uuid,
+            from: NaiveDateTime,
+            to: NaiveDateTime,
+        ) -> AppResult<Vec<Earning>> {
+            Ok(self
+                .earnings
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|earning| {
+                    earning.professional_id == *professional_id
+                        && earning.earned_at >= from
+                        && earning.earned_at < to
+                })
+                .cloned()
+                .collect())
+        }
+
+        async fn read_pending_payouts(&self, _until: NaiveDateTime) -> AppResult<Vec<PayoutDTO>> {
+            Ok(vec![])
+        }
+
+        async fn settle_payouts(
+            &self,
+            _until: NaiveDateTime,
+            _paid_out_at: NaiveDateTime,
+        ) -> AppResult<Vec<PayoutDTO>> {
+            Ok(vec![])
+        }
+    }
+
+    struct MockProfessionalPersistence;
+
+    #[async_trait]
+    impl ProfessionalPersistence for MockProfessionalPersistence {
+        async fn create(&self, _professional: &Professional) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_all(&self) -> AppResult<Vec<Professional>> {
+            Ok(vec![])
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<Professional> {
+            Ok(Professional {
+                id: Some(*id),
+                user_id: Some(Uuid::new_v4()),
+                gender: Gender::default(),
+                birthdate: None,
+                license_number: None,
+                bio: None,
+                education: None,
+                experience_years: None,
+                hourly_rate: Some(60.0),
+                accepts_insurance: false,
+                created_at: None,
+            })
+        }
+
+        async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<Professional> {
+            self.read_single(&PROFESSIONAL_ID).await
+        }
+
+        async fn update(&self, _professional: &Professional) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn selector(&self) -> AppResult<Vec<ProfessionalSelectorDTO>> {
+            Ok(vec![])
+        }
+    }
+
+    fn paid_transaction(amount: i64) -> Transaction {
+        let mut transaction =
+            Transaction::new(String::from("cs_test"), Some(amount), Some(String::from("eur")));
+        transaction.booked_session_id = Some(Uuid::new_v4());
+        transaction.complete(String::from("pi_test"));
+        transaction
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn commission_is_rounded_to_the_nearest_cent() {
+        let commission = PlatformCommission { percent: 15 };
+
+        assert_eq!(commission.of(6000), 900);
+        assert_eq!(commission.of(4550), 683); // 682.5
+        assert_eq!(PlatformCommission { percent: 0 }.of(6000), 0);
+    }
+
+    #[tokio::test]
+    async fn record_earning_applies_the_commission_once() {
+        let persistence = MockEarningPersistence::default();
+        let transaction = paid_transaction(6000);
+        let commission = PlatformCommission { percent: 15 };
+
+        record_earning(&persistence, &transaction, PROFESSIONAL_ID, &commission).await.unwrap();
+        record_earning(&persistence, &transaction, PROFESSIONAL_ID, &commission).await.unwrap();
+
+        let earnings = persistence.earnings.lock().unwrap();
+        assert_eq!(earnings.len(), 1);
+        assert_eq!(earnings[0].professional_id, PROFESSIONAL_ID);
+        assert_eq!(earnings[0].session_id, transaction.booked_session_id);
+        assert_eq!(earnings[0].commission_amount, 900);
+        assert_eq!(earnings[0].net_amount, 5100);
+    }
+
+    #[tokio::test]
+    async fn record_refund_reverses_with_the_original_commission() {
+        let persistence = MockEarningPersistence::default();
+        let transaction = paid_transaction(6000);
+
+        record_earning(&persistence, &transaction, PROFESSIONAL_ID, &PlatformCommission { percent: 20 })
+            .await
+            .unwrap();
+        record_refund(&persistence, &transaction, 3000).await.unwrap();
+
+        let earnings = persistence.earnings.lock().unwrap();
+        assert_eq!(earnings.len(), 2);
+        assert_eq!(earnings[1].kind, EarningKind::Refund);
+        assert_eq!(earnings[1].gross_amount, -3000);
+        assert_eq!(earnings[1].commission_amount, -600);
+        assert_eq!(earnings[1].net_amount, -2400);
+    }
+
+    #[tokio::test]
+    async fn record_refund_of_unrecorded_payment_does_nothing() {
+        let persistence = MockEarningPersistence::default();
+
+        record_refund(&persistence, &paid_transaction(6000), 6000).await.unwrap();
+
+        assert!(persistence.earnings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_summary_groups_by_month() {
+        let persistence = Arc::new(MockEarningPersistence::default());
+        let commission = PlatformCommission { percent: 10 };
+
+        let mut january = Earning::for_session(&paid_transaction(6000), PROFESSIONAL_ID, &commission, String::from("eur"));
+        january.earned_at = date(2026, 1, 10).and_hms_opt(10, 0, 0).unwrap();
+        january.paid_out_at = Some(date(2026, 2, 1).and_hms_opt(0, 0, 0).unwrap());
+        let mut february = Earning::for_session(&paid_transaction(4000), PROFESSIONAL_ID, &commission, String::from("eur"));
+        february.earned_at = date(2026, 2, 28).and_hms_opt(23, 0, 0).unwrap();
+        let mut refund = february.reversal(4000);
+        refund.earned_at = date(2026, 2, 28).and_hms_opt(23, 30, 0).unwrap();
+        let mut march = Earning::for_session(&paid_transaction(6000), PROFESSIONAL_ID, &commission, String::from("eur"));
+        march.earned_at = date(2026, 3, 1).and_hms_opt(0, 0, 0).unwrap();
+
+        for earning in [&january, &february, &refund, &march] {
+            persistence.create(earning).await.unwrap();
+        }
+
+        let use_cases = EarningUseCases::new(persistence, Arc::new(MockProfessionalPersistence));
+
+        let summary = use_cases
+            .read_summary_by_user(&Uuid::new_v4(), date(2026, 1, 1), date(2026, 2, 28))
+            .await
+            .unwrap();
+
+        assert_eq!(summary.professional_id, PROFESSIONAL_ID);
+        assert_eq!(
+            summary.totals,
+            EarningsTotalsDTO {
+                sessions: 2,
+                gross_amount: 6000,
+                commission_amount: 600,
+                net_amount: 5400,
+                paid_out_amount: 5400,
+            }
+        );
+        let months: Vec<_> = summary.months.iter().map(|month| (month.month.as_str(), month.totals.net_amount)).collect();
+        assert_eq!(months, vec![("2026-01", 5400), ("2026-02", 0)]);
+    }
+
+    #[tokio::test]
+    async fn read_summary_rejects_reversed_period() {
+        let use_cases = EarningUseCases::new(
+            Arc::new(MockEarningPersistence::default()),
+            Arc::new(MockProfessionalPersistence),
+        );
+
+        let result = use_cases
+            .read_summary(&PROFESSIONAL_ID, date(2026, 2, 1), date(2026, 1, 1))
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+
+    #[tokio::test]
+    async fn settle_payouts_rejects_open_days() {
+        let use_cases = EarningUseCases::new(
+            Arc::new(MockEarningPersistence::default()),
+            Arc::new(MockProfessionalPersistence),
+        );
+
+        let today = chrono::Utc::now().date_naive();
+
+        assert!(matches!(use_cases.settle_payouts(today).await, Err(AppError::Unavailable(_))));
+        assert!(use_cases.settle_payouts(today.pred_opt().unwrap()).await.is_ok());
+    }
+}
@@ -0,0 +1,400 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Datelike;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    dtos::invoice::source::InvoiceSourceDTO,
+    entities::{
+        invoice::{BillingDetails, Invoice, InvoiceLine},
+        permission::Permission,
+        transaction::{Transaction, TransactionStatus},
+        user::Role,
+    },
+    use_cases::payment::{BOOKING_CURRENCY, TransactionPersistence, booking_product_name},
+};
+
+#[async_trait]
+pub trait InvoicePersistence: Send + Sync {
+    /// Stores the invoice with the next number of the fiscal year of its issuer, the professional, and returns it numbered.
+    /// Fails with a Conflict if the transaction was already invoiced
+    async fn create(&self, invoice: &Invoice) -> AppResult<Invoice>;
+
+    async fn read_single(&self, id: &Uuid) -> AppResult<Invoice>;
+
+    async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Option<Invoice>>;
+
+    /// Invoices where the user is the patient or the professional, newest first
+    async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<Invoice>>;
+
+    /// Whether the user is the patient or the professional of the invoice
+    async fn is_party(&self, invoice_id: &Uuid, user_id: &Uuid) -> AppResult<bool>;
+
+    async fn read_invoice_source(&self, session_id: &Uuid) -> AppResult<InvoiceSourceDTO>;
+
+    async fn read_billing_details(&self, user_id: &Uuid) -> AppResult<Option<BillingDetails>>;
+
+    async fn upsert_billing_details(&self, details: &BillingDetails) -> AppResult<()>;
+}
+
+pub trait InvoiceRenderer: Send + Sync {
+    fn render_pdf(&self, invoice: &Invoice) -> AppResult<Vec<u8>>;
+}
+
+#[derive(Clone)]
+pub struct InvoiceUseCases {
+    persistence: Arc<dyn InvoicePersistence>,
+    transaction_persistence: Arc<dyn TransactionPersistence>,
+    renderer: Arc<dyn InvoiceRenderer>,
+}
+
+impl InvoiceUseCases {
+    pub fn new(
+        persistence: Arc<dyn InvoicePersistence>,
+        transaction_persistence: Arc<dyn TransactionPersistence>,
+        renderer: Arc<dyn InvoiceRenderer>,
+    ) -> Self {
+        Self {
+            persistence,
+            transaction_persistence,
+            renderer,
+        }
+    }
+
+    /// Issues the invoice of a paid session if it doesn't have one yet (e.g. issuing failed when the payment was confirmed)
+    #[instrument(skip(self))]
+    pub async fn issue_for_session(&self, session_id: &Uuid) -> AppResult<Invoice> {
+        info!("Attempting issue invoice for session...");
+
+        let transaction = self
+            .transaction_persistence
+            .get_by_booked_session_id(session_id)
+            .await?;
+
+        issue_invoice(self.persistence.as_ref(), &transaction).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Vec<Invoice>> {
+        info!("Attempting read invoices by user...");
+
+        self.persistence.read_by_user(user_id).await
+    }
+
+    /// Renders the invoice, only its patient, its professional or the staff reading every invoice can get it
+    #[instrument(skip(self))]
+    pub async fn read_pdf(
+        &self,
+        id: &Uuid,
+        user_id: &Uuid,
+        role: &Role,
+    ) -> AppResult<(Invoice, Vec<u8>)> {
+        info!("Attempting read invoice pdf...");
+
+        if !role.can(Permission::ReadInvoices) && !self.persistence.is_party(id, user_id).await? {
+            // Same answer as a missing invoice, so ids can't be probed
+            return Err(AppError::NotFound(format!("Invoice {} not found", id)));
+        }
+
+        let invoice = self.persistence.read_single(id).await?;
+        let pdf = self.renderer.render_pdf(&invoice)?;
+
+        Ok((invoice, pdf))
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read_billing_details(&self, user_id: &Uuid) -> AppResult<Option<BillingDetails>> {
+        info!("Attempting read billing details...");
+
+        self.persistence.read_billing_details(user_id).await
+    }
+
+    /// Only affects the invoices issued from now on
+    #[instrument(skip(self))]
+    pub async fn update_billing_details(&self, details: &BillingDetails) -> AppResult<()> {
+        info!("Attempting update billing details...");
+
+        self.persistence.upsert_billing_details(details).await?;
+
+        info!("Billing details updated.");
+
+        Ok(())
+    }
+}
+
+/// Issues the invoice of a paid booking, returns the existing one if it was already issued
+pub(crate) async fn issue_invoice(
+    persistence: &dyn InvoicePersistence,
+    transaction: &Transaction,
+) -> AppResult<Invoice> {
+    if let Some(invoice) = persistence.read_by_transaction(&transaction.id).await? {
+        return Ok(invoice);
+    }
+
+    if !matches!(
+        transaction.status,
+        TransactionStatus::Completed | TransactionStatus::Refunded | TransactionStatus::PartiallyRefunded
+    ) {
+        return Err(AppError::Unavailable(format!(
+            "Transaction {} is {}, only paid transactions are invoiced",
+            transaction.id, transaction.status
+        )));
+    }
+
+    let session_id = transaction.booked_session_id.ok_or_else(|| {
+        AppError::Unavailable(format!("Transaction {} didn't pay a session", transaction.id))
+    })?;
+
+    let source = persistence.read_invoice_source(&session_id).await?;
+
+    let session_type_name = source.session_type_name.as_deref().unwrap_or("Therapy");
+    let description = match (source.session_date, source.session_duration) {
+        (Some(date), Some(duration)) => booking_product_name(session_type_name, duration, date),
+        _ => format!("{} session", session_type_name),
+    };
+
+    let issued_at = chrono::Utc::now().naive_utc();
+
+    let invoice = Invoice {
+        id: Uuid::new_v4(),
+        transaction_id: transaction.id,
+        session_id: Some(session_id),
+        patient_id: source.patient_id,
+        professional_id: source.professional_id,
+        fiscal_year: issued_at.year(),
+        number: 0,
+        issued_at,
+        issuer: source.issuer,
+        recipient: source.recipient,
+        currency: transaction
+            .currency
+            .clone()
+            .unwrap_or_else(|| BOOKING_CURRENCY.to_string()),
+        lines: vec![InvoiceLine::healthcare_service(
+            description,
+            transaction.amount.unwrap_or_default(),
+        )],
+    };
+
+    match persistence.create(&invoice).await {
+        Ok(invoice) => {
+            info!("Invoice {} issued for transaction {}", invoice.invoice_number(), transaction.id);
+            Ok(invoice)
+        }
+        // Issued concurrently by someone else, the number taken by this attempt was rolled back with it
+        Err(AppError::Conflict(_)) => persistence
+            .read_by_transaction(&transaction.id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Invoice conflict without invoice".into())),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::entities::invoice::{BillingParty, HEALTHCARE_VAT_EXEMPTION};
+
+    use super::*;
+
+    const PARTY_USER_ID: Uuid = Uuid::from_u128(1);
+    const INVOICE_ID: Uuid = Uuid::from_u128(2);
+    const PROFESSIONAL_ID: Uuid = Uuid::from_u128(3);
+
+    #[derive(Default)]
+    struct MockInvoicePersistence {
+        created: Mutex<Vec<Invoice>>,
+    }
+
+    #[async_trait]
+    impl InvoicePersistence for MockInvoicePersistence {
+        async fn create(&self, invoice: &Invoice) -> AppResult<Invoice> {
+            let mut created = self.created.lock().unwrap();
+
+            if created.iter().any(|i| i.transaction_id == invoice.transaction_id) {
+                return Err(AppError::Conflict("Transaction already invoiced".into()));
+            }
+
+            let mut invoice = invoice.clone();
+            invoice.number = created
+                .iter()
+                .filter(|i| i.professional_id == invoice.professional_id && i.fiscal_year == invoice.fiscal_year)
+                .count() as i32
+                + 1;
+            created.push(invoice.clone());
+
+            Ok(invoice)
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<Invoice> {
+            let mut invoice = sample_invoice();
+            invoice.id = *id;
+
+            Ok(invoice)
+        }
+
+        async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Option<Invoice>> {
+            Ok(self
+                .created
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|i| i.transaction_id == *transaction_id)
+                .cloned())
+        }
+
+        async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<Vec<Invoice>> {
+            Ok(self.created.lock().unwrap().clone())
+        }
+
+        async fn is_party(&self, _invoice_id: &Uuid, user_id: &Uuid) -> AppResult<bool> {
+            Ok(*user_id == PARTY_USER_ID)
+        }
+
+        async fn read_invoice_source(&self, _session_id: &Uuid) -> AppResult<InvoiceSourceDTO> {
+            Ok(InvoiceSourceDTO {
+                patient_id: Uuid::new_v4(),
+                professional_id: PROFESSIONAL_ID,
+                issuer: BillingParty {
+                    name: String::from("Ana García"),
+                    tax_id: Some(String::from("12345678Z")),
+                    address: Some(String::from("Calle Mayor 1, 28013 Madrid, ES")),
+                },
+                recipient: BillingParty { name: String::from("Juan Pérez"), tax_id: None, address: None },
+                session_type_name: Some(String::from("Individual therapy")),
+                session_date: Some(chrono::Utc::now().naive_utc()),
+                session_duration: Some(50),
+            })
+        }
+
+        async fn read_billing_details(&self, _user_id: &Uuid) -> AppResult<Option<BillingDetails>> {
+            Ok(None)
+        }
+
+        async fn upsert_billing_details(&self, _details: &BillingDetails) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockTransactionPersistence;
+
+    #[async_trait]
+    impl TransactionPersistence for MockTransactionPersistence {
+        async fn create(&self, transaction: &Transaction) -> AppResult<Transaction> {
+            Ok(transaction.clone())
+        }
+
+        async fn update(&self, transaction: &Transaction) -> AppResult<Transaction> {
+            Ok(transaction.clone())
+        }
+
+        async fn get_by_session_id(&self, _session_id: &str) -> AppResult<Transaction> {
+            Ok(paid_transaction())
+        }
+
+        async fn get_by_booked_session_id(&self, _booked_session_id: &Uuid) -> AppResult<Transaction> {
+            Ok(paid_transaction())
+        }
+    }
+
+    struct MockRenderer;
+
+    impl InvoiceRenderer for MockRenderer {
+        fn render_pdf(&self, _invoice: &Invoice) -> AppResult<Vec<u8>> {
+            Ok(b"%PDF".to_vec())
+        }
+    }
+
+    fn sample_invoice() -> Invoice {
+        Invoice {
+            id: INVOICE_ID,
+            transaction_id: Uuid::new_v4(),
+            session_id: None,
+            patient_id: Uuid::new_v4(),
+            professional_id: Uuid::new_v4(),
+            fiscal_year: 2026,
+            number: 42,
+            issued_at: chrono::Utc::now().naive_utc(),
+            issuer: BillingParty::default(),
+            recipient: BillingParty::default(),
+            currency: String::from("eur"),
+            lines: vec![InvoiceLine::healthcare_service(String::from("Session"), 6000)],
+        }
+    }
+
+    fn paid_transaction() -> Transaction {
+        let mut transaction = Transaction::new(String::from("cs_test"), Some(6000), Some("eur".into()));
+        transaction.booked_session_id = Some(Uuid::new_v4());
+        transaction.complete(String::from("pi_test"));
+        transaction
+    }
+
+    #[test]
+    fn invoice_number_and_totals_work() {
+        let invoice = sample_invoice();
+
+        assert_eq!(invoice.invoice_number(), "2026-000042");
+        assert_eq!(invoice.total(), 6000);
+        assert_eq!(invoice.lines[0].vat(), 0);
+        assert_eq!(invoice.lines[0].vat_exemption.as_deref(), Some(HEALTHCARE_VAT_EXEMPTION));
+    }
+
+    #[tokio::test]
+    async fn issue_invoice_numbers_sequentially() {
+        let persistence = MockInvoicePersistence::default();
+
+        let first = issue_invoice(&persistence, &paid_transaction()).await.unwrap();
+        let second = issue_invoice(&persistence, &paid_transaction()).await.unwrap();
+
+        assert_eq!(first.number, 1);
+        assert_eq!(second.number, 2);
+        assert_eq!(second.total(), 6000);
+        assert_eq!(second.issuer.name, "Ana García");
+    }
+
+    #[tokio::test]
+    async fn issue_invoice_twice_returns_the_same() {
+        let persistence = MockInvoicePersistence::default();
+        let transaction = paid_transaction();
+
+        let first = issue_invoice(&persistence, &transaction).await.unwrap();
+        let second = issue_invoice(&persistence, &transaction).await.unwrap();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(persistence.created.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn issue_invoice_unpaid_fails() {
+        let persistence = MockInvoicePersistence::default();
+        let mut transaction = paid_transaction();
+        transaction.status = TransactionStatus::Pending;
+
+        let res = issue_invoice(&persistence, &transaction).await;
+
+        assert!(matches!(res, Err(AppError::Unavailable(_))));
+        assert!(persistence.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_pdf_by_other_user_fails() {
+        let use_cases = InvoiceUseCases::new(
+            Arc::new(MockInvoicePersistence::default()),
+            Arc::new(MockTransactionPersistence),
+            Arc::new(MockRenderer),
+        );
+
+        let res = use_cases.read_pdf(&INVOICE_ID, &Uuid::new_v4(), &Role::Patient).await;
+        assert!(matches!(res, Err(AppError::NotFound(_))));
+
+        let (invoice, pdf) = use_cases.read_pdf(&INVOICE_ID, &PARTY_USER_ID, &Role::Patient).await.unwrap();
+        assert_eq!(invoice.id, INVOICE_ID);
+        assert!(pdf.starts_with(b"%PDF"));
+
+        assert!(use_cases.read_pdf(&INVOICE_ID, &Uuid::new_v4(), &Role::Admin).await.is_ok());
+    }
+}
@@ -1,8 +1,17 @@
+pub mod account_deletion;
+pub mod audit;
 pub mod blog_post;
+pub mod cancellation;
+pub mod clinical_note;
+pub mod data_export;
+pub mod earning;
 pub mod email;
+pub mod invoice;
+pub mod organization;
 pub mod parent_consent;
 pub mod patient;
 pub mod professional;
+pub mod professional_availability;
 pub mod professional_language;
 pub mod professional_specialization;
 pub mod session;
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::organization::{Organization, OrganizationMember},
+};
+
+#[async_trait]
+pub trait OrganizationPersistence: Send + Sync {
+    /// Moves the admin out of its organization into the new one and makes it an admin there
+    async fn create(&self, organization: &Organization, admin_user_id: &Uuid, from_organization_id: &Uuid) -> AppResult<()>;
+
+    async fn read(&self, id: &Uuid) -> AppResult<Organization>;
+
+    async fn read_members(&self, organization_id: &Uuid) -> AppResult<Vec<OrganizationMember>>;
+
+    /// Whether the user operates the platform, which is not tied to any organization
+    async fn is_platform_admin(&self, user_id: &Uuid) -> AppResult<bool>;
+}
+
+#[derive(Clone)]
+pub struct OrganizationUseCases {
+    persistence: Arc<dyn OrganizationPersistence>,
+}
+
+impl OrganizationUseCases {
+    pub fn new(persistence: Arc<dyn OrganizationPersistence>) -> Self {
+        Self { persistence }
+    }
+
+    /// A new clinic, only the platform admins can create them. Its first admin is handed over by the organization
+    /// creating it, the records of the admin (its patient or professional profile, its sessions) stay in the
+    /// organization it leaves
+    #[instrument(skip(self))]
+    pub async fn create(
+        &self,
+        name: &str,
+        admin_user_id: &Uuid,
+        from_organization_id: &Uuid,
+        requested_by: &Uuid,
+    ) -> AppResult<Uuid> {
+        info!("Attempting create organization...");
+
+        if !self.persistence.is_platform_admin(requested_by).await? {
+            return Err(AppError::Unauthorized(String::from("Only the platform admins can create organizations")));
+        }
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidPayload);
+        }
+
+        let organization = Organization {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            created_at: None,
+        };
+
+        self.persistence
+            .create(&organization, admin_user_id, from_organization_id)
+            .await?;
+
+        info!("Organization created.");
+
+        Ok(organization.id)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read(&self, id: &Uuid) -> AppResult<Organization> {
+        self.persistence.read(id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read_members(&self, organization_id: &Uuid) -> AppResult<Vec<OrganizationMember>> {
+        self.persistence.read_members(organization_id).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use crate::entities::user::Role;
+
+    use super::*;
+
+    const ORGANIZATION_ID: Uuid = Uuid::from_u128(1);
+    const ADMIN_USER_ID: Uuid = Uuid::from_u128(2);
+    const PLATFORM_ADMIN_USER_ID: Uuid = Uuid::from_u128(3);
+
+    #[derive(Default)]
+    struct MockOrganizationPersistence {
+        created: Mutex<Vec<(String, Uuid, Uuid)>>,
+    }
+
+    #[async_trait]
+    impl OrganizationPersistence for MockOrganizationPersistence {
+        async fn create(&self, organization: &Organization, admin_user_id: &Uuid, from_organization_id: &Uuid) -> AppResult<()> {
+            self.created
+                .lock()
+                .unwrap()
+                .push((organization.name.clone(), *admin_user_id, *from_organization_id));
+
+            Ok(())
+        }
+
+        async fn read(&self, id: &Uuid) -> AppResult<Organization> {
+            Ok(Organization {
+                id: *id,
+                name: String::from("Clinic"),
+                created_at: None,
+            })
+        }
+
+        async fn read_members(&self, _organization_id: &Uuid) -> AppResult<Vec<OrganizationMember>> {
+            Ok(vec![OrganizationMember {
+                user_id: ADMIN_USER_ID,
+                username: String::from("john"),
+                usersurname: String::from("doe"),
+                email: String::from("john@example.com"),
+                role: Role::Admin,
+                joined_at: None,
+            }])
+        }
+
+        async fn is_platform_admin(&self, user_id: &Uuid) -> AppResult<bool> {
+            Ok(*user_id == PLATFORM_ADMIN_USER_ID)
+        }
+    }
+
+    #[tokio::test]
+    async fn create_hands_the_admin_over() {
+        let persistence = Arc::new(MockOrganizationPersistence::default());
+        let use_cases = OrganizationUseCases::new(persistence.clone());
+
+        let id = use_cases
+            .create("  North clinic ", &ADMIN_USER_ID, &ORGANIZATION_ID, &PLATFORM_ADMIN_USER_ID)
+            .await
+            .unwrap();
+
+        assert_ne!(id, ORGANIZATION_ID);
+        assert_eq!(
+            *persistence.created.lock().unwrap(),
+            vec![(String::from("North clinic"), ADMIN_USER_ID, ORGANIZATION_ID)]
+        );
+    }
+
+    #[tokio::test]
+    async fn create_without_name_fails() {
+        let persistence = Arc::new(MockOrganizationPersistence::default());
+        let use_cases = OrganizationUseCases::new(persistence.clone());
+
+        let result = use_cases.create("   ", &ADMIN_USER_ID, &ORGANIZATION_ID, &PLATFORM_ADMIN_USER_ID).await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+        assert!(persistence.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn clinic_admin_cannot_create() {
+        let persistence = Arc::new(MockOrganizationPersistence::default());
+        let use_cases = OrganizationUseCases::new(persistence.clone());
+
+        let result = use_cases.create("North clinic", &ADMIN_USER_ID, &ORGANIZATION_ID, &ADMIN_USER_ID).await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        assert!(persistence.created.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_members_works() {
+        let use_cases = OrganizationUseCases::new(Arc::new(MockOrganizationPersistence::default()));
+
+        let members = use_cases.read_members(&ORGANIZATION_ID).await.unwrap();
+
+        assert_eq!(members.len(), 1);
+    }
+}
@@ -6,11 +6,20 @@ use tracing::{info, instrument};
 use uuid::Uuid;
 use chrono::NaiveDate;
 
-use crate::{app_error::AppResult, entities::patient::Patient};
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::{
+        audit_event::{AuditAction, AuditEvent, AuditResource},
+        patient::Patient,
+        permission::{Actor, Permission},
+        professional::Professional,
+    },
+    use_cases::audit::AuditPersistence,
+};
 
 #[async_trait]
 pub trait PatientPersistence: Send + Sync {
-    async fn create(&self, patient: &Patient) -> AppResult<()>;
+    async fn create(&self, patient: &Patient) -> AppResult<Uuid>;
 
     async fn read_all(&self) -> AppResult<Vec<Patient>>;
 
@@ -20,6 +29,10 @@ pub trait PatientPersistence: Send + Sync {
 
     async fn read_by_professional(&self, professional_id: &Uuid) -> AppResult<Vec<Patient>>;
 
+    /// Whether the patient has (or had) sessions with the professional of the user, the cancelled ones and the bookings
+    /// never paid don't count
+    async fn is_patient_of(&self, patient_id: &Uuid, professional_user_id: &Uuid) -> AppResult<bool>;
+
     async fn update(&self, patient: &Patient) -> AppResult<()>;
 
     async fn update_birthdate(&self, patient_id: &Uuid, birthdate: NaiveDate) -> AppResult<()>;
@@ -30,18 +43,31 @@ pub trait PatientPersistence: Send + Sync {
 #[derive(Clone)]
 pub struct PatientUseCases {
     persistence: Arc<dyn PatientPersistence>,
+    audit_persistence: Arc<dyn AuditPersistence>,
 }
 
 impl PatientUseCases {
-    pub fn new(persistence: Arc<dyn PatientPersistence>) -> Self {
-        Self { persistence }
+    pub fn new(persistence: Arc<dyn PatientPersistence>, audit_persistence: Arc<dyn AuditPersistence>) -> Self {
+        Self {
+            persistence,
+            audit_persistence,
+        }
     }
 
+    /// Users can create their own record, creating the record of other users needs the permission
     #[instrument(skip(self))]
-    pub async fn create(&self, patient: &Patient) -> AppResult<()> {
+    pub async fn create(&self, actor: &Actor, patient: &Patient) -> AppResult<()> {
         info!("Attempting create patient...");
 
-        self.persistence.create(patient).await?;
+        if !actor.is(patient.user_id.as_ref()) && !actor.can(Permission::CreatePatients) {
+            return Err(AppError::Unauthorized(String::from(
+                "You don't have permission to create this patient",
+            )));
+        }
+
+        let id = self.persistence.create(patient).await?;
+
+        self.audit(actor, AuditAction::Create, &[id]).await?;
 
         info!("Patient created.");
 
@@ -49,42 +75,136 @@ impl PatientUseCases {
     }
 
     #[instrument(skip(self))]
-    pub async fn read_all(&self) -> AppResult<Vec<Patient>> {
-        self.persistence.read_all().await
+    pub async fn read_all(&self, actor: &Actor) -> AppResult<Vec<Patient>> {
+        let patients = self.persistence.read_all().await?;
+
+        self.audit_read(actor, &patients).await?;
+
+        Ok(patients)
     }
 
     #[instrument(skip(self))]
-    pub async fn read_single(&self, id: &Uuid) -> AppResult<Patient> {
-        self.persistence.read_single(id).await
+    pub async fn read_single(&self, actor: &Actor, id: &Uuid) -> AppResult<Patient> {
+        let patient = self.persistence.read_single(id).await?;
+        let patient = self.visible_to(actor, patient).await?;
+
+        self.audit_read(actor, std::slice::from_ref(&patient)).await?;
+
+        Ok(patient)
     }
 
     #[instrument(skip(self))]
-    pub async fn read_by_user(&self, user_id: &Uuid) -> AppResult<Patient> {
-        self.persistence.read_by_user(user_id).await
+    pub async fn read_by_user(&self, actor: &Actor, user_id: &Uuid) -> AppResult<Patient> {
+        let patient = self.persistence.read_by_user(user_id).await?;
+        let patient = self.visible_to(actor, patient).await?;
+
+        self.audit_read(actor, std::slice::from_ref(&patient)).await?;
+
+        Ok(patient)
     }
 
+    /// The professional reads the whole records of their patients, the staff that reads every contact gets them
+    /// without the clinical data
     #[instrument(skip(self))]
-    pub async fn read_by_professional(&self, professional_id: &Uuid) -> AppResult<Vec<Patient>> {
-        self.persistence.read_by_professional(professional_id).await
+    pub async fn read_by_professional(&self, actor: &Actor, professional: &Professional) -> AppResult<Vec<Patient>> {
+        let professional_id = professional
+            .id
+            .ok_or_else(|| AppError::Internal(String::from("Professional without id")))?;
+
+        let full_access = actor.can(Permission::ReadPatients) || actor.is(professional.user_id.as_ref());
+        if !full_access && !actor.can(Permission::ReadPatientContacts) {
+            return Err(AppError::Unauthorized(String::from(
+                "You don't have permission to read these patients",
+            )));
+        }
+
+        let patients = self.persistence.read_by_professional(&professional_id).await?;
+
+        self.audit_read(actor, &patients).await?;
+
+        if full_access {
+            return Ok(patients);
+        }
+
+        Ok(patients.into_iter().map(Patient::without_clinical_data).collect())
     }
 
+    /// Users can update their own record and professionals the ones of their patients
     #[instrument(skip(self))]
-    pub async fn update(&self, patient: &Patient) -> AppResult<()> {
+    pub async fn update(&self, actor: &Actor, patient: &Patient) -> AppResult<()> {
         info!("Attempting update patient...");
 
+        let id = patient
+            .id
+            .ok_or_else(|| AppError::Internal(String::from("Patient without id")))?;
+        let stored = self.persistence.read_single(&id).await?;
+
+        if !self.has_full_access(actor, &stored, Permission::UpdatePatients).await? {
+            return Err(AppError::Unauthorized(String::from(
+                "You don't have permission to update this patient",
+            )));
+        }
+
         self.persistence.update(patient).await?;
 
+        self.audit(actor, AuditAction::Update, &[id]).await?;
+
         info!("Patient updated.");
 
         Ok(())
     }
 
+    /// The whole record for its own user, the professionals the patient has sessions with and the ones allowed to
+    /// read every patient. Only the contact data for the ones allowed to read every contact
+    async fn visible_to(&self, actor: &Actor, patient: Patient) -> AppResult<Patient> {
+        if self.has_full_access(actor, &patient, Permission::ReadPatients).await? {
+            return Ok(patient);
+        }
+
+        if actor.can(Permission::ReadPatientContacts) {
+            return Ok(patient.without_clinical_data());
+        }
+
+        Err(AppError::Unauthorized(String::from(
+            "You don't have permission to read this patient",
+        )))
+    }
+
+    async fn has_full_access(&self, actor: &Actor, patient: &Patient, permission: Permission) -> AppResult<bool> {
+        if actor.can(permission) || actor.is(patient.user_id.as_ref()) {
+            return Ok(true);
+        }
+
+        match patient.id {
+            Some(id) => self.persistence.is_patient_of(&id, &actor.user_id).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Every access is recorded before the data leaves the use case, a read that can't be audited fails
+    async fn audit(&self, actor: &Actor, action: AuditAction, ids: &[Uuid]) -> AppResult<()> {
+        let events: Vec<AuditEvent> = ids
+            .iter()
+            .map(|id| AuditEvent::new(actor, action, AuditResource::Patient, *id))
+            .collect();
+
+        self.audit_persistence.record(&events).await
+    }
+
+    async fn audit_read(&self, actor: &Actor, patients: &[Patient]) -> AppResult<()> {
+        let ids: Vec<Uuid> = patients.iter().filter_map(|patient| patient.id).collect();
+
+        self.audit(actor, AuditAction::Read, &ids).await
+    }
+
     #[instrument(skip(self))]
-    pub async fn delete(&self, id: &Uuid) -> AppResult<()> {
+    pub async fn delete(&self, actor: &Actor, id: &Uuid) -> AppResult<()> {
         info!("Attempting delete patient...");
 
         self.persistence.delete(id).await?;
 
+        self.audit(actor, AuditAction::Delete, &[*id]).await?;
+
         info!("Patient deleted.");
 
         Ok(())
@@ -93,28 +213,57 @@ impl PatientUseCases {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
     use async_trait::async_trait;
 
     use crate::{
         app_error::AppError,
-        entities::{gender::Gender, sexual_orientation::SexualOrientation},
+        entities::{audit_event::AuditEventFilter, gender::Gender, sexual_orientation::SexualOrientation, user::Role},
     };
 
     use super::*;
 
+    const PATIENT_USER_ID: Uuid = Uuid::from_u128(1);
+    const PROFESSIONAL_USER_ID: Uuid = Uuid::from_u128(2);
+
+    fn actor(user_id: Uuid, role: Role) -> Actor {
+        Actor::new(user_id, role)
+    }
+
+    fn admin() -> Actor {
+        actor(Uuid::new_v4(), Role::Admin)
+    }
+
+    fn professional(user_id: Uuid) -> Professional {
+        Professional {
+            id: Some(Uuid::new_v4()),
+            user_id: Some(user_id),
+            gender: Gender::Female,
+            birthdate: None,
+            license_number: None,
+            bio: None,
+            education: None,
+            experience_years: None,
+            hourly_rate: None,
+            accepts_insurance: false,
+            created_at: None,
+        }
+    }
+
     #[allow(dead_code)]
     struct MockPatientPersistence;
 
     #[async_trait]
     impl PatientPersistence for MockPatientPersistence {
-        async fn create(&self, patient: &Patient) -> AppResult<()> {
+        async fn create(&self, patient: &Patient) -> AppResult<Uuid> {
             if patient.id.is_some() {
                 return Err(AppError::Internal(
                     "patient id must be None when creating".into(),
                 ));
             }
 
-            Ok(())
+            Ok(Uuid::new_v4())
         }
 
         async fn read_all(&self) -> AppResult<Vec<Patient>> {
@@ -124,7 +273,7 @@ mod test {
         async fn read_single(&self, _id: &Uuid) -> AppResult<Patient> {
             Ok(Patient {
                 id: Some(Uuid::new_v4()),
-                user_id: None,
+                user_id: Some(PATIENT_USER_ID),
                 gender: Gender::Male,
                 sexual_orientation: SexualOrientation::Straight,
                 birthdate: None,
@@ -132,7 +281,7 @@ mod test {
                 emergency_contact_name: None,
                 emergency_contact_phone: None,
                 insurance_policy_number: None,
-                medical_history: None,
+                medical_history: Some("Anxiety".to_string()),
                 current_medications: None,
                 allergies: None,
                 created_at: Some(chrono::Utc::now().naive_utc()),
@@ -142,7 +291,7 @@ mod test {
         async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<Patient> {
             Ok(Patient {
                 id: Some(Uuid::new_v4()),
-                user_id: None,
+                user_id: Some(PATIENT_USER_ID),
                 gender: Gender::Male,
                 sexual_orientation: SexualOrientation::Straight,
                 birthdate: None,
@@ -150,7 +299,7 @@ mod test {
                 emergency_contact_name: None,
                 emergency_contact_phone: None,
                 insurance_policy_number: None,
-                medical_history: None,
+                medical_history: Some("Anxiety".to_string()),
                 current_medications: None,
                 allergies: None,
                 created_at: Some(chrono::Utc::now().naive_utc()),
@@ -158,7 +307,11 @@ mod test {
         }
 
         async fn read_by_professional(&self, _professional_id: &Uuid) -> AppResult<Vec<Patient>> {
-            Ok(vec![])
+            Ok(vec![self.read_single(&Uuid::new_v4()).await?])
+        }
+
+        async fn is_patient_of(&self, _patient_id: &Uuid, professional_user_id: &Uuid) -> AppResult<bool> {
+            Ok(*professional_user_id == PROFESSIONAL_USER_ID)
         }
 
         async fn update(&self, patient: &Patient) -> AppResult<()> {
@@ -176,12 +329,36 @@ mod test {
         }
     }
 
+    #[derive(Default)]
+    struct MockAuditPersistence {
+        events: Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait]
+    impl AuditPersistence for MockAuditPersistence {
+        async fn record(&self, events: &[AuditEvent]) -> AppResult<()> {
+            self.events.lock().unwrap().extend_from_slice(events);
+
+            Ok(())
+        }
+
+        async fn read(&self, _filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>> {
+            Ok(self.events.lock().unwrap().clone())
+        }
+    }
+
+    fn use_cases() -> (PatientUseCases, Arc<MockAuditPersistence>) {
+        let audit = Arc::new(MockAuditPersistence::default());
+
+        (PatientUseCases::new(Arc::new(MockPatientPersistence), audit.clone()), audit)
+    }
+
     #[tokio::test]
     async fn create_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
         let result = use_cases
-            .create(&Patient {
+            .create(&admin(), &Patient {
                 id: None,
                 user_id: None,
                 gender: Gender::Male,
@@ -203,10 +380,10 @@ mod test {
 
     #[tokio::test]
     async fn create_with_id_fails() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
         let result = use_cases
-            .create(&Patient {
+            .create(&admin(), &Patient {
                 id: Some(Uuid::new_v4()),
                 user_id: None,
                 gender: Gender::Male,
@@ -228,46 +405,46 @@ mod test {
 
     #[tokio::test]
     async fn read_all_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_all().await;
+        let result = use_cases.read_all(&admin()).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn read_single_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_single(&Uuid::new_v4()).await;
+        let result = use_cases.read_single(&admin(), &Uuid::new_v4()).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn read_user_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_by_user(&Uuid::new_v4()).await;
+        let result = use_cases.read_by_user(&admin(), &Uuid::new_v4()).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn read_by_professional_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_by_professional(&Uuid::new_v4()).await;
+        let result = use_cases.read_by_professional(&admin(), &professional(PROFESSIONAL_USER_ID)).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn update_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
         let result = use_cases
-            .update(&Patient {
+            .update(&admin(), &Patient {
                 id: Some(Uuid::new_v4()),
                 user_id: None,
                 gender: Gender::Male,
@@ -289,10 +466,130 @@ mod test {
 
     #[tokio::test]
     async fn delete_works() {
-        let use_cases = PatientUseCases::new(Arc::new(MockPatientPersistence));
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.delete(&Uuid::new_v4()).await;
+        let result = use_cases.delete(&admin(), &Uuid::new_v4()).await;
 
         assert!(result.is_ok());
     }
+
+    async fn read_as(actor: &Actor) -> AppResult<Patient> {
+        use_cases().0.read_single(actor, &Uuid::new_v4()).await
+    }
+
+    #[tokio::test]
+    async fn patients_read_only_their_own_record() {
+        assert!(read_as(&actor(PATIENT_USER_ID, Role::Patient)).await.is_ok());
+        assert!(matches!(
+            read_as(&actor(Uuid::new_v4(), Role::Patient)).await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn professionals_read_only_the_patients_they_have_sessions_with() {
+        let patient = read_as(&actor(PROFESSIONAL_USER_ID, Role::Professional)).await.unwrap();
+        assert!(patient.medical_history.is_some());
+
+        assert!(matches!(
+            read_as(&actor(Uuid::new_v4(), Role::Professional)).await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn receptionists_read_patients_without_clinical_data() {
+        let patient = read_as(&actor(Uuid::new_v4(), Role::Receptionist)).await.unwrap();
+
+        assert!(patient.medical_history.is_none());
+        assert_eq!(patient.phone, "123456789");
+    }
+
+    #[tokio::test]
+    async fn read_by_professional_is_scoped_to_the_professional() {
+        let (use_cases, _) = use_cases();
+        let own = professional(PROFESSIONAL_USER_ID);
+
+        let patients = use_cases
+            .read_by_professional(&actor(PROFESSIONAL_USER_ID, Role::Professional), &own)
+            .await
+            .unwrap();
+        assert!(patients[0].medical_history.is_some());
+
+        let patients = use_cases
+            .read_by_professional(&actor(Uuid::new_v4(), Role::Receptionist), &own)
+            .await
+            .unwrap();
+        assert!(patients[0].medical_history.is_none());
+
+        assert!(matches!(
+            use_cases
+                .read_by_professional(&actor(Uuid::new_v4(), Role::Professional), &own)
+                .await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn create_and_update_of_other_users_need_permission() {
+        let (use_cases, _) = use_cases();
+        let patient = |id: Option<Uuid>| Patient {
+            id,
+            user_id: Some(Uuid::new_v4()),
+            gender: Gender::Male,
+            sexual_orientation: SexualOrientation::Straight,
+            birthdate: None,
+            phone: "123456789".to_string(),
+            emergency_contact_name: None,
+            emergency_contact_phone: None,
+            insurance_policy_number: None,
+            medical_history: None,
+            current_medications: None,
+            allergies: None,
+            created_at: None,
+        };
+        let other_patient = actor(Uuid::new_v4(), Role::Patient);
+
+        assert!(matches!(
+            use_cases.create(&other_patient, &patient(None)).await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            use_cases.update(&other_patient, &patient(Some(Uuid::new_v4()))).await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            use_cases.update(&actor(Uuid::new_v4(), Role::Receptionist), &patient(Some(Uuid::new_v4()))).await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(use_cases.update(&actor(PATIENT_USER_ID, Role::Patient), &patient(Some(Uuid::new_v4()))).await.is_ok());
+        assert!(use_cases.update(&actor(PROFESSIONAL_USER_ID, Role::Professional), &patient(Some(Uuid::new_v4()))).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reads_and_changes_are_audited() {
+        let (use_cases, audit) = use_cases();
+        let professional_actor = actor(PROFESSIONAL_USER_ID, Role::Professional);
+
+        let patient = use_cases.read_single(&professional_actor, &Uuid::new_v4()).await.unwrap();
+        use_cases.update(&professional_actor, &patient).await.unwrap();
+
+        let events = audit.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].action, AuditAction::Read);
+        assert_eq!(events[1].action, AuditAction::Update);
+        assert!(events.iter().all(|event| event.actor_user_id == PROFESSIONAL_USER_ID
+            && event.resource_type == AuditResource::Patient
+            && Some(event.resource_id) == patient.id));
+    }
+
+    #[tokio::test]
+    async fn denied_reads_are_not_audited() {
+        let (use_cases, audit) = use_cases();
+
+        let result = use_cases.read_single(&actor(Uuid::new_v4(), Role::Patient), &Uuid::new_v4()).await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        assert!(audit.events.lock().unwrap().is_empty());
+    }
 }
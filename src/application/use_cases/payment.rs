@@ -1,18 +1,38 @@
 use async_trait::async_trait;
 
-use std::sync::Arc;
-use tracing::{info, instrument, error};
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+use tracing::{info, instrument, error, warn};
 
 use crate::{
-    app_error::AppResult,
-    domain::entities::transaction::Transaction,
+    app_error::{AppError, AppResult},
+    dtos::payment::booking::{BookingCheckoutDTO, BookingRequestDTO},
+    domain::entities::{
+        earning::PlatformCommission,
+        professional_availability::TimeSlot,
+        session::{Session, SessionStatus},
+        transaction::{Transaction, TransactionStatus},
+    },
+    use_cases::{
+        earning::{EarningPersistence, record_earning},
+        invoice::{InvoicePersistence, issue_invoice},
+        professional::ProfessionalPersistence,
+        professional_availability::{ProfessionalAvailabilityPersistence, read_free_slots},
+        session::{SessionPersistence, ensure_no_overlap},
+        session_type::SessionTypePersistence,
+    },
 };
 
+/// Currency used for the session bookings
+pub(crate) const BOOKING_CURRENCY: &str = "eur";
+
 #[async_trait]
 pub trait TransactionPersistence: Send + Sync {
     async fn create(&self, transaction: &Transaction) -> AppResult<Transaction>;
     async fn update(&self, transaction: &Transaction) -> AppResult<Transaction>;
     async fn get_by_session_id(&self, session_id: &str) -> AppResult<Transaction>;
+    /// Latest transaction that paid for the given booked session
+    async fn get_by_booked_session_id(&self, booked_session_id: &Uuid) -> AppResult<Transaction>;
 }
 
 #[async_trait]
@@ -23,60 +43,1388 @@ pub trait PaymentGateway: Send + Sync {
         currency: &str,
         success_url: &str,
         cancel_url: &str,
-        metadata: Option<std::collections::HashMap<String, String>>,
+        reference: CheckoutReference,
     ) -> AppResult<(String, String)>; // (client_secret, session_id)
+
+    async fn checkout_status(&self, session_id: &str) -> AppResult<CheckoutStatus>;
+
+    /// Checks the signature of a webhook call and extracts the event it carries
+    fn webhook_event(&self, payload: &str, signature: &str) -> AppResult<PaymentEvent>;
+
+    /// Checkout session that created the given payment intent, if any
+    async fn checkout_session_for_payment_intent(
+        &self,
+        payment_intent_id: &str,
+    ) -> AppResult<Option<String>>;
+
+    /// Closes an open checkout so it can't be paid anymore, fails once it was paid
+    async fn expire_checkout(&self, session_id: &str) -> AppResult<()>;
+
+    /// Gives back `amount` (cents) of the given payment intent, returns the refund id.
+    /// Retries with the same idempotency key don't refund it again
+    async fn refund(&self, payment_intent_id: &str, amount: i64, idempotency_key: &str) -> AppResult<String>;
+}
+
+/// Our records behind a checkout, lets the payment gateway link its session back to them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckoutReference {
+    pub transaction_id: Uuid,
+    pub patient_id: Uuid,
+    pub professional_id: Uuid,
+    pub session_id: Uuid,
+    pub product_name: String, // shown to the patient on the checkout page
+}
+
+impl CheckoutReference {
+    /// Key/value pairs attached to the checkout on the gateway side
+    pub fn metadata(&self) -> HashMap<String, String> {
+        HashMap::from([
+            (String::from("transaction_id"), self.transaction_id.to_string()),
+            (String::from("patient_id"), self.patient_id.to_string()),
+            (String::from("professional_id"), self.professional_id.to_string()),
+            (String::from("session_id"), self.session_id.to_string()),
+        ])
+    }
+}
+
+/// Payment gateway notifications the app reacts to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaymentEvent {
+    CheckoutCompleted {
+        checkout_session_id: String,
+        payment_intent_id: Option<String>,
+    },
+    CheckoutExpired {
+        checkout_session_id: String,
+    },
+    /// Delayed payment of a completed checkout that didn't go through, the checkout can't be paid anymore
+    CheckoutPaymentFailed {
+        checkout_session_id: String,
+    },
+    PaymentFailed {
+        payment_intent_id: String,
+    },
+    Ignored,
+}
+
+/// State of a checkout session as reported by the payment gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckoutStatus {
+    Open,
+    Paid { payment_intent_id: Option<String> },
+    Expired,
 }
 
 #[derive(Clone)]
 pub struct PaymentUseCases {
     transaction_persistence: Arc<dyn TransactionPersistence>,
     payment_gateway: Arc<dyn PaymentGateway>,
+    session_persistence: Arc<dyn SessionPersistence>,
+    availability_persistence: Arc<dyn ProfessionalAvailabilityPersistence>,
+    professional_persistence: Arc<dyn ProfessionalPersistence>,
+    session_type_persistence: Arc<dyn SessionTypePersistence>,
+    invoice_persistence: Arc<dyn InvoicePersistence>,
+    earning_persistence: Arc<dyn EarningPersistence>,
+    commission: PlatformCommission,
 }
 
 impl PaymentUseCases {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         transaction_persistence: Arc<dyn TransactionPersistence>,
         payment_gateway: Arc<dyn PaymentGateway>,
+        session_persistence: Arc<dyn SessionPersistence>,
+        availability_persistence: Arc<dyn ProfessionalAvailabilityPersistence>,
+        professional_persistence: Arc<dyn ProfessionalPersistence>,
+        session_type_persistence: Arc<dyn SessionTypePersistence>,
+        invoice_persistence: Arc<dyn InvoicePersistence>,
+        earning_persistence: Arc<dyn EarningPersistence>,
+        commission: PlatformCommission,
     ) -> Self {
         Self {
             transaction_persistence,
             payment_gateway,
+            session_persistence,
+            availability_persistence,
+            professional_persistence,
+            session_type_persistence,
+            invoice_persistence,
+            earning_persistence,
+            commission,
         }
     }
 
+    /// Reserves the requested slot for the patient and opens a checkout priced server-side, the session lasts as long
+    /// as the availability slot it is booked in. The session stays as PendingPayment until the checkout is paid
     #[instrument(skip(self))]
-    pub async fn create_checkout_session(
-        &self,
-        amount: i64, // Amount in cents
-        currency: String,
-        success_url: String,
-        cancel_url: String,
-    ) -> AppResult<String> {
-        info!("Initiating checkout session creation...");
-
-        // 1. Create Stripe Session
-        let (client_secret, session_id) = self.payment_gateway.create_checkout_session(
-            amount,
-            &currency,
-            &success_url,
-            &cancel_url,
-            None, // Metadata
-        ).await.map_err(|e| {
-            error!("Failed to create stripe session: {:?}", e);
-            e
+    pub async fn book_session(&self, request: BookingRequestDTO) -> AppResult<BookingCheckoutDTO> {
+        info!("Attempting book session...");
+
+        let professional = self
+            .professional_persistence
+            .read_single(&request.professional_id)
+            .await?;
+
+        let hourly_rate = professional.hourly_rate.ok_or_else(|| {
+            AppError::Unavailable("The professional has no hourly rate set".into())
         })?;
 
-        // 2. Create Transaction
-        let transaction = Transaction::new(session_id.clone(), Some(amount), Some(currency.clone()));
+        // Make sure the session type exists before reserving anything
+        let session_type = self
+            .session_type_persistence
+            .read_single(request.session_type_id)
+            .await?;
+
+        let slot = open_slot_at(
+            self.availability_persistence.as_ref(),
+            &request.professional_id,
+            request.session_date,
+        )
+        .await?;
+        let session_duration = (slot.end - slot.start).num_minutes() as i32;
+
+        let amount = session_price_cents(hourly_rate, session_duration);
+        if amount <= 0 {
+            return Err(AppError::Unavailable(
+                "The professional has no hourly rate set".into(),
+            ));
+        }
+
+        let mut session = Session {
+            id: None,
+            patient_id: request.patient_id,
+            professional_id: request.professional_id,
+            session_type_id: Some(request.session_type_id),
+            session_status: SessionStatus::PendingPayment,
+            session_date: Some(request.session_date),
+            videocall_url: None,
+            videocall_provider: None,
+            videocall_host_url: None,
+            videocall_meeting_id: None,
+            completed: false,
+            session_duration: Some(session_duration),
+            created_at: None,
+        };
+
+        ensure_no_overlap(self.session_persistence.as_ref(), &session).await?;
+
+        let session_id = self.session_persistence.create(&session).await?;
+        session.id = Some(session_id);
+
+        // The transaction id is sent to the gateway, so it has to be known before the checkout exists
+        let transaction_id = Uuid::new_v4();
+
+        let reference = CheckoutReference {
+            transaction_id,
+            patient_id: request.patient_id,
+            professional_id: request.professional_id,
+            session_id,
+            product_name: booking_product_name(
+                &session_type.name,
+                session_duration,
+                request.session_date,
+            ),
+        };
+
+        let checkout = self
+            .payment_gateway
+            .create_checkout_session(
+                amount,
+                BOOKING_CURRENCY,
+                &request.success_url,
+                &request.cancel_url,
+                reference,
+            )
+            .await;
+
+        let (client_secret, checkout_session_id) = match checkout {
+            Ok(checkout) => checkout,
+            Err(e) => {
+                error!("Failed to create stripe session: {:?}", e);
+                // Release the slot, the patient will have to try again
+                self.session_persistence
+                    .transition(&session_id, &[SessionStatus::PendingPayment], &SessionStatus::Cancelled)
+                    .await?;
+                return Err(e);
+            }
+        };
+
+        let mut transaction = Transaction::new(
+            checkout_session_id,
+            Some(amount),
+            Some(BOOKING_CURRENCY.to_string()),
+        );
+        transaction.id = transaction_id;
+        transaction.booked_session_id = Some(session_id);
+
+        self.transaction_persistence.create(&transaction).await?;
+
+        info!("Session booked. Transaction ID: {}", transaction.id);
+
+        Ok(BookingCheckoutDTO {
+            session_id,
+            client_secret,
+            amount,
+            currency: BOOKING_CURRENCY.to_string(),
+        })
+    }
+
+    /// Asks the payment gateway for the state of the checkout and applies it to the booking
+    #[instrument(skip(self))]
+    pub async fn sync_booking(&self, checkout_session_id: &str) -> AppResult<TransactionStatus> {
+        info!("Attempting sync booking...");
+
+        match self.payment_gateway.checkout_status(checkout_session_id).await? {
+            CheckoutStatus::Paid { payment_intent_id } => {
+                self.confirm_booking(checkout_session_id, payment_intent_id).await
+            }
+            CheckoutStatus::Expired => self.expire_booking(checkout_session_id).await,
+            CheckoutStatus::Open => Ok(self
+                .transaction_persistence
+                .get_by_session_id(checkout_session_id)
+                .await?
+                .status),
+        }
+    }
+
+    /// Verifies a webhook call from the payment gateway and applies its event
+    #[instrument(skip(self, payload, signature))]
+    pub async fn handle_webhook(&self, payload: &str, signature: &str) -> AppResult<()> {
+        let event = self.payment_gateway.webhook_event(payload, signature)?;
+
+        self.handle_payment_event(event).await
+    }
 
-        // 3. Persist Transaction
-        self.transaction_persistence.create(&transaction).await.map_err(|e| {
-             error!("Failed to persist transaction: {:?}", e);
-             e
+    /// Applies a verified webhook event to the matching transaction, events can be delivered more than once
+    #[instrument(skip(self))]
+    pub async fn handle_payment_event(&self, event: PaymentEvent) -> AppResult<()> {
+        info!("Attempting handle payment event...");
+
+        let result = match event {
+            PaymentEvent::CheckoutCompleted { checkout_session_id, payment_intent_id } => {
+                self.confirm_booking(&checkout_session_id, payment_intent_id).await
+            }
+            PaymentEvent::CheckoutExpired { checkout_session_id } => {
+                self.expire_booking(&checkout_session_id).await
+            }
+            PaymentEvent::CheckoutPaymentFailed { checkout_session_id } => {
+                self.release_unpaid_booking(&checkout_session_id).await
+            }
+            PaymentEvent::PaymentFailed { payment_intent_id } => {
+                let checkout_session_id = self
+                    .payment_gateway
+                    .checkout_session_for_payment_intent(&payment_intent_id)
+                    .await?;
+
+                match checkout_session_id {
+                    Some(checkout_session_id) => self.fail_payment(&checkout_session_id).await,
+                    None => {
+                        warn!("No checkout session for payment intent {}", payment_intent_id);
+                        return Ok(());
+                    }
+                }
+            }
+            PaymentEvent::Ignored => return Ok(()),
+        };
+
+        match result {
+            Ok(status) => {
+                info!("Payment event handled. Transaction status: {}", status.to_string());
+                Ok(())
+            }
+            // Checkouts not created by this app can't be reconciled, acknowledge them so they are not retried
+            Err(AppError::NotFound(msg)) => {
+                warn!("Payment event for unknown transaction: {}", msg);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Marks the transaction as failed, the booked session keeps its slot as the patient can still retry until the checkout expires
+    #[instrument(skip(self))]
+    pub async fn fail_payment(&self, checkout_session_id: &str) -> AppResult<TransactionStatus> {
+        let mut transaction = self
+            .transaction_persistence
+            .get_by_session_id(checkout_session_id)
+            .await?;
+
+        if transaction.status != TransactionStatus::Pending {
+            return Ok(transaction.status);
+        }
+
+        transaction.fail();
+        self.transaction_persistence.update(&transaction).await?;
+
+        info!("Payment failed. Transaction ID: {}", transaction.id);
+
+        Ok(transaction.status)
+    }
+
+    /// Marks the transaction as completed and confirms the booked session, safe to call more than once.
+    /// A booking cancelled or expired before the payment went through is refunded instead
+    #[instrument(skip(self))]
+    pub async fn confirm_booking(
+        &self,
+        checkout_session_id: &str,
+        payment_intent_id: Option<String>,
+    ) -> AppResult<TransactionStatus> {
+        let mut transaction = self
+            .transaction_persistence
+            .get_by_session_id(checkout_session_id)
+            .await?;
+
+        // Already paid, it may have been refunded since then
+        let already_paid = matches!(
+            transaction.status,
+            TransactionStatus::Completed | TransactionStatus::Refunded | TransactionStatus::PartiallyRefunded
+        );
+
+        if !already_paid {
+            // The session is confirmed first, a redelivery after a failed transaction update finds it Scheduled
+            if let Some(session_id) = transaction.booked_session_id {
+                let confirmed = self
+                    .session_persistence
+                    .transition(
+                        &session_id,
+                        &[SessionStatus::PendingPayment, SessionStatus::Scheduled],
+                        &SessionStatus::Scheduled,
+                    )
+                    .await?;
+
+                if confirmed.is_none() {
+                    return self
+                        .refund_unbooked(transaction, payment_intent_id, &session_id)
+                        .await;
+                }
+            }
+
+            transaction.complete(payment_intent_id.unwrap_or_default());
+            self.transaction_persistence.update(&transaction).await?;
+
+            info!("Booking confirmed. Transaction ID: {}", transaction.id);
+        }
+
+        // Also done on redeliveries, so a failure gets another chance. It doesn't undo the payment though
+        if let Some(session_id) = transaction.booked_session_id
+            && let Err(e) = self.bill(&transaction, &session_id).await
+        {
+            error!("Failed to bill transaction {}: {:?}", transaction.id, e);
+        }
+
+        Ok(transaction.status)
+    }
+
+    /// Gives the whole payment back, the session it booked was cancelled or expired before the checkout was paid
+    async fn refund_unbooked(
+        &self,
+        mut transaction: Transaction,
+        payment_intent_id: Option<String>,
+        session_id: &Uuid,
+    ) -> AppResult<TransactionStatus> {
+        warn!("Session {} was paid after being released, refunding transaction {}", session_id, transaction.id);
+
+        let payment_intent_id = payment_intent_id.ok_or_else(|| {
+            AppError::Internal(format!("Paid transaction {} has no payment intent", transaction.id))
         })?;
+        let amount = transaction.amount.unwrap_or_default();
+
+        self.payment_gateway
+            .refund(&payment_intent_id, amount, &refund_idempotency_key(session_id))
+            .await
+            .map_err(|e| {
+                error!("Failed to refund transaction {}: {:?}", transaction.id, e);
+                e
+            })?;
+
+        transaction.complete(payment_intent_id);
+        transaction.refund(amount);
+        self.transaction_persistence.update(&transaction).await?;
+
+        info!("Released booking refunded. Transaction ID: {}", transaction.id);
+
+        Ok(transaction.status)
+    }
+
+    /// Invoices the payment and records it in the earnings of the professional of the session,
+    /// bookings refunded for being released before the payment went through get neither
+    async fn bill(&self, transaction: &Transaction, session_id: &Uuid) -> AppResult<()> {
+        let session = self.session_persistence.read_single(session_id).await?;
+
+        if matches!(session.session_status, SessionStatus::Cancelled)
+            && transaction.status == TransactionStatus::Refunded
+        {
+            return Ok(());
+        }
+
+        if let Err(e) = issue_invoice(self.invoice_persistence.as_ref(), transaction).await {
+            error!("Failed to issue the invoice of transaction {}: {:?}", transaction.id, e);
+        }
+
+        if let Err(e) = record_earning(
+            self.earning_persistence.as_ref(),
+            transaction,
+            session.professional_id,
+            &self.commission,
+        )
+        .await
+        {
+            error!("Failed to record the earning of transaction {}: {:?}", transaction.id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Marks a pending or failed transaction as expired and releases the slot it was holding
+    #[instrument(skip(self))]
+    pub async fn expire_booking(&self, checkout_session_id: &str) -> AppResult<TransactionStatus> {
+        let mut transaction = self
+            .transaction_persistence
+            .get_by_session_id(checkout_session_id)
+            .await?;
+
+        if !matches!(transaction.status, TransactionStatus::Pending | TransactionStatus::Failed) {
+            return Ok(transaction.status);
+        }
+
+        transaction.expire();
+        self.transaction_persistence.update(&transaction).await?;
+
+        if let Some(session_id) = transaction.booked_session_id {
+            self.session_persistence
+                .transition(&session_id, &[SessionStatus::PendingPayment], &SessionStatus::Cancelled)
+                .await?;
+        }
+
+        info!("Booking expired. Transaction ID: {}", transaction.id);
+
+        Ok(transaction.status)
+    }
+
+    /// Marks the transaction as failed and releases the slot, the delayed payment of its checkout didn't go through
+    #[instrument(skip(self))]
+    pub async fn release_unpaid_booking(&self, checkout_session_id: &str) -> AppResult<TransactionStatus> {
+        let mut transaction = self
+            .transaction_persistence
+            .get_by_session_id(checkout_session_id)
+            .await?;
+
+        if !matches!(transaction.status, TransactionStatus::Pending | TransactionStatus::Failed) {
+            return Ok(transaction.status);
+        }
+
+        transaction.fail();
+        self.transaction_persistence.update(&transaction).await?;
+
+        if let Some(session_id) = transaction.booked_session_id {
+            self.session_persistence
+                .transition(&session_id, &[SessionStatus::PendingPayment], &SessionStatus::Cancelled)
+                .await?;
+        }
+
+        info!("Unpaid booking released. Transaction ID: {}", transaction.id);
+
+        Ok(transaction.status)
+    }
+}
+
+/// Free slot of the professional availability starting at the given date
+async fn open_slot_at(
+    availability_persistence: &dyn ProfessionalAvailabilityPersistence,
+    professional_id: &Uuid,
+    session_date: chrono::NaiveDateTime,
+) -> AppResult<TimeSlot> {
+    read_free_slots(availability_persistence, professional_id, session_date.date(), session_date.date(), None)
+        .await?
+        .into_iter()
+        .find(|slot| slot.start == session_date)
+        .ok_or_else(|| AppError::Unavailable("The professional is not available at the requested time".into()))
+}
+
+/// Idempotency key of the refund of a booked session, a session is refunded at most once
+pub(crate) fn refund_idempotency_key(session_id: &Uuid) -> String {
+    format!("refund-session-{}", session_id)
+}
+
+/// Price of a session in cents, from the hourly rate of the professional
+pub(crate) fn session_price_cents(hourly_rate: f32, duration_minutes: i32) -> i64 {
+    (f64::from(hourly_rate) * 100.0 * f64::from(duration_minutes) / 60.0).round() as i64
+}
+
+/// Line item name of a booking, e.g. "Individual therapy session (45 min) - 2026-01-01 10:00 UTC"
+pub(crate) fn booking_product_name(
+    session_type_name: &str,
+    duration_minutes: i32,
+    session_date: chrono::NaiveDateTime,
+) -> String {
+    format!(
+        "{} session ({} min) - {} UTC",
+        session_type_name,
+        duration_minutes,
+        session_date.format("%Y-%m-%d %H:%M")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+    use crate::{
+        dtos::{earning::payout::PayoutDTO, invoice::source::InvoiceSourceDTO},
+        entities::{
+            earning::Earning,
+            invoice::{BillingDetails, BillingParty, Invoice},
+        },
+    };
+    use crate::entities::{
+        gender::Gender,
+        professional::Professional,
+        professional_availability::{AvailabilityException, ProfessionalAvailability, TimeSlot},
+        session_type::SessionType,
+    };
+
+    use super::*;
+
+    /// Professional without an hourly rate
+    const UNPRICED_PROFESSIONAL_ID: Uuid = Uuid::from_u128(1);
+    const BOOKED_SESSION_ID: Uuid = Uuid::from_u128(2);
+    const TRANSACTION_ID: Uuid = Uuid::from_u128(3);
+
+    fn tomorrow_at(hour: u32, minute: u32) -> NaiveDateTime {
+        (chrono::Utc::now().date_naive() + chrono::Duration::days(1))
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[derive(Default)]
+    struct MockTransactionPersistence {
+        status: Mutex<Option<TransactionStatus>>,
+        created_id: Mutex<Option<Uuid>>,
+        updates: Mutex<Vec<TransactionStatus>>,
+    }
+
+    #[async_trait]
+    impl TransactionPersistence for MockTransactionPersistence {
+        async fn create(&self, transaction: &Transaction) -> AppResult<Transaction> {
+            assert_eq!(transaction.booked_session_id, Some(BOOKED_SESSION_ID));
+            *self.status.lock().unwrap() = Some(transaction.status.clone());
+            *self.created_id.lock().unwrap() = Some(transaction.id);
+
+            Ok(transaction.clone())
+        }
+
+        async fn update(&self, transaction: &Transaction) -> AppResult<Transaction> {
+            self.updates.lock().unwrap().push(transaction.status.clone());
+
+            Ok(transaction.clone())
+        }
+
+        async fn get_by_session_id(&self, session_id: &str) -> AppResult<Transaction> {
+            let mut transaction = Transaction::new(session_id.to_string(), Some(6000), Some("eur".into()));
+            transaction.id = TRANSACTION_ID;
+            transaction.booked_session_id = Some(BOOKED_SESSION_ID);
+            transaction.status = self.status.lock().unwrap().clone().unwrap_or(TransactionStatus::Pending);
+
+            Ok(transaction)
+        }
+
+        async fn get_by_booked_session_id(&self, _booked_session_id: &Uuid) -> AppResult<Transaction> {
+            Err(AppError::NotFound("Transaction not found".into()))
+        }
+    }
+
+    struct MockPaymentGateway {
+        amount: Mutex<Option<i64>>,
+        reference: Mutex<Option<CheckoutReference>>,
+        refunds: Mutex<Vec<(i64, String)>>, // amount and idempotency key
+        status: CheckoutStatus,
+    }
+
+    impl MockPaymentGateway {
+        fn new(status: CheckoutStatus) -> Self {
+            Self { amount: Mutex::new(None), reference: Mutex::new(None), refunds: Mutex::new(vec![]), status }
+        }
+    }
+
+    /// Payment intent that wasn't created by a checkout
+    const ORPHAN_PAYMENT_INTENT_ID: &str = "pi_orphan";
+
+    #[async_trait]
+    impl PaymentGateway for MockPaymentGateway {
+        async fn create_checkout_session(
+            &self,
+            amount: i64,
+            _currency: &str,
+            _success_url: &str,
+            _cancel_url: &str,
+            reference: CheckoutReference,
+        ) -> AppResult<(String, String)> {
+            *self.amount.lock().unwrap() = Some(amount);
+            *self.reference.lock().unwrap() = Some(reference);
+
+            Ok((String::from("secret"), String::from("cs_test")))
+        }
+
+        async fn checkout_status(&self, _session_id: &str) -> AppResult<CheckoutStatus> {
+            Ok(self.status.clone())
+        }
+
+        fn webhook_event(&self, _payload: &str, _signature: &str) -> AppResult<PaymentEvent> {
+            Ok(PaymentEvent::Ignored)
+        }
+
+        async fn checkout_session_for_payment_intent(
+            &self,
+            payment_intent_id: &str,
+        ) -> AppResult<Option<String>> {
+            if payment_intent_id == ORPHAN_PAYMENT_INTENT_ID {
+                return Ok(None);
+            }
+
+            Ok(Some(String::from("cs_test")))
+        }
+
+        async fn expire_checkout(&self, _session_id: &str) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn refund(&self, _payment_intent_id: &str, amount: i64, idempotency_key: &str) -> AppResult<String> {
+            self.refunds.lock().unwrap().push((amount, idempotency_key.to_string()));
+
+            Ok(String::from("re_test"))
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSessionPersistence {
+        statuses: Mutex<Vec<i32>>,
+        released: bool, // cancelled before its checkout was paid
+    }
+
+    impl MockSessionPersistence {
+        fn status(&self) -> SessionStatus {
+            if self.released { SessionStatus::Cancelled } else { SessionStatus::PendingPayment }
+        }
+    }
+
+    #[async_trait]
+    impl SessionPersistence for MockSessionPersistence {
+        async fn create(&self, session: &Session) -> AppResult<Uuid> {
+            self.statuses.lock().unwrap().push(session.session_status.to_id());
+
+            Ok(BOOKED_SESSION_ID)
+        }
+
+        async fn read_all(&self) -> AppResult<Vec<Session>> {
+            Ok(vec![])
+        }
+
+        async fn read_patient(&self, _patient_id: &Uuid) -> AppResult<Vec<Session>> {
+            Ok(vec![])
+        }
+
+        async fn read_professional(&self, _professional_id: &Uuid) -> AppResult<Vec<Session>> {
+            Ok(vec![])
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<Session> {
+            Ok(Session {
+                id: Some(*id),
+                patient_id: Uuid::new_v4(),
+                professional_id: Uuid::new_v4(),
+                session_type_id: Some(Uuid::new_v4()),
+                session_status: self.status(),
+                session_date: Some(tomorrow_at(10, 0)),
+                videocall_url: None,
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
+                session_duration: Some(60),
+                completed: false,
+                created_at: None,
+            })
+        }
+
+        async fn is_professional_user(&self, _professional_id: &Uuid, _user_id: &Uuid) -> AppResult<bool> {
+            Ok(false)
+        }
+
+        async fn update(&self, session: &Session) -> AppResult<()> {
+            self.statuses.lock().unwrap().push(session.session_status.to_id());
+
+            Ok(())
+        }
+
+        async fn transition(
+            &self,
+            _id: &Uuid,
+            from: &[SessionStatus],
+            to: &SessionStatus,
+        ) -> AppResult<Option<SessionStatus>> {
+            let current = self.status();
+            if !from.iter().any(|status| status.to_id() == current.to_id()) {
+                return Ok(None);
+            }
+
+            self.statuses.lock().unwrap().push(to.to_id());
+
+            Ok(Some(current))
+        }
+
+        async fn has_overlapping(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+            _exclude_id: Option<&Uuid>,
+        ) -> AppResult<bool> {
+            Ok(false)
+        }
+
+        async fn delete(&self, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Professional available from 9:00 to 13:00 every day of the week
+    struct MockAvailabilityPersistence;
+
+    #[async_trait]
+    impl ProfessionalAvailabilityPersistence for MockAvailabilityPersistence {
+        async fn create_rule(&self, _rule: &ProfessionalAvailability) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_rules(&self, professional_id: &Uuid) -> AppResult<Vec<ProfessionalAvailability>> {
+            let tomorrow = chrono::Utc::now().date_naive() + chrono::Duration::days(1);
+
+            Ok(vec![ProfessionalAvailability {
+                id: Some(Uuid::new_v4()),
+                professional_id: *professional_id,
+                weekday: tomorrow.weekday(),
+                start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end_time: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+                slot_duration: 60,
+                created_at: None,
+            }])
+        }
+
+        async fn delete_rule(&self, _professional_id: &Uuid, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn create_exception(&self, _exception: &AvailabilityException) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_exceptions(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDate,
+            _to: NaiveDate,
+        ) -> AppResult<Vec<AvailabilityException>> {
+            Ok(vec![])
+        }
+
+        async fn delete_exception(&self, _professional_id: &Uuid, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_booked(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> AppResult<Vec<TimeSlot>> {
+            Ok(vec![])
+        }
+    }
+
+    struct MockProfessionalPersistence;
+
+    #[async_trait]
+    impl ProfessionalPersistence for MockProfessionalPersistence {
+        async fn create(&self, _professional: &Professional) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_all(&self) -> AppResult<Vec<Professional>> {
+            Ok(vec![])
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<Professional> {
+            Ok(Professional {
+                id: Some(*id),
+                user_id: Some(Uuid::new_v4()),
+                gender: Gender::default(),
+                birthdate: None,
+                license_number: None,
+                bio: None,
+                education: None,
+                experience_years: None,
+                hourly_rate: if *id == UNPRICED_PROFESSIONAL_ID { None } else { Some(60.0) },
+                accepts_insurance: false,
+                created_at: None,
+            })
+        }
+
+        async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<Professional> {
+            self.read_single(&Uuid::new_v4()).await
+        }
+
+        async fn update(&self, _professional: &Professional) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn selector(&self) -> AppResult<Vec<crate::dtos::professional::selector::ProfessionalSelectorDTO>> {
+            Ok(vec![])
+        }
+    }
+
+    struct MockSessionTypePersistence;
+
+    #[async_trait]
+    impl SessionTypePersistence for MockSessionTypePersistence {
+        async fn create(&self, _name: &str) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_all(&self) -> AppResult<Vec<SessionType>> {
+            Ok(vec![])
+        }
+
+        async fn read_single(&self, id: Uuid) -> AppResult<SessionType> {
+            Ok(SessionType { id, name: String::from("Individual therapy"), created_at: None })
+        }
+
+        async fn update(&self, _id: Uuid, _name: &str) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Records the transactions invoiced
+    #[derive(Default)]
+    struct MockInvoicePersistence {
+        invoiced: Mutex<Vec<Uuid>>,
+    }
+
+    #[async_trait]
+    impl InvoicePersistence for MockInvoicePersistence {
+        async fn create(&self, invoice: &Invoice) -> AppResult<Invoice> {
+            let mut invoiced = self.invoiced.lock().unwrap();
+            invoiced.push(invoice.transaction_id);
+
+            let mut invoice = invoice.clone();
+            invoice.number = invoiced.len() as i32;
+
+            Ok(invoice)
+        }
+
+        async fn read_single(&self, id: &Uuid) -> AppResult<Invoice> {
+            Err(AppError::NotFound(format!("Invoice {} not found", id)))
+        }
+
+        async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Option<Invoice>> {
+            if !self.invoiced.lock().unwrap().contains(transaction_id) {
+                return Ok(None);
+            }
+
+            Ok(Some(Invoice {
+                id: Uuid::new_v4(),
+                transaction_id: *transaction_id,
+                session_id: Some(BOOKED_SESSION_ID),
+                patient_id: Uuid::new_v4(),
+                professional_id: Uuid::new_v4(),
+                fiscal_year: 2026,
+                number: 1,
+                issued_at: tomorrow_at(10, 0),
+                issuer: BillingParty::default(),
+                recipient: BillingParty::default(),
+                currency: String::from("eur"),
+                lines: vec![],
+            }))
+        }
+
+        async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<Vec<Invoice>> {
+            Ok(vec![])
+        }
+
+        async fn is_party(&self, _invoice_id: &Uuid, _user_id: &Uuid) -> AppResult<bool> {
+            Ok(false)
+        }
+
+        async fn read_invoice_source(&self, _session_id: &Uuid) -> AppResult<InvoiceSourceDTO> {
+            Ok(InvoiceSourceDTO {
+                patient_id: Uuid::new_v4(),
+                professional_id: Uuid::new_v4(),
+                issuer: BillingParty::default(),
+                recipient: BillingParty::default(),
+                session_type_name: None,
+                session_date: Some(tomorrow_at(10, 0)),
+                session_duration: Some(60),
+            })
+        }
+
+        async fn read_billing_details(&self, _user_id: &Uuid) -> AppResult<Option<BillingDetails>> {
+            Ok(None)
+        }
+
+        async fn upsert_billing_details(&self, _details: &BillingDetails) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Records the earnings created
+    #[derive(Default)]
+    struct MockEarningPersistence {
+        earnings: Mutex<Vec<Earning>>,
+    }
+
+    #[async_trait]
+    impl EarningPersistence for MockEarningPersistence {
+        async fn create(&self, earning: &Earning) -> AppResult<()> {
+            self.earnings.lock().unwrap().push(earning.clone());
+            Ok(())
+        }
+
+        async fn read_by_transaction(&self, transaction_id: &Uuid) -> AppResult<Vec<Earning>> {
+            Ok(self
+                .earnings
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|earning| earning.transaction_id == *transaction_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn read_by_professional(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> AppResult<Vec<Earning>> {
+            Ok(vec![])
+        }
+
+        async fn read_pending_payouts(&self, _until: NaiveDateTime) -> AppResult<Vec<PayoutDTO>> {
+            Ok(vec![])
+        }
+
+        async fn settle_payouts(
+            &self,
+            _until: NaiveDateTime,
+            _paid_out_at: NaiveDateTime,
+        ) -> AppResult<Vec<PayoutDTO>> {
+            Ok(vec![])
+        }
+    }
+
+    fn use_cases(
+        transactions: Arc<MockTransactionPersistence>,
+        gateway: Arc<MockPaymentGateway>,
+        sessions: Arc<MockSessionPersistence>,
+    ) -> PaymentUseCases {
+        use_cases_with_ledgers(
+            transactions,
+            gateway,
+            sessions,
+            Arc::new(MockInvoicePersistence::default()),
+            Arc::new(MockEarningPersistence::default()),
+        )
+    }
+
+    fn use_cases_with_ledgers(
+        transactions: Arc<MockTransactionPersistence>,
+        gateway: Arc<MockPaymentGateway>,
+        sessions: Arc<MockSessionPersistence>,
+        invoices: Arc<MockInvoicePersistence>,
+        earnings: Arc<MockEarningPersistence>,
+    ) -> PaymentUseCases {
+        PaymentUseCases::new(
+            transactions,
+            gateway,
+            sessions,
+            Arc::new(MockAvailabilityPersistence),
+            Arc::new(MockProfessionalPersistence),
+            Arc::new(MockSessionTypePersistence),
+            invoices,
+            earnings,
+            PlatformCommission { percent: 15 },
+        )
+    }
+
+    fn booking_request(professional_id: Uuid, hour: u32, minute: u32) -> BookingRequestDTO {
+        BookingRequestDTO {
+            patient_id: Uuid::new_v4(),
+            professional_id,
+            session_type_id: Uuid::new_v4(),
+            session_date: tomorrow_at(hour, minute),
+            success_url: String::from("https://example.com/success"),
+            cancel_url: String::from("https://example.com/cancel"),
+        }
+    }
+
+    #[test]
+    fn session_price_cents_works() {
+        assert_eq!(session_price_cents(60.0, 60), 6000);
+        assert_eq!(session_price_cents(60.0, 45), 4500);
+        assert_eq!(session_price_cents(55.5, 50), 4625);
+    }
+
+    #[tokio::test]
+    async fn book_session_works() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let gateway = Arc::new(MockPaymentGateway::new(CheckoutStatus::Open));
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(transactions.clone(), gateway.clone(), sessions.clone());
+
+        let result = use_cases
+            .book_session(booking_request(Uuid::new_v4(), 10, 0))
+            .await
+            .unwrap();
+
+        assert_eq!(result.session_id, BOOKED_SESSION_ID);
+        assert_eq!(result.amount, 6000);
+        assert_eq!(*gateway.amount.lock().unwrap(), Some(6000));
+        assert_eq!(*sessions.statuses.lock().unwrap(), vec![SessionStatus::PendingPayment.to_id()]);
+        assert_eq!(*transactions.status.lock().unwrap(), Some(TransactionStatus::Pending));
+    }
+
+    #[tokio::test]
+    async fn book_session_references_our_records() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let gateway = Arc::new(MockPaymentGateway::new(CheckoutStatus::Open));
+        let use_cases = use_cases(
+            transactions.clone(),
+            gateway.clone(),
+            Arc::new(MockSessionPersistence::default()),
+        );
+        let request = booking_request(Uuid::new_v4(), 10, 0);
+
+        use_cases.book_session(request.clone()).await.unwrap();
+
+        let reference = gateway.reference.lock().unwrap().clone().unwrap();
+        assert_eq!(Some(reference.transaction_id), *transactions.created_id.lock().unwrap());
+        assert_eq!(reference.patient_id, request.patient_id);
+        assert_eq!(reference.professional_id, request.professional_id);
+        assert_eq!(reference.session_id, BOOKED_SESSION_ID);
+        assert_eq!(
+            reference.product_name,
+            booking_product_name("Individual therapy", 60, request.session_date)
+        );
+        assert_eq!(
+            reference.metadata().get("transaction_id"),
+            Some(&reference.transaction_id.to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn book_session_outside_availability_fails() {
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            Arc::new(MockTransactionPersistence::default()),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let result = use_cases
+            .book_session(booking_request(Uuid::new_v4(), 15, 0))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unavailable(_))));
+        assert!(sessions.statuses.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn book_session_off_the_slot_start_fails() {
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            Arc::new(MockTransactionPersistence::default()),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let result = use_cases
+            .book_session(booking_request(Uuid::new_v4(), 10, 30))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unavailable(_))));
+        assert!(sessions.statuses.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn book_session_without_hourly_rate_fails() {
+        let use_cases = use_cases(
+            Arc::new(MockTransactionPersistence::default()),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            Arc::new(MockSessionPersistence::default()),
+        );
+
+        let result = use_cases
+            .book_session(booking_request(UNPRICED_PROFESSIONAL_ID, 10, 0))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn sync_paid_booking_confirms_session() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Paid { payment_intent_id: Some("pi_test".into()) })),
+            sessions.clone(),
+        );
+
+        let result = use_cases.sync_booking("cs_test").await.unwrap();
+
+        assert_eq!(result, TransactionStatus::Completed);
+        assert_eq!(*transactions.updates.lock().unwrap(), vec![TransactionStatus::Completed]);
+        assert_eq!(*sessions.statuses.lock().unwrap(), vec![SessionStatus::Scheduled.to_id()]);
+    }
+
+    #[tokio::test]
+    async fn sync_expired_booking_cancels_session() {
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            Arc::new(MockTransactionPersistence::default()),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Expired)),
+            sessions.clone(),
+        );
+
+        let result = use_cases.sync_booking("cs_test").await.unwrap();
+
+        assert_eq!(result, TransactionStatus::Expired);
+        assert_eq!(*sessions.statuses.lock().unwrap(), vec![SessionStatus::Cancelled.to_id()]);
+    }
+
+    #[tokio::test]
+    async fn confirm_booking_issues_invoice_once() {
+        let invoices = Arc::new(MockInvoicePersistence::default());
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let use_cases = use_cases_with_ledgers(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            Arc::new(MockSessionPersistence::default()),
+            invoices.clone(),
+            Arc::new(MockEarningPersistence::default()),
+        );
+
+        use_cases.confirm_booking("cs_test", Some("pi_test".into())).await.unwrap();
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Completed);
+        use_cases.confirm_booking("cs_test", Some("pi_test".into())).await.unwrap();
+
+        assert_eq!(invoices.invoiced.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn confirm_booking_records_earning_once() {
+        let earnings = Arc::new(MockEarningPersistence::default());
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let use_cases = use_cases_with_ledgers(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            Arc::new(MockSessionPersistence::default()),
+            Arc::new(MockInvoicePersistence::default()),
+            earnings.clone(),
+        );
+
+        use_cases.confirm_booking("cs_test", Some("pi_test".into())).await.unwrap();
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Completed);
+        use_cases.confirm_booking("cs_test", Some("pi_test".into())).await.unwrap();
+
+        let earnings = earnings.earnings.lock().unwrap();
+        assert_eq!(earnings.len(), 1);
+        assert_eq!(earnings[0].transaction_id, TRANSACTION_ID);
+        assert_eq!(earnings[0].session_id, Some(BOOKED_SESSION_ID));
+        assert_eq!(earnings[0].commission_amount, 900);
+        assert_eq!(earnings[0].net_amount, 5100);
+    }
+
+    #[tokio::test]
+    async fn confirm_released_booking_refunds_it() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let gateway = Arc::new(MockPaymentGateway::new(CheckoutStatus::Open));
+        let sessions = Arc::new(MockSessionPersistence { released: true, ..Default::default() });
+        let invoices = Arc::new(MockInvoicePersistence::default());
+        let earnings = Arc::new(MockEarningPersistence::default());
+        let use_cases = use_cases_with_ledgers(
+            transactions.clone(),
+            gateway.clone(),
+            sessions.clone(),
+            invoices.clone(),
+            earnings.clone(),
+        );
+
+        let result = use_cases.confirm_booking("cs_test", Some("pi_test".into())).await.unwrap();
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Refunded);
+        use_cases.confirm_booking("cs_test", Some("pi_test".into())).await.unwrap();
+
+        assert_eq!(result, TransactionStatus::Refunded);
+        assert_eq!(
+            *gateway.refunds.lock().unwrap(),
+            vec![(6000, refund_idempotency_key(&BOOKED_SESSION_ID))]
+        );
+        assert_eq!(*transactions.updates.lock().unwrap(), vec![TransactionStatus::Refunded]);
+        assert!(sessions.statuses.lock().unwrap().is_empty());
+        assert!(invoices.invoiced.lock().unwrap().is_empty());
+        assert!(earnings.earnings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn confirm_refunded_booking_does_nothing() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Refunded);
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            Arc::new(MockSessionPersistence::default()),
+        );
+
+        let result = use_cases.confirm_booking("cs_test", None).await.unwrap();
+
+        assert_eq!(result, TransactionStatus::Refunded);
+        assert!(transactions.updates.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn confirm_completed_booking_does_nothing() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Completed);
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let result = use_cases.confirm_booking("cs_test", None).await.unwrap();
+
+        assert_eq!(result, TransactionStatus::Completed);
+        assert!(transactions.updates.lock().unwrap().is_empty());
+        assert!(sessions.statuses.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn checkout_completed_event_confirms_session() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let event = PaymentEvent::CheckoutCompleted {
+            checkout_session_id: String::from("cs_test"),
+            payment_intent_id: Some(String::from("pi_test")),
+        };
+        let result = use_cases.handle_payment_event(event).await;
+
+        assert!(result.is_ok());
+        assert_eq!(*transactions.updates.lock().unwrap(), vec![TransactionStatus::Completed]);
+        assert_eq!(*sessions.statuses.lock().unwrap(), vec![SessionStatus::Scheduled.to_id()]);
+    }
+
+    #[tokio::test]
+    async fn checkout_expired_event_releases_failed_booking() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Failed);
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let event = PaymentEvent::CheckoutExpired { checkout_session_id: String::from("cs_test") };
+        let result = use_cases.handle_payment_event(event).await;
+
+        assert!(result.is_ok());
+        assert_eq!(*transactions.updates.lock().unwrap(), vec![TransactionStatus::Expired]);
+        assert_eq!(*sessions.statuses.lock().unwrap(), vec![SessionStatus::Cancelled.to_id()]);
+    }
+
+    #[tokio::test]
+    async fn checkout_payment_failed_event_releases_booking() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let event = PaymentEvent::CheckoutPaymentFailed { checkout_session_id: String::from("cs_test") };
+        let result = use_cases.handle_payment_event(event).await;
+
+        assert!(result.is_ok());
+        assert_eq!(*transactions.updates.lock().unwrap(), vec![TransactionStatus::Failed]);
+        assert_eq!(*sessions.statuses.lock().unwrap(), vec![SessionStatus::Cancelled.to_id()]);
+    }
+
+    #[tokio::test]
+    async fn payment_failed_event_keeps_session() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let sessions = Arc::new(MockSessionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            sessions.clone(),
+        );
+
+        let event = PaymentEvent::PaymentFailed { payment_intent_id: String::from("pi_test") };
+        let result = use_cases.handle_payment_event(event).await;
+
+        assert!(result.is_ok());
+        assert_eq!(*transactions.updates.lock().unwrap(), vec![TransactionStatus::Failed]);
+        assert!(sessions.statuses.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn payment_failed_event_on_completed_transaction_does_nothing() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        *transactions.status.lock().unwrap() = Some(TransactionStatus::Completed);
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            Arc::new(MockSessionPersistence::default()),
+        );
+
+        let event = PaymentEvent::PaymentFailed { payment_intent_id: String::from("pi_test") };
+        let result = use_cases.handle_payment_event(event).await;
+
+        assert!(result.is_ok());
+        assert!(transactions.updates.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn payment_failed_event_without_checkout_is_ignored() {
+        let transactions = Arc::new(MockTransactionPersistence::default());
+        let use_cases = use_cases(
+            transactions.clone(),
+            Arc::new(MockPaymentGateway::new(CheckoutStatus::Open)),
+            Arc::new(MockSessionPersistence::default()),
+        );
 
-        info!("Checkout session created successfully. Transaction ID: {}", transaction.id);
+        let event = PaymentEvent::PaymentFailed {
+            payment_intent_id: String::from(ORPHAN_PAYMENT_INTENT_ID),
+        };
+        let result = use_cases.handle_payment_event(event).await;
 
-        Ok(client_secret)
+        assert!(result.is_ok());
+        assert!(transactions.updates.lock().unwrap().is_empty());
     }
 }
@@ -0,0 +1,487 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::professional_availability::{
+        AvailabilityException, ProfessionalAvailability, TimeSlot,
+    },
+};
+
+/// Max amount of days that can be requested at once when computing free slots
+pub const MAX_SLOTS_RANGE_DAYS: i64 = 62;
+
+#[async_trait]
+pub trait ProfessionalAvailabilityPersistence: Send + Sync {
+    async fn create_rule(&self, rule: &ProfessionalAvailability) -> AppResult<()>;
+
+    async fn read_rules(&self, professional_id: &Uuid) -> AppResult<Vec<ProfessionalAvailability>>;
+
+    async fn delete_rule(&self, professional_id: &Uuid, id: &Uuid) -> AppResult<()>;
+
+    async fn create_exception(&self, exception: &AvailabilityException) -> AppResult<()>;
+
+    async fn read_exceptions(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> AppResult<Vec<AvailabilityException>>;
+
+    async fn delete_exception(&self, professional_id: &Uuid, id: &Uuid) -> AppResult<()>;
+
+    /// Returns the time ranges of the non cancelled sessions of the professional that overlap the given range
+    async fn read_booked(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> AppResult<Vec<TimeSlot>>;
+}
+
+#[derive(Clone)]
+pub struct ProfessionalAvailabilityUseCases {
+    persistence: Arc<dyn ProfessionalAvailabilityPersistence>,
+}
+
+impl ProfessionalAvailabilityUseCases {
+    pub fn new(persistence: Arc<dyn ProfessionalAvailabilityPersistence>) -> Self {
+        Self { persistence }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn create_rule(&self, rule: &ProfessionalAvailability) -> AppResult<()> {
+        info!("Attempting create availability rule...");
+
+        if rule.start_time >= rule.end_time || rule.slot_duration <= 0 {
+            return Err(AppError::InvalidPayload);
+        }
+
+        self.persistence.create_rule(rule).await?;
+
+        info!("Availability rule created.");
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read_rules(&self, professional_id: &Uuid) -> AppResult<Vec<ProfessionalAvailability>> {
+        self.persistence.read_rules(professional_id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_rule(&self, professional_id: &Uuid, id: &Uuid) -> AppResult<()> {
+        info!("Attempting delete availability rule...");
+
+        self.persistence.delete_rule(professional_id, id).await?;
+
+        info!("Availability rule deleted.");
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn create_exception(&self, exception: &AvailabilityException) -> AppResult<()> {
+        info!("Attempting create availability exception...");
+
+        match (exception.start_time, exception.end_time) {
+            (None, None) => {}
+            (Some(start), Some(end)) if start < end => {}
+            _ => return Err(AppError::InvalidPayload),
+        }
+
+        self.persistence.create_exception(exception).await?;
+
+        info!("Availability exception created.");
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn read_exceptions(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> AppResult<Vec<AvailabilityException>> {
+        validate_range(from, to)?;
+
+        self.persistence
+            .read_exceptions(professional_id, from, to)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_exception(&self, professional_id: &Uuid, id: &Uuid) -> AppResult<()> {
+        info!("Attempting delete availability exception...");
+
+        self.persistence.delete_exception(professional_id, id).await?;
+
+        info!("Availability exception deleted.");
+
+        Ok(())
+    }
+
+    /// Returns the bookable slots of the professional between the given dates (both included)
+    #[instrument(skip(self))]
+    pub async fn free_slots(
+        &self,
+        professional_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> AppResult<Vec<TimeSlot>> {
+        validate_range(from, to)?;
+
+        read_free_slots(self.persistence.as_ref(), professional_id, from, to, None).await
+    }
+}
+
+/// Reads everything needed from the persistence and computes the free slots of a professional. `released` is a
+/// booking counted as free, the window a session holds while it is being moved
+pub(crate) async fn read_free_slots(
+    persistence: &dyn ProfessionalAvailabilityPersistence,
+    professional_id: &Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+    released: Option<&TimeSlot>,
+) -> AppResult<Vec<TimeSlot>> {
+    let rules = persistence.read_rules(professional_id).await?;
+    let exceptions = persistence.read_exceptions(professional_id, from, to).await?;
+    let mut booked = persistence
+        .read_booked(
+            professional_id,
+            from.and_time(NaiveTime::MIN),
+            (to + Duration::days(1)).and_time(NaiveTime::MIN),
+        )
+        .await?;
+    booked.retain(|slot| Some(slot) != released);
+
+    Ok(compute_free_slots(
+        &rules,
+        &exceptions,
+        &booked,
+        from,
+        to,
+        chrono::Utc::now().naive_utc(),
+    ))
+}
+
+fn validate_range(from: NaiveDate, to: NaiveDate) -> AppResult<()> {
+    if from > to || (to - from).num_days() >= MAX_SLOTS_RANGE_DAYS {
+        return Err(AppError::InvalidPayload);
+    }
+
+    Ok(())
+}
+
+/// Splits the weekly rules in concrete slots for every day in the range, dropping the ones that already started,
+/// fall inside an exception or overlap a booked session. The result is sorted by start.
+pub(crate) fn compute_free_slots(
+    rules: &[ProfessionalAvailability],
+    exceptions: &[AvailabilityException],
+    booked: &[TimeSlot],
+    from: NaiveDate,
+    to: NaiveDate,
+    now: NaiveDateTime,
+) -> Vec<TimeSlot> {
+    let blocked: Vec<TimeSlot> = exceptions.iter().map(AvailabilityException::window).collect();
+
+    let mut slots = Vec::new();
+
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        for rule in rules.iter().filter(|rule| rule.weekday == date.weekday()) {
+            let step = Duration::minutes(rule.slot_duration.into());
+            let window_end = date.and_time(rule.end_time);
+            let mut start = date.and_time(rule.start_time);
+
+            while start + step <= window_end {
+                let slot = TimeSlot {
+                    start,
+                    end: start + step,
+                };
+
+                let is_free = slot.start >= now
+                    && !blocked.iter().any(|block| block.overlaps(&slot))
+                    && !booked.iter().any(|session| session.overlaps(&slot));
+
+                if is_free {
+                    slots.push(slot);
+                }
+
+                start += step;
+            }
+        }
+    }
+
+    slots.sort_by_key(|slot| slot.start);
+    slots.dedup();
+
+    slots
+}
+
+/// Checks if the requested range fits inside the free slots, contiguous slots are merged so a session can span
+/// more than one slot
+pub(crate) fn fits_free_slots(free_slots: &[TimeSlot], requested: &TimeSlot) -> bool {
+    let mut window: Option<TimeSlot> = None;
+
+    for slot in free_slots {
+        window = match window {
+            Some(current) if current.end >= slot.start => Some(TimeSlot {
+                start: current.start,
+                end: current.end.max(slot.end),
+            }),
+            Some(current) if current.contains(requested) => return true,
+            _ => Some(*slot),
+        };
+    }
+
+    window.is_some_and(|current| current.contains(requested))
+}
+
+#[cfg(test)]
+mod test {
+    use async_trait::async_trait;
+    use chrono::Weekday;
+
+    use super::*;
+
+    fn at(date: NaiveDate, hour: u32, minute: u32) -> NaiveDateTime {
+        date.and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn rule(weekday: Weekday, start: u32, end: u32) -> ProfessionalAvailability {
+        ProfessionalAvailability {
+            id: Some(Uuid::new_v4()),
+            professional_id: Uuid::new_v4(),
+            weekday,
+            start_time: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+            slot_duration: 60,
+            created_at: None,
+        }
+    }
+
+    // 2030-01-07 is a Monday
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2030, 1, 7).unwrap()
+    }
+
+    struct MockProfessionalAvailabilityPersistence;
+
+    #[async_trait]
+    impl ProfessionalAvailabilityPersistence for MockProfessionalAvailabilityPersistence {
+        async fn create_rule(&self, rule: &ProfessionalAvailability) -> AppResult<()> {
+            if rule.id.is_some() {
+                return Err(AppError::Internal(
+                    "rule id must be None when creating".into(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        async fn read_rules(&self, _professional_id: &Uuid) -> AppResult<Vec<ProfessionalAvailability>> {
+            Ok(vec![rule(Weekday::Mon, 9, 12)])
+        }
+
+        async fn delete_rule(&self, _professional_id: &Uuid, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn create_exception(&self, exception: &AvailabilityException) -> AppResult<()> {
+            assert!(exception.id.is_none());
+
+            Ok(())
+        }
+
+        async fn read_exceptions(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDate,
+            _to: NaiveDate,
+        ) -> AppResult<Vec<AvailabilityException>> {
+            Ok(vec![])
+        }
+
+        async fn delete_exception(&self, _professional_id: &Uuid, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_booked(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> AppResult<Vec<TimeSlot>> {
+            Ok(vec![TimeSlot {
+                start: at(monday(), 10, 0),
+                end: at(monday(), 11, 0),
+            }])
+        }
+    }
+
+    #[test]
+    fn compute_free_slots_splits_rules_by_slot_duration() {
+        let slots = compute_free_slots(
+            &[rule(Weekday::Mon, 9, 12)],
+            &[],
+            &[],
+            monday(),
+            monday() + Duration::days(6),
+            at(monday(), 0, 0),
+        );
+
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots[0].start, at(monday(), 9, 0));
+        assert_eq!(slots[2].end, at(monday(), 12, 0));
+    }
+
+    #[test]
+    fn compute_free_slots_skips_exceptions_booked_and_past_slots() {
+        let whole_day_off = AvailabilityException {
+            id: None,
+            professional_id: Uuid::new_v4(),
+            exception_date: monday() + Duration::days(7),
+            start_time: None,
+            end_time: None,
+            reason: Some(String::from("Holiday")),
+            created_at: None,
+        };
+        let booked = TimeSlot {
+            start: at(monday(), 10, 30),
+            end: at(monday(), 11, 30),
+        };
+
+        let slots = compute_free_slots(
+            &[rule(Weekday::Mon, 9, 13)],
+            &[whole_day_off],
+            &[booked],
+            monday(),
+            monday() + Duration::days(7),
+            at(monday(), 9, 30),
+        );
+
+        // 9:00 already started, 10:00 and 11:00 overlap the booked session, next monday is a holiday
+        assert_eq!(
+            slots,
+            vec![TimeSlot {
+                start: at(monday(), 12, 0),
+                end: at(monday(), 13, 0),
+            }]
+        );
+    }
+
+    #[test]
+    fn fits_free_slots_merges_contiguous_slots() {
+        let slots = compute_free_slots(
+            &[rule(Weekday::Mon, 9, 12)],
+            &[],
+            &[],
+            monday(),
+            monday(),
+            at(monday(), 0, 0),
+        );
+
+        let ninety_minutes = TimeSlot {
+            start: at(monday(), 9, 30),
+            end: at(monday(), 11, 0),
+        };
+        let outside = TimeSlot {
+            start: at(monday(), 11, 30),
+            end: at(monday(), 12, 30),
+        };
+
+        assert!(fits_free_slots(&slots, &ninety_minutes));
+        assert!(!fits_free_slots(&slots, &outside));
+    }
+
+    #[tokio::test]
+    async fn create_rule_works() {
+        let use_cases =
+            ProfessionalAvailabilityUseCases::new(Arc::new(MockProfessionalAvailabilityPersistence));
+
+        let mut new_rule = rule(Weekday::Tue, 9, 14);
+        new_rule.id = None;
+
+        let result = use_cases.create_rule(&new_rule).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn create_rule_with_inverted_times_fails() {
+        let use_cases =
+            ProfessionalAvailabilityUseCases::new(Arc::new(MockProfessionalAvailabilityPersistence));
+
+        let mut new_rule = rule(Weekday::Tue, 14, 9);
+        new_rule.id = None;
+
+        let result = use_cases.create_rule(&new_rule).await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+
+    #[tokio::test]
+    async fn create_exception_with_half_window_fails() {
+        let use_cases =
+            ProfessionalAvailabilityUseCases::new(Arc::new(MockProfessionalAvailabilityPersistence));
+
+        let result = use_cases
+            .create_exception(&AvailabilityException {
+                id: None,
+                professional_id: Uuid::new_v4(),
+                exception_date: monday(),
+                start_time: NaiveTime::from_hms_opt(9, 0, 0),
+                end_time: None,
+                reason: None,
+                created_at: None,
+            })
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn free_slots_works() {
+        let use_cases =
+            ProfessionalAvailabilityUseCases::new(Arc::new(MockProfessionalAvailabilityPersistence));
+
+        let result = use_cases
+            .free_slots(&Uuid::new_v4(), monday(), monday() + Duration::days(6))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.iter().map(|slot| slot.start).collect::<Vec<_>>(),
+            vec![at(monday(), 9, 0), at(monday(), 11, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn free_slots_with_too_big_range_fails() {
+        let use_cases =
+            ProfessionalAvailabilityUseCases::new(Arc::new(MockProfessionalAvailabilityPersistence));
+
+        let result = use_cases
+            .free_slots(&Uuid::new_v4(), monday(), monday() + Duration::days(365))
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+
+    #[tokio::test]
+    async fn delete_rule_works() {
+        let use_cases =
+            ProfessionalAvailabilityUseCases::new(Arc::new(MockProfessionalAvailabilityPersistence));
+
+        let result = use_cases
+            .delete_rule(&Uuid::new_v4(), &Uuid::new_v4())
+            .await;
+
+        assert!(result.is_ok());
+    }
+}
@@ -1,14 +1,33 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
-use crate::{app_error::AppResult, entities::session::Session};
+use crate::{
+    app_error::{AppError, AppResult},
+    entities::{
+        audit_event::{AuditAction, AuditEvent, AuditResource},
+        professional_availability::TimeSlot,
+        permission::{Actor, Permission},
+        session::{DEFAULT_SESSION_DURATION_MINUTES, Session, SessionStatus},
+        user::Role,
+        videocall::{JoinWindow, Meeting, VideoCallProvider},
+    },
+    use_cases::{
+        audit::AuditPersistence,
+        cancellation::CancellationPersistence,
+        patient::PatientPersistence,
+        professional_availability::{
+            ProfessionalAvailabilityPersistence, fits_free_slots, read_free_slots,
+        },
+    },
+};
 
 #[async_trait]
 pub trait SessionPersistence: Send + Sync {
-    async fn create(&self, session: &Session) -> AppResult<()>;
+    /// Returns the id of the created session
+    async fn create(&self, session: &Session) -> AppResult<Uuid>;
 
     async fn read_all(&self) -> AppResult<Vec<Session>>;
 
@@ -18,69 +37,209 @@ pub trait SessionPersistence: Send + Sync {
 
     async fn read_single(&self, id: &Uuid) -> AppResult<Session>;
 
+    /// Whether the professional profile belongs to the user
+    async fn is_professional_user(&self, professional_id: &Uuid, user_id: &Uuid) -> AppResult<bool>;
+
+    /// Keeps the status, it only changes through `transition`
     async fn update(&self, session: &Session) -> AppResult<()>;
 
+    /// Moves the session to `to` only while its status is one of `from`, returns the status it had or None when it
+    /// wasn't in any of them. Of two concurrent transitions of a session only one can succeed
+    async fn transition(&self, id: &Uuid, from: &[SessionStatus], to: &SessionStatus) -> AppResult<Option<SessionStatus>>;
+
+    /// Whether the professional has a non cancelled session overlapping the given window, ignoring exclude_id
+    async fn has_overlapping(
+        &self,
+        professional_id: &Uuid,
+        from: chrono::NaiveDateTime,
+        to: chrono::NaiveDateTime,
+        exclude_id: Option<&Uuid>,
+    ) -> AppResult<bool>;
+
     async fn delete(&self, id: &Uuid) -> AppResult<()>;
 }
 
 #[async_trait]
 pub trait VideoCallService: Send + Sync {
-    async fn create_meeting(&self, end_date: chrono::NaiveDateTime) -> AppResult<String>;
+    /// Creates a room open until `end_date`
+    async fn create_meeting(&self, end_date: chrono::NaiveDateTime) -> AppResult<Meeting>;
+
+    /// Removes the room from the provider so its links stop working
+    async fn delete_meeting(&self, meeting_id: &str) -> AppResult<()>;
+
+    fn provider(&self) -> VideoCallProvider;
 }
 
 #[derive(Clone)]
 pub struct SessionUseCases {
     persistence: Arc<dyn SessionPersistence>,
     videocall_service: Arc<dyn VideoCallService>,
+    availability_persistence: Arc<dyn ProfessionalAvailabilityPersistence>,
+    parties_persistence: Arc<dyn CancellationPersistence>,
+    patient_persistence: Arc<dyn PatientPersistence>,
+    audit_persistence: Arc<dyn AuditPersistence>,
+    join_window: JoinWindow,
 }
 
 impl SessionUseCases {
     pub fn new(
         persistence: Arc<dyn SessionPersistence>,
         videocall_service: Arc<dyn VideoCallService>,
+        availability_persistence: Arc<dyn ProfessionalAvailabilityPersistence>,
+        parties_persistence: Arc<dyn CancellationPersistence>,
+        patient_persistence: Arc<dyn PatientPersistence>,
+        audit_persistence: Arc<dyn AuditPersistence>,
+        join_window: JoinWindow,
     ) -> Self {
         Self {
             persistence,
             videocall_service,
+            availability_persistence,
+            parties_persistence,
+            patient_persistence,
+            audit_persistence,
+            join_window,
         }
     }
 
+    /// Sessions set up by the staff or the professional itself start scheduled, patients book and pay through the checkout.
+    /// Professionals only set them up for the patients they already see, a session opens the clinical record of the patient
     #[instrument(skip(self))]
-    pub async fn create(&self, session: Session) -> AppResult<()> {
+    pub async fn create(&self, actor: &Actor, session: Session) -> AppResult<Uuid> {
         info!("Attempting create session...");
 
-        self.persistence.create(&session).await?;
+        if !actor.can(Permission::ManageSessions) {
+            if !self.persistence.is_professional_user(&session.professional_id, &actor.user_id).await? {
+                return Err(AppError::Unauthorized(String::from(
+                    "Only the staff or the professional of the session can create it, patients book it through the checkout",
+                )));
+            }
+
+            if !self.patient_persistence.is_patient_of(&session.patient_id, &actor.user_id).await? {
+                return Err(AppError::Unauthorized(String::from(
+                    "Professionals can only create sessions for their own patients",
+                )));
+            }
+        }
+
+        let session = Session {
+            session_status: SessionStatus::Scheduled,
+            completed: false,
+            ..session
+        };
+
+        ensure_no_overlap(self.persistence.as_ref(), &session).await?;
+
+        ensure_fits_open_slot(self.availability_persistence.as_ref(), &session, None).await?;
+
+        let id = self.persistence.create(&session).await?;
+
+        self.audit(actor, AuditAction::Create, &[id]).await?;
 
         info!("Session created.");
 
-        Ok(())
+        Ok(id)
     }
 
     #[instrument(skip(self))]
-    pub async fn read_all(&self) -> AppResult<Vec<Session>> {
-        self.persistence.read_all().await
+    pub async fn read_all(&self, actor: &Actor) -> AppResult<Vec<Session>> {
+        let sessions = self.persistence.read_all().await?;
+
+        self.audit_read(actor, &sessions).await?;
+
+        Ok(sessions)
     }
 
     #[instrument(skip(self))]
-    pub async fn read_patient(&self, patient_id: &Uuid) -> AppResult<Vec<Session>> {
-        self.persistence.read_patient(patient_id).await
+    pub async fn read_patient(&self, actor: &Actor, patient_id: &Uuid) -> AppResult<Vec<Session>> {
+        let sessions = self.persistence.read_patient(patient_id).await?;
+
+        self.audit_read(actor, &sessions).await?;
+
+        Ok(sessions)
     }
 
+    /// Patients check the availability through the free slots, the sessions of a professional tell who its patients are
     #[instrument(skip(self))]
-    pub async fn read_professional(&self, professional_id: &Uuid) -> AppResult<Vec<Session>> {
-        self.persistence.read_professional(professional_id).await
+    pub async fn read_professional(&self, actor: &Actor, professional_id: &Uuid) -> AppResult<Vec<Session>> {
+        if !actor.can(Permission::ReadProfessionalSchedules)
+            && !self.persistence.is_professional_user(professional_id, &actor.user_id).await?
+        {
+            return Err(AppError::Unauthorized(String::from(
+                "Only the staff or the professional itself can read the sessions of a professional",
+            )));
+        }
+
+        let sessions = self.persistence.read_professional(professional_id).await?;
+
+        self.audit_read(actor, &sessions).await?;
+
+        Ok(sessions)
     }
 
     #[instrument(skip(self))]
-    pub async fn read_single(&self, id: &Uuid) -> AppResult<Session> {
-        self.persistence.read_single(id).await
+    pub async fn read_single(&self, actor: &Actor, id: &Uuid) -> AppResult<Session> {
+        if !actor.can(Permission::ManageSessions) {
+            let parties = self.parties_persistence.read_session_parties(id).await?;
+
+            if !actor.is(parties.patient_user_id.as_ref()) && !actor.is(parties.professional_user_id.as_ref()) {
+                return Err(AppError::Unauthorized(String::from(
+                    "Only the session patient, professional or staff can read the session",
+                )));
+            }
+        }
+
+        let session = self.persistence.read_single(id).await?;
+
+        self.audit_read(actor, std::slice::from_ref(&session)).await?;
+
+        Ok(session)
     }
 
+    /// The patient and the professional of a session can't be changed, the session is cancelled and booked again instead.
+    /// Neither can its status, sessions are cancelled through the cancellation so the payment is refunded
     #[instrument(skip(self))]
-    pub async fn update(&self, session: &Session) -> AppResult<()> {
+    pub async fn update(&self, actor: &Actor, session: &Session) -> AppResult<()> {
         info!("Attempting update session...");
 
-        self.persistence.update(session).await?;
+        let id = session.id.ok_or(AppError::InvalidPayload)?;
+
+        if !actor.can(Permission::ManageSessions) {
+            let parties = self.parties_persistence.read_session_parties(&id).await?;
+
+            if !actor.is(parties.professional_user_id.as_ref()) {
+                return Err(AppError::Unauthorized(String::from(
+                    "Only the session professional or staff can update the session",
+                )));
+            }
+        }
+
+        let current = self.persistence.read_single(&id).await?;
+        if session.patient_id != current.patient_id || session.professional_id != current.professional_id {
+            return Err(AppError::Conflict(String::from(
+                "The patient and the professional of a session can't be changed",
+            )));
+        }
+
+        let session = Session { session_status: current.session_status, ..session.clone() };
+
+        if session.session_date != current.session_date || session.session_duration != current.session_duration {
+            let held = current.session_date.map(|start| TimeSlot {
+                start,
+                end: start
+                    + chrono::Duration::minutes(
+                        current.session_duration.unwrap_or(DEFAULT_SESSION_DURATION_MINUTES).into(),
+                    ),
+            });
+
+            ensure_fits_open_slot(self.availability_persistence.as_ref(), &session, held.as_ref()).await?;
+        }
+
+        ensure_no_overlap(self.persistence.as_ref(), &session).await?;
+
+        self.persistence.update(&session).await?;
+
+        self.audit(actor, AuditAction::Update, &[id]).await?;
 
         info!("Sessión updated.");
 
@@ -88,95 +247,269 @@ impl SessionUseCases {
     }
 
     #[instrument(skip(self))]
-    pub async fn delete(&self, id: &Uuid) -> AppResult<()> {
+    pub async fn delete(&self, actor: &Actor, id: &Uuid) -> AppResult<()> {
         info!("Attempting delete session...");
 
+        let session = self.persistence.read_single(id).await?;
+
         self.persistence.delete(id).await?;
 
+        self.audit(actor, AuditAction::Delete, &[*id]).await?;
+
         info!("Session deleted.");
 
+        if let Err(e) = delete_meeting(self.videocall_service.as_ref(), &session).await {
+            warn!("Failed to delete the meeting of the session: {:?}", e);
+        }
+
         Ok(())
     }
 
+    /// Who sees which professional and when is clinical data too, every access is recorded before it leaves the use case
+    async fn audit(&self, actor: &Actor, action: AuditAction, ids: &[Uuid]) -> AppResult<()> {
+        let events: Vec<AuditEvent> = ids
+            .iter()
+            .map(|id| AuditEvent::new(actor, action, AuditResource::Session, *id))
+            .collect();
+
+        self.audit_persistence.record(&events).await
+    }
+
+    async fn audit_read(&self, actor: &Actor, sessions: &[Session]) -> AppResult<()> {
+        let ids: Vec<Uuid> = sessions.iter().filter_map(|session| session.id).collect();
+
+        self.audit(actor, AuditAction::Read, &ids).await
+    }
+
     #[instrument(skip(self))]
-    pub async fn get_videocall_url(&self, id: &Uuid, user_id: &Uuid) -> AppResult<String> {
+    pub async fn get_videocall_url(&self, id: &Uuid, user_id: &Uuid, role: &Role) -> AppResult<String> {
         info!("Attempting to get videocall URL for session {}", id);
 
         let mut session = self.persistence.read_single(id).await?;
 
-        // 1. Authorization check: user must be patient or professional
-        // In a real scenario, we'd check if the user_id matches the user associated with patient_id or professional_id.
-        // For now, let's assume the IDs in Session are already the User IDs or we can verify them.
-        // Actually, let's check the patient/professional entities to see how they map to users.
-        // But for simplicity in this implementation, I'll check against patient_id/professional_id if they are UUIDs.
-        // Wait, the user state says Active Document: backend/.env, so I can't check other files easily without listing.
-        
-        // Let's assume for now that if the user_id is provided, we should verify it.
-        // TODO: Detailed authorization check if needed.
-
-        /* 
-        // 2. Timing check: 5 minutes before session_date
-        if let Some(session_date) = session.session_date {
-            let now = chrono::Utc::now().naive_utc();
-            let five_minutes_before = session_date - chrono::Duration::minutes(5);
+        if matches!(session.session_status, SessionStatus::Cancelled) {
+            return Err(AppError::Conflict(String::from("Session is cancelled")));
+        }
 
-            if now < five_minutes_before {
-                return Err(crate::app_error::AppError::Internal(
-                    "Cannot enter the session yet. Please try again 5 minutes before the start.".into(),
-                ));
-            }
-        } else {
-            return Err(crate::app_error::AppError::Internal(
-                "Session date not set".into(),
-            ));
+        // 1. Only the patient, the professional or who is allowed to join any videocall
+        let parties = self.parties_persistence.read_session_parties(id).await?;
+
+        let is_participant = parties.patient_user_id.as_ref() == Some(user_id)
+            || parties.professional_user_id.as_ref() == Some(user_id);
+
+        if !role.can(Permission::JoinVideocalls) && !is_participant {
+            return Err(AppError::Unauthorized(String::from(
+                "Only the session patient, professional or an admin can join the videocall",
+            )));
+        }
+
+        // 2. The videocall is open from a bit before the start until a bit after the end
+        let (Some(session_date), Some(duration)) = (session.session_date, session.session_duration) else {
+            return Err(AppError::Unavailable(String::from(
+                "Session date or duration not set, cannot join the videocall",
+            )));
+        };
+        let end_date = session_date + chrono::Duration::minutes(duration as i64);
+        let now = chrono::Utc::now().naive_utc();
+
+        let opens_at = self.join_window.opens_at(session_date);
+        if now < opens_at {
+            return Err(AppError::Unavailable(format!(
+                "Too early to join, the videocall opens at {} UTC",
+                opens_at.format("%Y-%m-%d %H:%M")
+            )));
+        }
+
+        if now > self.join_window.closes_at(end_date) {
+            return Err(AppError::Unavailable(String::from("Session is over, the videocall is closed")));
         }
-        */
 
-        // 3. Generate if missing or if it's an old link (not whereby)
-        let needs_generation = session.videocall_url.is_none() 
-            || !session.videocall_url.as_ref().unwrap().contains("whereby.com");
+        // 3. Generate if missing or if it's a link set by hand. A generated link keeps working
+        // when the configured provider changes, so it is not regenerated for that
+        let needs_generation = session.videocall_url.is_none() || session.videocall_provider.is_none();
 
         if needs_generation {
-            if let Some(duration) = session.session_duration {
-                info!("Generating/Migrating Whereby meeting...");
-                let end_date = session.session_date.unwrap() + chrono::Duration::minutes(duration as i64);
-                let meeting_url = self.videocall_service.create_meeting(end_date).await?;
-                session.videocall_url = Some(meeting_url.clone());
-                
-                // Save it back to the database
-                self.persistence.update(&session).await?;
-                info!("Whereby meeting generated and saved.");
-            } else {
-                return Err(crate::app_error::AppError::Internal(
-                    "Session duration not set, cannot generate meeting".into(),
-                ));
-            }
+            let provider = self.videocall_service.provider();
+            info!("Generating {} meeting...", provider);
+            let meeting = self.videocall_service.create_meeting(end_date).await?;
+            session.videocall_url = Some(meeting.room_url);
+            session.videocall_host_url = Some(meeting.host_room_url);
+            session.videocall_meeting_id = Some(meeting.id);
+            session.videocall_provider = Some(provider);
+
+            // Save it back to the database
+            self.persistence.update(&session).await?;
+            info!("{} meeting generated and saved.", provider);
         }
 
-        Ok(session.videocall_url.unwrap())
+        // 4. The patient joins as a participant, the professional or an admin as the host.
+        // The links set by hand and the ones generated before there were host links have no host link
+        let is_patient = parties.patient_user_id.as_ref() == Some(user_id);
+
+        let url = if is_patient {
+            session.videocall_url
+        } else {
+            session.videocall_host_url.or(session.videocall_url)
+        };
+
+        url.ok_or_else(|| AppError::Internal(String::from("Session has no videocall link")))
+    }
+}
+
+/// Removes the meeting of the session from the provider, the meetings of a previous provider are left to expire
+pub(crate) async fn delete_meeting(
+    videocall_service: &dyn VideoCallService,
+    session: &Session,
+) -> AppResult<()> {
+    let Some(meeting_id) = &session.videocall_meeting_id else {
+        return Ok(());
+    };
+
+    if session.videocall_provider != Some(videocall_service.provider()) {
+        warn!("Meeting {} was not created by the current provider, it's left to expire", meeting_id);
+        return Ok(());
     }
+
+    videocall_service.delete_meeting(meeting_id).await
+}
+
+/// A professional can't be booked twice at the same time, cancelled sessions don't block anything
+pub(crate) async fn ensure_no_overlap(
+    persistence: &dyn SessionPersistence,
+    session: &Session,
+) -> AppResult<()> {
+    let Some(session_date) = session.session_date else {
+        return Ok(());
+    };
+
+    if matches!(session.session_status, SessionStatus::Cancelled) {
+        return Ok(());
+    }
+
+    let duration = session
+        .session_duration
+        .unwrap_or(DEFAULT_SESSION_DURATION_MINUTES);
+
+    let overlapping = persistence
+        .has_overlapping(
+            &session.professional_id,
+            session_date,
+            session_date + chrono::Duration::minutes(duration.into()),
+            session.id.as_ref(),
+        )
+        .await?;
+
+    if overlapping {
+        return Err(AppError::Conflict(
+            "The professional already has a session at the requested time".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Sessions can only be booked inside the free slots of the professional availability, `released` is the window the
+/// session already holds when it is moved
+pub(crate) async fn ensure_fits_open_slot(
+    availability_persistence: &dyn ProfessionalAvailabilityPersistence,
+    session: &Session,
+    released: Option<&TimeSlot>,
+) -> AppResult<()> {
+    let (Some(session_date), Some(duration)) = (session.session_date, session.session_duration)
+    else {
+        return Err(AppError::InvalidPayload);
+    };
+
+    let requested = TimeSlot {
+        start: session_date,
+        end: session_date + chrono::Duration::minutes(duration.into()),
+    };
+
+    let free_slots = read_free_slots(
+        availability_persistence,
+        &session.professional_id,
+        requested.start.date(),
+        requested.end.date(),
+        released,
+    )
+    .await?;
+
+    if !fits_free_slots(&free_slots, &requested) {
+        return Err(AppError::Unavailable(
+            "The professional is not available at the requested time".into(),
+        ));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
     use async_trait::async_trait;
+    use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
 
-    use crate::{app_error::AppError, entities::session::SessionStatus};
+    use crate::{
+        dtos::session::parties::SessionPartiesDTO,
+        entities::{
+            audit_event::AuditEventFilter,
+            patient::Patient,
+            professional_availability::{AvailabilityException, ProfessionalAvailability},
+        },
+    };
 
     use super::*;
 
-    struct MockSessionPersistence;
+    /// Professional that already has a session booked at any time
+    const BUSY_PROFESSIONAL_ID: Uuid = Uuid::from_u128(1);
+    /// Session in progress with a Whereby link generated before the provider was changed
+    const WHEREBY_SESSION_ID: Uuid = Uuid::from_u128(2);
+    /// Session in progress with a link set by hand
+    const HAND_SET_LINK_SESSION_ID: Uuid = Uuid::from_u128(3);
+    /// Session starting tomorrow at 10:00
+    const UPCOMING_SESSION_ID: Uuid = Uuid::from_u128(4);
+    /// Session that was starting now, cancelled
+    const CANCELLED_SESSION_ID: Uuid = Uuid::from_u128(5);
+    /// Session that ended two hours ago
+    const FINISHED_SESSION_ID: Uuid = Uuid::from_u128(6);
+    /// Session in progress with a meeting of the current provider
+    const JITSI_SESSION_ID: Uuid = Uuid::from_u128(7);
+    /// Session of the busy professional
+    const BUSY_SESSION_ID: Uuid = Uuid::from_u128(8);
+    /// Cancelled session of the busy professional
+    const CANCELLED_BUSY_SESSION_ID: Uuid = Uuid::from_u128(9);
+    /// Patient of every session, seen by the professional of PROFESSIONAL_USER_ID
+    const PATIENT_ID: Uuid = Uuid::from_u128(20);
+    /// Professional of every session but the busy one
+    const PROFESSIONAL_ID: Uuid = Uuid::from_u128(21);
+
+    const PATIENT_USER_ID: Uuid = Uuid::from_u128(10);
+    const PROFESSIONAL_USER_ID: Uuid = Uuid::from_u128(11);
+    const OTHER_USER_ID: Uuid = Uuid::from_u128(12);
+
+    fn tomorrow_at(hour: u32, minute: u32) -> NaiveDateTime {
+        (chrono::Utc::now().date_naive() + chrono::Duration::days(1))
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    /// Records the sessions updated
+    #[derive(Default)]
+    struct MockSessionPersistence {
+        updated: Mutex<Vec<Session>>,
+    }
 
     #[async_trait]
     impl SessionPersistence for MockSessionPersistence {
-        async fn create(&self, session: &Session) -> AppResult<()> {
+        async fn create(&self, session: &Session) -> AppResult<Uuid> {
             if session.id.is_some() {
                 return Err(AppError::Internal(
                     "session id must be None when creating".into(),
                 ));
             }
 
-            Ok(())
+            Ok(Uuid::new_v4())
         }
 
         async fn read_all(&self) -> AppResult<Vec<Session>> {
@@ -191,59 +524,306 @@ mod test {
             Ok(vec![])
         }
 
-        async fn read_single(&self, _id: &Uuid) -> AppResult<Session> {
+        async fn read_single(&self, id: &Uuid) -> AppResult<Session> {
+            let now = chrono::Utc::now().naive_utc();
+            let session_date = match *id {
+                WHEREBY_SESSION_ID | HAND_SET_LINK_SESSION_ID | CANCELLED_SESSION_ID | JITSI_SESSION_ID => Some(now),
+                UPCOMING_SESSION_ID => Some(tomorrow_at(10, 0)),
+                FINISHED_SESSION_ID => Some(now - chrono::Duration::hours(3)),
+                _ => None,
+            };
+
+            if session_date.is_some() {
+                return Ok(Session {
+                    id: Some(*id),
+                    patient_id: PATIENT_ID,
+                    professional_id: PROFESSIONAL_ID,
+                    session_type_id: Some(Uuid::new_v4()),
+                    session_status: if *id == CANCELLED_SESSION_ID {
+                        SessionStatus::Cancelled
+                    } else {
+                        SessionStatus::Scheduled
+                    },
+                    session_date,
+                    videocall_url: Some(String::from("https://whereby.com/existing-room")),
+                    videocall_provider: match *id {
+                        HAND_SET_LINK_SESSION_ID => None,
+                        JITSI_SESSION_ID => Some(VideoCallProvider::Jitsi),
+                        _ => Some(VideoCallProvider::Whereby),
+                    },
+                    videocall_host_url: (*id != HAND_SET_LINK_SESSION_ID)
+                        .then(|| String::from("https://whereby.com/existing-room?roomKey=host")),
+                    videocall_meeting_id: (*id != HAND_SET_LINK_SESSION_ID).then(|| format!("meeting-{}", id.as_u128())),
+                    session_duration: Some(60),
+                    completed: false,
+                    created_at: None,
+                });
+            }
+
             Ok(Session {
-                id: Some(Uuid::new_v4()),
-                patient_id: Uuid::new_v4(),
-                professional_id: Uuid::new_v4(),
+                id: Some(*id),
+                patient_id: PATIENT_ID,
+                professional_id: match *id {
+                    BUSY_SESSION_ID | CANCELLED_BUSY_SESSION_ID => BUSY_PROFESSIONAL_ID,
+                    _ => PROFESSIONAL_ID,
+                },
                 session_type_id: Some(Uuid::new_v4()),
-                session_status: SessionStatus::Scheduled,
+                session_status: if *id == CANCELLED_BUSY_SESSION_ID {
+                    SessionStatus::Cancelled
+                } else {
+                    SessionStatus::Scheduled
+                },
                 session_date: None,
                 videocall_url: Some(String::from("https://videocallurl.com")),
-                notes: Some(String::from("")),
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
                 session_duration: Some(30),
                 completed: false,
                 created_at: None,
             })
         }
 
+        async fn is_professional_user(&self, _professional_id: &Uuid, user_id: &Uuid) -> AppResult<bool> {
+            Ok(*user_id == PROFESSIONAL_USER_ID)
+        }
+
         async fn update(&self, session: &Session) -> AppResult<()> {
             assert!(session.id.is_some());
 
+            self.updated.lock().unwrap().push(session.clone());
+
             Ok(())
         }
 
+        async fn transition(
+            &self,
+            _id: &Uuid,
+            _from: &[SessionStatus],
+            _to: &SessionStatus,
+        ) -> AppResult<Option<SessionStatus>> {
+            Ok(None)
+        }
+
+        async fn has_overlapping(
+            &self,
+            professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+            _exclude_id: Option<&Uuid>,
+        ) -> AppResult<bool> {
+            Ok(*professional_id == BUSY_PROFESSIONAL_ID)
+        }
+
         async fn delete(&self, _id: &Uuid) -> AppResult<()> {
             Ok(())
         }
     }
 
-    struct MockVideoCallService;
+    /// Jitsi service, records the meetings deleted
+    #[derive(Default)]
+    struct MockVideoCallService {
+        deleted: Mutex<Vec<String>>,
+    }
 
     #[async_trait]
     impl VideoCallService for MockVideoCallService {
-        async fn create_meeting(&self, _end_date: chrono::NaiveDateTime) -> AppResult<String> {
-            Ok(String::from("https://whereby.com/mock-room"))
+        async fn create_meeting(&self, _end_date: chrono::NaiveDateTime) -> AppResult<Meeting> {
+            Ok(Meeting {
+                id: String::from("mock-room"),
+                room_url: String::from("https://meet.example.com/mock-room"),
+                host_room_url: String::from("https://meet.example.com/mock-room?host=true"),
+            })
+        }
+
+        async fn delete_meeting(&self, meeting_id: &str) -> AppResult<()> {
+            self.deleted.lock().unwrap().push(meeting_id.to_string());
+            Ok(())
+        }
+
+        fn provider(&self) -> VideoCallProvider {
+            VideoCallProvider::Jitsi
         }
     }
 
-    #[tokio::test]
-    async fn create_works() {
+    struct MockPartiesPersistence;
+
+    #[derive(Default)]
+    struct MockAuditPersistence {
+        events: Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait]
+    impl AuditPersistence for MockAuditPersistence {
+        async fn record(&self, events: &[AuditEvent]) -> AppResult<()> {
+            self.events.lock().unwrap().extend_from_slice(events);
+
+            Ok(())
+        }
+
+        async fn read(&self, _filter: &AuditEventFilter) -> AppResult<Vec<AuditEvent>> {
+            Ok(self.events.lock().unwrap().clone())
+        }
+    }
+
+    #[async_trait]
+    impl CancellationPersistence for MockPartiesPersistence {
+        async fn read_session_parties(&self, _session_id: &Uuid) -> AppResult<SessionPartiesDTO> {
+            Ok(SessionPartiesDTO {
+                patient_user_id: Some(PATIENT_USER_ID),
+                patient_email: Some(String::from("patient@example.com")),
+                professional_user_id: Some(PROFESSIONAL_USER_ID),
+                professional_email: Some(String::from("professional@example.com")),
+            })
+        }
+    }
+
+    /// Only the patient PATIENT_ID is seen by a professional, the one of PROFESSIONAL_USER_ID
+    struct MockPatientPersistence;
+
+    #[async_trait]
+    impl PatientPersistence for MockPatientPersistence {
+        async fn create(&self, _patient: &Patient) -> AppResult<Uuid> {
+            Ok(Uuid::new_v4())
+        }
+
+        async fn read_all(&self) -> AppResult<Vec<Patient>> {
+            Ok(vec![])
+        }
+
+        async fn read_single(&self, _id: &Uuid) -> AppResult<Patient> {
+            Err(AppError::NotFound(String::from("Patient not found")))
+        }
+
+        async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<Patient> {
+            Err(AppError::NotFound(String::from("Patient not found")))
+        }
+
+        async fn read_by_professional(&self, _professional_id: &Uuid) -> AppResult<Vec<Patient>> {
+            Ok(vec![])
+        }
+
+        async fn is_patient_of(&self, patient_id: &Uuid, professional_user_id: &Uuid) -> AppResult<bool> {
+            Ok(*patient_id == PATIENT_ID && *professional_user_id == PROFESSIONAL_USER_ID)
+        }
+
+        async fn update(&self, _patient: &Patient) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn update_birthdate(&self, _patient_id: &Uuid, _birthdate: NaiveDate) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn admin() -> Actor {
+        Actor::new(Uuid::new_v4(), Role::Admin)
+    }
+
+    fn join_window() -> JoinWindow {
+        JoinWindow { minutes_before_start: 5, minutes_after_end: 15 }
+    }
+
+    struct Mocks {
+        sessions: Arc<MockSessionPersistence>,
+        videocalls: Arc<MockVideoCallService>,
+        audit: Arc<MockAuditPersistence>,
+    }
+
+    fn use_cases() -> (SessionUseCases, Mocks) {
+        let mocks = Mocks {
+            sessions: Arc::new(MockSessionPersistence::default()),
+            videocalls: Arc::new(MockVideoCallService::default()),
+            audit: Arc::new(MockAuditPersistence::default()),
+        };
+
         let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
+            mocks.sessions.clone(),
+            mocks.videocalls.clone(),
+            Arc::new(MockAvailabilityPersistence),
+            Arc::new(MockPartiesPersistence),
+            Arc::new(MockPatientPersistence),
+            mocks.audit.clone(),
+            join_window(),
         );
 
+        (use_cases, mocks)
+    }
+
+    /// Professional available from 9:00 to 13:00 every day of the week
+    struct MockAvailabilityPersistence;
+
+    #[async_trait]
+    impl ProfessionalAvailabilityPersistence for MockAvailabilityPersistence {
+        async fn create_rule(&self, _rule: &ProfessionalAvailability) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_rules(&self, professional_id: &Uuid) -> AppResult<Vec<ProfessionalAvailability>> {
+            let tomorrow = chrono::Utc::now().date_naive() + chrono::Duration::days(1);
+
+            Ok(vec![ProfessionalAvailability {
+                id: Some(Uuid::new_v4()),
+                professional_id: *professional_id,
+                weekday: tomorrow.weekday(),
+                start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end_time: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+                slot_duration: 60,
+                created_at: None,
+            }])
+        }
+
+        async fn delete_rule(&self, _professional_id: &Uuid, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn create_exception(&self, _exception: &AvailabilityException) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_exceptions(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDate,
+            _to: NaiveDate,
+        ) -> AppResult<Vec<AvailabilityException>> {
+            Ok(vec![])
+        }
+
+        async fn delete_exception(&self, _professional_id: &Uuid, _id: &Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn read_booked(
+            &self,
+            _professional_id: &Uuid,
+            _from: NaiveDateTime,
+            _to: NaiveDateTime,
+        ) -> AppResult<Vec<TimeSlot>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn create_works() {
+        let (use_cases, _) = use_cases();
+
         let result = use_cases
-            .create(Session {
+            .create(&admin(), Session {
                 id: None,
                 patient_id: Uuid::new_v4(),
                 professional_id: Uuid::new_v4(),
                 session_type_id: Some(Uuid::new_v4()),
                 session_status: SessionStatus::Scheduled,
-                session_date: None,
+                session_date: Some(tomorrow_at(10, 0)),
                 videocall_url: Some(String::from("https://videocallurl.com")),
-                notes: Some(String::from("")),
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
                 session_duration: Some(30),
                 completed: false,
                 created_at: None,
@@ -255,21 +835,20 @@ mod test {
 
     #[tokio::test]
     async fn create_with_id_fails() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
 
         let result = use_cases
-            .create(Session {
+            .create(&admin(), Session {
                 id: Some(Uuid::new_v4()),
                 patient_id: Uuid::new_v4(),
                 professional_id: Uuid::new_v4(),
                 session_type_id: Some(Uuid::new_v4()),
                 session_status: SessionStatus::Scheduled,
-                session_date: None,
+                session_date: Some(tomorrow_at(10, 0)),
                 videocall_url: Some(String::from("https://videocallurl.com")),
-                notes: Some(String::from("")),
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
                 session_duration: Some(30),
                 completed: false,
                 created_at: None,
@@ -279,71 +858,202 @@ mod test {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn create_overlapping_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .create(&admin(), Session {
+                id: None,
+                patient_id: Uuid::new_v4(),
+                professional_id: BUSY_PROFESSIONAL_ID,
+                session_type_id: Some(Uuid::new_v4()),
+                session_status: SessionStatus::Scheduled,
+                session_date: Some(tomorrow_at(10, 0)),
+                videocall_url: None,
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
+                session_duration: Some(60),
+                completed: false,
+                created_at: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn create_outside_availability_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .create(&admin(), Session {
+                id: None,
+                patient_id: Uuid::new_v4(),
+                professional_id: Uuid::new_v4(),
+                session_type_id: Some(Uuid::new_v4()),
+                session_status: SessionStatus::Scheduled,
+                session_date: Some(tomorrow_at(12, 30)),
+                videocall_url: None,
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
+                session_duration: Some(60),
+                completed: false,
+                created_at: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn create_without_date_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .create(&admin(), Session {
+                id: None,
+                patient_id: Uuid::new_v4(),
+                professional_id: Uuid::new_v4(),
+                session_type_id: Some(Uuid::new_v4()),
+                session_status: SessionStatus::Scheduled,
+                session_date: None,
+                videocall_url: None,
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
+                session_duration: Some(30),
+                completed: false,
+                created_at: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidPayload)));
+    }
+
+    #[tokio::test]
+    async fn create_is_scoped_to_the_staff_and_the_session_professional() {
+        let (use_cases, _) = use_cases();
+        let session = || Session {
+            id: None,
+            patient_id: PATIENT_ID,
+            professional_id: Uuid::new_v4(),
+            session_type_id: None,
+            session_status: SessionStatus::Scheduled,
+            session_date: Some(tomorrow_at(10, 0)),
+            videocall_url: None,
+            videocall_provider: None,
+            videocall_host_url: None,
+            videocall_meeting_id: None,
+            session_duration: Some(60),
+            completed: false,
+            created_at: None,
+        };
+
+        assert!(use_cases.create(&Actor::new(PROFESSIONAL_USER_ID, Role::Professional), session()).await.is_ok());
+        assert!(use_cases.create(&Actor::new(OTHER_USER_ID, Role::Receptionist), session()).await.is_ok());
+
+        let result = use_cases.create(&Actor::new(OTHER_USER_ID, Role::Professional), session()).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+        let result = use_cases.create(&Actor::new(PATIENT_USER_ID, Role::Patient), session()).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn professional_cannot_create_sessions_for_other_patients() {
+        let (use_cases, _) = use_cases();
+        let session = || Session {
+            id: None,
+            patient_id: Uuid::new_v4(),
+            professional_id: PROFESSIONAL_ID,
+            session_type_id: None,
+            session_status: SessionStatus::Scheduled,
+            session_date: Some(tomorrow_at(10, 0)),
+            videocall_url: None,
+            videocall_provider: None,
+            videocall_host_url: None,
+            videocall_meeting_id: None,
+            session_duration: Some(60),
+            completed: false,
+            created_at: None,
+        };
+
+        let result = use_cases.create(&Actor::new(PROFESSIONAL_USER_ID, Role::Professional), session()).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+        assert!(use_cases.create(&Actor::new(OTHER_USER_ID, Role::Receptionist), session()).await.is_ok());
+    }
+
     #[tokio::test]
     async fn read_all_works() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_all().await;
+        let result = use_cases.read_all(&admin()).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn read_patient_works() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_patient(&Uuid::new_v4()).await;
+        let result = use_cases.read_patient(&admin(), &Uuid::new_v4()).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn read_professional_works() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_professional(&Uuid::new_v4()).await;
+        let result = use_cases.read_professional(&admin(), &Uuid::new_v4()).await;
 
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn read_professional_is_scoped_to_the_staff_and_the_professional() {
+        let (use_cases, _) = use_cases();
+        let id = Uuid::new_v4();
+
+        assert!(use_cases.read_professional(&Actor::new(PROFESSIONAL_USER_ID, Role::Professional), &id).await.is_ok());
+        assert!(use_cases.read_professional(&Actor::new(OTHER_USER_ID, Role::Receptionist), &id).await.is_ok());
+
+        let result = use_cases.read_professional(&Actor::new(OTHER_USER_ID, Role::Professional), &id).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+        let result = use_cases.read_professional(&Actor::new(PATIENT_USER_ID, Role::Patient), &id).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
     #[tokio::test]
     async fn read_single_works() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
 
-        let result = use_cases.read_single(&Uuid::new_v4()).await;
+        let result = use_cases.read_single(&admin(), &Uuid::new_v4()).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn update_works() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
 
         let result = use_cases
-            .update(&Session {
+            .update(&admin(), &Session {
                 id: Some(Uuid::new_v4()),
-                patient_id: Uuid::new_v4(),
-                professional_id: Uuid::new_v4(),
+                patient_id: PATIENT_ID,
+                professional_id: PROFESSIONAL_ID,
                 session_type_id: Some(Uuid::new_v4()),
                 session_status: SessionStatus::Scheduled,
                 session_date: None,
                 videocall_url: Some(String::from("https://videocallurl.com")),
-                notes: Some(String::from("")),
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
                 session_duration: Some(30),
                 completed: false,
                 created_at: None,
@@ -353,15 +1063,288 @@ mod test {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn update_can_only_move_the_session_to_an_open_slot() {
+        let (use_cases, mocks) = use_cases();
+
+        let moved = |session_date| Session {
+            id: Some(UPCOMING_SESSION_ID),
+            patient_id: PATIENT_ID,
+            professional_id: PROFESSIONAL_ID,
+            session_type_id: None,
+            session_status: SessionStatus::Scheduled,
+            session_date: Some(session_date),
+            videocall_url: None,
+            videocall_provider: None,
+            videocall_host_url: None,
+            videocall_meeting_id: None,
+            session_duration: Some(60),
+            completed: false,
+            created_at: None,
+        };
+
+        let result = use_cases.update(&admin(), &moved(tomorrow_at(15, 0))).await;
+        assert!(matches!(result, Err(AppError::Unavailable(_))));
+        assert!(mocks.sessions.updated.lock().unwrap().is_empty());
+
+        let result = use_cases.update(&admin(), &moved(tomorrow_at(11, 0))).await;
+        assert!(result.is_ok());
+        assert_eq!(mocks.sessions.updated.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn update_overlapping_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .update(&admin(), &Session {
+                id: Some(BUSY_SESSION_ID),
+                patient_id: PATIENT_ID,
+                professional_id: BUSY_PROFESSIONAL_ID,
+                session_type_id: Some(Uuid::new_v4()),
+                session_status: SessionStatus::Scheduled,
+                session_date: Some(tomorrow_at(10, 0)),
+                videocall_url: None,
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
+                session_duration: Some(60),
+                completed: false,
+                created_at: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn update_cancelled_overlapping_works() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .update(&admin(), &Session {
+                id: Some(CANCELLED_BUSY_SESSION_ID),
+                patient_id: PATIENT_ID,
+                professional_id: BUSY_PROFESSIONAL_ID,
+                session_type_id: Some(Uuid::new_v4()),
+                session_status: SessionStatus::Cancelled,
+                session_date: Some(tomorrow_at(10, 0)),
+                videocall_url: None,
+                videocall_provider: None,
+                videocall_host_url: None,
+                videocall_meeting_id: None,
+                session_duration: Some(60),
+                completed: false,
+                created_at: None,
+            })
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn read_single_is_scoped_to_the_session_parties() {
+        let (use_cases, _) = use_cases();
+
+        let id = Uuid::new_v4();
+        assert!(use_cases.read_single(&Actor::new(PATIENT_USER_ID, Role::Patient), &id).await.is_ok());
+        assert!(use_cases.read_single(&Actor::new(PROFESSIONAL_USER_ID, Role::Professional), &id).await.is_ok());
+        assert!(use_cases.read_single(&Actor::new(OTHER_USER_ID, Role::Receptionist), &id).await.is_ok());
+
+        let result = use_cases.read_single(&Actor::new(OTHER_USER_ID, Role::Professional), &id).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn update_is_scoped_to_the_session_professional() {
+        let (use_cases, _) = use_cases();
+
+        let session = || Session {
+            id: Some(Uuid::new_v4()),
+            patient_id: PATIENT_ID,
+            professional_id: PROFESSIONAL_ID,
+            session_type_id: None,
+            session_status: SessionStatus::Scheduled,
+            session_date: None,
+            videocall_url: None,
+            videocall_provider: None,
+            videocall_host_url: None,
+            videocall_meeting_id: None,
+            session_duration: Some(30),
+            completed: false,
+            created_at: None,
+        };
+
+        assert!(use_cases.update(&Actor::new(PROFESSIONAL_USER_ID, Role::Professional), &session()).await.is_ok());
+        assert!(use_cases.update(&Actor::new(OTHER_USER_ID, Role::Receptionist), &session()).await.is_ok());
+
+        let result = use_cases.update(&Actor::new(OTHER_USER_ID, Role::Professional), &session()).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+        let result = use_cases.update(&Actor::new(PATIENT_USER_ID, Role::Patient), &session()).await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn update_keeps_the_status() {
+        let (use_cases, mocks) = use_cases();
+
+        let mut session = use_cases.read_single(&admin(), &UPCOMING_SESSION_ID).await.unwrap();
+        session.session_status = SessionStatus::Cancelled;
+        use_cases.update(&admin(), &session).await.unwrap();
+
+        let updated = mocks.sessions.updated.lock().unwrap();
+        assert!(matches!(updated[0].session_status, SessionStatus::Scheduled));
+    }
+
+    #[tokio::test]
+    async fn update_cannot_change_the_patient_or_the_professional() {
+        let (use_cases, _) = use_cases();
+        let professional = Actor::new(PROFESSIONAL_USER_ID, Role::Professional);
+
+        let mut session = use_cases.read_single(&professional, &Uuid::new_v4()).await.unwrap();
+        session.patient_id = Uuid::new_v4();
+        let result = use_cases.update(&professional, &session).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        let mut session = use_cases.read_single(&admin(), &Uuid::new_v4()).await.unwrap();
+        session.professional_id = Uuid::new_v4();
+        let result = use_cases.update(&admin(), &session).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
     #[tokio::test]
     async fn delete_works() {
-        let use_cases = SessionUseCases::new(
-            Arc::new(MockSessionPersistence),
-            Arc::new(MockVideoCallService),
-        );
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases.delete(&admin(), &Uuid::new_v4()).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn videocall_link_of_previous_provider_is_kept() {
+        let (use_cases, _) = use_cases();
+
+        let url = use_cases
+            .get_videocall_url(&WHEREBY_SESSION_ID, &PATIENT_USER_ID, &Role::Patient)
+            .await
+            .unwrap();
 
-        let result = use_cases.delete(&Uuid::new_v4()).await;
+        assert_eq!(url, "https://whereby.com/existing-room");
+    }
+
+    #[tokio::test]
+    async fn videocall_link_set_by_hand_is_regenerated() {
+        let (use_cases, _) = use_cases();
+
+        let url = use_cases
+            .get_videocall_url(&HAND_SET_LINK_SESSION_ID, &PROFESSIONAL_USER_ID, &Role::Professional)
+            .await
+            .unwrap();
+
+        assert_eq!(url, "https://meet.example.com/mock-room?host=true");
+    }
+
+    #[tokio::test]
+    async fn videocall_admin_can_join() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .get_videocall_url(&WHEREBY_SESSION_ID, &OTHER_USER_ID, &Role::Admin)
+            .await;
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn videocall_by_other_user_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .get_videocall_url(&WHEREBY_SESSION_ID, &OTHER_USER_ID, &Role::Professional)
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn videocall_too_early_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .get_videocall_url(&UPCOMING_SESSION_ID, &PATIENT_USER_ID, &Role::Patient)
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unavailable(msg)) if msg.starts_with("Too early")));
+    }
+
+    #[tokio::test]
+    async fn videocall_after_session_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .get_videocall_url(&FINISHED_SESSION_ID, &PATIENT_USER_ID, &Role::Patient)
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unavailable(msg)) if msg.starts_with("Session is over")));
+    }
+
+    #[tokio::test]
+    async fn videocall_of_cancelled_session_fails() {
+        let (use_cases, _) = use_cases();
+
+        let result = use_cases
+            .get_videocall_url(&CANCELLED_SESSION_ID, &PATIENT_USER_ID, &Role::Patient)
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn videocall_professional_gets_host_link() {
+        let (use_cases, _) = use_cases();
+
+        let url = use_cases
+            .get_videocall_url(&WHEREBY_SESSION_ID, &PROFESSIONAL_USER_ID, &Role::Professional)
+            .await
+            .unwrap();
+
+        assert_eq!(url, "https://whereby.com/existing-room?roomKey=host");
+    }
+
+    #[tokio::test]
+    async fn delete_removes_meeting() {
+        let (use_cases, mocks) = use_cases();
+
+        use_cases.delete(&admin(), &JITSI_SESSION_ID).await.unwrap();
+
+        assert_eq!(*mocks.videocalls.deleted.lock().unwrap(), vec![String::from("meeting-7")]);
+    }
+
+    #[tokio::test]
+    async fn delete_keeps_meeting_of_previous_provider() {
+        let (use_cases, mocks) = use_cases();
+
+        use_cases.delete(&admin(), &WHEREBY_SESSION_ID).await.unwrap();
+
+        assert!(mocks.videocalls.deleted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reads_and_updates_are_audited() {
+        let (use_cases, mocks) = use_cases();
+        let professional = Actor::new(PROFESSIONAL_USER_ID, Role::Professional);
+
+        let mut session = use_cases.read_single(&professional, &UPCOMING_SESSION_ID).await.unwrap();
+        session.session_duration = Some(90);
+        use_cases.update(&professional, &session).await.unwrap();
+
+        let events = mocks.audit.events.lock().unwrap();
+        let actions: Vec<AuditAction> = events.iter().map(|event| event.action).collect();
+        assert_eq!(actions, vec![AuditAction::Read, AuditAction::Update]);
+        assert!(events.iter().all(|event| event.actor_user_id == PROFESSIONAL_USER_ID
+            && event.resource_type == AuditResource::Session
+            && event.resource_id == UPCOMING_SESSION_ID));
+    }
 }
@@ -1,11 +1,32 @@
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use secrecy::{ExposeSecret, SecretString};
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
-use crate::{adapters::crypto::jwt::Claims, app_error::AppResult, entities::user::User};
+use crate::{
+    adapters::{
+        crypto::jwt::Claims,
+        utils::{
+            recovery_code::{generate_recovery_codes, hash_recovery_code},
+            refresh_token::{generate_refresh_token, hash_refresh_token},
+        },
+    },
+    app_error::{AppError, AppResult},
+    dtos::user::{
+        auth_tokens::AuthTokensDTO,
+        login::LoginOutcomeDTO,
+        two_factor::{TwoFactorEnrollmentDTO, TwoFactorLoginActivationDTO},
+    },
+    entities::{
+        login_throttle::{LoginFailures, LoginLockout, LoginSubjectKind, LoginThrottle},
+        refresh_token::RefreshToken,
+        two_factor::TwoFactor,
+        user::{Role, User},
+    },
+};
 
 #[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 pub struct OnboardingDto {
@@ -34,12 +55,23 @@ pub trait UserPersistence: Send + Sync {
         usersurname: &str,
         email: &str,
         password_hash: &str,
+        organization_id: &Uuid,
     ) -> AppResult<User>;
 
-    async fn get_user_by_email(&self, email: &str) -> AppResult<User>;
+    async fn get_user_by_email(&self, email: &str) -> AppResult<Option<User>>;
 
     async fn get_user_by_id(&self, user_id: &Uuid) -> AppResult<User>;
 
+    /// The organization the tokens of the user are scoped to
+    async fn get_organization_id(&self, user_id: &Uuid) -> AppResult<Uuid>;
+
+    /// Version the access tokens of the user have to carry, not scoped to an organization since it is read before
+    async fn get_credentials_version(&self, user_id: &Uuid) -> AppResult<i32>;
+
+    /// Bumps the credentials version, the access tokens already issued to the user stop working
+    async fn revoke_access_tokens(&self, user_id: &Uuid) -> AppResult<()>;
+
+    /// Only the users of the organization of the request
     async fn get_all_users(&self) -> AppResult<Vec<User>>;
 
     async fn get_onboarding_info(&self, user_id: &Uuid) -> AppResult<Option<OnboardingDto>>;
@@ -51,6 +83,79 @@ pub trait UserPersistence: Send + Sync {
         user_id: &Uuid,
         profile_picture_url: &str,
     ) -> AppResult<()>;
+
+    /// Also invalidates the pending password reset and email change tokens, and the access tokens of the user
+    async fn update_password(&self, user_id: &Uuid, password_hash: &str) -> AppResult<()>;
+
+    async fn update_role(&self, user_id: &Uuid, role: &Role) -> AppResult<()>;
+}
+
+#[async_trait]
+pub trait RefreshTokenPersistence: Send + Sync {
+    async fn create(&self, token: &RefreshToken) -> AppResult<()>;
+
+    async fn read_by_hash(&self, token_hash: &str) -> AppResult<Option<RefreshToken>>;
+
+    /// Revokes `current` and stores `next` in its place, returns false when `current` was already revoked
+    async fn rotate(&self, current: &Uuid, next: &RefreshToken) -> AppResult<bool>;
+
+    async fn revoke(&self, id: &Uuid) -> AppResult<()>;
+
+    async fn revoke_family(&self, family_id: &Uuid) -> AppResult<()>;
+
+    /// Returns the number of tokens revoked
+    async fn revoke_all(&self, user_id: &Uuid) -> AppResult<u64>;
+}
+
+#[async_trait]
+pub trait TwoFactorPersistence: Send + Sync {
+    async fn read(&self, user_id: &Uuid) -> AppResult<Option<TwoFactor>>;
+
+    /// Replaces the pending secret of the user, does nothing once it is enabled
+    async fn save_pending_secret(&self, user_id: &Uuid, secret: &str) -> AppResult<()>;
+
+    /// Enables the pending secret and replaces the recovery codes of the user
+    async fn enable(&self, user_id: &Uuid, recovery_code_hashes: &[String]) -> AppResult<()>;
+
+    /// Removes the secret and the recovery codes of the user
+    async fn disable(&self, user_id: &Uuid) -> AppResult<()>;
+
+    /// Marks the recovery code as used, returns false when it does not exist or was already used
+    async fn use_recovery_code(&self, user_id: &Uuid, code_hash: &str) -> AppResult<bool>;
+
+    async fn is_required(&self, role: &Role) -> AppResult<bool>;
+
+    async fn set_required(&self, role: &Role, required: bool) -> AppResult<()>;
+}
+
+#[async_trait]
+pub trait LoginThrottlePersistence: Send + Sync {
+    async fn read_failures(&self, kind: LoginSubjectKind, subject: &str) -> AppResult<Option<LoginFailures>>;
+
+    /// Adds a failure to the subject and returns its failures, the count restarts when the last failure is
+    /// before `forget_before`
+    async fn record_failure(
+        &self,
+        kind: LoginSubjectKind,
+        subject: &str,
+        now: NaiveDateTime,
+        forget_before: NaiveDateTime,
+    ) -> AppResult<LoginFailures>;
+
+    async fn clear_failures(&self, kind: LoginSubjectKind, subject: &str) -> AppResult<()>;
+
+    async fn record_lockout(&self, lockout: &LoginLockout) -> AppResult<()>;
+
+    /// Most recent first
+    async fn list_lockouts(&self, since: NaiveDateTime) -> AppResult<Vec<LoginLockout>>;
+}
+
+pub trait UserTotpService: Send + Sync {
+    /// Returns a new base32 secret
+    fn generate_secret(&self) -> String;
+    /// The otpauth:// uri the authenticator apps read from a QR code
+    fn provisioning_uri(&self, secret: &str, account_name: &str) -> AppResult<String>;
+    fn verify_code(&self, secret: &str, code: &str) -> AppResult<bool>;
 }
 
 pub trait UserCredentialsHasher: Send + Sync {
@@ -59,35 +164,56 @@ pub trait UserCredentialsHasher: Send + Sync {
 }
 
 pub trait UserJwtService: Send + Sync {
-    fn generate_token(&self, user: &User) -> AppResult<String>;
+    fn generate_token(&self, user: &User, organization_id: &Uuid, credentials_version: i32) -> AppResult<String>;
     fn validate_token(&self, token: &str) -> AppResult<Claims>;
+    /// Short lived token handed out instead of the tokens while the second login step is pending
+    fn generate_pre_auth_token(&self, user_id: &Uuid) -> AppResult<String>;
+    /// Returns the user id of the pre auth token
+    fn validate_pre_auth_token(&self, token: &str) -> AppResult<Uuid>;
 }
 
 #[derive(Clone)]
 pub struct UserUseCases {
-    pub(crate) jwt_service: Arc<dyn UserJwtService>, // TODO: I had to pub this to access it from the auth middleware, still not sure if this is the okay way to do it.
+    jwt_service: Arc<dyn UserJwtService>,
     hasher: Arc<dyn UserCredentialsHasher>,
     persistence: Arc<dyn UserPersistence>,
-    #[allow(dead_code)]
-    patient_persistence: Arc<dyn crate::use_cases::patient::PatientPersistence>,
-    #[allow(dead_code)]
-    parent_consent_persistence: Arc<dyn crate::use_cases::parent_consent::ParentConsentPersistence>,
+    refresh_token_persistence: Arc<dyn RefreshTokenPersistence>,
+    refresh_token_ttl: chrono::Duration,
+    two_factor_persistence: Arc<dyn TwoFactorPersistence>,
+    totp_service: Arc<dyn UserTotpService>,
+    login_throttle_persistence: Arc<dyn LoginThrottlePersistence>,
+    login_throttle: LoginThrottle,
+    dummy_password_hash: String, // unknown emails are checked against it so they take as long as a wrong password
 }
 
 impl UserUseCases {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         jwt_service: Arc<dyn UserJwtService>,
         hasher: Arc<dyn UserCredentialsHasher>,
         persistence: Arc<dyn UserPersistence>,
-        patient_persistence: Arc<dyn crate::use_cases::patient::PatientPersistence>,
-        parent_consent_persistence: Arc<dyn crate::use_cases::parent_consent::ParentConsentPersistence>,
+        refresh_token_persistence: Arc<dyn RefreshTokenPersistence>,
+        refresh_token_ttl: chrono::Duration,
+        two_factor_persistence: Arc<dyn TwoFactorPersistence>,
+        totp_service: Arc<dyn UserTotpService>,
+        login_throttle_persistence: Arc<dyn LoginThrottlePersistence>,
+        login_throttle: LoginThrottle,
     ) -> Self {
+        let dummy_password_hash = hasher
+            .hash_password(&Uuid::new_v4().to_string())
+            .unwrap_or_default();
+
         Self {
             hasher,
             jwt_service,
             persistence,
-            patient_persistence,
-            parent_consent_persistence,
+            refresh_token_persistence,
+            refresh_token_ttl,
+            two_factor_persistence,
+            totp_service,
+            login_throttle_persistence,
+            login_throttle,
+            dummy_password_hash,
         }
     }
 
@@ -98,33 +224,453 @@ impl UserUseCases {
         usersurname: &str,
         email: &str,
         password: &SecretString,
-    ) -> AppResult<String> {
+        organization_id: &Uuid,
+    ) -> AppResult<AuthTokensDTO> {
         info!("Adding user...");
 
         let hash = &self.hasher.hash_password(password.expose_secret())?;
         let user = self
             .persistence
-            .create_user_and_patient(username, usersurname, email, hash)
+            .create_user_and_patient(username, usersurname, email, hash, organization_id)
             .await?;
 
-        let jwt_token = self.jwt_service.generate_token(&user)?;
-
-        Ok(jwt_token)
+        self.issue_tokens(&user, Uuid::new_v4(), None).await
     }
 
+    /// Users with two factor authentication enabled, or whose role requires it, get a pre auth token instead of
+    /// the tokens.
+    /// Failed logins are throttled by account and by ip, an unknown email fails the same way as a wrong password
     #[instrument(skip(self))]
-    pub async fn login(&self, email: &str, password: &SecretString) -> AppResult<String> {
+    pub async fn login(&self, email: &str, password: &SecretString, ip: &IpAddr) -> AppResult<LoginOutcomeDTO> {
         info!("Attempting user login...");
 
-        let user = self.persistence.get_user_by_email(email).await?;
+        let account = login_account(email);
+        let ip = ip.to_string();
+        self.check_login_throttle(&account, &ip).await?;
+
+        let user = match self.persistence.get_user_by_email(email).await? {
+            Some(user) => user,
+            None => {
+                let _ = self
+                    .hasher
+                    .verify_password(&self.dummy_password_hash, password.expose_secret());
+                self.record_login_failure(&account, &ip).await?;
+                return Err(AppError::InvalidCredentials);
+            }
+        };
+
+        if let Err(err) = self
+            .hasher
+            .verify_password(&user.password_hash, password.expose_secret())
+        {
+            if matches!(err, AppError::InvalidCredentials) {
+                self.record_login_failure(&account, &ip).await?;
+            }
+            return Err(err);
+        }
+
+        info!("User login is valid.");
+
+        let two_factor = self.two_factor_persistence.read(&user.id).await?;
+        if two_factor.is_some_and(|two_factor| two_factor.is_enabled()) {
+            // The failures are kept until the second step succeeds, so they keep throttling code guesses
+            info!("Two factor code required.");
+            return Ok(LoginOutcomeDTO::TwoFactorRequired {
+                pre_auth_token: self.jwt_service.generate_pre_auth_token(&user.id)?,
+            });
+        }
+
+        self.login_throttle_persistence
+            .clear_failures(LoginSubjectKind::Account, &account)
+            .await?;
+
+        if self.two_factor_persistence.is_required(&user.role).await? {
+            info!("Two factor enrollment required.");
+            return Ok(LoginOutcomeDTO::TwoFactorEnrollmentRequired {
+                pre_auth_token: self.jwt_service.generate_pre_auth_token(&user.id)?,
+            });
+        }
+
+        Ok(LoginOutcomeDTO::Authenticated(
+            self.issue_tokens(&user, Uuid::new_v4(), None).await?,
+        ))
+    }
+
+    /// Second login step, the code can be a TOTP code or one of the recovery codes.
+    /// Wrong codes count as failed logins of the account
+    #[instrument(skip(self, pre_auth_token, code))]
+    pub async fn login_two_factor(&self, pre_auth_token: &str, code: &str, ip: &IpAddr) -> AppResult<AuthTokensDTO> {
+        info!("Attempting two factor login...");
+
+        let user_id = self.jwt_service.validate_pre_auth_token(pre_auth_token)?;
+        let user = self.persistence.get_user_by_id(&user_id).await?;
+
+        let account = login_account(&user.email);
+        let ip = ip.to_string();
+        self.check_login_throttle(&account, &ip).await?;
+
+        let two_factor = self
+            .two_factor_persistence
+            .read(&user_id)
+            .await?
+            .filter(TwoFactor::is_enabled)
+            .ok_or_else(|| AppError::Unauthorized(String::from("Two factor authentication is not enabled")))?;
+
+        if !self.totp_service.verify_code(&two_factor.secret, code)? {
+            let recovery_code_used = self
+                .two_factor_persistence
+                .use_recovery_code(&user_id, &hash_recovery_code(code))
+                .await?;
+
+            if !recovery_code_used {
+                self.record_login_failure(&account, &ip).await?;
+                return Err(AppError::Unauthorized(String::from("Invalid two factor code")));
+            }
+
+            warn!("Two factor login with a recovery code");
+        }
+
+        info!("Two factor login is valid.");
+
+        self.login_throttle_persistence
+            .clear_failures(LoginSubjectKind::Account, &account)
+            .await?;
+
+        self.issue_tokens(&user, Uuid::new_v4(), None).await
+    }
+
+    /// Lockouts recorded since the given time, most recent first
+    #[instrument(skip(self))]
+    pub async fn get_login_lockouts(&self, since: NaiveDateTime) -> AppResult<Vec<LoginLockout>> {
+        info!("Getting login lockouts...");
+
+        let lockouts = self.login_throttle_persistence.list_lockouts(since).await?;
+
+        info!("Got login lockouts.");
+
+        Ok(lockouts)
+    }
+
+    /// Fails with the seconds to wait when the account or the ip are throttled
+    async fn check_login_throttle(&self, account: &str, ip: &str) -> AppResult<()> {
+        let now = chrono::Utc::now().naive_utc();
+
+        for (kind, subject) in [(LoginSubjectKind::Account, account), (LoginSubjectKind::Ip, ip)] {
+            let Some(failures) = self.login_throttle_persistence.read_failures(kind, subject).await? else {
+                continue;
+            };
+
+            if let Some(blocked_until) = self.login_throttle.policy(kind).blocked_until(&failures)
+                && blocked_until > now
+            {
+                warn!("Login throttled by {}", kind);
+                return Err(AppError::TooManyRequests(
+                    (blocked_until - now).num_seconds().max(1),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the failure for the account and the ip, and a lockout for the ones it locks
+    async fn record_login_failure(&self, account: &str, ip: &str) -> AppResult<()> {
+        let now = chrono::Utc::now().naive_utc();
+
+        for (kind, subject) in [(LoginSubjectKind::Account, account), (LoginSubjectKind::Ip, ip)] {
+            let policy = self.login_throttle.policy(kind);
+            let failures = self
+                .login_throttle_persistence
+                .record_failure(kind, subject, now, policy.forget_before(now))
+                .await?;
+
+            if policy.locks(&failures)
+                && let Some(locked_until) = policy.blocked_until(&failures)
+            {
+                warn!("Login locked by {} after {} failures", kind, failures.failures);
+                self.login_throttle_persistence
+                    .record_lockout(&LoginLockout {
+                        id: Uuid::new_v4(),
+                        kind,
+                        subject: subject.to_string(),
+                        failures: failures.failures,
+                        locked_until,
+                        created_at: None,
+                    })
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts (or restarts) the enrollment, nothing changes on login until it is activated with a code
+    #[instrument(skip(self))]
+    pub async fn enroll_two_factor(&self, user_id: &Uuid) -> AppResult<TwoFactorEnrollmentDTO> {
+        info!("Attempting two factor enrollment...");
+
+        let user = self.persistence.get_user_by_id(user_id).await?;
+
+        let two_factor = self.two_factor_persistence.read(user_id).await?;
+        if two_factor.is_some_and(|two_factor| two_factor.is_enabled()) {
+            return Err(AppError::Conflict(String::from("Two factor authentication is already enabled")));
+        }
+
+        let secret = self.totp_service.generate_secret();
+        self.two_factor_persistence.save_pending_secret(user_id, &secret).await?;
+
+        info!("Two factor enrollment started.");
+
+        Ok(TwoFactorEnrollmentDTO {
+            otpauth_uri: self.totp_service.provisioning_uri(&secret, &user.email)?,
+            secret,
+        })
+    }
+
+    /// Enrollment of a user that can't login until it has two factor authentication
+    #[instrument(skip(self, pre_auth_token))]
+    pub async fn enroll_two_factor_on_login(&self, pre_auth_token: &str) -> AppResult<TwoFactorEnrollmentDTO> {
+        let user_id = self.jwt_service.validate_pre_auth_token(pre_auth_token)?;
+
+        self.enroll_two_factor(&user_id).await
+    }
+
+    /// Enables the pending secret once a code generated with it is valid, returns the recovery codes
+    #[instrument(skip(self, code))]
+    pub async fn activate_two_factor(&self, user_id: &Uuid, code: &str) -> AppResult<Vec<String>> {
+        info!("Attempting two factor activation...");
+
+        let two_factor = self
+            .two_factor_persistence
+            .read(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(String::from("Two factor enrollment not found")))?;
+
+        if two_factor.is_enabled() {
+            return Err(AppError::Conflict(String::from("Two factor authentication is already enabled")));
+        }
+
+        if !self.totp_service.verify_code(&two_factor.secret, code)? {
+            return Err(AppError::Unauthorized(String::from("Invalid two factor code")));
+        }
+
+        let recovery_codes = generate_recovery_codes();
+        let recovery_code_hashes: Vec<String> = recovery_codes.iter().map(|code| hash_recovery_code(code)).collect();
+
+        self.two_factor_persistence.enable(user_id, &recovery_code_hashes).await?;
+
+        info!("Two factor authentication enabled.");
+
+        Ok(recovery_codes)
+    }
+
+    /// Activation of a user that can't login until it has two factor authentication, logs the user in as well
+    #[instrument(skip(self, pre_auth_token, code))]
+    pub async fn activate_two_factor_on_login(
+        &self,
+        pre_auth_token: &str,
+        code: &str,
+    ) -> AppResult<TwoFactorLoginActivationDTO> {
+        let user_id = self.jwt_service.validate_pre_auth_token(pre_auth_token)?;
+
+        let recovery_codes = self.activate_two_factor(&user_id, code).await?;
+
+        let user = self.persistence.get_user_by_id(&user_id).await?;
+
+        Ok(TwoFactorLoginActivationDTO {
+            tokens: self.issue_tokens(&user, Uuid::new_v4(), None).await?,
+            recovery_codes,
+        })
+    }
+
+    /// Requires the password, not allowed when the role of the user requires two factor authentication.
+    /// Logs the user out of every device
+    #[instrument(skip(self))]
+    pub async fn disable_two_factor(&self, user_id: &Uuid, password: &SecretString) -> AppResult<()> {
+        info!("Attempting to disable two factor authentication...");
+
+        let user = self.persistence.get_user_by_id(user_id).await?;
         self.hasher
             .verify_password(&user.password_hash, password.expose_secret())?;
 
-        info!("User login is valid.");
+        if self.two_factor_persistence.is_required(&user.role).await? {
+            return Err(AppError::Conflict(format!(
+                "Two factor authentication is required for the {} role",
+                user.role
+            )));
+        }
+
+        self.two_factor_persistence.disable(user_id).await?;
+        self.refresh_token_persistence.revoke_all(user_id).await?;
+        self.persistence.revoke_access_tokens(user_id).await?;
+
+        info!("Two factor authentication disabled.");
+
+        Ok(())
+    }
+
+    /// Users of a role that requires two factor authentication have to enroll on their next login
+    #[instrument(skip(self))]
+    pub async fn set_two_factor_requirement(&self, role: &Role, required: bool) -> AppResult<()> {
+        info!("Setting two factor requirement...");
+
+        self.two_factor_persistence.set_required(role, required).await?;
+
+        info!("Two factor requirement set.");
+
+        Ok(())
+    }
+
+    /// The access tokens already issued keep the old role until they are refreshed
+    #[instrument(skip(self))]
+    pub async fn set_role(&self, user_id: &Uuid, role: &Role) -> AppResult<()> {
+        info!("Attempting set user role...");
+
+        self.persistence.update_role(user_id, role).await?;
+
+        info!("User role set.");
+
+        Ok(())
+    }
+
+    /// Claims of a valid access token. The token stops working once the password changes or the sessions of the user
+    /// are revoked, even before it expires
+    pub async fn authenticate(&self, access_token: &str) -> AppResult<Claims> {
+        let claims = self.jwt_service.validate_token(access_token)?;
+
+        let user_id = Uuid::parse_str(&claims.uuid).map_err(|_| AppError::Unauthorized(String::from("Invalid token")))?;
+        if claims.ver != self.persistence.get_credentials_version(&user_id).await? {
+            return Err(AppError::Unauthorized(String::from("Token revoked")));
+        }
+
+        Ok(claims)
+    }
+
+    /// Exchanges a refresh token for a new access token and a new refresh token, the used one stops working.
+    /// Presenting an already rotated token means it was stolen (or replayed), so its whole family is revoked
+    #[instrument(skip(self, refresh_token))]
+    pub async fn refresh(&self, refresh_token: &str) -> AppResult<AuthTokensDTO> {
+        info!("Attempting token refresh...");
+
+        let stored = self
+            .refresh_token_persistence
+            .read_by_hash(&hash_refresh_token(refresh_token))
+            .await?
+            .ok_or_else(|| AppError::Unauthorized(String::from("Invalid refresh token")))?;
+
+        if stored.revoked_at.is_some() {
+            warn!("Revoked refresh token reused, revoking the sessions of its login");
+            self.refresh_token_persistence.revoke_family(&stored.family_id).await?;
+            return Err(AppError::Unauthorized(String::from("Refresh token revoked")));
+        }
+
+        if stored.expires_at <= chrono::Utc::now().naive_utc() {
+            return Err(AppError::Unauthorized(String::from("Refresh token expired")));
+        }
+
+        // Read again so the new access token has the current role and verification status
+        let user = self.persistence.get_user_by_id(&stored.user_id).await?;
+
+        let tokens = self.issue_tokens(&user, stored.family_id, Some(&stored.id)).await?;
+
+        info!("Token refreshed.");
+
+        Ok(tokens)
+    }
+
+    /// Revokes the refresh token, the access tokens already issued keep working until they expire
+    #[instrument(skip(self, refresh_token))]
+    pub async fn logout(&self, refresh_token: &str) -> AppResult<()> {
+        info!("Attempting logout...");
+
+        if let Some(stored) = self
+            .refresh_token_persistence
+            .read_by_hash(&hash_refresh_token(refresh_token))
+            .await?
+        {
+            self.refresh_token_persistence.revoke(&stored.id).await?;
+        }
+
+        info!("Logged out.");
+
+        Ok(())
+    }
+
+    /// Logs the user out of every device
+    #[instrument(skip(self))]
+    pub async fn revoke_sessions(&self, user_id: &Uuid) -> AppResult<u64> {
+        info!("Attempting to revoke user sessions...");
+
+        let revoked = self.refresh_token_persistence.revoke_all(user_id).await?;
+        self.persistence.revoke_access_tokens(user_id).await?;
 
-        let jwt_token = self.jwt_service.generate_token(&user)?;
+        info!("Revoked {} user sessions.", revoked);
 
-        Ok(jwt_token)
+        Ok(revoked)
+    }
+
+    /// Requires the current password. Every login and access token of the user is revoked, the one changing the
+    /// password gets new tokens
+    #[instrument(skip(self))]
+    pub async fn change_password(
+        &self,
+        user_id: &Uuid,
+        current_password: &SecretString,
+        new_password: &SecretString,
+    ) -> AppResult<AuthTokensDTO> {
+        info!("Attempting password change...");
+
+        let user = self.persistence.get_user_by_id(user_id).await?;
+        self.hasher
+            .verify_password(&user.password_hash, current_password.expose_secret())?;
+
+        let hash = self.hasher.hash_password(new_password.expose_secret())?;
+        self.persistence.update_password(user_id, &hash).await?;
+
+        let revoked = self.refresh_token_persistence.revoke_all(user_id).await?;
+
+        info!("Password changed, revoked {} user sessions.", revoked);
+
+        self.issue_tokens(&user, Uuid::new_v4(), None).await
+    }
+
+    /// Stores a new refresh token of the family, in place of `rotated` when given
+    async fn issue_tokens(
+        &self,
+        user: &User,
+        family_id: Uuid,
+        rotated: Option<&Uuid>,
+    ) -> AppResult<AuthTokensDTO> {
+        // The user is scoped to its organization for as long as the access token lasts
+        let organization_id = self.persistence.get_organization_id(&user.id).await?;
+        let credentials_version = self.persistence.get_credentials_version(&user.id).await?;
+
+        let refresh_token = generate_refresh_token();
+        let stored = RefreshToken::new(
+            user.id,
+            family_id,
+            hash_refresh_token(&refresh_token),
+            self.refresh_token_ttl,
+        );
+
+        match rotated {
+            Some(current) => {
+                // Lost a race against another refresh with the same token, same as a reuse
+                if !self.refresh_token_persistence.rotate(current, &stored).await? {
+                    self.refresh_token_persistence.revoke_family(&family_id).await?;
+                    return Err(AppError::Unauthorized(String::from("Refresh token revoked")));
+                }
+            }
+            None => self.refresh_token_persistence.create(&stored).await?,
+        }
+
+        Ok(AuthTokensDTO {
+            access_token: self
+                .jwt_service
+                .generate_token(user, &organization_id, credentials_version)?,
+            refresh_token,
+            refresh_token_expires_at: stored.expires_at,
+        })
     }
 
     #[instrument(skip(self))]
@@ -185,19 +731,29 @@ impl UserUseCases {
     }
 }
 
+/// Failed logins of an account are counted by the email typed, whatever its case
+pub(crate) fn login_account(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
     use async_trait::async_trait;
 
-    use chrono::NaiveDate;
     use uuid::Uuid;
 
-    use crate::entities::user::Role;
-    use crate::domain::entities::parent_consent::ParentConsent;
+    use crate::entities::{
+        login_throttle::LoginThrottlePolicy, organization::DEFAULT_ORGANIZATION_ID, two_factor::TwoFactor, user::Role,
+    };
 
     use super::*;
 
-    struct MockUserPersistence;
+    #[derive(Default)]
+    struct MockUserPersistence {
+        credentials_version: Mutex<i32>,
+    }
 
     #[async_trait]
     impl UserPersistence for MockUserPersistence {
@@ -207,6 +763,7 @@ mod test {
             usersurname: &str,
             email: &str,
             _password_hash: &str,
+            _organization_id: &Uuid,
         ) -> AppResult<User> {
             assert_eq!(username, "john");
             assert_eq!(usersurname, "doe");
@@ -226,9 +783,11 @@ mod test {
             })
         }
 
-        async fn get_user_by_email(&self, email: &str) -> AppResult<User> {
-            assert_eq!(email, "testuser@gmail.com");
-            Ok(User {
+        async fn get_user_by_email(&self, email: &str) -> AppResult<Option<User>> {
+            if email != "testuser@gmail.com" {
+                return Ok(None);
+            }
+            Ok(Some(User {
                 id: Uuid::new_v4(),
                 role: Role::default(),
                 username: "john".to_string(),
@@ -236,27 +795,40 @@ mod test {
                 email: email.to_string(),
                 verified: Some(false),
                 needs_onboarding: Some(false),
-                password_hash: "hashed_password".to_string(),
+                password_hash: "testuser_pw_hash".to_string(),
                 profile_picture_url: None,
                 created_at: None,
-            })
+            }))
         }
 
         async fn get_user_by_id(&self, user_id: &Uuid) -> AppResult<User> {
             Ok(User {
-                id: user_id.clone(),
+                id: *user_id,
                 role: Role::default(),
                 username: "john".to_string(),
                 usersurname: "doe".to_string(),
                 email: "testuser@gmail.com".to_string(),
                 verified: Some(false),
                 needs_onboarding: Some(false),
-                password_hash: "hashed_password".to_string(),
+                password_hash: "testuser_pw_hash".to_string(),
                 profile_picture_url: None,
                 created_at: None,
             })
         }
 
+        async fn get_organization_id(&self, _user_id: &Uuid) -> AppResult<Uuid> {
+            Ok(DEFAULT_ORGANIZATION_ID)
+        }
+
+        async fn get_credentials_version(&self, _user_id: &Uuid) -> AppResult<i32> {
+            Ok(*self.credentials_version.lock().unwrap())
+        }
+
+        async fn revoke_access_tokens(&self, _user_id: &Uuid) -> AppResult<()> {
+            *self.credentials_version.lock().unwrap() += 1;
+            Ok(())
+        }
+
         async fn get_all_users(&self) -> AppResult<Vec<User>> {
             Ok(vec![User {
                 id: Uuid::new_v4(),
@@ -287,6 +859,16 @@ mod test {
         ) -> AppResult<()> {
             Ok(())
         }
+
+        async fn update_password(&self, _user_id: &Uuid, password_hash: &str) -> AppResult<()> {
+            assert_eq!(password_hash, "new_pw_hash");
+            *self.credentials_version.lock().unwrap() += 1;
+            Ok(())
+        }
+
+        async fn update_role(&self, _user_id: &Uuid, _role: &Role) -> AppResult<()> {
+            Ok(())
+        }
     }
 
     struct MockUserCredentialsHasher;
@@ -307,76 +889,704 @@ mod test {
         }
     }
 
-    struct MockPatientPersistence;
+    struct MockUserJWTService;
+
+    impl UserJwtService for MockUserJWTService {
+        fn generate_token(&self, user: &User, _organization_id: &Uuid, credentials_version: i32) -> AppResult<String> {
+            Ok(format!("token_{}_{}", user.username, credentials_version))
+        }
+
+        fn validate_token(&self, token: &str) -> AppResult<Claims> {
+            match token.strip_prefix("token_").and_then(|claims| claims.rsplit_once('_')) {
+                Some((_, version)) => {
+                    let mut claims = Claims::default();
+                    claims.uuid = Uuid::nil().to_string();
+                    claims.ver = version.parse().unwrap();
+                    Ok(claims)
+                }
+                None => Err(crate::app_error::AppError::Unauthorized(
+                    "Invalid Token".into(),
+                )),
+            }
+        }
+
+        fn generate_pre_auth_token(&self, user_id: &Uuid) -> AppResult<String> {
+            Ok(format!("pre_auth_{}", user_id))
+        }
+
+        fn validate_pre_auth_token(&self, token: &str) -> AppResult<Uuid> {
+            token
+                .strip_prefix("pre_auth_")
+                .and_then(|user_id| Uuid::parse_str(user_id).ok())
+                .ok_or_else(|| AppError::Unauthorized("Invalid pre auth token".into()))
+        }
+    }
+
+    const VALID_TOTP_CODE: &str = "123456";
+    const RECOVERY_CODE: &str = "a1b2c-3d4e5";
+
+    /// Holds the enrollment of whichever user asks, the mocked users get a new id on every read
+    #[derive(Default)]
+    struct MockTwoFactorPersistence {
+        two_factor: Mutex<Option<TwoFactor>>,
+        recovery_code_hashes: Mutex<Vec<String>>,
+        required_roles: Mutex<Vec<Role>>,
+    }
+
+    impl MockTwoFactorPersistence {
+        fn enabled() -> Self {
+            let persistence = Self::default();
+            *persistence.two_factor.lock().unwrap() = Some(TwoFactor {
+                user_id: Uuid::new_v4(),
+                secret: String::from("JBSWY3DPEHPK3PXP"),
+                enabled_at: Some(chrono::Utc::now().naive_utc()),
+                created_at: None,
+            });
+            *persistence.recovery_code_hashes.lock().unwrap() = vec![hash_recovery_code(RECOVERY_CODE)];
+            persistence
+        }
+
+        fn required_for(role: Role) -> Self {
+            let persistence = Self::default();
+            persistence.required_roles.lock().unwrap().push(role);
+            persistence
+        }
+    }
 
     #[async_trait]
-    impl crate::use_cases::patient::PatientPersistence for MockPatientPersistence {
-        async fn create(&self, _patient: &crate::entities::patient::Patient) -> AppResult<()> { Ok(()) }
-        async fn read_all(&self) -> AppResult<Vec<crate::entities::patient::Patient>> { Ok(vec![]) }
-        async fn read_single(&self, _id: &Uuid) -> AppResult<crate::entities::patient::Patient> { Err(crate::app_error::AppError::Internal("Not impl".into())) }
-        async fn read_by_user(&self, _user_id: &Uuid) -> AppResult<crate::entities::patient::Patient> { 
-             Ok(crate::entities::patient::Patient {
-                id: Some(Uuid::new_v4()),
-                user_id: None,
-                gender: crate::entities::gender::Gender::default(),
-                sexual_orientation: crate::entities::sexual_orientation::SexualOrientation::default(),
-                birthdate: None,
-                phone: "".to_string(),
-                emergency_contact_name: None,
-                emergency_contact_phone: None,
-                insurance_policy_number: None,
-                medical_history: None,
-                current_medications: None,
-                allergies: None,
+    impl TwoFactorPersistence for MockTwoFactorPersistence {
+        async fn read(&self, _user_id: &Uuid) -> AppResult<Option<TwoFactor>> {
+            Ok(self.two_factor.lock().unwrap().clone())
+        }
+
+        async fn save_pending_secret(&self, user_id: &Uuid, secret: &str) -> AppResult<()> {
+            *self.two_factor.lock().unwrap() = Some(TwoFactor {
+                user_id: *user_id,
+                secret: secret.to_string(),
+                enabled_at: None,
                 created_at: None,
-            })
+            });
+            Ok(())
+        }
+
+        async fn enable(&self, _user_id: &Uuid, recovery_code_hashes: &[String]) -> AppResult<()> {
+            if let Some(two_factor) = self.two_factor.lock().unwrap().as_mut() {
+                two_factor.enabled_at = Some(chrono::Utc::now().naive_utc());
+            }
+            *self.recovery_code_hashes.lock().unwrap() = recovery_code_hashes.to_vec();
+            Ok(())
+        }
+
+        async fn disable(&self, _user_id: &Uuid) -> AppResult<()> {
+            *self.two_factor.lock().unwrap() = None;
+            self.recovery_code_hashes.lock().unwrap().clear();
+            Ok(())
+        }
+
+        async fn use_recovery_code(&self, _user_id: &Uuid, code_hash: &str) -> AppResult<bool> {
+            let mut hashes = self.recovery_code_hashes.lock().unwrap();
+            let before = hashes.len();
+            hashes.retain(|hash| hash != code_hash);
+            Ok(hashes.len() < before)
+        }
+
+        async fn is_required(&self, role: &Role) -> AppResult<bool> {
+            Ok(self.required_roles.lock().unwrap().contains(role))
+        }
+
+        async fn set_required(&self, role: &Role, required: bool) -> AppResult<()> {
+            let mut roles = self.required_roles.lock().unwrap();
+            roles.retain(|required_role| required_role != role);
+            if required {
+                roles.push(role.clone());
+            }
+            Ok(())
         }
-        async fn read_by_professional(&self, _professional_id: &Uuid) -> AppResult<Vec<crate::entities::patient::Patient>> { Ok(vec![]) }
-        async fn update(&self, _patient: &crate::entities::patient::Patient) -> AppResult<()> { Ok(()) }
-        async fn update_birthdate(&self, _patient_id: &Uuid, _birthdate: NaiveDate) -> AppResult<()> { Ok(()) }
-        async fn delete(&self, _id: &Uuid) -> AppResult<()> { Ok(()) }
     }
 
-    struct MockParentConsentPersistence;
+    struct MockUserTotpService;
+
+    impl UserTotpService for MockUserTotpService {
+        fn generate_secret(&self) -> String {
+            String::from("JBSWY3DPEHPK3PXP")
+        }
+
+        fn provisioning_uri(&self, secret: &str, account_name: &str) -> AppResult<String> {
+            Ok(format!("otpauth://totp/{}?secret={}", account_name, secret))
+        }
+
+        fn verify_code(&self, _secret: &str, code: &str) -> AppResult<bool> {
+            Ok(code == VALID_TOTP_CODE)
+        }
+    }
+
+    /// Keeps the failures and the lockouts in memory
+    #[derive(Default)]
+    struct MockLoginThrottlePersistence {
+        failures: Mutex<Vec<LoginFailures>>,
+        lockouts: Mutex<Vec<LoginLockout>>,
+    }
+
+    impl MockLoginThrottlePersistence {
+        fn failures_of(&self, kind: LoginSubjectKind) -> i32 {
+            self.failures
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|failures| failures.kind == kind)
+                .map_or(0, |failures| failures.failures)
+        }
+
+        /// Moves the last failures back in time, as if the client waited
+        fn wait(&self, duration: chrono::Duration) {
+            for failures in self.failures.lock().unwrap().iter_mut() {
+                failures.last_failed_at -= duration;
+            }
+        }
+    }
 
     #[async_trait]
-    impl crate::use_cases::parent_consent::ParentConsentPersistence for MockParentConsentPersistence {
-        async fn create(&self, _consent: &ParentConsent) -> AppResult<()> { Ok(()) }
-        async fn read_by_patient(&self, _patient_id: &Uuid) -> AppResult<Option<ParentConsent>> { Ok(None) }
+    impl LoginThrottlePersistence for MockLoginThrottlePersistence {
+        async fn read_failures(&self, kind: LoginSubjectKind, subject: &str) -> AppResult<Option<LoginFailures>> {
+            Ok(self
+                .failures
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|failures| failures.kind == kind && failures.subject == subject)
+                .cloned())
+        }
+
+        async fn record_failure(
+            &self,
+            kind: LoginSubjectKind,
+            subject: &str,
+            now: NaiveDateTime,
+            forget_before: NaiveDateTime,
+        ) -> AppResult<LoginFailures> {
+            let mut all_failures = self.failures.lock().unwrap();
+            match all_failures
+                .iter_mut()
+                .find(|failures| failures.kind == kind && failures.subject == subject)
+            {
+                Some(failures) => {
+                    failures.failures = if failures.last_failed_at < forget_before { 1 } else { failures.failures + 1 };
+                    failures.last_failed_at = now;
+                    Ok(failures.clone())
+                }
+                None => {
+                    let failures = LoginFailures {
+                        kind,
+                        subject: subject.to_string(),
+                        failures: 1,
+                        last_failed_at: now,
+                    };
+                    all_failures.push(failures.clone());
+                    Ok(failures)
+                }
+            }
+        }
+
+        async fn clear_failures(&self, kind: LoginSubjectKind, subject: &str) -> AppResult<()> {
+            self.failures
+                .lock()
+                .unwrap()
+                .retain(|failures| failures.kind != kind || failures.subject != subject);
+            Ok(())
+        }
+
+        async fn record_lockout(&self, lockout: &LoginLockout) -> AppResult<()> {
+            self.lockouts.lock().unwrap().push(lockout.clone());
+            Ok(())
+        }
+
+        async fn list_lockouts(&self, _since: NaiveDateTime) -> AppResult<Vec<LoginLockout>> {
+            Ok(self.lockouts.lock().unwrap().clone())
+        }
     }
 
-    struct MockUserJWTService;
+    /// Keeps the tokens in memory
+    #[derive(Default)]
+    struct MockRefreshTokenPersistence {
+        tokens: Mutex<Vec<RefreshToken>>,
+    }
 
-    impl UserJwtService for MockUserJWTService {
-        fn generate_token(&self, user: &User) -> AppResult<String> {
-            Ok(format!("token_{}", user.username))
+    impl MockRefreshTokenPersistence {
+        fn revoke_where(&self, filter: impl Fn(&RefreshToken) -> bool) -> u64 {
+            let mut revoked = 0;
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.revoked_at.is_none() && filter(token) {
+                    token.revoked_at = Some(chrono::Utc::now().naive_utc());
+                    revoked += 1;
+                }
+            }
+            revoked
         }
+    }
 
-        fn validate_token(&self, token: &str) -> AppResult<Claims> {
-            if token.starts_with("token_") {
-                Ok(Claims::default())
-            } else {
-                Err(crate::app_error::AppError::Unauthorized(
-                    "Invalid Token".into(),
-                ))
+    #[async_trait]
+    impl RefreshTokenPersistence for MockRefreshTokenPersistence {
+        async fn create(&self, token: &RefreshToken) -> AppResult<()> {
+            self.tokens.lock().unwrap().push(token.clone());
+            Ok(())
+        }
+
+        async fn read_by_hash(&self, token_hash: &str) -> AppResult<Option<RefreshToken>> {
+            Ok(self.tokens.lock().unwrap().iter().find(|token| token.token_hash == token_hash).cloned())
+        }
+
+        async fn rotate(&self, current: &Uuid, next: &RefreshToken) -> AppResult<bool> {
+            if self.revoke_where(|token| token.id == *current) == 0 {
+                return Ok(false);
             }
+            self.tokens.lock().unwrap().push(next.clone());
+            Ok(true)
+        }
+
+        async fn revoke(&self, id: &Uuid) -> AppResult<()> {
+            self.revoke_where(|token| token.id == *id);
+            Ok(())
+        }
+
+        async fn revoke_family(&self, family_id: &Uuid) -> AppResult<()> {
+            self.revoke_where(|token| token.family_id == *family_id);
+            Ok(())
+        }
+
+        async fn revoke_all(&self, user_id: &Uuid) -> AppResult<u64> {
+            Ok(self.revoke_where(|token| token.user_id == *user_id))
         }
     }
 
-    #[tokio::test]
-    async fn add_user_works() {
-        let user_use_cases = UserUseCases::new(
+    fn build_use_cases(
+        refresh_tokens: Arc<MockRefreshTokenPersistence>,
+        refresh_token_ttl: chrono::Duration,
+        two_factor: Arc<MockTwoFactorPersistence>,
+        login_throttle: Arc<MockLoginThrottlePersistence>,
+    ) -> UserUseCases {
+        UserUseCases::new(
             Arc::new(MockUserJWTService),
             Arc::new(MockUserCredentialsHasher),
-            Arc::new(MockUserPersistence),
-            Arc::new(MockPatientPersistence),
-            Arc::new(MockParentConsentPersistence),
-        );
+            Arc::new(MockUserPersistence::default()),
+            refresh_tokens,
+            refresh_token_ttl,
+            two_factor,
+            Arc::new(MockUserTotpService),
+            login_throttle,
+            LoginThrottle {
+                per_account: LoginThrottlePolicy {
+                    max_failures: 4,
+                    lockout_duration: chrono::Duration::minutes(15),
+                },
+                per_ip: LoginThrottlePolicy {
+                    max_failures: 10,
+                    lockout_duration: chrono::Duration::minutes(15),
+                },
+            },
+        )
+    }
+
+    fn use_cases_with_refresh_ttl(
+        refresh_tokens: Arc<MockRefreshTokenPersistence>,
+        refresh_token_ttl: chrono::Duration,
+    ) -> UserUseCases {
+        build_use_cases(refresh_tokens, refresh_token_ttl, Default::default(), Default::default())
+    }
+
+    fn use_cases_with_two_factor(two_factor: Arc<MockTwoFactorPersistence>) -> UserUseCases {
+        build_use_cases(Default::default(), chrono::Duration::days(30), two_factor, Default::default())
+    }
+
+    fn use_cases_with_login_throttle(login_throttle: Arc<MockLoginThrottlePersistence>) -> UserUseCases {
+        build_use_cases(
+            Default::default(),
+            chrono::Duration::days(30),
+            Default::default(),
+            login_throttle,
+        )
+    }
+
+    fn use_cases(refresh_tokens: Arc<MockRefreshTokenPersistence>) -> UserUseCases {
+        use_cases_with_refresh_ttl(refresh_tokens, chrono::Duration::days(30))
+    }
+
+    async fn registered(use_cases: &UserUseCases) -> AuthTokensDTO {
+        use_cases
+            .add("john", "doe", "testuser@gmail.com", &"testuser_pw".into(), &DEFAULT_ORGANIZATION_ID)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn add_user_works() {
+        let refresh_tokens = Arc::new(MockRefreshTokenPersistence::default());
+        let user_use_cases = use_cases(refresh_tokens.clone());
 
         let result = user_use_cases
-            .add("john", "doe", "testuser@gmail.com", &"testuser_pw".into())
+            .add("john", "doe", "testuser@gmail.com", &"testuser_pw".into(), &DEFAULT_ORGANIZATION_ID)
             .await;
 
         assert!(result.is_ok());
+
+        let tokens = result.unwrap();
+        assert_eq!(tokens.access_token, "token_john_0");
+        // Only the hash is stored
+        let stored = refresh_tokens.tokens.lock().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].token_hash, hash_refresh_token(&tokens.refresh_token));
+    }
+
+    #[tokio::test]
+    async fn refresh_rotates_token() {
+        let refresh_tokens = Arc::new(MockRefreshTokenPersistence::default());
+        let use_cases = use_cases(refresh_tokens.clone());
+        let tokens = registered(&use_cases).await;
+
+        let refreshed = use_cases.refresh(&tokens.refresh_token).await.unwrap();
+
+        assert_ne!(refreshed.refresh_token, tokens.refresh_token);
+        assert!(use_cases.refresh(&refreshed.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reused_refresh_token_revokes_family() {
+        let refresh_tokens = Arc::new(MockRefreshTokenPersistence::default());
+        let use_cases = use_cases(refresh_tokens.clone());
+        let tokens = registered(&use_cases).await;
+        let refreshed = use_cases.refresh(&tokens.refresh_token).await.unwrap();
+
+        let reused = use_cases.refresh(&tokens.refresh_token).await;
+
+        assert!(matches!(reused, Err(AppError::Unauthorized(_))));
+        assert!(matches!(
+            use_cases.refresh(&refreshed.refresh_token).await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn refresh_with_unknown_token_fails() {
+        let use_cases = use_cases(Arc::new(MockRefreshTokenPersistence::default()));
+
+        let result = use_cases.refresh("unknown").await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn refresh_with_expired_token_fails() {
+        let use_cases = use_cases_with_refresh_ttl(
+            Arc::new(MockRefreshTokenPersistence::default()),
+            chrono::Duration::seconds(-1),
+        );
+        let tokens = registered(&use_cases).await;
+
+        let result = use_cases.refresh(&tokens.refresh_token).await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn logout_revokes_refresh_token() {
+        let use_cases = use_cases(Arc::new(MockRefreshTokenPersistence::default()));
+        let tokens = registered(&use_cases).await;
+
+        use_cases.logout(&tokens.refresh_token).await.unwrap();
+
+        assert!(use_cases.refresh(&tokens.refresh_token).await.is_err());
+        assert!(use_cases.logout("unknown").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn revoke_sessions_revokes_every_login() {
+        let refresh_tokens = Arc::new(MockRefreshTokenPersistence::default());
+        let use_cases = use_cases(refresh_tokens.clone());
+        let first = registered(&use_cases).await;
+        let second = registered(&use_cases).await;
+        // Same user on both logins
+        let user_id = refresh_tokens.tokens.lock().unwrap()[0].user_id;
+        refresh_tokens.tokens.lock().unwrap()[1].user_id = user_id;
+
+        let revoked = use_cases.revoke_sessions(&user_id).await.unwrap();
+
+        assert_eq!(revoked, 2);
+        assert!(use_cases.refresh(&first.refresh_token).await.is_err());
+        assert!(use_cases.refresh(&second.refresh_token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn change_password_revokes_other_logins() {
+        let refresh_tokens = Arc::new(MockRefreshTokenPersistence::default());
+        let use_cases = use_cases(refresh_tokens.clone());
+        let tokens = registered(&use_cases).await;
+        let user_id = refresh_tokens.tokens.lock().unwrap()[0].user_id;
+
+        let changed = use_cases
+            .change_password(&user_id, &"testuser_pw".into(), &"new_pw".into())
+            .await
+            .unwrap();
+
+        assert!(use_cases.refresh(&tokens.refresh_token).await.is_err());
+        assert!(use_cases.refresh(&changed.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn change_password_with_wrong_current_password_fails() {
+        let refresh_tokens = Arc::new(MockRefreshTokenPersistence::default());
+        let use_cases = use_cases(refresh_tokens.clone());
+        let tokens = registered(&use_cases).await;
+        let user_id = refresh_tokens.tokens.lock().unwrap()[0].user_id;
+
+        let result = use_cases
+            .change_password(&user_id, &"wrong_pw".into(), &"new_pw".into())
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidCredentials)));
+        assert!(use_cases.refresh(&tokens.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn change_password_revokes_access_tokens() {
+        let use_cases = use_cases(Default::default());
+        let tokens = registered(&use_cases).await;
+        assert!(use_cases.authenticate(&tokens.access_token).await.is_ok());
+
+        let changed = use_cases
+            .change_password(&Uuid::nil(), &"testuser_pw".into(), &"new_pw".into())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            use_cases.authenticate(&tokens.access_token).await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(use_cases.authenticate(&changed.access_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn revoke_sessions_revokes_access_tokens() {
+        let use_cases = use_cases(Default::default());
+        let tokens = registered(&use_cases).await;
+
+        use_cases.revoke_sessions(&Uuid::nil()).await.unwrap();
+
+        assert!(matches!(
+            use_cases.authenticate(&tokens.access_token).await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    const CLIENT_IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7));
+
+    async fn logged_in(use_cases: &UserUseCases) -> LoginOutcomeDTO {
+        use_cases
+            .login("testuser@gmail.com", &"testuser_pw".into(), &CLIENT_IP)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn login_without_two_factor_returns_tokens() {
+        let use_cases = use_cases_with_two_factor(Default::default());
+
+        let outcome = logged_in(&use_cases).await;
+
+        assert!(matches!(outcome, LoginOutcomeDTO::Authenticated(_)));
+    }
+
+    #[tokio::test]
+    async fn login_with_two_factor_requires_code() {
+        let use_cases = use_cases_with_two_factor(Arc::new(MockTwoFactorPersistence::enabled()));
+
+        let LoginOutcomeDTO::TwoFactorRequired { pre_auth_token } = logged_in(&use_cases).await else {
+            panic!("Expected the two factor step");
+        };
+
+        assert!(matches!(
+            use_cases.login_two_factor(&pre_auth_token, "000000", &CLIENT_IP).await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            use_cases.login_two_factor("token_john_0", VALID_TOTP_CODE, &CLIENT_IP).await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(use_cases.login_two_factor(&pre_auth_token, VALID_TOTP_CODE, &CLIENT_IP).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn login_two_factor_recovery_code_works_once() {
+        let use_cases = use_cases_with_two_factor(Arc::new(MockTwoFactorPersistence::enabled()));
+        let LoginOutcomeDTO::TwoFactorRequired { pre_auth_token } = logged_in(&use_cases).await else {
+            panic!("Expected the two factor step");
+        };
+
+        assert!(use_cases.login_two_factor(&pre_auth_token, RECOVERY_CODE, &CLIENT_IP).await.is_ok());
+        assert!(matches!(
+            use_cases.login_two_factor(&pre_auth_token, RECOVERY_CODE, &CLIENT_IP).await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn login_with_required_role_enrolls_two_factor() {
+        let two_factor = Arc::new(MockTwoFactorPersistence::required_for(Role::Patient));
+        let use_cases = use_cases_with_two_factor(two_factor.clone());
+
+        let LoginOutcomeDTO::TwoFactorEnrollmentRequired { pre_auth_token } = logged_in(&use_cases).await
+        else {
+            panic!("Expected the two factor enrollment");
+        };
+
+        let enrollment = use_cases.enroll_two_factor_on_login(&pre_auth_token).await.unwrap();
+        assert_eq!(enrollment.otpauth_uri, "otpauth://totp/testuser@gmail.com?secret=JBSWY3DPEHPK3PXP");
+
+        let activation = use_cases
+            .activate_two_factor_on_login(&pre_auth_token, VALID_TOTP_CODE)
+            .await
+            .unwrap();
+
+        assert_eq!(activation.recovery_codes.len(), 10);
+        assert_eq!(activation.tokens.access_token, "token_john_0");
+        assert!(two_factor.two_factor.lock().unwrap().as_ref().unwrap().is_enabled());
+        assert!(matches!(logged_in(&use_cases).await, LoginOutcomeDTO::TwoFactorRequired { .. }));
+    }
+
+    #[tokio::test]
+    async fn activate_two_factor_with_wrong_code_stays_pending() {
+        let two_factor = Arc::new(MockTwoFactorPersistence::default());
+        let use_cases = use_cases_with_two_factor(two_factor.clone());
+        let user_id = Uuid::new_v4();
+        use_cases.enroll_two_factor(&user_id).await.unwrap();
+
+        let result = use_cases.activate_two_factor(&user_id, "000000").await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        assert!(!two_factor.two_factor.lock().unwrap().as_ref().unwrap().is_enabled());
+        assert!(matches!(logged_in(&use_cases).await, LoginOutcomeDTO::Authenticated(_)));
+    }
+
+    #[tokio::test]
+    async fn enroll_two_factor_when_enabled_conflicts() {
+        let use_cases = use_cases_with_two_factor(Arc::new(MockTwoFactorPersistence::enabled()));
+
+        let result = use_cases.enroll_two_factor(&Uuid::new_v4()).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn disable_two_factor_respects_role_requirement() {
+        let two_factor = Arc::new(MockTwoFactorPersistence::enabled());
+        let use_cases = use_cases_with_two_factor(two_factor.clone());
+        let user_id = Uuid::new_v4();
+        use_cases.set_two_factor_requirement(&Role::Patient, true).await.unwrap();
+
+        assert!(matches!(
+            use_cases.disable_two_factor(&user_id, &"testuser_pw".into()).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        use_cases.set_two_factor_requirement(&Role::Patient, false).await.unwrap();
+
+        assert!(matches!(
+            use_cases.disable_two_factor(&user_id, &"wrong_pw".into()).await,
+            Err(AppError::InvalidCredentials)
+        ));
+        assert!(use_cases.disable_two_factor(&user_id, &"testuser_pw".into()).await.is_ok());
+        assert!(two_factor.two_factor.lock().unwrap().is_none());
+    }
+
+    async fn failed_login(use_cases: &UserUseCases, email: &str) -> AppResult<LoginOutcomeDTO> {
+        use_cases.login(email, &"wrong_pw".into(), &CLIENT_IP).await
+    }
+
+    #[tokio::test]
+    async fn login_with_unknown_email_fails_like_wrong_password() {
+        let login_throttle = Arc::new(MockLoginThrottlePersistence::default());
+        let use_cases = use_cases_with_login_throttle(login_throttle.clone());
+
+        let unknown = failed_login(&use_cases, "nobody@gmail.com").await;
+        let wrong_password = failed_login(&use_cases, "testuser@gmail.com").await;
+
+        assert!(matches!(unknown, Err(AppError::InvalidCredentials)));
+        assert!(matches!(wrong_password, Err(AppError::InvalidCredentials)));
+        assert_eq!(login_throttle.failures_of(LoginSubjectKind::Ip), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_logins_back_off_then_lock_the_account() {
+        let login_throttle = Arc::new(MockLoginThrottlePersistence::default());
+        let use_cases = use_cases_with_login_throttle(login_throttle.clone());
+
+        for _ in 0..3 {
+            assert!(matches!(
+                failed_login(&use_cases, "TestUser@gmail.com ").await,
+                Err(AppError::InvalidCredentials)
+            ));
+        }
+
+        // Past half the allowed failures even the right password has to wait
+        assert!(matches!(logged_in_result(&use_cases).await, Err(AppError::TooManyRequests(1))));
+
+        login_throttle.wait(chrono::Duration::seconds(2));
+        assert!(matches!(
+            failed_login(&use_cases, "testuser@gmail.com").await,
+            Err(AppError::InvalidCredentials)
+        ));
+
+        let lockouts = use_cases
+            .get_login_lockouts(chrono::Utc::now().naive_utc() - chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(lockouts.len(), 1);
+        assert_eq!(lockouts[0].kind, LoginSubjectKind::Account);
+        assert_eq!(lockouts[0].subject, "testuser@gmail.com");
+        assert!(matches!(
+            logged_in_result(&use_cases).await,
+            Err(AppError::TooManyRequests(seconds)) if seconds > 60
+        ));
+
+        login_throttle.wait(chrono::Duration::minutes(15));
+        assert!(logged_in_result(&use_cases).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn successful_login_clears_account_failures_only() {
+        let login_throttle = Arc::new(MockLoginThrottlePersistence::default());
+        let use_cases = use_cases_with_login_throttle(login_throttle.clone());
+        failed_login(&use_cases, "testuser@gmail.com").await.unwrap_err();
+
+        logged_in(&use_cases).await;
+
+        assert_eq!(login_throttle.failures_of(LoginSubjectKind::Account), 0);
+        assert_eq!(login_throttle.failures_of(LoginSubjectKind::Ip), 1);
+    }
+
+    #[tokio::test]
+    async fn wrong_two_factor_codes_count_as_failed_logins() {
+        let login_throttle = Arc::new(MockLoginThrottlePersistence::default());
+        let use_cases = build_use_cases(
+            Default::default(),
+            chrono::Duration::days(30),
+            Arc::new(MockTwoFactorPersistence::enabled()),
+            login_throttle.clone(),
+        );
+        let LoginOutcomeDTO::TwoFactorRequired { pre_auth_token } = logged_in(&use_cases).await else {
+            panic!("Expected the two factor step");
+        };
+
+        for _ in 0..3 {
+            use_cases.login_two_factor(&pre_auth_token, "000000", &CLIENT_IP).await.unwrap_err();
+        }
+
+        assert!(matches!(
+            use_cases.login_two_factor(&pre_auth_token, VALID_TOTP_CODE, &CLIENT_IP).await,
+            Err(AppError::TooManyRequests(_))
+        ));
+        assert_eq!(login_throttle.failures_of(LoginSubjectKind::Account), 3);
+    }
+
+    async fn logged_in_result(use_cases: &UserUseCases) -> AppResult<LoginOutcomeDTO> {
+        use_cases.login("testuser@gmail.com", &"testuser_pw".into(), &CLIENT_IP).await
     }
 }
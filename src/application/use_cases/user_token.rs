@@ -2,13 +2,15 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
+use secrecy::{ExposeSecret, SecretString};
 use tracing::{info, instrument};
 use uuid::Uuid;
 
 use crate::{
     adapters::utils::verification_token::generate_verification_token,
     app_error::{AppError, AppResult},
-    entities::user_token::UserToken,
+    entities::user_token::{TokenPurpose, UserToken},
+    use_cases::user::UserCredentialsHasher,
 };
 
 #[async_trait]
@@ -17,9 +19,11 @@ pub trait UserTokenPersistence: Send + Sync {
         &self,
         user_id: Uuid,
         token: String,
+        purpose: TokenPurpose,
         expires_at: NaiveDateTime,
     ) -> AppResult<UserToken>;
 
+    /// Returns the current verification token of the user
     async fn check_user_token(&self, user_id: &Uuid) -> AppResult<Option<UserToken>>;
 
     async fn check_validation_status(&self, user_id: &Uuid) -> AppResult<bool>;
@@ -29,6 +33,30 @@ pub trait UserTokenPersistence: Send + Sync {
     async fn add_verification_email(&self, from: &str, to: &str, body: &str) -> AppResult<()>;
 
     async fn verify_user_token(&self, token: &str) -> AppResult<()>;
+
+    async fn get_user_id_by_email(&self, email: &str) -> AppResult<Option<Uuid>>;
+
+    async fn add_password_reset_email(&self, from: &str, to: &str, body: &str) -> AppResult<()>;
+
+    /// Sets the password of the user of a valid reset token, uses up the user reset tokens and revokes the user refresh
+    /// and access tokens, all at once. Returns the user id
+    async fn reset_password(&self, token: &str, password_hash: &str) -> AppResult<Uuid>;
+
+    async fn get_user_password_hash(&self, user_id: &Uuid) -> AppResult<String>;
+
+    async fn add_email_change_token(
+        &self,
+        user_id: Uuid,
+        token: String,
+        new_email: &str,
+        expires_at: NaiveDateTime,
+    ) -> AppResult<UserToken>;
+
+    async fn add_email_change_email(&self, from: &str, to: &str, body: &str) -> AppResult<()>;
+
+    /// Swaps the user email for the one of a valid email change token, uses up the user pending tokens and revokes the user
+    /// refresh and access tokens, all at once. Returns the user id
+    async fn change_email(&self, token: &str) -> AppResult<Uuid>;
 }
 
 #[async_trait]
@@ -39,6 +67,20 @@ pub trait UserTokenEmailService: Send + Sync {
         to: &[String],
         token: &str,
     ) -> AppResult<(String, String)>;
+
+    /// Returns the 'from' email and the email body
+    async fn send_password_reset_email(
+        &self,
+        to: &[String],
+        token: &str,
+    ) -> AppResult<(String, String)>;
+
+    /// Returns the 'from' email and the email body
+    async fn send_email_change_email(
+        &self,
+        to: &[String],
+        token: &str,
+    ) -> AppResult<(String, String)>;
 }
 
 pub trait UserTokenJwtService: Send + Sync {
@@ -50,6 +92,8 @@ pub struct UserTokenUseCases {
     jwt_service: Arc<dyn UserTokenJwtService>,
     email_service: Arc<dyn UserTokenEmailService>,
     persistence: Arc<dyn UserTokenPersistence>,
+    hasher: Arc<dyn UserCredentialsHasher>,
+    password_reset_ttl: chrono::Duration,
 }
 
 impl UserTokenUseCases {
@@ -57,11 +101,15 @@ impl UserTokenUseCases {
         jwt_service: Arc<dyn UserTokenJwtService>,
         email_service: Arc<dyn UserTokenEmailService>,
         persistence: Arc<dyn UserTokenPersistence>,
+        hasher: Arc<dyn UserCredentialsHasher>,
+        password_reset_ttl: chrono::Duration,
     ) -> Self {
         Self {
             jwt_service,
             email_service,
             persistence,
+            hasher,
+            password_reset_ttl,
         }
     }
 
@@ -99,7 +147,7 @@ impl UserTokenUseCases {
 
             let token = self
                 .persistence
-                .add_user_token(user_uuid, token, token_expiry_date)
+                .add_user_token(user_uuid, token, TokenPurpose::Verification, token_expiry_date)
                 .await?;
 
             info!("User token generated.");
@@ -127,7 +175,7 @@ impl UserTokenUseCases {
         Ok(())
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip(self, token))]
     pub async fn verify_token(&self, token: &str) -> AppResult<()> {
         info!("Attempting to verify token...");
 
@@ -138,7 +186,118 @@ impl UserTokenUseCases {
         Ok(())
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip(self, email))]
+    pub async fn request_password_reset(&self, email: &str) -> AppResult<()> {
+        // Flow of this should be:
+        // 0 - Find the user with that email, if there is none do nothing so the emails registered can't be guessed
+        // 1 - Generate a short lived reset token
+        // 2 - Attempt to send the reset email
+        // 3 - If email is sent correctly save email in the database
+
+        info!("Attempting password reset request...");
+
+        let Some(user_id) = self.persistence.get_user_id_by_email(email).await? else {
+            info!("No user with that email, nothing to reset");
+            return Ok(());
+        };
+
+        let token = self
+            .persistence
+            .add_user_token(
+                user_id,
+                generate_verification_token(),
+                TokenPurpose::PasswordReset,
+                (chrono::Utc::now() + self.password_reset_ttl).naive_utc(),
+            )
+            .await?;
+
+        info!("Sending password reset email");
+        let email_res = self
+            .email_service
+            .send_password_reset_email(&[email.to_string()], &token.token)
+            .await?;
+        info!("Sent password reset email");
+
+        self.persistence
+            .add_password_reset_email(&email_res.0, email, &email_res.1)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sets the new password and logs the user out of every device
+    #[instrument(skip(self, token, password))]
+    pub async fn confirm_password_reset(&self, token: &str, password: &SecretString) -> AppResult<()> {
+        info!("Attempting password reset...");
+
+        let password_hash = self.hasher.hash_password(password.expose_secret())?;
+
+        self.persistence.reset_password(token, &password_hash).await?;
+
+        info!("Password reset.");
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, new_email, password))]
+    pub async fn request_email_change(
+        &self,
+        user_id: &Uuid,
+        new_email: &str,
+        password: &SecretString,
+    ) -> AppResult<()> {
+        // Flow of this should be:
+        // 0 - Check the password, a stolen jwt alone should not be enough to take over the account
+        // 1 - Check nobody uses the new email
+        // 2 - Generate a token that carries the new email
+        // 3 - Attempt to send the verification email to the new address
+        // 4 - If email is sent correctly save email in the database
+        // The email of the user is only swapped when the link is opened, see confirm_email_change
+
+        info!("Attempting email change request...");
+
+        let password_hash = self.persistence.get_user_password_hash(user_id).await?;
+        self.hasher.verify_password(&password_hash, password.expose_secret())?;
+
+        if self.persistence.get_user_id_by_email(new_email).await?.is_some() {
+            return Err(AppError::Conflict(String::from("Email already in use")));
+        }
+
+        // expiry date = 1 day from now
+        let token_expiry_date = (chrono::Utc::now() + chrono::Duration::days(1)).naive_utc();
+
+        let token = self
+            .persistence
+            .add_email_change_token(*user_id, generate_verification_token(), new_email, token_expiry_date)
+            .await?;
+
+        info!("Sending email change email");
+        let email_res = self
+            .email_service
+            .send_email_change_email(&[new_email.to_string()], &token.token)
+            .await?;
+        info!("Sent email change email");
+
+        self.persistence
+            .add_email_change_email(&email_res.0, new_email, &email_res.1)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Swaps in the new email and logs the user out of every device
+    #[instrument(skip(self, token))]
+    pub async fn confirm_email_change(&self, token: &str) -> AppResult<()> {
+        info!("Attempting email change...");
+
+        self.persistence.change_email(token).await?;
+
+        info!("Email changed.");
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, token))]
     pub async fn validate_token(&self, token: &str) -> AppResult<()> {
         info!("Attempting to validate token...");
 
@@ -152,12 +311,26 @@ impl UserTokenUseCases {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
     use async_trait::async_trait;
     use uuid::Uuid;
 
     use super::*;
 
-    struct MockUserTokenPersistence;
+    const REGISTERED_EMAIL: &str = "registered@example.com";
+    const RESET_TOKEN: &str = "valid_reset_token";
+    const EMAIL_CHANGE_TOKEN: &str = "valid_email_change_token";
+    const NEW_EMAIL: &str = "new@example.com";
+
+    #[derive(Default)]
+    struct MockUserTokenPersistence {
+        tokens: Mutex<Vec<UserToken>>,
+        reset_emails: Mutex<Vec<String>>,
+        password_hashes: Mutex<Vec<String>>,
+        email_change_emails: Mutex<Vec<String>>,
+        revoked_users: Mutex<Vec<Uuid>>,
+    }
 
     #[async_trait]
     impl UserTokenPersistence for MockUserTokenPersistence {
@@ -165,15 +338,20 @@ mod test {
             &self,
             user_id: Uuid,
             token: String,
+            purpose: TokenPurpose,
             expires_at: NaiveDateTime,
         ) -> AppResult<UserToken> {
-            Ok(UserToken {
+            let token = UserToken {
                 id: Uuid::new_v4(),
                 user_id,
                 token,
+                purpose,
+                new_email: None,
                 expires_at: Some(expires_at),
                 created_at: None,
-            })
+            };
+            self.tokens.lock().unwrap().push(token.clone());
+            Ok(token)
         }
 
         async fn check_user_token(&self, _user_id: &Uuid) -> AppResult<Option<UserToken>> {
@@ -201,6 +379,67 @@ mod test {
             assert!(!token.is_empty());
             Ok(())
         }
+
+        async fn get_user_id_by_email(&self, email: &str) -> AppResult<Option<Uuid>> {
+            Ok((email == REGISTERED_EMAIL).then(Uuid::new_v4))
+        }
+
+        async fn add_password_reset_email(&self, _from: &str, to: &str, _body: &str) -> AppResult<()> {
+            self.reset_emails.lock().unwrap().push(to.to_string());
+            Ok(())
+        }
+
+        async fn reset_password(&self, token: &str, password_hash: &str) -> AppResult<Uuid> {
+            if token != RESET_TOKEN {
+                return Err(AppError::Unauthorized(
+                    "Invalid or expired password reset token".to_string(),
+                ));
+            }
+            self.password_hashes.lock().unwrap().push(password_hash.to_string());
+            let user_id = Uuid::new_v4();
+            self.revoked_users.lock().unwrap().push(user_id);
+            Ok(user_id)
+        }
+
+        async fn get_user_password_hash(&self, _user_id: &Uuid) -> AppResult<String> {
+            Ok(String::from("current_password_hash"))
+        }
+
+        async fn add_email_change_token(
+            &self,
+            user_id: Uuid,
+            token: String,
+            new_email: &str,
+            expires_at: NaiveDateTime,
+        ) -> AppResult<UserToken> {
+            let token = UserToken {
+                id: Uuid::new_v4(),
+                user_id,
+                token,
+                purpose: TokenPurpose::EmailChange,
+                new_email: Some(new_email.to_string()),
+                expires_at: Some(expires_at),
+                created_at: None,
+            };
+            self.tokens.lock().unwrap().push(token.clone());
+            Ok(token)
+        }
+
+        async fn add_email_change_email(&self, _from: &str, to: &str, _body: &str) -> AppResult<()> {
+            self.email_change_emails.lock().unwrap().push(to.to_string());
+            Ok(())
+        }
+
+        async fn change_email(&self, token: &str) -> AppResult<Uuid> {
+            if token != EMAIL_CHANGE_TOKEN {
+                return Err(AppError::Unauthorized(
+                    "Invalid or expired email change token".to_string(),
+                ));
+            }
+            let user_id = Uuid::new_v4();
+            self.revoked_users.lock().unwrap().push(user_id);
+            Ok(user_id)
+        }
     }
 
     struct MockUserTokenEmailService;
@@ -214,6 +453,22 @@ mod test {
         ) -> AppResult<(String, String)> {
             Ok((String::new(), String::new()))
         }
+
+        async fn send_password_reset_email(
+            &self,
+            _to: &[String],
+            _token: &str,
+        ) -> AppResult<(String, String)> {
+            Ok((String::new(), String::new()))
+        }
+
+        async fn send_email_change_email(
+            &self,
+            _to: &[String],
+            _token: &str,
+        ) -> AppResult<(String, String)> {
+            Ok((String::new(), String::new()))
+        }
     }
 
     struct MockUserTokenJwtService;
@@ -224,13 +479,35 @@ mod test {
         }
     }
 
-    #[tokio::test]
-    async fn generate_token_works() {
-        let user_token_use_cases = UserTokenUseCases::new(
+    struct MockUserCredentialsHasher;
+
+    impl UserCredentialsHasher for MockUserCredentialsHasher {
+        fn hash_password(&self, password: &str) -> AppResult<String> {
+            Ok(format!("{}_hash", password))
+        }
+
+        fn verify_password(&self, user_password_hash: &str, input_password: &str) -> AppResult<()> {
+            if user_password_hash == format!("{}_hash", input_password) {
+                Ok(())
+            } else {
+                Err(AppError::InvalidCredentials)
+            }
+        }
+    }
+
+    fn use_cases(persistence: Arc<MockUserTokenPersistence>) -> UserTokenUseCases {
+        UserTokenUseCases::new(
             Arc::new(MockUserTokenJwtService),
             Arc::new(MockUserTokenEmailService),
-            Arc::new(MockUserTokenPersistence),
-        );
+            persistence,
+            Arc::new(MockUserCredentialsHasher),
+            chrono::Duration::minutes(30),
+        )
+    }
+
+    #[tokio::test]
+    async fn generate_token_works() {
+        let user_token_use_cases = use_cases(Default::default());
 
         let result = user_token_use_cases
             .generate_token_and_send_mail("24d7fa6e-4c52-40ff-ad25-5271e8c48345") // this does not mean the user is in the db, this is just a valid uuid
@@ -238,4 +515,123 @@ mod test {
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn request_password_reset_stores_short_lived_reset_token() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases.request_password_reset(REGISTERED_EMAIL).await;
+
+        assert!(result.is_ok());
+        let tokens = persistence.tokens.lock().unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].purpose, TokenPurpose::PasswordReset);
+        assert!(
+            tokens[0].expires_at.unwrap()
+                <= (chrono::Utc::now() + chrono::Duration::minutes(30)).naive_utc()
+        );
+        assert_eq!(*persistence.reset_emails.lock().unwrap(), vec![REGISTERED_EMAIL.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn request_password_reset_unknown_email_does_nothing() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases.request_password_reset("unknown@example.com").await;
+
+        assert!(result.is_ok());
+        assert!(persistence.tokens.lock().unwrap().is_empty());
+        assert!(persistence.reset_emails.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn confirm_password_reset_rehashes_and_revokes_sessions() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases
+            .confirm_password_reset(RESET_TOKEN, &SecretString::from("new_password"))
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(
+            *persistence.password_hashes.lock().unwrap(),
+            vec!["new_password_hash".to_string()]
+        );
+        assert_eq!(persistence.revoked_users.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn confirm_password_reset_invalid_token_fails() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases
+            .confirm_password_reset("wrong_token", &SecretString::from("new_password"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        assert!(persistence.revoked_users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn request_email_change_sends_token_to_new_email() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases
+            .request_email_change(&Uuid::new_v4(), NEW_EMAIL, &SecretString::from("current_password"))
+            .await;
+
+        assert!(result.is_ok());
+        let tokens = persistence.tokens.lock().unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].purpose, TokenPurpose::EmailChange);
+        assert_eq!(tokens[0].new_email.as_deref(), Some(NEW_EMAIL));
+        assert_eq!(*persistence.email_change_emails.lock().unwrap(), vec![NEW_EMAIL.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn request_email_change_with_wrong_password_fails() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases
+            .request_email_change(&Uuid::new_v4(), NEW_EMAIL, &SecretString::from("wrong_password"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidCredentials)));
+        assert!(persistence.tokens.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn request_email_change_to_used_email_conflicts() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases
+            .request_email_change(&Uuid::new_v4(), REGISTERED_EMAIL, &SecretString::from("current_password"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert!(persistence.tokens.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn confirm_email_change_revokes_sessions() {
+        let persistence = Arc::new(MockUserTokenPersistence::default());
+        let user_token_use_cases = use_cases(persistence.clone());
+
+        let result = user_token_use_cases.confirm_email_change(EMAIL_CHANGE_TOKEN).await;
+
+        assert!(result.is_ok());
+        assert_eq!(persistence.revoked_users.lock().unwrap().len(), 1);
+
+        let invalid = user_token_use_cases.confirm_email_change("wrong_token").await;
+
+        assert!(matches!(invalid, Err(AppError::Unauthorized(_))));
+        assert_eq!(persistence.revoked_users.lock().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Erasure of the personal data of a user, requested by the user and carried out once its grace period is over.
+/// Until then the user can change its mind
+#[derive(Debug, Clone)]
+pub struct AccountDeletion {
+    pub user_id: Uuid,
+    pub requested_by: Uuid, // the user itself, or the admin erasing it without grace period
+    pub requested_at: Option<NaiveDateTime>,
+    pub scheduled_for: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl AccountDeletion {
+    pub fn new(user_id: Uuid, requested_by: Uuid, scheduled_for: NaiveDateTime) -> Self {
+        Self {
+            user_id,
+            requested_by,
+            requested_at: None,
+            scheduled_for,
+            completed_at: None,
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+}
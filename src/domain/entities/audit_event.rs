@@ -0,0 +1,113 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::entities::{permission::Actor, user::Role};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Read,
+    Update,
+    Delete,
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditAction::Create => write!(f, "create"),
+            AuditAction::Read => write!(f, "read"),
+            AuditAction::Update => write!(f, "update"),
+            AuditAction::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(AuditAction::Create),
+            "read" => Ok(AuditAction::Read),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            _ => Err(format!("Unknown audit action {}", s)),
+        }
+    }
+}
+
+/// Kinds of records whose access is audited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditResource {
+    Patient,
+    Session,
+    ClinicalNote, // every version included
+}
+
+impl Display for AuditResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditResource::Patient => write!(f, "patient"),
+            AuditResource::Session => write!(f, "session"),
+            AuditResource::ClinicalNote => write!(f, "clinical_note"),
+        }
+    }
+}
+
+impl FromStr for AuditResource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "patient" => Ok(AuditResource::Patient),
+            "session" => Ok(AuditResource::Session),
+            "clinical_note" => Ok(AuditResource::ClinicalNote),
+            _ => Err(format!("Unknown audit resource {}", s)),
+        }
+    }
+}
+
+/// A read or a change of a clinical record by an actor, never updated nor deleted
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub actor_user_id: Uuid,
+    pub actor_role: Role,
+    pub action: AuditAction,
+    pub resource_type: AuditResource,
+    pub resource_id: Uuid,
+    pub ip: Option<String>,
+    pub request_id: Option<Uuid>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl AuditEvent {
+    pub fn new(actor: &Actor, action: AuditAction, resource_type: AuditResource, resource_id: Uuid) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            actor_user_id: actor.user_id,
+            actor_role: actor.role.clone(),
+            action,
+            resource_type,
+            resource_id,
+            ip: actor.origin.map(|origin| origin.ip.to_string()),
+            request_id: actor.origin.map(|origin| origin.request_id),
+            created_at: None,
+        }
+    }
+}
+
+/// Events an admin looks for, every field narrows the result
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub actor_user_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub resource_type: Option<AuditResource>,
+    pub resource_id: Option<Uuid>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub limit: i64,
+    pub offset: i64,
+}
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+
+/// Refund applied when a patient cancels a paid session
+#[derive(Debug, Clone, Copy)]
+pub struct CancellationPolicy {
+    pub full_refund_notice_hours: i64, // cancelling at least this long before the session refunds everything
+    pub late_refund_percent: i64, // share refunded for the cancellations after that, 0 means no refund
+}
+
+impl CancellationPolicy {
+    /// Amount to give back, in the same unit as `paid`
+    pub fn refund_amount(&self, paid: i64, session_date: NaiveDateTime, cancelled_at: NaiveDateTime) -> i64 {
+        if session_date - cancelled_at >= chrono::Duration::hours(self.full_refund_notice_hours) {
+            return paid;
+        }
+
+        paid * self.late_refund_percent.clamp(0, 100) / 100
+    }
+}
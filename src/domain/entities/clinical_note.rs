@@ -0,0 +1,134 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Note written by the professional about a session. It is never changed in place, every edit adds a version
+#[derive(Debug, Clone)]
+pub struct ClinicalNote {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub author_user_id: Option<Uuid>, // None for the legacy notes of a professional without a user
+    pub legacy: bool,                 // moved from the free text notes of the sessions
+    pub created_at: Option<NaiveDateTime>,
+    pub current: ClinicalNoteVersion, // the latest version
+}
+
+/// Content of a note as written at some point
+#[derive(Debug, Clone)]
+pub struct ClinicalNoteVersion {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub version: i32, // starts at 1
+    pub visibility: NoteVisibility,
+    pub sections: SoapSections,
+    pub edited_by: Option<Uuid>, // same as the author for the first version
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Sections of a SOAP note, any of them can be left out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoapSections {
+    pub subjective: Option<String>, // what the patient reports
+    pub objective: Option<String>,  // what the professional observes
+    pub assessment: Option<String>,
+    pub plan: Option<String>,
+}
+
+/// Who can read a note besides the professional of the session and the admins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NoteVisibility {
+    #[default]
+    Professional, // professional only
+    Patient,      // shared with the patient of the session
+}
+
+impl Display for NoteVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoteVisibility::Professional => write!(f, "professional"),
+            NoteVisibility::Patient => write!(f, "patient"),
+        }
+    }
+}
+
+impl FromStr for NoteVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "professional" => Ok(NoteVisibility::Professional),
+            "patient" => Ok(NoteVisibility::Patient),
+            _ => Err(format!("Unknown note visibility {}", s)),
+        }
+    }
+}
+
+impl SoapSections {
+    /// Blank sections count as left out
+    pub fn is_empty(&self) -> bool {
+        [&self.subjective, &self.objective, &self.assessment, &self.plan]
+            .iter()
+            .all(|section| section.as_deref().is_none_or(|text| text.trim().is_empty()))
+    }
+}
+
+impl ClinicalNote {
+    pub fn new(
+        session_id: Uuid,
+        author_user_id: Uuid,
+        visibility: NoteVisibility,
+        sections: SoapSections,
+    ) -> Self {
+        Self::with_author(session_id, Some(author_user_id), false, visibility, sections)
+    }
+
+    /// Note holding the free text notes of a session, kept from the patient like they were
+    pub fn legacy(session_id: Uuid, author_user_id: Option<Uuid>, sections: SoapSections) -> Self {
+        Self::with_author(session_id, author_user_id, true, NoteVisibility::Professional, sections)
+    }
+
+    fn with_author(
+        session_id: Uuid,
+        author_user_id: Option<Uuid>,
+        legacy: bool,
+        visibility: NoteVisibility,
+        sections: SoapSections,
+    ) -> Self {
+        let id = Uuid::new_v4();
+
+        Self {
+            id,
+            session_id,
+            author_user_id,
+            legacy,
+            created_at: None,
+            current: ClinicalNoteVersion {
+                id: Uuid::new_v4(),
+                note_id: id,
+                version: 1,
+                visibility,
+                sections,
+                edited_by: author_user_id,
+                created_at: None,
+            },
+        }
+    }
+
+    /// The version an edit of the note adds
+    pub fn revise(&self, edited_by: Uuid, visibility: NoteVisibility, sections: SoapSections) -> ClinicalNoteVersion {
+        ClinicalNoteVersion {
+            id: Uuid::new_v4(),
+            note_id: self.id,
+            version: self.current.version + 1,
+            visibility,
+            sections,
+            edited_by: Some(edited_by),
+            created_at: None,
+        }
+    }
+
+    pub fn is_visible_to_patient(&self) -> bool {
+        self.current.visibility == NoteVisibility::Patient
+    }
+}
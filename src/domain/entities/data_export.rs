@@ -0,0 +1,73 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// A pending export older than this was interrupted (e.g. by a restart) and no longer blocks a new one
+pub const DATA_EXPORT_GENERATION_TIMEOUT_MINUTES: i64 = 30;
+
+/// Copy of the personal data of a user, requested by the user itself and generated in the background
+#[derive(Debug, Clone)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: DataExportStatus,
+    pub created_at: Option<NaiveDateTime>,
+    pub completed_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>, // set once ready, the archive can't be downloaded after it
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataExportStatus {
+    #[default]
+    Pending,
+    Ready,
+    Failed,
+}
+
+impl Display for DataExportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataExportStatus::Pending => write!(f, "pending"),
+            DataExportStatus::Ready => write!(f, "ready"),
+            DataExportStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl FromStr for DataExportStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(DataExportStatus::Pending),
+            "ready" => Ok(DataExportStatus::Ready),
+            "failed" => Ok(DataExportStatus::Failed),
+            _ => Err(format!("Unknown data export status {}", s)),
+        }
+    }
+}
+
+impl DataExport {
+    pub fn new(user_id: Uuid) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            status: DataExportStatus::Pending,
+            created_at: None,
+            completed_at: None,
+            expires_at: None,
+        }
+    }
+
+    pub fn is_generating(&self, now: NaiveDateTime) -> bool {
+        self.status == DataExportStatus::Pending
+            && self.created_at.is_none_or(|created_at| {
+                created_at + chrono::Duration::minutes(DATA_EXPORT_GENERATION_TIMEOUT_MINUTES) > now
+            })
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
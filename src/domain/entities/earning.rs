@@ -0,0 +1,105 @@
+use std::fmt::Display;
+
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::entities::transaction::Transaction;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EarningKind {
+    Session, // payment of a session
+    Refund,  // part of a payment given back, negative amounts
+}
+
+impl Display for EarningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EarningKind::Session => write!(f, "session"),
+            EarningKind::Refund => write!(f, "refund"),
+        }
+    }
+}
+
+impl From<String> for EarningKind {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "refund" => EarningKind::Refund,
+            _ => EarningKind::Session,
+        }
+    }
+}
+
+/// Entry of the earnings ledger, amounts are in cents
+#[derive(Debug, Clone)]
+pub struct Earning {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub professional_id: Uuid,
+    pub kind: EarningKind,
+    pub gross_amount: i64,
+    pub commission_percent: i64,
+    pub commission_amount: i64,
+    pub net_amount: i64,
+    pub currency: String,
+    pub earned_at: NaiveDateTime,
+    pub paid_out_at: Option<NaiveDateTime>,
+}
+
+impl Earning {
+    /// Attributes a paid transaction to the professional of the session, keeping the commission of the platform
+    pub fn for_session(
+        transaction: &Transaction,
+        professional_id: Uuid,
+        commission: &PlatformCommission,
+        currency: String,
+    ) -> Self {
+        let gross_amount = transaction.amount.unwrap_or_default();
+        let commission_amount = commission.of(gross_amount);
+
+        Self {
+            id: Uuid::new_v4(),
+            transaction_id: transaction.id,
+            session_id: transaction.booked_session_id,
+            professional_id,
+            kind: EarningKind::Session,
+            gross_amount,
+            commission_percent: commission.percent,
+            commission_amount,
+            net_amount: gross_amount - commission_amount,
+            currency,
+            earned_at: chrono::Utc::now().naive_utc(),
+            paid_out_at: None,
+        }
+    }
+
+    /// Takes back a refunded amount of this entry, the commission is given back in the same proportion
+    pub fn reversal(&self, refunded_amount: i64) -> Self {
+        let commission = PlatformCommission { percent: self.commission_percent };
+        let commission_amount = commission.of(refunded_amount);
+
+        Self {
+            id: Uuid::new_v4(),
+            kind: EarningKind::Refund,
+            gross_amount: -refunded_amount,
+            commission_amount: -commission_amount,
+            net_amount: -(refunded_amount - commission_amount),
+            earned_at: chrono::Utc::now().naive_utc(),
+            paid_out_at: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// Share of every payment kept by the platform
+#[derive(Debug, Clone, Copy)]
+pub struct PlatformCommission {
+    pub percent: i64,
+}
+
+impl PlatformCommission {
+    /// Commission of an amount, rounded to the nearest cent
+    pub fn of(&self, amount: i64) -> i64 {
+        (amount * self.percent.clamp(0, 100) + 50).div_euclid(100)
+    }
+}
@@ -17,12 +17,18 @@ pub struct Email {
 pub enum EmailKind {
     #[default]
     Verification,
+    SessionCancelled,
+    PasswordReset,
+    EmailChange,
 }
 
 impl Display for EmailKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self {
             EmailKind::Verification => write!(f, "Verification"),
+            EmailKind::SessionCancelled => write!(f, "SessionCancelled"),
+            EmailKind::PasswordReset => write!(f, "PasswordReset"),
+            EmailKind::EmailChange => write!(f, "EmailChange"),
         }
     }
 }
@@ -31,12 +37,18 @@ impl EmailKind {
     pub fn to_id(self) -> i32 {
         match self {
             EmailKind::Verification => 1,
+            EmailKind::SessionCancelled => 2,
+            EmailKind::PasswordReset => 3,
+            EmailKind::EmailChange => 4,
         }
     }
 
     pub fn from_id(id: i32) -> Option<Self> {
         match id {
             1 => Some(EmailKind::Verification),
+            2 => Some(EmailKind::SessionCancelled),
+            3 => Some(EmailKind::PasswordReset),
+            4 => Some(EmailKind::EmailChange),
             _ => None,
         }
     }
@@ -0,0 +1,88 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Exemption that applies to the healthcare services provided by the professionals
+pub const HEALTHCARE_VAT_EXEMPTION: &str = "Operación exenta de IVA (art. 20.Uno.3º Ley 37/1992)";
+
+/// Invoice issued for a paid transaction, the billing parties are a snapshot taken when it was issued
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub patient_id: Uuid,
+    pub professional_id: Uuid,
+    pub fiscal_year: i32,
+    pub number: i32, // 0 until the persistence assigns the next one of the fiscal year of the professional
+    pub issued_at: NaiveDateTime,
+    pub issuer: BillingParty,    // the professional providing the service
+    pub recipient: BillingParty, // the patient
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+}
+
+impl Invoice {
+    /// Number shown on the invoice, e.g. 2026-000042
+    pub fn invoice_number(&self) -> String {
+        format!("{}-{:06}", self.fiscal_year, self.number)
+    }
+
+    /// Cents, VAT included
+    pub fn total(&self) -> i64 {
+        self.lines.iter().map(InvoiceLine::total).sum()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BillingParty {
+    pub name: String,
+    pub tax_id: Option<String>, // NIF/NIE
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub quantity: i32,
+    pub unit_amount: i64, // cents, VAT excluded
+    pub vat_rate: i32,    // percentage
+    pub vat_exemption: Option<String>, // legal reason when the line is exempt
+}
+
+impl InvoiceLine {
+    /// VAT-exempt healthcare service
+    pub fn healthcare_service(description: String, unit_amount: i64) -> Self {
+        Self {
+            description,
+            quantity: 1,
+            unit_amount,
+            vat_rate: 0,
+            vat_exemption: Some(HEALTHCARE_VAT_EXEMPTION.to_string()),
+        }
+    }
+
+    pub fn base(&self) -> i64 {
+        self.unit_amount * i64::from(self.quantity)
+    }
+
+    pub fn vat(&self) -> i64 {
+        self.base() * i64::from(self.vat_rate) / 100
+    }
+
+    pub fn total(&self) -> i64 {
+        self.base() + self.vat()
+    }
+}
+
+/// Billing details a user fills in to appear on the invoices
+#[derive(Debug, Clone)]
+pub struct BillingDetails {
+    pub user_id: Uuid,
+    pub legal_name: String,
+    pub tax_id: String,
+    pub address: String,
+    pub postal_code: String,
+    pub city: String,
+    pub country: String, // ISO 3166-1 alpha-2
+    pub updated_at: Option<NaiveDateTime>,
+}
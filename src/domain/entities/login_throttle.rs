@@ -0,0 +1,104 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{Duration, NaiveDateTime};
+use uuid::Uuid;
+
+/// What the failed logins are counted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginSubjectKind {
+    Account, // the email typed, whether it exists or not
+    Ip,
+}
+
+impl Display for LoginSubjectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginSubjectKind::Account => write!(f, "account"),
+            LoginSubjectKind::Ip => write!(f, "ip"),
+        }
+    }
+}
+
+impl FromStr for LoginSubjectKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "account" => Ok(LoginSubjectKind::Account),
+            "ip" => Ok(LoginSubjectKind::Ip),
+            _ => Err(format!("Unknown login subject kind {}", s)),
+        }
+    }
+}
+
+/// Consecutive failed logins of a subject
+#[derive(Debug, Clone)]
+pub struct LoginFailures {
+    pub kind: LoginSubjectKind,
+    pub subject: String,
+    pub failures: i32,
+    pub last_failed_at: NaiveDateTime,
+}
+
+/// Recorded every time a subject gets locked, for the admins
+#[derive(Debug, Clone)]
+pub struct LoginLockout {
+    pub id: Uuid,
+    pub kind: LoginSubjectKind,
+    pub subject: String,
+    pub failures: i32,
+    pub locked_until: NaiveDateTime,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// After half the allowed failures every new one doubles the wait, from one second up to the lockout duration.
+/// Reaching the allowed failures locks the subject for the lockout duration
+#[derive(Debug, Clone, Copy)]
+pub struct LoginThrottlePolicy {
+    pub max_failures: i32,
+    pub lockout_duration: Duration, // failures older than this are forgotten
+}
+
+impl LoginThrottlePolicy {
+    /// When the subject can try again, none if it is not throttled
+    pub fn blocked_until(&self, failures: &LoginFailures) -> Option<NaiveDateTime> {
+        let wait = if failures.failures >= self.max_failures {
+            self.lockout_duration
+        } else {
+            let backoff_steps = failures.failures - self.max_failures / 2;
+            if backoff_steps <= 0 {
+                return None;
+            }
+            // Capped before the shift can overflow, the lockout duration is the longest wait anyway
+            Duration::seconds(1 << (backoff_steps - 1).min(30)).min(self.lockout_duration)
+        };
+
+        Some(failures.last_failed_at + wait)
+    }
+
+    /// Whether this failure is the one that locks the subject
+    pub fn locks(&self, failures: &LoginFailures) -> bool {
+        failures.failures == self.max_failures
+    }
+
+    /// Failures before this are forgotten when a new one is recorded
+    pub fn forget_before(&self, now: NaiveDateTime) -> NaiveDateTime {
+        now - self.lockout_duration
+    }
+}
+
+/// Failed logins are throttled by account and by ip, an ip can be shared so it is allowed more failures
+#[derive(Debug, Clone, Copy)]
+pub struct LoginThrottle {
+    pub per_account: LoginThrottlePolicy,
+    pub per_ip: LoginThrottlePolicy,
+}
+
+impl LoginThrottle {
+    pub fn policy(&self, kind: LoginSubjectKind) -> &LoginThrottlePolicy {
+        match kind {
+            LoginSubjectKind::Account => &self.per_account,
+            LoginSubjectKind::Ip => &self.per_ip,
+        }
+    }
+}
@@ -1,15 +1,29 @@
 pub mod transaction;
+pub mod account_deletion;
+pub mod audit_event;
 pub mod blog_post;
+pub mod cancellation_policy;
+pub mod clinical_note;
+pub mod data_export;
+pub mod earning;
 pub mod email;
 pub mod gender;
+pub mod invoice;
+pub mod login_throttle;
+pub mod organization;
 pub mod parent_consent;
 pub mod patient;
+pub mod permission;
 pub mod professional;
+pub mod professional_availability;
 pub mod professional_language;
 pub mod professional_specialization;
+pub mod refresh_token;
 pub mod session;
 pub mod session_type;
 pub mod sexual_orientation;
+pub mod two_factor;
 pub mod user;
 pub mod user_token;
+pub mod videocall;
 pub mod onboarding;
@@ -0,0 +1,24 @@
+use uuid::Uuid;
+
+use crate::entities::user::Role;
+
+/// The organization everything created before there were several belongs to, and the one users register into by default
+pub const DEFAULT_ORGANIZATION_ID: Uuid = Uuid::from_u128(1);
+
+/// A clinic, its professionals, patients, sessions, session types and blog posts are only visible to its members
+#[derive(Debug)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug)]
+pub struct OrganizationMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub usersurname: String,
+    pub email: String,
+    pub role: Role,
+    pub joined_at: Option<chrono::NaiveDateTime>,
+}
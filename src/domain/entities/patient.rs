@@ -41,3 +41,15 @@ impl From<&User> for Patient {
         }
     }
 }
+
+impl Patient {
+    /// The record without the medical history, the medications and the allergies
+    pub fn without_clinical_data(self) -> Self {
+        Self {
+            medical_history: None,
+            current_medications: None,
+            allergies: None,
+            ..self
+        }
+    }
+}
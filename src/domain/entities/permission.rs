@@ -0,0 +1,84 @@
+use std::net::IpAddr;
+
+use uuid::Uuid;
+
+use crate::entities::user::Role;
+
+/// Actions a role is granted over every resource of a kind. Access to the resources the user takes part in
+/// (their own patient record, the sessions they attend) is not a permission, the use cases check it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ReadPatients,              // every patient record, clinical data included
+    ReadPatientContacts,       // every patient record without the clinical data
+    CreatePatients,            // records of other users
+    UpdatePatients,            // records of other users, clinical data included
+    ReadProfessionalSchedules, // sessions of every professional, patients included
+    ManageSessions,            // read, update and cancel the sessions of every professional
+    ReadClinicalNotes,         // notes of every session, the professional only ones included
+    ManageAvailability,        // availability rules and exceptions of every professional
+    JoinVideocalls,            // videocalls of sessions the user doesn't take part in
+    ReadInvoices,              // invoices the user isn't a party of
+    SendVerificationEmails,    // to other users
+}
+
+impl Role {
+    /// Admins can do anything
+    pub fn can(&self, permission: Permission) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Receptionist => matches!(
+                permission,
+                Permission::ReadPatientContacts
+                    | Permission::CreatePatients
+                    | Permission::ReadProfessionalSchedules
+                    | Permission::ManageSessions
+                    | Permission::ManageAvailability
+                    | Permission::ReadInvoices
+                    | Permission::SendVerificationEmails
+            ),
+            Role::Professional => matches!(permission, Permission::CreatePatients),
+            Role::Patient => false, // their own record and sessions only, checked by the use cases
+        }
+    }
+}
+
+/// The authenticated user a use case acts for
+#[derive(Debug, Clone)]
+pub struct Actor {
+    pub user_id: Uuid,
+    pub role: Role,
+    pub origin: Option<RequestOrigin>, // None outside of a request
+}
+
+/// Where the request of an actor came from, recorded with what the actor does
+#[derive(Debug, Clone, Copy)]
+pub struct RequestOrigin {
+    pub ip: IpAddr,
+    pub request_id: Uuid,
+}
+
+impl Actor {
+    pub fn new(user_id: Uuid, role: Role) -> Self {
+        Self {
+            user_id,
+            role,
+            origin: None,
+        }
+    }
+
+    pub fn with_origin(self, origin: RequestOrigin) -> Self {
+        Self {
+            origin: Some(origin),
+            ..self
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.can(permission)
+    }
+
+    /// Whether the actor is the given user, false when there is none
+    pub fn is(&self, user_id: Option<&Uuid>) -> bool {
+        user_id == Some(&self.user_id)
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use uuid::Uuid;
+
+/// Weekly recurring window in which a professional accepts sessions
+#[derive(Debug)]
+pub struct ProfessionalAvailability {
+    pub id: Option<Uuid>, // we option this so we can use the same type for update and create but aside that on_create it should never be None
+    pub professional_id: Uuid,
+    pub weekday: Weekday,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub slot_duration: i32, // minutes
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// One-off block on the availability of a professional (holidays, days off...)
+#[derive(Debug)]
+pub struct AvailabilityException {
+    pub id: Option<Uuid>,
+    pub professional_id: Uuid,
+    pub exception_date: NaiveDate,
+    pub start_time: Option<NaiveTime>, // None on both start and end means the whole day is blocked
+    pub end_time: Option<NaiveTime>,
+    pub reason: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl AvailabilityException {
+    /// Returns the blocked window of this exception on its date
+    pub fn window(&self) -> TimeSlot {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => TimeSlot {
+                start: self.exception_date.and_time(start),
+                end: self.exception_date.and_time(end),
+            },
+            _ => TimeSlot {
+                start: self.exception_date.and_time(NaiveTime::MIN),
+                end: (self.exception_date + chrono::Duration::days(1)).and_time(NaiveTime::MIN),
+            },
+        }
+    }
+}
+
+/// Concrete time range, used both for bookable slots and for already booked sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSlot {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl TimeSlot {
+    pub fn overlaps(&self, other: &TimeSlot) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn contains(&self, other: &TimeSlot) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+}
+
+/// ISO 8601 weekday number, 1 = Monday ... 7 = Sunday
+pub fn weekday_to_id(weekday: Weekday) -> i32 {
+    weekday.number_from_monday() as i32
+}
+
+pub fn weekday_from_id(id: i32) -> Option<Weekday> {
+    if !(1..=7).contains(&id) {
+        return None;
+    }
+
+    Weekday::try_from((id - 1) as u8).ok()
+}
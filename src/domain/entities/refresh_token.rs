@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Long lived token exchanged for new access tokens, only its hash is stored
+#[derive(Debug, Clone)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid, // shared by all the tokens rotated from the same login
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>, // set when rotated, on logout or when the user sessions are revoked
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl RefreshToken {
+    pub fn new(user_id: Uuid, family_id: Uuid, token_hash: String, ttl: chrono::Duration) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            family_id,
+            token_hash,
+            expires_at: chrono::Utc::now().naive_utc() + ttl,
+            revoked_at: None,
+            created_at: None,
+        }
+    }
+}
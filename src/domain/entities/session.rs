@@ -1,7 +1,12 @@
 use std::fmt::Display;
 use uuid::Uuid;
 
-#[derive(Debug)]
+use crate::entities::videocall::VideoCallProvider;
+
+/// Duration assumed for the sessions stored without one, must match the one used by the sessions overlap constraint
+pub const DEFAULT_SESSION_DURATION_MINUTES: i32 = 60;
+
+#[derive(Debug, Clone)]
 pub struct Session {
     pub id: Option<Uuid>, // we option this so we can use the same type for update and create but aside that on_create it should never be None
     pub patient_id: Uuid,
@@ -9,20 +14,23 @@ pub struct Session {
     pub session_type_id: Option<Uuid>,
     pub session_status: SessionStatus,
     pub session_date: Option<chrono::NaiveDateTime>,
-    pub videocall_url: Option<String>,
-    pub notes: Option<String>,
+    pub videocall_url: Option<String>, // joined by the patient
+    pub videocall_provider: Option<VideoCallProvider>, // None for the links set by hand
+    pub videocall_host_url: Option<String>, // joined by the professional, with moderation rights
+    pub videocall_meeting_id: Option<String>,
     pub completed: bool,
     pub session_duration: Option<i32>,
     pub created_at: Option<chrono::NaiveDateTime>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum SessionStatus {
     #[default]
     Scheduled,
     InProgress,
     Completed,
     Cancelled,
+    PendingPayment, // reserved by a patient, becomes Scheduled once the checkout is paid
 }
 
 impl Display for SessionStatus {
@@ -32,6 +40,7 @@ impl Display for SessionStatus {
             SessionStatus::InProgress => write!(f, "InProgress"),
             SessionStatus::Completed => write!(f, "Completed"),
             SessionStatus::Cancelled => write!(f, "Cancelled"),
+            SessionStatus::PendingPayment => write!(f, "PendingPayment"),
         }
     }
 }
@@ -42,6 +51,7 @@ impl SessionStatus {
         Self::InProgress,
         Self::Completed,
         Self::Cancelled,
+        Self::PendingPayment,
     ];
 
     pub fn to_id(&self) -> i32 {
@@ -50,6 +60,7 @@ impl SessionStatus {
             SessionStatus::InProgress => 2,
             SessionStatus::Completed => 3,
             SessionStatus::Cancelled => 4,
+            SessionStatus::PendingPayment => 5,
         }
     }
 
@@ -59,6 +70,7 @@ impl SessionStatus {
             2 => Some(SessionStatus::InProgress),
             3 => Some(SessionStatus::Completed),
             4 => Some(SessionStatus::Cancelled),
+            5 => Some(SessionStatus::PendingPayment),
             _ => None,
         }
     }
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -8,15 +10,19 @@ pub enum TransactionStatus {
     Completed,
     Failed,
     Expired,
+    Refunded,
+    PartiallyRefunded,
 }
 
-impl ToString for TransactionStatus {
-    fn to_string(&self) -> String {
+impl Display for TransactionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TransactionStatus::Pending => "pending".to_string(),
-            TransactionStatus::Completed => "completed".to_string(),
-            TransactionStatus::Failed => "failed".to_string(),
-            TransactionStatus::Expired => "expired".to_string(),
+            TransactionStatus::Pending => write!(f, "pending"),
+            TransactionStatus::Completed => write!(f, "completed"),
+            TransactionStatus::Failed => write!(f, "failed"),
+            TransactionStatus::Expired => write!(f, "expired"),
+            TransactionStatus::Refunded => write!(f, "refunded"),
+            TransactionStatus::PartiallyRefunded => write!(f, "partially_refunded"),
         }
     }
 }
@@ -27,6 +33,8 @@ impl From<String> for TransactionStatus {
             "completed" => TransactionStatus::Completed,
             "failed" => TransactionStatus::Failed,
             "expired" => TransactionStatus::Expired,
+            "refunded" => TransactionStatus::Refunded,
+            "partially_refunded" => TransactionStatus::PartiallyRefunded,
             _ => TransactionStatus::Pending,
         }
     }
@@ -36,10 +44,12 @@ impl From<String> for TransactionStatus {
 pub struct Transaction {
     pub id: Uuid,
     pub payment_intent_id: Option<String>,
-    pub session_id: String,
+    pub session_id: String, // stripe checkout session id
+    pub booked_session_id: Option<Uuid>, // therapy session paid by this transaction, if any
     pub amount: Option<i64>,
     pub currency: Option<String>,
     pub status: TransactionStatus,
+    pub refunded_amount: i64, // cents
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -50,9 +60,11 @@ impl Transaction {
             id: Uuid::new_v4(),
             payment_intent_id: None,
             session_id,
+            booked_session_id: None,
             amount,
             currency,
             status: TransactionStatus::Pending,
+            refunded_amount: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -68,4 +80,19 @@ impl Transaction {
         self.status = TransactionStatus::Failed;
         self.updated_at = Utc::now();
     }
+
+    pub fn expire(&mut self) {
+        self.status = TransactionStatus::Expired;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn refund(&mut self, amount: i64) {
+        self.refunded_amount += amount;
+        self.status = if self.refunded_amount >= self.amount.unwrap_or_default() {
+            TransactionStatus::Refunded
+        } else {
+            TransactionStatus::PartiallyRefunded
+        };
+        self.updated_at = Utc::now();
+    }
 }
@@ -0,0 +1,17 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// TOTP enrollment of a user, the second login step is only asked once it is enabled
+#[derive(Debug, Clone)]
+pub struct TwoFactor {
+    pub user_id: Uuid,
+    pub secret: String, // base32, the same the authenticator app stores
+    pub enabled_at: Option<NaiveDateTime>, // none while the enrollment is pending
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl TwoFactor {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
@@ -21,6 +21,7 @@ pub enum Role {
     Patient,
     Professional,
     Admin,
+    Receptionist, // clinic staff, books and manages the sessions without access to the clinical data
 }
 
 impl Display for Role {
@@ -29,18 +30,20 @@ impl Display for Role {
             Role::Patient => write!(f, "Patient"),
             Role::Professional => write!(f, "Professional"),
             Role::Admin => write!(f, "Admin"),
+            Role::Receptionist => write!(f, "Receptionist"),
         }
     }
 }
 
 impl Role {
-    pub const ALL: &'static [Self] = &[Self::Patient, Self::Professional, Self::Admin];
+    pub const ALL: &'static [Self] = &[Self::Patient, Self::Professional, Self::Admin, Self::Receptionist];
 
     pub fn to_id(&self) -> i32 {
         match self {
             Role::Patient => 1,
             Role::Professional => 2,
             Role::Admin => 3,
+            Role::Receptionist => 4,
         }
     }
 
@@ -49,6 +52,7 @@ impl Role {
             1 => Some(Role::Patient),
             2 => Some(Role::Professional),
             3 => Some(Role::Admin),
+            4 => Some(Role::Receptionist),
             _ => None,
         }
     }
@@ -1,10 +1,47 @@
+use std::{fmt::Display, str::FromStr};
+
 use uuid::Uuid;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UserToken {
     pub id: Uuid,
     pub user_id: Uuid,
     pub token: String,
+    pub purpose: TokenPurpose,
+    /// Only set on email change tokens
+    pub new_email: Option<String>,
     pub expires_at: Option<chrono::NaiveDateTime>,
     pub created_at: Option<chrono::NaiveDateTime>,
 }
+
+/// What a token sent by email can be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenPurpose {
+    #[default]
+    Verification,
+    PasswordReset,
+    EmailChange,
+}
+
+impl Display for TokenPurpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenPurpose::Verification => write!(f, "verification"),
+            TokenPurpose::PasswordReset => write!(f, "password_reset"),
+            TokenPurpose::EmailChange => write!(f, "email_change"),
+        }
+    }
+}
+
+impl FromStr for TokenPurpose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "verification" => Ok(TokenPurpose::Verification),
+            "password_reset" => Ok(TokenPurpose::PasswordReset),
+            "email_change" => Ok(TokenPurpose::EmailChange),
+            _ => Err(format!("Unknown token purpose {}", s)),
+        }
+    }
+}
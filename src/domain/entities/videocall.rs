@@ -0,0 +1,59 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::NaiveDateTime;
+
+/// Service hosting the videocalls of the sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoCallProvider {
+    Whereby,
+    Jitsi, // self-hosted, with signed room urls
+    Noop,  // local development, links lead nowhere
+}
+
+impl Display for VideoCallProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoCallProvider::Whereby => write!(f, "whereby"),
+            VideoCallProvider::Jitsi => write!(f, "jitsi"),
+            VideoCallProvider::Noop => write!(f, "noop"),
+        }
+    }
+}
+
+impl FromStr for VideoCallProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "whereby" => Ok(VideoCallProvider::Whereby),
+            "jitsi" => Ok(VideoCallProvider::Jitsi),
+            "noop" => Ok(VideoCallProvider::Noop),
+            _ => Err(format!("Unknown videocall provider {}", s)),
+        }
+    }
+}
+
+/// Room created at the provider for a session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meeting {
+    pub id: String, // used to delete it from the provider
+    pub room_url: String, // for the participants
+    pub host_room_url: String, // with moderation rights, for the professional
+}
+
+/// Time around a session in which its videocall can be joined
+#[derive(Debug, Clone, Copy)]
+pub struct JoinWindow {
+    pub minutes_before_start: i64,
+    pub minutes_after_end: i64, // leaves room for the sessions that run late
+}
+
+impl JoinWindow {
+    pub fn opens_at(&self, session_date: NaiveDateTime) -> NaiveDateTime {
+        session_date - chrono::Duration::minutes(self.minutes_before_start)
+    }
+
+    pub fn closes_at(&self, session_end: NaiveDateTime) -> NaiveDateTime {
+        session_end + chrono::Duration::minutes(self.minutes_after_end)
+    }
+}
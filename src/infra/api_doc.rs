@@ -9,13 +9,30 @@ use utoipa::{
     paths(
         //user
         routes::user::get_all::get_all_users,
+        routes::user::get_lockouts::get_lockouts,
         routes::user::login::login,
         routes::user::register::register,
+        routes::user::refresh::refresh,
+        routes::user::logout::logout,
+        routes::user::revoke_sessions::revoke_sessions,
+        routes::user::change_password::change_password,
+        routes::user::login_two_factor::login_two_factor,
+        routes::user::login_enroll_two_factor::login_enroll_two_factor,
+        routes::user::login_activate_two_factor::login_activate_two_factor,
+        routes::user::enroll_two_factor::enroll_two_factor,
+        routes::user::activate_two_factor::activate_two_factor,
+        routes::user::disable_two_factor::disable_two_factor,
+        routes::user::two_factor_policy::set_two_factor_policy,
+        routes::user::set_role::set_role,
         routes::user::onboard::onboard_user,
         //user_token
         routes::user_token::generate::generate_token,
         routes::user_token::verify::verify,
         routes::user_token::validate::validate_token,
+        routes::user_token::request_reset::request_reset,
+        routes::user_token::confirm_reset::confirm_reset,
+        routes::user_token::request_email_change::request_email_change,
+        routes::user_token::confirm_email_change::confirm_email_change,
         //patient
         routes::patient::create::create_patient,
         routes::patient::delete::delete_patient,
@@ -32,12 +49,43 @@ use utoipa::{
         routes::session_type::update::update_session_type,
         // sessions
         routes::session::create::create_session,
+        routes::session::cancel::cancel_session,
         routes::session::delete::delete_session,
         routes::session::read_all::read_all_sessions,
         routes::session::read_single::read_single_session,
         routes::session::update::update_session,
         routes::session::professional::read_professional_sessions,
         routes::session::patient::read_patient_sessions,
+        // checkout
+        routes::checkout::booking::book_session,
+        routes::checkout::confirm_booking::confirm_booking,
+        routes::checkout::webhook::stripe_webhook,
+        // invoices
+        routes::invoice::read_mine::read_my_invoices,
+        routes::invoice::download::download_invoice,
+        routes::invoice::issue::issue_invoice,
+        routes::invoice::read_billing_details::read_billing_details,
+        routes::invoice::update_billing_details::update_billing_details,
+        // organizations
+        routes::organization::create::create_organization,
+        routes::organization::read_current::read_current_organization,
+        routes::organization::read_members::read_organization_members,
+        // audit
+        routes::audit::read::read_audit_events,
+        // clinical notes
+        routes::clinical_note::create::create_clinical_note,
+        routes::clinical_note::revise::revise_clinical_note,
+        routes::clinical_note::read_by_session::read_session_clinical_notes,
+        routes::clinical_note::read_versions::read_clinical_note_versions,
+        // data exports
+        routes::data_export::request::request_data_export,
+        routes::data_export::read_mine::read_my_data_exports,
+        routes::data_export::download::download_data_export,
+        // account deletions
+        routes::account_deletion::request::request_account_deletion,
+        routes::account_deletion::cancel::cancel_account_deletion,
+        routes::account_deletion::read_mine::read_my_account_deletion,
+        routes::account_deletion::erase::erase_account,
         // professionals
         routes::professional::create::create_professional,
         routes::professional::delete::delete_professional,
@@ -46,6 +94,17 @@ use utoipa::{
         routes::professional::update::update_professional,
         routes::professional::read_by_user::read_professional_by_user,
         routes::professional::selector::professionals_selector,
+        routes::professional::earnings::read_earnings,
+        routes::professional::export_payouts::export_payouts,
+        routes::professional::settle_payouts::settle_payouts,
+        // professional availability
+        routes::professional_availability::read_slots::read_availability_slots,
+        routes::professional_availability::read_rules::read_availability_rules,
+        routes::professional_availability::create_rule::create_availability_rule,
+        routes::professional_availability::delete_rule::delete_availability_rule,
+        routes::professional_availability::read_exceptions::read_availability_exceptions,
+        routes::professional_availability::create_exception::create_availability_exception,
+        routes::professional_availability::delete_exception::delete_availability_exception,
         // professional languages
         routes::professional_language::create::create_professional_language,
         routes::professional_language::delete::delete_professional_language,
@@ -69,13 +128,33 @@ use utoipa::{
         schemas(
             // user
             routes::user::get_all::GetAllUsersResponse,
+            routes::user::get_lockouts::GetLockoutsResponse,
+            routes::user::get_lockouts::LockoutData,
             routes::user::login::LoginResponse,
             routes::user::register::RegisterResponse,
+            routes::user::refresh::RefreshResponse,
+            routes::user::logout::LogoutResponse,
+            routes::user::revoke_sessions::RevokeSessionsResponse,
+            routes::user::revoke_sessions::RevokeSessionsData,
+            routes::user::change_password::ChangePasswordResponse,
+            routes::user::login_two_factor::LoginTwoFactorResponse,
+            routes::user::enroll_two_factor::EnrollTwoFactorResponse,
+            routes::user::enroll_two_factor::TwoFactorEnrollmentData,
+            routes::user::activate_two_factor::ActivateTwoFactorResponse,
+            routes::user::activate_two_factor::ActivateTwoFactorData,
+            routes::user::login_activate_two_factor::LoginActivateTwoFactorResponse,
+            routes::user::disable_two_factor::DisableTwoFactorResponse,
+            routes::user::two_factor_policy::TwoFactorPolicyResponse,
+            routes::user::set_role::SetRoleResponse,
             routes::user::onboard::OnboardResponse,
             // user_token
             routes::user_token::generate::GenerateResponse,
             routes::user_token::verify::VerifyResponse,
             routes::user_token::validate::ValidateResponse,
+            routes::user_token::request_reset::RequestResetResponse,
+            routes::user_token::confirm_reset::ConfirmResetResponse,
+            routes::user_token::request_email_change::RequestEmailChangeResponse,
+            routes::user_token::confirm_email_change::ConfirmEmailChangeResponse,
             // patient
             routes::patient::create::PatientCreateResponse,
             routes::patient::delete::PatientDeleteResponse,
@@ -92,12 +171,41 @@ use utoipa::{
             routes::session_type::update::SessionTypeUpdateResponse,
             // sessions
             routes::session::create::SessionCreateResponse,
+            routes::session::cancel::SessionCancelResponse,
             routes::session::delete::SessionDeleteResponse,
             routes::session::read_all::SessionReadAllResponse,
             routes::session::read_single::SessionReadSingleResponse,
             routes::session::update::SessionUpdateResponse,
             routes::session::professional::SessionReadProfessionalResponse,
             routes::session::patient::SessionReadPatientResponse,
+            // checkout
+            routes::checkout::booking::BookingResponse,
+            routes::checkout::confirm_booking::ConfirmBookingResponse,
+            routes::checkout::webhook::StripeWebhookResponse,
+            // invoices
+            routes::invoice::read_mine::InvoiceReadMineResponse,
+            routes::invoice::issue::InvoiceIssueResponse,
+            routes::invoice::read_billing_details::BillingDetailsReadResponse,
+            routes::invoice::update_billing_details::BillingDetailsUpdateResponse,
+            // organizations
+            routes::organization::create::CreateOrganizationResponse,
+            routes::organization::read_current::OrganizationReadCurrentResponse,
+            routes::organization::read_members::OrganizationReadMembersResponse,
+            // audit
+            routes::audit::read::AuditEventsResponse,
+            // clinical notes
+            routes::clinical_note::create::ClinicalNoteCreateResponse,
+            routes::clinical_note::revise::ClinicalNoteReviseResponse,
+            routes::clinical_note::read_by_session::ClinicalNotesReadResponse,
+            routes::clinical_note::read_versions::ClinicalNoteVersionsReadResponse,
+            // data exports
+            routes::data_export::request::DataExportRequestResponse,
+            routes::data_export::read_mine::DataExportReadMineResponse,
+            // account deletions
+            routes::account_deletion::request::AccountDeletionRequestResponse,
+            routes::account_deletion::cancel::AccountDeletionCancelResponse,
+            routes::account_deletion::read_mine::AccountDeletionReadMineResponse,
+            routes::account_deletion::erase::AccountEraseResponse,
             // professionals
             routes::professional::create::ProfessionalCreateResponse,
             routes::professional::delete::ProfessionalDeleteResponse,
@@ -106,6 +214,16 @@ use utoipa::{
             routes::professional::update::ProfessionalUpdateResponse,
             routes::professional::read_by_user::ProfessionalReadByUserResponse,
             routes::professional::selector::ProfessionalSelectorResponse,
+            routes::professional::earnings::EarningsReadResponse,
+            routes::professional::settle_payouts::PayoutsSettleResponse,
+            // professional availability
+            routes::professional_availability::read_slots::AvailabilitySlotsResponse,
+            routes::professional_availability::read_rules::AvailabilityRulesReadResponse,
+            routes::professional_availability::create_rule::AvailabilityRuleCreateResponse,
+            routes::professional_availability::delete_rule::AvailabilityRuleDeleteResponse,
+            routes::professional_availability::read_exceptions::AvailabilityExceptionsReadResponse,
+            routes::professional_availability::create_exception::AvailabilityExceptionCreateResponse,
+            routes::professional_availability::delete_exception::AvailabilityExceptionDeleteResponse,
             // professional languages
             routes::professional_language::create::ProfessionalLanguageCreateResponse,
             routes::professional_language::delete::ProfessionalLanguageDeleteResponse,
@@ -133,7 +251,15 @@ use utoipa::{
         (name = "Patient", description = "Patient endpoints"),
         (name = "Session Type", description = "Session Type endpoints"),
         (name = "Session", description = "Session endpoints"),
+        (name = "Clinical Note", description = "Versioned SOAP notes of the sessions endpoints"),
+        (name = "Checkout", description = "Booking and payment endpoints"),
+        (name = "Invoice", description = "Invoice and billing details endpoints"),
+        (name = "Organization", description = "Clinic and membership endpoints"),
+        (name = "Audit", description = "Audit log of the clinical records endpoints"),
+        (name = "Data Export", description = "Copies of the personal data of the users endpoints"),
+        (name = "Account Deletion", description = "Account deletion and erasure endpoints"),
         (name = "Professional", description = "Professional endpoints"),
+        (name = "Professional Availability", description = "Professional availability and bookable slots endpoints"),
         (name = "Professional Language", description = "Professional languages endpoints"),
         (name = "Professional Specialization", description = "Professional specializations endpoints"),
         (name = "Blog Post", description = "Blog Post endpoints"),
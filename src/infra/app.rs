@@ -1,11 +1,20 @@
-use axum::{Extension, Router, http};
+use std::sync::Arc;
+
+use axum::{Extension, Router, http, middleware};
 use http::header::{AUTHORIZATION, CONTENT_TYPE};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use utoipa::OpenApi;
 use uuid::Uuid;
 
 use crate::{
-    adapters::{self, http::app_state::AppState},
+    adapters::{
+        self,
+        http::{
+            app_state::AppState,
+            rate_limit::RateLimiters,
+            routes::{RequestId, request_id_middleware},
+        },
+    },
     infra::setup::init_tracing,
 };
 
@@ -33,9 +42,11 @@ pub fn create_app(app_state: AppState) -> Router {
             http::header::ACCEPT,
             http::header::ORIGIN,
         ])
+        .expose_headers([http::HeaderName::from_static("x-request-id")])
         .allow_credentials(true);
 
-    let jwt_service_ext = app_state.user_use_cases.jwt_service.clone();
+    let user_use_cases_ext = app_state.user_use_cases.clone();
+    let rate_limiters_ext = Arc::new(RateLimiters::from_config(&app_state.config));
 
     Router::new()
         .merge(
@@ -48,11 +59,15 @@ pub fn create_app(app_state: AppState) -> Router {
             tower_http::services::ServeDir::new("uploads"),
         )
         .with_state(app_state)
-        .layer(Extension(jwt_service_ext))
+        .layer(Extension(user_use_cases_ext))
+        .layer(Extension(rate_limiters_ext))
         .layer(cors)
         .layer(
             TraceLayer::new_for_http().make_span_with(|request: &http::Request<_>| {
-                let request_id = Uuid::new_v4();
+                let request_id = request
+                    .extensions()
+                    .get::<RequestId>()
+                    .map_or_else(Uuid::new_v4, |RequestId(id)| *id);
                 tracing::info_span!(
                     "http-request",
                     method = %request.method(),
@@ -62,4 +77,5 @@ pub fn create_app(app_state: AppState) -> Router {
                 )
             }),
         )
+        .layer(middleware::from_fn(request_id_middleware))
 }
@@ -1,20 +1,74 @@
-use std::env;
+use std::{env, str::FromStr};
+
+use chrono::Duration;
+
+use crate::entities::videocall::VideoCallProvider;
+
+/// Payment gateway the app charges the bookings through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentGatewayKind {
+    Stripe,
+    Fake, // in-process, for local and test runs without Stripe
+}
+
+impl FromStr for PaymentGatewayKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stripe" => Ok(PaymentGatewayKind::Stripe),
+            "fake" => Ok(PaymentGatewayKind::Fake),
+            _ => Err(format!("Unknown payment gateway {}", s)),
+        }
+    }
+}
 
 pub struct AppConfig {
     pub jwt_secret: String,
+    pub field_encryption_keys: String, // id:hex_key pairs, the first one encrypts
     pub resend_key: String,
     pub resend_from_email: String,
     pub base_frontend_url: String,
+    pub payment_gateway: PaymentGatewayKind,
     pub stripe_secret_key: String,
+    pub stripe_webhook_secret: String,
+    pub videocall_provider: VideoCallProvider,
     pub whereby_key: String,
-    //pub access_token_ttl: Duration,
-    //pub refresh_token_ttl: Duration,
+    pub jitsi_domain: String,
+    pub jitsi_app_id: String,
+    pub jitsi_app_secret: String,
+    pub videocall_join_minutes_before: i64,
+    pub videocall_join_minutes_after: i64,
+    pub cancellation_full_refund_hours: i64,
+    pub cancellation_late_refund_percent: i64,
+    pub platform_commission_percent: i64,
+    pub access_token_ttl: Duration,
+    pub refresh_token_ttl: Duration,
+    pub password_reset_token_ttl: Duration,
+    pub pre_auth_token_ttl: Duration,
+    pub account_deletion_grace_period: Duration,
+    pub totp_issuer: String,
+    pub login_max_failures: i32,
+    pub login_max_failures_per_ip: i32,
+    pub login_lockout_minutes: i64,
+    pub trust_proxy_headers: bool,
+    pub rate_limit_public: u32,
+    pub rate_limit_public_per_minute: u32,
+    pub rate_limit_protected: u32,
+    pub rate_limit_protected_per_minute: u32,
+    pub rate_limit_auth: u32,
+    pub rate_limit_auth_per_minute: u32,
+    pub rate_limit_email: u32,
+    pub rate_limit_email_per_minute: u32,
 }
 
 impl AppConfig {
     pub fn from_env() -> Self {
         let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
 
+        let field_encryption_keys =
+            env::var("FIELD_ENCRYPTION_KEYS").expect("FIELD_ENCRYPTION_KEYS must be set");
+
         let resend_key = env::var("RESEND_KEY").expect("RESEND_KEY must be set");
 
         let resend_from_email =
@@ -23,30 +77,204 @@ impl AppConfig {
         let base_frontend_url =
             env::var("BASE_FRONTEND_URL").expect("BASE_FRONTEND_URL must be set");
 
-        let stripe_secret_key =
-            env::var("STRIPE_SECRET_KEY").expect("STRIPE_SECRET_KEY must be set");
+        let payment_gateway: PaymentGatewayKind = env::var("PAYMENT_GATEWAY")
+            .unwrap_or("stripe".to_string())
+            .parse()
+            .expect("PAYMENT_GATEWAY must be stripe or fake");
+
+        // The fake gateway never talks to Stripe, so it can run without its keys
+        let stripe_required = payment_gateway == PaymentGatewayKind::Stripe;
+
+        let stripe_secret_key = env::var("STRIPE_SECRET_KEY")
+            .ok()
+            .or_else(|| (!stripe_required).then(String::new))
+            .expect("STRIPE_SECRET_KEY must be set");
+
+        let stripe_webhook_secret = env::var("STRIPE_WEBHOOK_SECRET")
+            .ok()
+            .or_else(|| (!stripe_required).then(String::new))
+            .expect("STRIPE_WEBHOOK_SECRET must be set");
+
+        let videocall_provider: VideoCallProvider = env::var("VIDEOCALL_PROVIDER")
+            .unwrap_or("whereby".to_string())
+            .parse()
+            .expect("VIDEOCALL_PROVIDER must be whereby, jitsi or noop");
+
+        // Only the keys of the provider in use are needed
+        let whereby_required = videocall_provider == VideoCallProvider::Whereby;
+        let jitsi_required = videocall_provider == VideoCallProvider::Jitsi;
+
+        let whereby_key = env::var("WHEREBY_KEY")
+            .ok()
+            .or_else(|| (!whereby_required).then(String::new))
+            .expect("WHEREBY_KEY must be set");
+
+        let jitsi_domain = env::var("JITSI_DOMAIN")
+            .ok()
+            .or_else(|| (!jitsi_required).then(String::new))
+            .expect("JITSI_DOMAIN must be set");
+
+        let jitsi_app_id = env::var("JITSI_APP_ID")
+            .ok()
+            .or_else(|| (!jitsi_required).then(String::new))
+            .expect("JITSI_APP_ID must be set");
+
+        let jitsi_app_secret = env::var("JITSI_APP_SECRET")
+            .ok()
+            .or_else(|| (!jitsi_required).then(String::new))
+            .expect("JITSI_APP_SECRET must be set");
+
+        let videocall_join_minutes_before: i64 = env::var("VIDEOCALL_JOIN_MINUTES_BEFORE")
+            .unwrap_or("5".to_string())
+            .parse()
+            .expect("VIDEOCALL_JOIN_MINUTES_BEFORE must be a valid number");
+
+        let videocall_join_minutes_after: i64 = env::var("VIDEOCALL_JOIN_MINUTES_AFTER")
+            .unwrap_or("15".to_string())
+            .parse()
+            .expect("VIDEOCALL_JOIN_MINUTES_AFTER must be a valid number");
+
+        let cancellation_full_refund_hours: i64 = env::var("CANCELLATION_FULL_REFUND_HOURS")
+            .unwrap_or("24".to_string())
+            .parse()
+            .expect("CANCELLATION_FULL_REFUND_HOURS must be a valid number");
+
+        let cancellation_late_refund_percent: i64 = env::var("CANCELLATION_LATE_REFUND_PERCENT")
+            .unwrap_or("0".to_string())
+            .parse()
+            .expect("CANCELLATION_LATE_REFUND_PERCENT must be a valid number");
+
+        let platform_commission_percent: i64 = env::var("PLATFORM_COMMISSION_PERCENT")
+            .unwrap_or("15".to_string())
+            .parse()
+            .ok()
+            .filter(|percent| (0..=100).contains(percent))
+            .expect("PLATFORM_COMMISSION_PERCENT must be a number between 0 and 100");
+
+        let refresh_token_ttl_days: i64 = env::var("REFRESH_TOKEN_TTL_DAYS")
+            .unwrap_or("30".to_string())
+            .parse()
+            .expect("REFRESH_TOKEN_TTL_DAYS must be a valid number");
+
+        let access_token_ttl_secs: i64 = env::var("ACCESS_TOKEN_TTL_SECS")
+            .unwrap_or("900".to_string())
+            .parse()
+            .expect("ACCESS_TOKEN_TTL_SECS must be a valid number");
+
+        let password_reset_token_ttl_minutes: i64 = env::var("PASSWORD_RESET_TOKEN_TTL_MINUTES")
+            .unwrap_or("30".to_string())
+            .parse()
+            .expect("PASSWORD_RESET_TOKEN_TTL_MINUTES must be a valid number");
+
+        let pre_auth_token_ttl_secs: i64 = env::var("PRE_AUTH_TOKEN_TTL_SECS")
+            .unwrap_or("300".to_string())
+            .parse()
+            .expect("PRE_AUTH_TOKEN_TTL_SECS must be a valid number");
+
+        let account_deletion_grace_days: i64 = env::var("ACCOUNT_DELETION_GRACE_DAYS")
+            .unwrap_or("30".to_string())
+            .parse()
+            .expect("ACCOUNT_DELETION_GRACE_DAYS must be a valid number");
+
+        let totp_issuer = env::var("TOTP_ISSUER").unwrap_or("Mipsicored".to_string());
+
+        let login_max_failures: i32 = env::var("LOGIN_MAX_FAILURES")
+            .unwrap_or("10".to_string())
+            .parse()
+            .expect("LOGIN_MAX_FAILURES must be a valid number");
+
+        let login_max_failures_per_ip: i32 = env::var("LOGIN_MAX_FAILURES_PER_IP")
+            .unwrap_or("50".to_string())
+            .parse()
+            .expect("LOGIN_MAX_FAILURES_PER_IP must be a valid number");
+
+        let login_lockout_minutes: i64 = env::var("LOGIN_LOCKOUT_MINUTES")
+            .unwrap_or("15".to_string())
+            .parse()
+            .expect("LOGIN_LOCKOUT_MINUTES must be a valid number");
+
+        let trust_proxy_headers: bool = env::var("TRUST_PROXY_HEADERS")
+            .unwrap_or("false".to_string())
+            .parse()
+            .expect("TRUST_PROXY_HEADERS must be true or false");
+
+        let rate_limit_public: u32 = env::var("RATE_LIMIT_PUBLIC")
+            .unwrap_or("60".to_string())
+            .parse()
+            .expect("RATE_LIMIT_PUBLIC must be a valid number");
+
+        let rate_limit_public_per_minute: u32 = env::var("RATE_LIMIT_PUBLIC_PER_MINUTE")
+            .unwrap_or("60".to_string())
+            .parse()
+            .expect("RATE_LIMIT_PUBLIC_PER_MINUTE must be a valid number");
+
+        let rate_limit_protected: u32 = env::var("RATE_LIMIT_PROTECTED")
+            .unwrap_or("120".to_string())
+            .parse()
+            .expect("RATE_LIMIT_PROTECTED must be a valid number");
+
+        let rate_limit_protected_per_minute: u32 = env::var("RATE_LIMIT_PROTECTED_PER_MINUTE")
+            .unwrap_or("120".to_string())
+            .parse()
+            .expect("RATE_LIMIT_PROTECTED_PER_MINUTE must be a valid number");
+
+        let rate_limit_auth: u32 = env::var("RATE_LIMIT_AUTH")
+            .unwrap_or("10".to_string())
+            .parse()
+            .expect("RATE_LIMIT_AUTH must be a valid number");
 
-        let whereby_key = env::var("WHEREBY_KEY").expect("WHEREBY_KEY must be set");
+        let rate_limit_auth_per_minute: u32 = env::var("RATE_LIMIT_AUTH_PER_MINUTE")
+            .unwrap_or("5".to_string())
+            .parse()
+            .expect("RATE_LIMIT_AUTH_PER_MINUTE must be a valid number");
 
-        // let refresh_token_ttl_days: i64 = env::var("REFRESH_TOKEN_TTL_DAYS")
-        //     .unwrap_or("30".to_string())
-        //     .parse()
-        //     .expect("REFRESH_TOKEN_TTL_DAYS must be a valid number");
+        let rate_limit_email: u32 = env::var("RATE_LIMIT_EMAIL")
+            .unwrap_or("3".to_string())
+            .parse()
+            .expect("RATE_LIMIT_EMAIL must be a valid number");
 
-        // let access_token_ttl_secs: i64 = env::var("ACCESS_TOKEN_TTL_SECS")
-        //     .unwrap_or("30".to_string())
-        //     .parse()
-        //     .expect("ACCESS_TOKEN_TTL_SECS must be a valid number");
+        let rate_limit_email_per_minute: u32 = env::var("RATE_LIMIT_EMAIL_PER_MINUTE")
+            .unwrap_or("1".to_string())
+            .parse()
+            .expect("RATE_LIMIT_EMAIL_PER_MINUTE must be a valid number");
 
         Self {
             jwt_secret,
+            field_encryption_keys,
             resend_key,
             resend_from_email,
             base_frontend_url,
+            payment_gateway,
             stripe_secret_key,
+            stripe_webhook_secret,
+            videocall_provider,
             whereby_key,
-            //access_token_ttl: Duration::seconds(access_token_ttl_secs),
-            //refresh_token_ttl: Duration::days(refresh_token_ttl_days),
+            jitsi_domain,
+            jitsi_app_id,
+            jitsi_app_secret,
+            videocall_join_minutes_before,
+            videocall_join_minutes_after,
+            cancellation_full_refund_hours,
+            cancellation_late_refund_percent,
+            platform_commission_percent,
+            access_token_ttl: Duration::seconds(access_token_ttl_secs),
+            refresh_token_ttl: Duration::days(refresh_token_ttl_days),
+            password_reset_token_ttl: Duration::minutes(password_reset_token_ttl_minutes),
+            pre_auth_token_ttl: Duration::seconds(pre_auth_token_ttl_secs),
+            account_deletion_grace_period: Duration::days(account_deletion_grace_days),
+            totp_issuer,
+            login_max_failures,
+            login_max_failures_per_ip,
+            login_lockout_minutes,
+            trust_proxy_headers,
+            rate_limit_public,
+            rate_limit_public_per_minute,
+            rate_limit_protected,
+            rate_limit_protected_per_minute,
+            rate_limit_auth,
+            rate_limit_auth_per_minute,
+            rate_limit_email,
+            rate_limit_email_per_minute,
         }
     }
 }
@@ -2,12 +2,19 @@ use std::sync::Arc;
 
 use crate::{
     adapters::{
-        crypto::{argon2::ArgonPasswordHasher, jwt::JwtService},
+        crypto::{argon2::ArgonPasswordHasher, field_cipher::FieldCipher, jwt::JwtService, totp::TotpService},
         email::email_service::EmailService,
+        pdf::invoice_pdf::PdfInvoiceRenderer,
         persistence::PostgresPersistence,
-        videocall::whereby::WherebyService,
+        videocall::{jitsi::JitsiService, noop::NoopVideoCallService, whereby::WherebyService},
+        zip::data_export_zip::ZipDataExportArchiver,
     },
-    infra::{config::AppConfig, db::init_db},
+    entities::videocall::VideoCallProvider,
+    infra::{
+        config::{AppConfig, PaymentGatewayKind},
+        db::init_db,
+    },
+    use_cases::{payment::PaymentGateway, session::VideoCallService},
 };
 
 pub mod api_doc;
@@ -17,11 +24,12 @@ pub mod db;
 pub mod setup;
 pub mod payment;
 
-use self::payment::stripe_gateway::StripeGateway;
+use self::payment::{fake_gateway::FakeGateway, stripe_gateway::StripeGateway};
 
-pub async fn postgres_persistence() -> anyhow::Result<PostgresPersistence> {
+pub async fn postgres_persistence(config: Arc<AppConfig>) -> anyhow::Result<PostgresPersistence> {
     let pool = init_db().await?;
-    let persistence = PostgresPersistence::new(pool);
+    let cipher = FieldCipher::from_spec(&config.field_encryption_keys).map_err(anyhow::Error::msg)?;
+    let persistence = PostgresPersistence::new(pool, Arc::new(cipher));
 
     Ok(persistence)
 }
@@ -34,14 +42,33 @@ pub fn jwt_service(config: Arc<AppConfig>) -> JwtService {
     JwtService::new(config)
 }
 
+pub fn totp_service(config: Arc<AppConfig>) -> TotpService {
+    TotpService::new(config.totp_issuer.clone())
+}
+
 pub fn email_service(config: Arc<AppConfig>) -> EmailService {
     EmailService::new(config)
 }
 
-pub fn stripe_gateway(config: Arc<AppConfig>) -> StripeGateway {
-    StripeGateway::new(config)
+pub fn payment_gateway(config: Arc<AppConfig>) -> Arc<dyn PaymentGateway> {
+    match config.payment_gateway {
+        PaymentGatewayKind::Stripe => Arc::new(StripeGateway::new(config)),
+        PaymentGatewayKind::Fake => Arc::new(FakeGateway::new()),
+    }
+}
+
+pub fn videocall_service(config: Arc<AppConfig>) -> Arc<dyn VideoCallService> {
+    match config.videocall_provider {
+        VideoCallProvider::Whereby => Arc::new(WherebyService::new(config)),
+        VideoCallProvider::Jitsi => Arc::new(JitsiService::new(config)),
+        VideoCallProvider::Noop => Arc::new(NoopVideoCallService::new(config)),
+    }
+}
+
+pub fn invoice_renderer() -> PdfInvoiceRenderer {
+    PdfInvoiceRenderer
 }
 
-pub fn videocall_service(config: Arc<AppConfig>) -> WherebyService {
-    WherebyService::new(config)
+pub fn data_export_archiver() -> ZipDataExportArchiver {
+    ZipDataExportArchiver
 }
@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::{
+    app_error::{AppError, AppResult},
+    application::use_cases::payment::{CheckoutReference, CheckoutStatus, PaymentEvent, PaymentGateway},
+};
+
+/// In-process payment gateway for the local and test runs, no money is moved and nothing leaves the app.
+///
+/// The ids are derived from the transaction of the checkout, and the checkouts
+/// are completed, expired or failed by posting an event to the webhook, any signature is accepted:
+///
+/// `{"type": "checkout.session.completed", "checkout_session_id": "cs_fake_..."}`
+#[derive(Default)]
+pub struct FakeGateway {
+    checkouts: Mutex<HashMap<String, FakeCheckout>>,
+    refunds: Mutex<HashMap<String, String>>, // refund id by idempotency key
+    counter: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct FakeCheckout {
+    amount: i64,
+    status: CheckoutStatus,
+    refunded: i64,
+}
+
+/// Event posted to the webhook to move a checkout forward
+#[derive(Debug, Deserialize)]
+struct FakeEvent {
+    #[serde(rename = "type")]
+    type_: String,
+    checkout_session_id: String,
+}
+
+impl FakeGateway {
+    pub fn new() -> Self {
+        warn!("Using the fake payment gateway, checkouts are not charged");
+        Self::default()
+    }
+
+    /// Pays the checkout and returns the event the real gateway would send
+    pub fn complete(&self, checkout_session_id: &str) -> AppResult<PaymentEvent> {
+        let payment_intent_id = payment_intent_id(checkout_session_id);
+
+        self.transition(
+            checkout_session_id,
+            CheckoutStatus::Paid { payment_intent_id: Some(payment_intent_id.clone()) },
+        )?;
+
+        Ok(PaymentEvent::CheckoutCompleted {
+            checkout_session_id: checkout_session_id.to_string(),
+            payment_intent_id: Some(payment_intent_id),
+        })
+    }
+
+    /// Declines the payment, the checkout stays open as the patient could retry
+    pub fn fail(&self, checkout_session_id: &str) -> AppResult<PaymentEvent> {
+        self.transition(checkout_session_id, CheckoutStatus::Open)?;
+
+        Ok(PaymentEvent::PaymentFailed { payment_intent_id: payment_intent_id(checkout_session_id) })
+    }
+
+    pub fn expire(&self, checkout_session_id: &str) -> AppResult<PaymentEvent> {
+        self.transition(checkout_session_id, CheckoutStatus::Expired)?;
+
+        Ok(PaymentEvent::CheckoutExpired { checkout_session_id: checkout_session_id.to_string() })
+    }
+
+    fn transition(&self, checkout_session_id: &str, status: CheckoutStatus) -> AppResult<()> {
+        let mut checkouts = self.checkouts.lock().unwrap();
+
+        let checkout = checkouts.get_mut(checkout_session_id).ok_or_else(|| {
+            AppError::NotFound(format!("Checkout {} not found", checkout_session_id))
+        })?;
+
+        // Same as the real gateway, a paid or expired checkout doesn't change anymore
+        if matches!(checkout.status, CheckoutStatus::Open) {
+            checkout.status = status;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PaymentGateway for FakeGateway {
+    async fn create_checkout_session(
+        &self,
+        amount: i64,
+        _currency: &str,
+        _success_url: &str,
+        _cancel_url: &str,
+        reference: CheckoutReference,
+    ) -> AppResult<(String, String)> {
+        let id = format!("cs_fake_{}", reference.transaction_id.simple());
+
+        self.checkouts.lock().unwrap().insert(
+            id.clone(),
+            FakeCheckout { amount, status: CheckoutStatus::Open, refunded: 0 },
+        );
+
+        info!("Fake checkout {} created for {}", id, amount);
+
+        Ok((format!("{}_secret", id), id))
+    }
+
+    async fn checkout_status(&self, session_id: &str) -> AppResult<CheckoutStatus> {
+        self.checkouts
+            .lock()
+            .unwrap()
+            .get(session_id)
+            .map(|checkout| checkout.status.clone())
+            .ok_or_else(|| AppError::NotFound(format!("Checkout {} not found", session_id)))
+    }
+
+    fn webhook_event(&self, payload: &str, _signature: &str) -> AppResult<PaymentEvent> {
+        let event: FakeEvent = serde_json::from_str(payload).map_err(|_| AppError::InvalidPayload)?;
+
+        match event.type_.as_str() {
+            "checkout.session.completed" => self.complete(&event.checkout_session_id),
+            "checkout.session.expired" => self.expire(&event.checkout_session_id),
+            "payment_intent.payment_failed" => self.fail(&event.checkout_session_id),
+            _ => Ok(PaymentEvent::Ignored),
+        }
+    }
+
+    async fn checkout_session_for_payment_intent(
+        &self,
+        payment_intent_id: &str,
+    ) -> AppResult<Option<String>> {
+        let id = checkout_session_id(payment_intent_id);
+
+        Ok(self.checkouts.lock().unwrap().contains_key(&id).then_some(id))
+    }
+
+    async fn expire_checkout(&self, session_id: &str) -> AppResult<()> {
+        let mut checkouts = self.checkouts.lock().unwrap();
+
+        let checkout = checkouts
+            .get_mut(session_id)
+            .filter(|checkout| matches!(checkout.status, CheckoutStatus::Open))
+            .ok_or_else(|| AppError::ExternalServiceError(format!("No open checkout {} to expire", session_id)))?;
+
+        checkout.status = CheckoutStatus::Expired;
+
+        Ok(())
+    }
+
+    async fn refund(&self, payment_intent_id: &str, amount: i64, idempotency_key: &str) -> AppResult<String> {
+        let mut refunds = self.refunds.lock().unwrap();
+        if let Some(refund_id) = refunds.get(idempotency_key) {
+            return Ok(refund_id.clone());
+        }
+
+        let mut checkouts = self.checkouts.lock().unwrap();
+
+        let checkout = checkouts
+            .get_mut(&checkout_session_id(payment_intent_id))
+            .filter(|checkout| matches!(checkout.status, CheckoutStatus::Paid { .. }))
+            .ok_or_else(|| {
+                AppError::ExternalServiceError(format!("No payment {} to refund", payment_intent_id))
+            })?;
+
+        if checkout.refunded + amount > checkout.amount {
+            return Err(AppError::ExternalServiceError(String::from(
+                "Refund is greater than the unrefunded amount",
+            )));
+        }
+
+        checkout.refunded += amount;
+
+        let refund_id = format!("re_fake_{}", self.counter.fetch_add(1, Ordering::Relaxed) + 1);
+        refunds.insert(idempotency_key.to_string(), refund_id.clone());
+
+        Ok(refund_id)
+    }
+}
+
+fn payment_intent_id(checkout_session_id: &str) -> String {
+    checkout_session_id.replacen("cs_fake_", "pi_fake_", 1)
+}
+
+fn checkout_session_id(payment_intent_id: &str) -> String {
+    payment_intent_id.replacen("pi_fake_", "cs_fake_", 1)
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn reference(transaction_id: u128) -> CheckoutReference {
+        CheckoutReference {
+            transaction_id: Uuid::from_u128(transaction_id),
+            patient_id: Uuid::new_v4(),
+            professional_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            product_name: String::from("Individual session (60 min) - 2026-01-01 10:00 UTC"),
+        }
+    }
+
+    async fn checkout(gateway: &FakeGateway) -> String {
+        let (client_secret, id) = gateway
+            .create_checkout_session(6000, "eur", "https://example.com", "https://example.com", reference(1))
+            .await
+            .unwrap();
+
+        assert_eq!(id, "cs_fake_00000000000000000000000000000001");
+        assert_eq!(client_secret, format!("{}_secret", id));
+
+        id
+    }
+
+    #[tokio::test]
+    async fn checkout_ids_are_deterministic() {
+        let gateway = FakeGateway::default();
+
+        checkout(&gateway).await;
+        let (_, second) = gateway.create_checkout_session(100, "eur", "", "", reference(2)).await.unwrap();
+
+        assert_eq!(second, "cs_fake_00000000000000000000000000000002");
+    }
+
+    #[tokio::test]
+    async fn completed_webhook_pays_checkout() {
+        let gateway = FakeGateway::default();
+        let id = checkout(&gateway).await;
+
+        let payload = format!(r#"{{"type": "checkout.session.completed", "checkout_session_id": "{}"}}"#, id);
+        let event = gateway.webhook_event(&payload, "any").unwrap();
+
+        let payment_intent_id = Some(String::from("pi_fake_00000000000000000000000000000001"));
+        assert_eq!(
+            event,
+            PaymentEvent::CheckoutCompleted { checkout_session_id: id.clone(), payment_intent_id: payment_intent_id.clone() }
+        );
+        assert_eq!(gateway.checkout_status(&id).await.unwrap(), CheckoutStatus::Paid { payment_intent_id });
+    }
+
+    #[tokio::test]
+    async fn failed_payment_is_traced_back_to_checkout() {
+        let gateway = FakeGateway::default();
+        let id = checkout(&gateway).await;
+
+        let PaymentEvent::PaymentFailed { payment_intent_id } = gateway.fail(&id).unwrap() else {
+            panic!("Expected a payment failed event");
+        };
+
+        assert_eq!(gateway.checkout_session_for_payment_intent(&payment_intent_id).await.unwrap(), Some(id.clone()));
+        assert_eq!(gateway.checkout_status(&id).await.unwrap(), CheckoutStatus::Open);
+    }
+
+    #[tokio::test]
+    async fn expired_checkout_cannot_be_paid() {
+        let gateway = FakeGateway::default();
+        let id = checkout(&gateway).await;
+
+        gateway.expire(&id).unwrap();
+        gateway.complete(&id).unwrap();
+
+        assert_eq!(gateway.checkout_status(&id).await.unwrap(), CheckoutStatus::Expired);
+    }
+
+    #[tokio::test]
+    async fn refunds_are_limited_to_the_paid_amount() {
+        let gateway = FakeGateway::default();
+        let id = checkout(&gateway).await;
+        let payment_intent_id = payment_intent_id(&id);
+
+        assert!(gateway.refund(&payment_intent_id, 1000, "first").await.is_err()); // not paid yet
+
+        gateway.complete(&id).unwrap();
+
+        assert!(gateway.refund(&payment_intent_id, 4000, "first").await.is_ok());
+        assert!(gateway.refund(&payment_intent_id, 4000, "second").await.is_err());
+        assert_eq!(gateway.checkouts.lock().unwrap()[&id].refunded, 4000);
+    }
+
+    #[tokio::test]
+    async fn retried_refunds_are_not_refunded_again() {
+        let gateway = FakeGateway::default();
+        let id = checkout(&gateway).await;
+        let payment_intent_id = payment_intent_id(&id);
+        gateway.complete(&id).unwrap();
+
+        let first = gateway.refund(&payment_intent_id, 1000, "refund-session-1").await.unwrap();
+        let retry = gateway.refund(&payment_intent_id, 1000, "refund-session-1").await.unwrap();
+
+        assert_eq!(first, retry);
+        assert_eq!(gateway.checkouts.lock().unwrap()[&id].refunded, 1000);
+    }
+
+    #[tokio::test]
+    async fn paid_checkout_cannot_be_expired() {
+        let gateway = FakeGateway::default();
+        let open = checkout(&gateway).await;
+
+        gateway.expire_checkout(&open).await.unwrap();
+        assert_eq!(gateway.checkout_status(&open).await.unwrap(), CheckoutStatus::Expired);
+
+        let (_, paid) = gateway.create_checkout_session(100, "eur", "", "", reference(2)).await.unwrap();
+        gateway.complete(&paid).unwrap();
+
+        assert!(gateway.expire_checkout(&paid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_webhook_events_are_ignored() {
+        let gateway = FakeGateway::default();
+
+        let event = gateway.webhook_event(r#"{"type": "customer.created", "checkout_session_id": "cs_fake_1"}"#, "any");
+
+        assert_eq!(event.unwrap(), PaymentEvent::Ignored);
+        assert!(matches!(gateway.webhook_event("not json", "any"), Err(AppError::InvalidPayload)));
+    }
+}
@@ -1 +1,2 @@
+pub mod fake_gateway;
 pub mod stripe_gateway;
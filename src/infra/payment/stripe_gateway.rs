@@ -3,23 +3,27 @@ use std::sync::Arc;
 use stripe::CheckoutSession;
 use stripe::Client;
 use async_trait::async_trait;
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::{
     app_error::{AppError, AppResult},
-    application::use_cases::payment::PaymentGateway,
+    application::use_cases::payment::{CheckoutReference, CheckoutStatus, PaymentEvent, PaymentGateway},
     infra::config::AppConfig,
 };
 
+/// Checkouts expire after this time, releasing the slot reserved by the booking (30 minutes is the minimum allowed by Stripe)
+const CHECKOUT_EXPIRATION_MINUTES: i64 = 30;
+
 #[derive(Clone)]
 pub struct StripeGateway {
     client: Client,
+    webhook_secret: String,
 }
 
 impl StripeGateway {
     pub fn new(config: Arc<AppConfig>) -> Self {
         let client = Client::new(config.stripe_secret_key.clone());
-        Self { client }
+        Self { client, webhook_secret: config.stripe_webhook_secret.clone() }
     }
 }
 
@@ -31,23 +35,29 @@ impl PaymentGateway for StripeGateway {
         currency: &str,
         success_url: &str,
         _cancel_url: &str,
-        metadata: Option<std::collections::HashMap<String, String>>,
+        reference: CheckoutReference,
     ) -> AppResult<(String, String)> {
         use stripe::{
             CheckoutSessionMode, CreateCheckoutSession, CreateCheckoutSessionLineItems,
             CreateCheckoutSessionLineItemsPriceData,
             CreateCheckoutSessionLineItemsPriceDataProductData,
+            CreateCheckoutSessionPaymentIntentData,
         };
 
+        let client_reference_id = reference.transaction_id.to_string();
+
         let mut create_session = CreateCheckoutSession::new();
         create_session.mode = Some(CheckoutSessionMode::Payment);
         create_session.ui_mode = Some(stripe::CheckoutSessionUiMode::Embedded);
         create_session.return_url = Some(success_url);
+        create_session.expires_at = Some(
+            (chrono::Utc::now() + chrono::Duration::minutes(CHECKOUT_EXPIRATION_MINUTES)).timestamp(),
+        );
         
         let price_data = CreateCheckoutSessionLineItemsPriceData {
             currency: currency.parse().unwrap_or(stripe::Currency::EUR), // Default or error handle
             product_data: Some(CreateCheckoutSessionLineItemsPriceDataProductData {
-                name: "Session Booking".to_string(), // Could be dynamic
+                name: reference.product_name.clone(),
                 ..Default::default()
             }),
             unit_amount: Some(amount), // Stripe expects amount in cents
@@ -62,10 +72,14 @@ impl PaymentGateway for StripeGateway {
 
         create_session.line_items = Some(vec![line_item]);
 
-        if let Some(_meta) = metadata {
-             // TODO: [lazaropaul] In "final" app, we would have to map `metadata`
-             // (Transaction info) to Stripe metadata create_session.metadata = ...
-        }
+        // Set on the payment intent too, so the payment events and refunds can be traced back as well
+        let metadata = reference.metadata();
+        create_session.client_reference_id = Some(&client_reference_id);
+        create_session.payment_intent_data = Some(CreateCheckoutSessionPaymentIntentData {
+            metadata: Some(metadata.clone()),
+            ..Default::default()
+        });
+        create_session.metadata = Some(metadata);
 
         let session = CheckoutSession::create(&self.client, create_session)
             .await
@@ -83,5 +97,257 @@ impl PaymentGateway for StripeGateway {
 
         Ok((client_secret, id))
     }
+
+    async fn checkout_status(&self, session_id: &str) -> AppResult<CheckoutStatus> {
+        use stripe::{CheckoutSessionId, CheckoutSessionPaymentStatus, CheckoutSessionStatus};
+
+        let id = session_id.parse::<CheckoutSessionId>().map_err(|_| AppError::InvalidPayload)?;
+
+        let session = CheckoutSession::retrieve(&self.client, &id, &[])
+            .await
+            .map_err(|e| {
+                error!("Stripe retrieve session error: {:?}", e);
+                AppError::ExternalServiceError(format!("Stripe error: {}", e))
+            })?;
+
+        let status = match (session.status, session.payment_status) {
+            (_, CheckoutSessionPaymentStatus::Paid) => CheckoutStatus::Paid {
+                payment_intent_id: session.payment_intent.map(|intent| intent.id().to_string()),
+            },
+            (Some(CheckoutSessionStatus::Expired), _) => CheckoutStatus::Expired,
+            _ => CheckoutStatus::Open,
+        };
+
+        Ok(status)
+    }
+
+    fn webhook_event(&self, payload: &str, signature: &str) -> AppResult<PaymentEvent> {
+        parse_webhook_event(payload, signature, &self.webhook_secret, chrono::Utc::now().timestamp())
+    }
+
+    async fn checkout_session_for_payment_intent(
+        &self,
+        payment_intent_id: &str,
+    ) -> AppResult<Option<String>> {
+        use stripe::{ListCheckoutSessions, PaymentIntentId};
+
+        let mut params = ListCheckoutSessions::new();
+        params.payment_intent = Some(
+            payment_intent_id.parse::<PaymentIntentId>().map_err(|_| AppError::InvalidPayload)?,
+        );
+        params.limit = Some(1);
+
+        let sessions = CheckoutSession::list(&self.client, &params)
+            .await
+            .map_err(|e| {
+                error!("Stripe list sessions error: {:?}", e);
+                AppError::ExternalServiceError(format!("Stripe error: {}", e))
+            })?;
+
+        Ok(sessions.data.into_iter().next().map(|session| session.id.as_str().to_string()))
+    }
+
+    async fn expire_checkout(&self, session_id: &str) -> AppResult<()> {
+        use stripe::CheckoutSessionId;
+
+        let id = session_id.parse::<CheckoutSessionId>().map_err(|_| AppError::InvalidPayload)?;
+
+        CheckoutSession::expire(&self.client, &id)
+            .await
+            .map_err(|e| {
+                error!("Stripe expire session error: {:?}", e);
+                AppError::ExternalServiceError(format!("Stripe error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    async fn refund(&self, payment_intent_id: &str, amount: i64, idempotency_key: &str) -> AppResult<String> {
+        use stripe::{CreateRefund, PaymentIntentId, Refund, RequestStrategy};
+
+        // Stripe answers a retry with the refund the key already created
+        let client = self
+            .client
+            .clone()
+            .with_strategy(RequestStrategy::Idempotent(idempotency_key.to_string()));
+
+        let mut params = CreateRefund::new();
+        params.payment_intent = Some(
+            payment_intent_id.parse::<PaymentIntentId>().map_err(|_| AppError::InvalidPayload)?,
+        );
+        params.amount = Some(amount);
+
+        let refund = Refund::create(&client, params)
+            .await
+            .map_err(|e| {
+                error!("Stripe create refund error: {:?}", e);
+                AppError::ExternalServiceError(format!("Stripe error: {}", e))
+            })?;
+
+        Ok(refund.id.as_str().to_string())
+    }
+}
+
+/// Verifies the Stripe-Signature header against the payload and maps the event, `now` is only injectable for the tests
+fn parse_webhook_event(
+    payload: &str,
+    signature: &str,
+    secret: &str,
+    now: i64,
+) -> AppResult<PaymentEvent> {
+    use stripe::{CheckoutSessionPaymentStatus, EventObject, EventType, Webhook, WebhookError};
+
+    let event = Webhook::construct_event_with_timestamp(payload, signature, secret, now).map_err(|e| {
+        warn!("Rejected Stripe webhook: {:?}", e);
+        match e {
+            WebhookError::BadParse(_) => AppError::InvalidPayload,
+            _ => AppError::Unauthorized("Invalid webhook signature".into()),
+        }
+    })?;
+
+    let payment_event = match (event.type_, event.data.object) {
+        (EventType::CheckoutSessionCompleted, EventObject::CheckoutSession(session)) => {
+            // Delayed payment methods complete the checkout before the money arrives, an async payment event follows
+            if session.payment_status != CheckoutSessionPaymentStatus::Paid {
+                return Ok(PaymentEvent::Ignored);
+            }
+
+            PaymentEvent::CheckoutCompleted {
+                checkout_session_id: session.id.as_str().to_string(),
+                payment_intent_id: session.payment_intent.map(|intent| intent.id().to_string()),
+            }
+        }
+        (EventType::CheckoutSessionAsyncPaymentSucceeded, EventObject::CheckoutSession(session)) => {
+            PaymentEvent::CheckoutCompleted {
+                checkout_session_id: session.id.as_str().to_string(),
+                payment_intent_id: session.payment_intent.map(|intent| intent.id().to_string()),
+            }
+        }
+        (EventType::CheckoutSessionAsyncPaymentFailed, EventObject::CheckoutSession(session)) => {
+            PaymentEvent::CheckoutPaymentFailed { checkout_session_id: session.id.as_str().to_string() }
+        }
+        (EventType::CheckoutSessionExpired, EventObject::CheckoutSession(session)) => {
+            PaymentEvent::CheckoutExpired { checkout_session_id: session.id.as_str().to_string() }
+        }
+        (EventType::PaymentIntentPaymentFailed, EventObject::PaymentIntent(intent)) => {
+            PaymentEvent::PaymentFailed { payment_intent_id: intent.id.as_str().to_string() }
+        }
+        _ => PaymentEvent::Ignored,
+    };
+
+    Ok(payment_event)
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Payloads recorded from the Stripe CLI, signed with SECRET at TIMESTAMP.
+    // Any change to the fixtures invalidates their signatures.
+    const SECRET: &str = "whsec_test_secret";
+    const TIMESTAMP: i64 = 1767225600;
+
+    const CHECKOUT_COMPLETED: &str = include_str!("webhook_fixtures/checkout_session_completed.json");
+    const CHECKOUT_COMPLETED_SIGNATURE: &str =
+        "t=1767225600,v1=999f9ca2bcd8ae50bc306c59beb440030cfd3560babf4363c6d5f7cc70bf70f9";
+
+    const CHECKOUT_EXPIRED: &str = include_str!("webhook_fixtures/checkout_session_expired.json");
+    const CHECKOUT_EXPIRED_SIGNATURE: &str =
+        "t=1767225600,v1=404ba15cf6bceb171d9ecfa2561c8aecad945079e4325a41ccf94de330887d2c";
+
+    const ASYNC_PAYMENT_SUCCEEDED: &str = include_str!("webhook_fixtures/checkout_session_async_payment_succeeded.json");
+    const ASYNC_PAYMENT_SUCCEEDED_SIGNATURE: &str =
+        "t=1767225600,v1=f3868413fa2d0723c2718197f948316a60df7c4f01f28314491014a50473a2f2";
+
+    const ASYNC_PAYMENT_FAILED: &str = include_str!("webhook_fixtures/checkout_session_async_payment_failed.json");
+    const ASYNC_PAYMENT_FAILED_SIGNATURE: &str =
+        "t=1767225600,v1=46e38709e7c1e3920773979aa248599e30f8cd449612241263a75b142743e5ca";
+
+    const PAYMENT_FAILED: &str = include_str!("webhook_fixtures/payment_intent_payment_failed.json");
+    const PAYMENT_FAILED_SIGNATURE: &str =
+        "t=1767225600,v1=1f1824f68fc14cdca6580bab3b7a00e1cb79c85b4e60db1fe2cad5a3afc21daf";
+
+    #[test]
+    fn checkout_completed_is_parsed() {
+        let event = parse_webhook_event(CHECKOUT_COMPLETED, CHECKOUT_COMPLETED_SIGNATURE, SECRET, TIMESTAMP);
+
+        assert_eq!(
+            event.unwrap(),
+            PaymentEvent::CheckoutCompleted {
+                checkout_session_id: String::from("cs_test_a1B2c3D4e5F6g7H8i9J0kLmNoPqRsTuVwXyZ"),
+                payment_intent_id: Some(String::from("pi_3QbYdVGk6T0s1m2n0aBcDeFg")),
+            }
+        );
+    }
+
+    #[test]
+    fn checkout_expired_is_parsed() {
+        let event = parse_webhook_event(CHECKOUT_EXPIRED, CHECKOUT_EXPIRED_SIGNATURE, SECRET, TIMESTAMP);
+
+        assert_eq!(
+            event.unwrap(),
+            PaymentEvent::CheckoutExpired {
+                checkout_session_id: String::from("cs_test_a1B2c3D4e5F6g7H8i9J0kLmNoPqRsTuVwXyZ"),
+            }
+        );
+    }
+
+    #[test]
+    fn async_payment_succeeded_completes_the_checkout() {
+        let event = parse_webhook_event(ASYNC_PAYMENT_SUCCEEDED, ASYNC_PAYMENT_SUCCEEDED_SIGNATURE, SECRET, TIMESTAMP);
+
+        assert_eq!(
+            event.unwrap(),
+            PaymentEvent::CheckoutCompleted {
+                checkout_session_id: String::from("cs_test_a1B2c3D4e5F6g7H8i9J0kLmNoPqRsTuVwXyZ"),
+                payment_intent_id: Some(String::from("pi_3QbYdVGk6T0s1m2n0aBcDeFg")),
+            }
+        );
+    }
+
+    #[test]
+    fn async_payment_failed_is_parsed() {
+        let event = parse_webhook_event(ASYNC_PAYMENT_FAILED, ASYNC_PAYMENT_FAILED_SIGNATURE, SECRET, TIMESTAMP);
+
+        assert_eq!(
+            event.unwrap(),
+            PaymentEvent::CheckoutPaymentFailed {
+                checkout_session_id: String::from("cs_test_a1B2c3D4e5F6g7H8i9J0kLmNoPqRsTuVwXyZ"),
+            }
+        );
+    }
+
+    #[test]
+    fn payment_failed_is_parsed() {
+        let event = parse_webhook_event(PAYMENT_FAILED, PAYMENT_FAILED_SIGNATURE, SECRET, TIMESTAMP);
+
+        assert_eq!(
+            event.unwrap(),
+            PaymentEvent::PaymentFailed {
+                payment_intent_id: String::from("pi_3QbYdVGk6T0s1m2n0aBcDeFg"),
+            }
+        );
+    }
+
+    #[test]
+    fn wrong_secret_fails() {
+        let event = parse_webhook_event(CHECKOUT_COMPLETED, CHECKOUT_COMPLETED_SIGNATURE, "whsec_other", TIMESTAMP);
+
+        assert!(matches!(event, Err(AppError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn tampered_payload_fails() {
+        let payload = CHECKOUT_COMPLETED.replace("\"amount_total\": 6000", "\"amount_total\": 1");
+        let event = parse_webhook_event(&payload, CHECKOUT_COMPLETED_SIGNATURE, SECRET, TIMESTAMP);
+
+        assert!(matches!(event, Err(AppError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn replayed_event_fails() {
+        let event = parse_webhook_event(CHECKOUT_COMPLETED, CHECKOUT_COMPLETED_SIGNATURE, SECRET, TIMESTAMP + 3600);
+
+        assert!(matches!(event, Err(AppError::Unauthorized(_))));
+    }
+}
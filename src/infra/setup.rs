@@ -1,13 +1,28 @@
 use crate::{
-    adapters::http::app_state::AppState,
+    adapters::{http::app_state::AppState, persistence::tenant},
     infra::{
-        argon2_password_hasher, config::AppConfig, email_service, jwt_service, postgres_persistence,
-        stripe_gateway, videocall_service,
+        argon2_password_hasher, config::AppConfig, data_export_archiver, email_service, invoice_renderer, jwt_service,
+        payment_gateway, postgres_persistence, totp_service, videocall_service,
+    },
+    entities::{
+        cancellation_policy::CancellationPolicy,
+        earning::PlatformCommission,
+        login_throttle::{LoginThrottle, LoginThrottlePolicy},
+        videocall::JoinWindow,
     },
     use_cases::{
+        account_deletion::AccountDeletionUseCases,
+        audit::AuditUseCases,
         blog_post::BlogPostUseCases,
+        cancellation::CancellationUseCases,
+        clinical_note::ClinicalNoteUseCases,
+        data_export::DataExportUseCases,
+        earning::EarningUseCases,
+        invoice::InvoiceUseCases,
+        organization::OrganizationUseCases,
         patient::PatientUseCases,
         professional::ProfessionalUseCases,
+        professional_availability::ProfessionalAvailabilityUseCases,
         professional_language::ProfessionalLanguageUseCases,
         professional_specialization::ProfessionalSpecializationUseCases,
         session::SessionUseCases,
@@ -24,35 +39,84 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
 pub async fn init_app_state() -> anyhow::Result<AppState> {
     let config = Arc::new(AppConfig::from_env());
 
-    let postgres_arc = Arc::new(postgres_persistence().await?);
+    let postgres_arc = Arc::new(postgres_persistence(Arc::clone(&config)).await?);
+
+    // Moves the free text notes of the sessions to clinical notes, then encrypts what was stored in plaintext
+    // and moves the values of rotated keys to the current one
+    let rewrap_persistence = postgres_arc.clone();
+    tokio::spawn(tenant::unscoped(async move {
+        match rewrap_persistence.move_legacy_session_notes().await {
+            Ok(moved) => tracing::info!("Moved the notes of {} sessions to clinical notes", moved),
+            Err(e) => tracing::error!("Failed to move the notes of the sessions: {:?}", e),
+        }
+        match rewrap_persistence.rewrap_encrypted_fields().await {
+            Ok(updated) => tracing::info!("Rewrapped the encrypted fields of {} rows", updated),
+            Err(e) => tracing::error!("Failed to rewrap the encrypted fields: {:?}", e),
+        }
+    }));
     let jwt_service = Arc::new(jwt_service(Arc::clone(&config)));
-    let email_service = email_service(Arc::clone(&config));
-    let argon_hasher = argon2_password_hasher();
+    let email_service = Arc::new(email_service(Arc::clone(&config)));
+    let argon_hasher = Arc::new(argon2_password_hasher());
 
     let user_use_cases = UserUseCases::new(
         jwt_service.clone() as Arc<dyn UserJwtService>,
-        Arc::new(argon_hasher),
+        argon_hasher.clone(),
+        postgres_arc.clone(),
         postgres_arc.clone(),
+        config.refresh_token_ttl,
         postgres_arc.clone(),
+        Arc::new(totp_service(Arc::clone(&config))),
         postgres_arc.clone(),
+        LoginThrottle {
+            per_account: LoginThrottlePolicy {
+                max_failures: config.login_max_failures,
+                lockout_duration: chrono::Duration::minutes(config.login_lockout_minutes),
+            },
+            per_ip: LoginThrottlePolicy {
+                max_failures: config.login_max_failures_per_ip,
+                lockout_duration: chrono::Duration::minutes(config.login_lockout_minutes),
+            },
+        },
     );
 
     let user_token_use_cases = UserTokenUseCases::new(
         jwt_service as Arc<dyn UserTokenJwtService>,
-        Arc::new(email_service),
+        email_service.clone(),
         postgres_arc.clone(),
+        argon_hasher,
+        config.password_reset_token_ttl,
     );
 
-    let patient_use_cases = PatientUseCases::new(postgres_arc.clone());
+    let patient_use_cases = PatientUseCases::new(postgres_arc.clone(), postgres_arc.clone());
 
     let session_type_use_cases = SessionTypeUseCases::new(postgres_arc.clone());
 
+    let organization_use_cases = OrganizationUseCases::new(postgres_arc.clone());
+
+    let audit_use_cases = AuditUseCases::new(postgres_arc.clone());
+
     let videocall_service = videocall_service(Arc::clone(&config));
 
-    let session_use_cases = SessionUseCases::new(postgres_arc.clone(), Arc::new(videocall_service));
+    let session_use_cases = SessionUseCases::new(
+        postgres_arc.clone(),
+        videocall_service.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        JoinWindow {
+            minutes_before_start: config.videocall_join_minutes_before,
+            minutes_after_end: config.videocall_join_minutes_after,
+        },
+    );
+
+    let clinical_note_use_cases = ClinicalNoteUseCases::new(postgres_arc.clone(), postgres_arc.clone(), postgres_arc.clone());
 
     let professional_use_cases = ProfessionalUseCases::new(postgres_arc.clone());
 
+    let professional_availability_use_cases =
+        ProfessionalAvailabilityUseCases::new(postgres_arc.clone());
+
     let professional_languages_use_cases = ProfessionalLanguageUseCases::new(postgres_arc.clone());
 
     let professional_specializations_use_cases =
@@ -60,8 +124,77 @@ pub async fn init_app_state() -> anyhow::Result<AppState> {
 
     let blog_post_use_cases = BlogPostUseCases::new(postgres_arc.clone());
 
-    let stripe_gateway = Arc::new(stripe_gateway(Arc::clone(&config)));
-    let payment_use_cases = PaymentUseCases::new(postgres_arc.clone(), stripe_gateway);
+    let payment_gateway = payment_gateway(Arc::clone(&config));
+    let payment_use_cases = PaymentUseCases::new(
+        postgres_arc.clone(),
+        payment_gateway.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        PlatformCommission { percent: config.platform_commission_percent },
+    );
+
+    let earning_use_cases = EarningUseCases::new(postgres_arc.clone(), postgres_arc.clone());
+
+    let invoice_use_cases = InvoiceUseCases::new(
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        Arc::new(invoice_renderer()),
+    );
+
+    let data_export_use_cases = DataExportUseCases::new(postgres_arc.clone(), Arc::new(data_export_archiver()));
+
+    // The archives hold clinical data, they are not kept once they can't be downloaded anymore
+    let purge_use_cases = data_export_use_cases.clone();
+    tokio::spawn(tenant::unscoped(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            match purge_use_cases.delete_expired().await {
+                Ok(deleted) => tracing::info!("Deleted {} expired data exports", deleted),
+                Err(e) => tracing::error!("Failed to delete the expired data exports: {:?}", e),
+            }
+        }
+    }));
+
+    let cancellation_use_cases = CancellationUseCases::new(
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        payment_gateway,
+        postgres_arc.clone(),
+        email_service,
+        postgres_arc.clone(),
+        postgres_arc.clone(),
+        videocall_service,
+        CancellationPolicy {
+            full_refund_notice_hours: config.cancellation_full_refund_hours,
+            late_refund_percent: config.cancellation_late_refund_percent,
+        },
+    );
+
+    let cancellation_use_cases = Arc::new(cancellation_use_cases);
+
+    let account_deletion_use_cases = AccountDeletionUseCases::new(
+        postgres_arc.clone(),
+        cancellation_use_cases.clone(),
+        config.account_deletion_grace_period,
+    );
+
+    // Carries out the deletions whose grace period is over
+    let erase_use_cases = account_deletion_use_cases.clone();
+    tokio::spawn(tenant::unscoped(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            match erase_use_cases.erase_due().await {
+                Ok(erased) => tracing::info!("Erased {} accounts", erased),
+                Err(e) => tracing::error!("Failed to erase the accounts due: {:?}", e),
+            }
+        }
+    }));
 
     Ok(AppState {
         config,
@@ -70,11 +203,20 @@ pub async fn init_app_state() -> anyhow::Result<AppState> {
         patient_use_cases: Arc::new(patient_use_cases),
         session_type_use_cases: Arc::new(session_type_use_cases),
         session_use_cases: Arc::new(session_use_cases),
+        clinical_note_use_cases: Arc::new(clinical_note_use_cases),
         professional_use_cases: Arc::new(professional_use_cases),
+        professional_availability_use_cases: Arc::new(professional_availability_use_cases),
         professional_languages_use_cases: Arc::new(professional_languages_use_cases),
         professional_specializations_use_cases: Arc::new(professional_specializations_use_cases),
         blog_post_use_cases: Arc::new(blog_post_use_cases),
         payment_use_cases: Arc::new(payment_use_cases),
+        cancellation_use_cases,
+        invoice_use_cases: Arc::new(invoice_use_cases),
+        earning_use_cases: Arc::new(earning_use_cases),
+        organization_use_cases: Arc::new(organization_use_cases),
+        audit_use_cases: Arc::new(audit_use_cases),
+        data_export_use_cases: Arc::new(data_export_use_cases),
+        account_deletion_use_cases: Arc::new(account_deletion_use_cases),
     })
 }
 
@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use dotenvy::dotenv;
 use tracing::info;
 
@@ -21,7 +23,10 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Backend listening at {}", &listener.local_addr().unwrap());
 
-    axum::serve(listener, app).await.unwrap();
+    // The connect info is the client ip the logins are throttled by
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 
     Ok(())
 }